@@ -22,11 +22,17 @@ fn main() {
 	if cargo.ends_with("rls") {
 		env::set_var("CARGO", cargo.replace("rls", "cargo"));
 	}
+	// This instructs LLD to export __heap_base as a global variable, which is used by the
+	// external memory allocator.
+	let mut rustflags = String::from("-Clink-arg=--export=__heap_base");
+	// The wasm runtime is built without default features, so the offchain worker is compiled
+	// into it the same way it is compiled into the native runtime.
+	if env::var("CARGO_FEATURE_OFFCHAIN").is_ok() {
+		rustflags.push_str(" --cfg feature=\"offchain\"");
+	}
 	build_current_project_with_rustflags(
 		"wasm_binary.rs",
 		WasmBuilderSource::Crates("1.0.8"),
-		// This instructs LLD to export __heap_base as a global variable, which is used by the
-		// external memory allocator.
-		"-Clink-arg=--export=__heap_base",
+		&rustflags,
 	);
 }
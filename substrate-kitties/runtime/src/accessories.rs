@@ -0,0 +1,435 @@
+//! Accessories (hats, collars...) which can be equipped onto kitties.
+//!
+//! An equipped accessory is held by the kitty rather than by an account, so it
+//! follows the kitty when the kitty is transferred or sold, until the kitty's
+//! owner unequips it.
+
+use support::{decl_module, decl_storage, decl_event, ensure, Parameter};
+use sr_primitives::traits::{SimpleArithmetic, Bounded, Member};
+use codec::{Encode, Decode};
+use runtime_io::blake2_128;
+use system::{ensure_signed, ensure_root};
+use rstd::result;
+use support::traits::Randomness;
+use crate::linked_item::{LinkedList, LinkedItem};
+use crate::traits::{ItemTransfer, ItemTransferError, IsItemLocked, OnItemBurned, OnItemTransferred};
+
+pub trait Trait: system::Trait {
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+	type AccessoryIndex: Parameter + Member + SimpleArithmetic + Bounded + Default + Copy;
+	/// Kitty index
+	type KittyIndex: Parameter + Member + Copy;
+	/// Interface for checking kitty ownership
+	type Kitties: ItemTransfer<Self::AccountId, Self::KittyIndex>;
+	/// Tell whether a kitty is locked, e.g. in an auction
+	type KittyLocks: IsItemLocked<Self::KittyIndex>;
+	/// Tell whether an accessory is locked, e.g. in an auction
+	type AccessoryLocks: IsItemLocked<Self::AccessoryIndex>;
+	/// Handler for accessories being minted or changing hands, e.g. provenance
+	type OnAccessoryTransferred: OnItemTransferred<Self::AccountId, Self::AccessoryIndex>;
+}
+
+#[derive(Encode, Decode, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum AccessoryKind {
+	Hat,
+	Collar,
+}
+
+#[derive(Encode, Decode, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct Accessory {
+	pub kind: AccessoryKind,
+	pub dna: [u8; 16],
+}
+
+type AccessoryLinkedItem<T> = LinkedItem<<T as Trait>::AccessoryIndex>;
+type OwnedAccessoriesList<T> = LinkedList<OwnedAccessories<T>, <T as system::Trait>::AccountId, <T as Trait>::AccessoryIndex>;
+
+decl_storage! {
+	trait Store for Module<T: Trait> as Accessories {
+		/// Stores all the accessories, key is the accessory id / index
+		pub Accessories get(accessory): map T::AccessoryIndex => Option<Accessory>;
+		/// Stores the total number of accessories. i.e. the next accessory index
+		pub AccessoriesCount get(accessories_count): T::AccessoryIndex;
+
+		pub OwnedAccessories get(owned_accessories): map (T::AccountId, Option<T::AccessoryIndex>) => Option<AccessoryLinkedItem<T>>;
+
+		/// Owner of an unequipped accessory
+		pub AccessoryOwners get(accessory_owner): map T::AccessoryIndex => Option<T::AccountId>;
+		/// The kitty an accessory is equipped on
+		pub EquippedOn get(equipped_on): map T::AccessoryIndex => Option<T::KittyIndex>;
+		/// Accessory equipped on a kitty for each kind
+		pub KittyEquipment get(kitty_equipment): map (T::KittyIndex, AccessoryKind) => Option<T::AccessoryIndex>;
+		/// Accessories held by another module, e.g. listed in an auction
+		pub ReservedAccessories get(is_reserved): map T::AccessoryIndex => bool;
+	}
+}
+
+decl_event!(
+	pub enum Event<T> where
+		<T as system::Trait>::AccountId,
+		<T as Trait>::AccessoryIndex,
+		<T as Trait>::KittyIndex,
+	{
+		/// An accessory is minted. (owner, accessory_id, kind)
+		Minted(AccountId, AccessoryIndex, AccessoryKind),
+		/// An accessory is transferred. (from, to, accessory_id)
+		Transferred(AccountId, AccountId, AccessoryIndex),
+		/// An accessory is equipped onto a kitty. (owner, accessory_id, kitty_id)
+		Equipped(AccountId, AccessoryIndex, KittyIndex),
+		/// An accessory is unequipped from a kitty. (owner, accessory_id, kitty_id)
+		Unequipped(AccountId, AccessoryIndex, KittyIndex),
+	}
+);
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		fn deposit_event() = default;
+
+		/// Mint a new accessory to an account
+		pub fn mint(origin, to: T::AccountId, kind: AccessoryKind) {
+			ensure_root(origin)?;
+
+			let accessory_id = Self::next_accessory_id()?;
+			let dna = Self::random_value(&to);
+			Self::insert_accessory(&to, accessory_id, Accessory { kind: kind, dna: dna });
+
+			Self::deposit_event(RawEvent::Minted(to, accessory_id, kind));
+		}
+
+		/// Transfer an unequipped accessory to new owner
+		pub fn transfer(origin, to: T::AccountId, accessory_id: T::AccessoryIndex) {
+			let sender = ensure_signed(origin)?;
+			ensure!(!Self::is_locked(accessory_id), "Accessory is locked in an auction");
+
+			Self::transfer_accessory(&sender, &to, accessory_id)?;
+		}
+
+		/// Equip an accessory onto a kitty, replacing the one of the same kind
+		pub fn equip(origin, accessory_id: T::AccessoryIndex, kitty_id: T::KittyIndex) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(Self::is_accessory_owner(&sender, accessory_id), "Only owner can equip accessory");
+			ensure!(T::Kitties::is_item_owner(&sender, kitty_id), "Only kitty owner can equip accessory");
+			ensure!(!Self::is_locked(accessory_id), "Accessory is locked in an auction");
+			ensure!(!T::KittyLocks::is_item_locked(kitty_id), "Kitty is locked in an auction");
+			let accessory = Self::accessory(accessory_id).ok_or("Invalid accessory_id")?;
+
+			// the replaced accessory goes back to the owner
+			if let Some(old_id) = Self::kitty_equipment(&(kitty_id, accessory.kind)) {
+				Self::do_unequip(&sender, old_id, kitty_id, accessory.kind);
+			}
+
+			<OwnedAccessoriesList<T>>::remove(&sender, accessory_id);
+			<AccessoryOwners<T>>::remove(accessory_id);
+			<EquippedOn<T>>::insert(accessory_id, kitty_id);
+			<KittyEquipment<T>>::insert(&(kitty_id, accessory.kind), accessory_id);
+
+			Self::deposit_event(RawEvent::Equipped(sender, accessory_id, kitty_id));
+		}
+
+		/// Unequip an accessory from a kitty back to the kitty's owner
+		pub fn unequip(origin, accessory_id: T::AccessoryIndex) {
+			let sender = ensure_signed(origin)?;
+
+			let kitty_id = Self::equipped_on(accessory_id).ok_or("Accessory is not equipped")?;
+			ensure!(T::Kitties::is_item_owner(&sender, kitty_id), "Only kitty owner can unequip accessory");
+			ensure!(!T::KittyLocks::is_item_locked(kitty_id), "Kitty is locked in an auction");
+			let accessory = Self::accessory(accessory_id).ok_or("Invalid accessory_id")?;
+
+			Self::do_unequip(&sender, accessory_id, kitty_id, accessory.kind);
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	fn random_value(sender: &T::AccountId) -> [u8; 16] {
+		let payload = (<randomness_collective_flip::Module<T>>::random_seed(), sender, <system::Module<T>>::extrinsic_index(), <system::Module<T>>::block_number());
+		payload.using_encoded(blake2_128)
+	}
+
+	fn next_accessory_id() -> result::Result<T::AccessoryIndex, &'static str> {
+		let accessory_id = Self::accessories_count();
+		if accessory_id == T::AccessoryIndex::max_value() {
+			return Err("Accessories count overflow");
+		}
+		Ok(accessory_id)
+	}
+
+	fn insert_accessory(owner: &T::AccountId, accessory_id: T::AccessoryIndex, accessory: Accessory) {
+		<Accessories<T>>::insert(accessory_id, accessory);
+		<AccessoriesCount<T>>::put(accessory_id + 1.into());
+		<AccessoryOwners<T>>::insert(accessory_id, owner.clone());
+
+		<OwnedAccessoriesList<T>>::append(owner, accessory_id);
+		T::OnAccessoryTransferred::on_item_minted(owner, accessory_id);
+	}
+
+	/// Error of moving an accessory not owned by the account, an equipped one is held by its kitty
+	fn ownership_error(accessory_id: T::AccessoryIndex) -> ItemTransferError {
+		if <EquippedOn<T>>::exists(accessory_id) {
+			ItemTransferError::Frozen
+		} else if <Accessories<T>>::exists(accessory_id) {
+			ItemTransferError::NotOwner
+		} else {
+			ItemTransferError::Unknown
+		}
+	}
+
+	/// Whether the accessory is held by another module, or locked by one
+	fn is_locked(accessory_id: T::AccessoryIndex) -> bool {
+		Self::is_reserved(accessory_id) || T::AccessoryLocks::is_item_locked(accessory_id)
+	}
+
+	fn is_accessory_owner(who: &T::AccountId, accessory_id: T::AccessoryIndex) -> bool {
+		Self::accessory_owner(accessory_id).map(|owner| owner == *who).unwrap_or(false)
+	}
+
+	// an equipped accessory has no owner until it is unequipped
+	fn ensure_accessory_transferable(owner: &T::AccountId, accessory_id: T::AccessoryIndex) -> result::Result<(), &'static str> {
+		ensure!(Self::is_accessory_owner(owner, accessory_id), "Only owner can transfer accessory");
+		Ok(())
+	}
+
+	fn transfer_accessory(owner: &T::AccountId, to: &T::AccountId, accessory_id: T::AccessoryIndex) -> result::Result<(), &'static str> {
+		Self::ensure_accessory_transferable(owner, accessory_id)?;
+		// 被占用的配饰只能由占用方转移
+		ensure!(!Self::is_reserved(accessory_id), "Accessory is reserved");
+
+		<OwnedAccessoriesList<T>>::remove(owner, accessory_id);
+		<OwnedAccessoriesList<T>>::append(to, accessory_id);
+		<AccessoryOwners<T>>::insert(accessory_id, to);
+		// the hold is released once the accessory changes hands
+		<ReservedAccessories<T>>::remove(accessory_id);
+		Self::deposit_event(RawEvent::Transferred(owner.clone(), to.clone(), accessory_id));
+		T::OnAccessoryTransferred::on_item_transferred(owner, to, accessory_id, false);
+
+		Ok(())
+	}
+
+	fn do_unequip(owner: &T::AccountId, accessory_id: T::AccessoryIndex, kitty_id: T::KittyIndex, kind: AccessoryKind) {
+		<EquippedOn<T>>::remove(accessory_id);
+		<KittyEquipment<T>>::remove(&(kitty_id, kind));
+		<AccessoryOwners<T>>::insert(accessory_id, owner.clone());
+		<OwnedAccessoriesList<T>>::append(owner, accessory_id);
+
+		Self::deposit_event(RawEvent::Unequipped(owner.clone(), accessory_id, kitty_id));
+	}
+}
+
+/// Accessories equipped on a burned kitty go back to the kitty's owner
+impl<T: Trait> OnItemBurned<<T as system::Trait>::AccountId, T::KittyIndex> for Module<T> {
+	fn on_item_burned(owner: &<T as system::Trait>::AccountId, kitty_id: T::KittyIndex) {
+		for kind in [AccessoryKind::Hat, AccessoryKind::Collar].iter() {
+			if let Some(accessory_id) = Self::kitty_equipment(&(kitty_id, *kind)) {
+				Self::do_unequip(owner, accessory_id, kitty_id, *kind);
+			}
+		}
+	}
+}
+
+impl<T: Trait> ItemTransfer<<T as system::Trait>::AccountId, T::AccessoryIndex> for Module<T> {
+	fn is_item_owner(
+		who: &<T as system::Trait>::AccountId,
+		item_id: T::AccessoryIndex
+	) -> bool {
+		Module::<T>::is_accessory_owner(who, item_id)
+	}
+
+	fn transfer_item(
+		source: &<T as system::Trait>::AccountId,
+		dest: &<T as system::Trait>::AccountId,
+		item_id: T::AccessoryIndex
+	) -> result::Result<(), ItemTransferError> {
+		ensure!(!Module::<T>::is_reserved(item_id), ItemTransferError::Frozen);
+		Module::<T>::transfer_accessory(source, dest, item_id).map_err(|_| Module::<T>::ownership_error(item_id))
+	}
+
+	fn transfer_reserved_item(
+		source: &<T as system::Trait>::AccountId,
+		dest: &<T as system::Trait>::AccountId,
+		item_id: T::AccessoryIndex
+	) -> result::Result<(), ItemTransferError> {
+		Self::can_transfer_reserved(source, item_id)?;
+		<ReservedAccessories<T>>::remove(item_id);
+		Module::<T>::transfer_accessory(source, dest, item_id).map_err(|_| Module::<T>::ownership_error(item_id))
+	}
+
+	fn can_transfer(
+		from: &<T as system::Trait>::AccountId,
+		item_id: T::AccessoryIndex
+	) -> result::Result<(), ItemTransferError> {
+		Module::<T>::ensure_accessory_transferable(from, item_id).map_err(|_| Module::<T>::ownership_error(item_id))?;
+		ensure!(!Module::<T>::is_locked(item_id), ItemTransferError::Frozen);
+		Ok(())
+	}
+
+	fn can_transfer_reserved(
+		from: &<T as system::Trait>::AccountId,
+		item_id: T::AccessoryIndex
+	) -> result::Result<(), ItemTransferError> {
+		ensure!(Module::<T>::is_accessory_owner(from, item_id), Module::<T>::ownership_error(item_id));
+		ensure!(Module::<T>::is_reserved(item_id), ItemTransferError::Unknown);
+		Ok(())
+	}
+
+	fn reserve_item(
+		who: &<T as system::Trait>::AccountId,
+		item_id: T::AccessoryIndex
+	) -> result::Result<(), ItemTransferError> {
+		ensure!(Module::<T>::is_accessory_owner(who, item_id), Module::<T>::ownership_error(item_id));
+		ensure!(!Module::<T>::is_reserved(item_id), ItemTransferError::Frozen);
+		<ReservedAccessories<T>>::insert(item_id, true);
+		Ok(())
+	}
+
+	fn unreserve_item(item_id: T::AccessoryIndex) {
+		<ReservedAccessories<T>>::remove(item_id);
+	}
+}
+
+/// Tests for Accessories module
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use primitives::H256;
+	use support::{impl_outer_origin, parameter_types, assert_ok, assert_noop};
+	use sr_primitives::{traits::{BlakeTwo256, IdentityLookup}, testing::Header};
+	use sr_primitives::weights::Weight;
+	use sr_primitives::Perbill;
+	use crate::mock_items::{TestItems, TestLocks};
+
+	impl_outer_origin! {
+		pub enum Origin for Test {}
+	}
+
+	#[derive(Clone, Eq, PartialEq, Debug)]
+	pub struct Test;
+	parameter_types! {
+		pub const BlockHashCount: u64 = 250;
+		pub const MaximumBlockWeight: Weight = 1024;
+		pub const MaximumBlockLength: u32 = 2 * 1024;
+		pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+	}
+	impl system::Trait for Test {
+		type Origin = Origin;
+		type Call = ();
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type Event = ();
+		type BlockHashCount = BlockHashCount;
+		type MaximumBlockWeight = MaximumBlockWeight;
+		type MaximumBlockLength = MaximumBlockLength;
+		type AvailableBlockRatio = AvailableBlockRatio;
+		type Version = ();
+	}
+	impl Trait for Test {
+		type Event = ();
+		type AccessoryIndex = u32;
+		type KittyIndex = u32;
+		type Kitties = TestItems;
+		type KittyLocks = TestLocks;
+		type AccessoryLocks = ();
+		type OnAccessoryTransferred = ();
+	}
+	type AccessoryModule = Module<Test>;
+
+	fn new_test_ext() -> runtime_io::TestExternalities {
+		TestItems::set_owners(vec![(1, 0), (2, 1)]);
+		system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
+	}
+
+	#[test]
+	fn only_root_mints_accessories() {
+		new_test_ext().execute_with(|| {
+			assert!(AccessoryModule::mint(Origin::signed(1), 1, AccessoryKind::Hat).is_err());
+			assert_eq!(AccessoryModule::accessories_count(), 0);
+			assert_ok!(AccessoryModule::mint(Origin::ROOT, 1, AccessoryKind::Hat));
+			assert_eq!(AccessoryModule::accessories_count(), 1);
+			assert_eq!(AccessoryModule::accessory_owner(0), Some(1));
+			assert_eq!(AccessoryModule::accessory(0).map(|a| a.kind), Some(AccessoryKind::Hat));
+		});
+	}
+
+	#[test]
+	fn accessories_are_transferred_by_their_owner() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(AccessoryModule::mint(Origin::ROOT, 1, AccessoryKind::Hat));
+			assert_noop!(AccessoryModule::transfer(Origin::signed(2), 3, 0), "Only owner can transfer accessory");
+			assert_ok!(AccessoryModule::transfer(Origin::signed(1), 2, 0));
+			assert_eq!(AccessoryModule::accessory_owner(0), Some(2));
+			assert!(AccessoryModule::is_item_owner(&2, 0));
+		});
+	}
+
+	#[test]
+	fn reserved_accessories_are_moved_only_by_the_holder() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(AccessoryModule::mint(Origin::ROOT, 1, AccessoryKind::Hat));
+			assert_ok!(AccessoryModule::reserve_item(&1, 0));
+			assert_eq!(AccessoryModule::can_transfer(&1, 0), Err(ItemTransferError::Frozen));
+			assert_noop!(AccessoryModule::transfer_item(&1, &2, 0), ItemTransferError::Frozen);
+			assert_ok!(AccessoryModule::can_transfer_reserved(&1, 0));
+
+			// 占用方转移配饰时释放占用
+			assert_ok!(AccessoryModule::transfer_reserved_item(&1, &2, 0));
+			assert!(!AccessoryModule::is_reserved(0));
+			assert_eq!(AccessoryModule::accessory_owner(0), Some(2));
+			assert_noop!(AccessoryModule::transfer_reserved_item(&2, &1, 0), ItemTransferError::Unknown);
+			assert_eq!(AccessoryModule::can_transfer_reserved(&2, 0), Err(ItemTransferError::Unknown));
+			assert_ok!(AccessoryModule::transfer_item(&2, &1, 0));
+		});
+	}
+
+	#[test]
+	fn equipped_accessories_follow_the_kitty_until_unequipped() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(AccessoryModule::mint(Origin::ROOT, 1, AccessoryKind::Hat));
+			assert_ok!(AccessoryModule::mint(Origin::ROOT, 1, AccessoryKind::Hat));
+			assert_noop!(AccessoryModule::equip(Origin::signed(1), 0, 1), "Only kitty owner can equip accessory");
+			assert_ok!(AccessoryModule::equip(Origin::signed(1), 0, 0));
+			assert_eq!(AccessoryModule::equipped_on(0), Some(0));
+			assert_eq!(AccessoryModule::accessory_owner(0), None);
+			assert_noop!(AccessoryModule::transfer(Origin::signed(1), 2, 0), "Only owner can transfer accessory");
+			assert_eq!(AccessoryModule::can_transfer(&1, 0), Err(ItemTransferError::Frozen));
+
+			// 同类配饰替换后旧配饰回到主人名下
+			assert_ok!(AccessoryModule::equip(Origin::signed(1), 1, 0));
+			assert_eq!(AccessoryModule::kitty_equipment(&(0, AccessoryKind::Hat)), Some(1));
+			assert_eq!(AccessoryModule::accessory_owner(0), Some(1));
+
+			// 猫咪转移后由新主人卸下
+			assert_ok!(TestItems::transfer_item(&1, &2, 0));
+			assert_noop!(AccessoryModule::unequip(Origin::signed(1), 1), "Only kitty owner can unequip accessory");
+			TestItems::set_locked(vec![0]);
+			assert_noop!(AccessoryModule::unequip(Origin::signed(2), 1), "Kitty is locked in an auction");
+			TestItems::set_locked(vec![]);
+			assert_ok!(AccessoryModule::unequip(Origin::signed(2), 1));
+			assert_eq!(AccessoryModule::accessory_owner(1), Some(2));
+			assert_eq!(AccessoryModule::kitty_equipment(&(0, AccessoryKind::Hat)), None);
+		});
+	}
+
+	#[test]
+	fn accessories_of_a_burned_kitty_go_back_to_its_owner() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(AccessoryModule::mint(Origin::ROOT, 1, AccessoryKind::Hat));
+			assert_ok!(AccessoryModule::mint(Origin::ROOT, 1, AccessoryKind::Collar));
+			assert_ok!(AccessoryModule::equip(Origin::signed(1), 0, 0));
+			assert_ok!(AccessoryModule::equip(Origin::signed(1), 1, 0));
+
+			AccessoryModule::on_item_burned(&1, 0);
+			assert_eq!(AccessoryModule::accessory_owner(0), Some(1));
+			assert_eq!(AccessoryModule::accessory_owner(1), Some(1));
+			assert_eq!(AccessoryModule::equipped_on(0), None);
+			assert_eq!(AccessoryModule::equipped_on(1), None);
+		});
+	}
+}
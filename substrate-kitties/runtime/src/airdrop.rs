@@ -0,0 +1,352 @@
+//! Airdrops to active market participants.
+//!
+//! Root submits candidate accounts, the accounts meeting the criteria (bid in the
+//! last epochs, own enough kitties) are snapshotted at creation. Rewards are then
+//! paid out in bounded batches at the start of the following blocks.
+//!
+//! Token rewards are paid from the pot account of the module, which has to be
+//! funded beforehand. An airdrop is only created if the pot covers its rewards
+//! on top of the rewards of the airdrops still being paid.
+
+use support::{decl_module, decl_storage, decl_event, ensure};
+use support::traits::{Get, Currency};
+use sr_primitives::traits::{Zero, Saturating, AccountIdConversion};
+use sr_primitives::ModuleId;
+use codec::{Encode, Decode};
+use system::ensure_root;
+use rstd::prelude::*;
+use crate::traits::{OwnedItems, ItemMint, OnBidPlaced};
+
+pub trait Trait: system::Trait {
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+	/// Kitty index
+	type KittyIndex: Copy;
+	/// Interface for counting and minting kitties
+	type Kitties: OwnedItems<Self::AccountId, Self::KittyIndex> + ItemMint<Self::AccountId, Self::KittyIndex>;
+	/// Currency of the token rewards
+	type Currency: Currency<Self::AccountId>;
+	/// Id of the module, token rewards are paid from its pot account
+	type ModuleId: Get<ModuleId>;
+	/// Number of blocks in an activity epoch
+	type EpochLength: Get<Self::BlockNumber>;
+	/// Max number of candidates of an airdrop
+	type MaxRecipients: Get<u32>;
+	/// Max number of rewards paid in a block
+	type MaxPaymentsPerBlock: Get<u32>;
+}
+
+pub type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum AirdropReward<Balance> {
+	/// Tokens for each recipient
+	Tokens(Balance),
+	/// A new gen-0 kitty for each recipient
+	Kitty,
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as Airdrop {
+		NextAirdropId get(fn next_airdrop_id): u32;
+		/// Reward of an airdrop still being paid
+		pub Airdrops get(fn airdrop): map u32 => Option<AirdropReward<BalanceOf<T>>>;
+		/// Recipients of an airdrop not paid yet
+		pub PendingRecipients get(fn pending_recipients): map u32 => Vec<T::AccountId>;
+		/// Airdrops being paid, in order
+		pub ActiveAirdrops get(fn active_airdrops): Vec<u32>;
+		/// Token rewards of the active airdrops not paid yet, kept in the pot
+		pub CommittedRewards get(fn committed_rewards): BalanceOf<T>;
+
+		/// Block of the last auction bid of an account
+		pub LastBidAt get(fn last_bid_at): map T::AccountId => Option<T::BlockNumber>;
+	}
+}
+
+decl_event!(
+	pub enum Event<T> where
+		<T as system::Trait>::AccountId,
+		Balance = BalanceOf<T>,
+	{
+		/// An airdrop is created. (airdrop_id, reward, number of recipients)
+		AirdropCreated(u32, AirdropReward<Balance>, u32),
+		/// A recipient is paid. (airdrop_id, recipient)
+		AirdropPaid(u32, AccountId),
+		/// Paying a recipient failed, e.g. kitties overflow or an emptied pot. (airdrop_id, recipient)
+		AirdropFailed(u32, AccountId),
+		/// All recipients of an airdrop are paid. (airdrop_id)
+		AirdropCompleted(u32),
+	}
+);
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		fn deposit_event() = default;
+
+		/// Id of the module, token rewards are paid from its pot account
+		const ModuleId: ModuleId = T::ModuleId::get();
+
+		/// Snapshot the candidates meeting the criteria and queue the airdrop
+		pub fn create_airdrop(
+			origin,
+			candidates: Vec<T::AccountId>,
+			active_epochs: Option<u32>,
+			min_kitties: u32,
+			reward: AirdropReward<BalanceOf<T>>
+		) {
+			ensure_root(origin)?;
+			ensure!(candidates.len() as u32 <= T::MaxRecipients::get(), "Too many candidates");
+			if let AirdropReward::Tokens(amount) = reward {
+				ensure!(!amount.is_zero(), "Reward should not be zero");
+			}
+
+			let mut recipients: Vec<T::AccountId> = Vec::new();
+			for who in candidates.into_iter() {
+				if !recipients.contains(&who) && Self::meets_criteria(&who, active_epochs, min_kitties) {
+					recipients.push(who);
+				}
+			}
+			ensure!(!recipients.is_empty(), "No candidate meets the criteria");
+
+			let count = recipients.len() as u32;
+			let committed = match reward {
+				AirdropReward::Tokens(amount) => {
+					let total = Self::committed_rewards().saturating_add(amount.saturating_mul(count.into()));
+					ensure!(T::Currency::free_balance(&Self::pot_account()) >= total, "Airdrop pot can not cover the rewards");
+					total
+				},
+				AirdropReward::Kitty => Self::committed_rewards(),
+			};
+
+			let airdrop_id = Self::next_airdrop_id();
+			NextAirdropId::put(airdrop_id.wrapping_add(1));
+
+			<CommittedRewards<T>>::put(committed);
+			<Airdrops<T>>::insert(airdrop_id, reward);
+			<PendingRecipients<T>>::insert(airdrop_id, recipients);
+			ActiveAirdrops::mutate(|ids| ids.push(airdrop_id));
+
+			Self::deposit_event(RawEvent::AirdropCreated(airdrop_id, reward, count));
+		}
+
+		fn on_initialize(_n: T::BlockNumber) {
+			Self::pay_batch(T::MaxPaymentsPerBlock::get());
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// The account token rewards are paid from
+	pub fn pot_account() -> T::AccountId {
+		T::ModuleId::get().into_account()
+	}
+
+	fn meets_criteria(who: &T::AccountId, active_epochs: Option<u32>, min_kitties: u32) -> bool {
+		if let Some(epochs) = active_epochs {
+			let window = T::EpochLength::get().saturating_mul(epochs.into());
+			let since = <system::Module<T>>::block_number().saturating_sub(window);
+			match Self::last_bid_at(who) {
+				Some(at) if at >= since => {},
+				_ => return false,
+			}
+		}
+		min_kitties == 0 || T::Kitties::owned_items(who).len() as u32 >= min_kitties
+	}
+
+	/// Pay at most `limit` recipients, oldest airdrops first
+	fn pay_batch(limit: u32) {
+		let mut active = Self::active_airdrops();
+		if active.is_empty() {
+			return;
+		}
+
+		let pot = Self::pot_account();
+		let mut left = limit;
+		while left > 0 && !active.is_empty() {
+			let airdrop_id = active[0];
+			let mut recipients = Self::pending_recipients(airdrop_id);
+			let reward = Self::airdrop(airdrop_id);
+
+			let take = recipients.len().min(left as usize);
+			for who in recipients.drain(..take) {
+				let paid = match reward {
+					Some(AirdropReward::Tokens(amount)) => {
+						// 失败的奖励不再保留
+						<CommittedRewards<T>>::mutate(|committed| *committed = committed.saturating_sub(amount));
+						T::Currency::transfer(&pot, &who, amount).is_ok()
+					},
+					Some(AirdropReward::Kitty) => T::Kitties::mint_item(&who).is_ok(),
+					None => false,
+				};
+				if paid {
+					Self::deposit_event(RawEvent::AirdropPaid(airdrop_id, who));
+				} else {
+					Self::deposit_event(RawEvent::AirdropFailed(airdrop_id, who));
+				}
+			}
+			left -= take as u32;
+
+			if recipients.is_empty() {
+				<PendingRecipients<T>>::remove(airdrop_id);
+				<Airdrops<T>>::remove(airdrop_id);
+				active.remove(0);
+				Self::deposit_event(RawEvent::AirdropCompleted(airdrop_id));
+			} else {
+				<PendingRecipients<T>>::insert(airdrop_id, recipients);
+			}
+		}
+		ActiveAirdrops::put(active);
+	}
+}
+
+impl<T: Trait> OnBidPlaced<T::AccountId, BalanceOf<T>> for Module<T> {
+	fn on_bid_placed(bidder: &T::AccountId, _price: BalanceOf<T>) {
+		<LastBidAt<T>>::insert(bidder, <system::Module<T>>::block_number());
+	}
+}
+
+/// Tests for Airdrop module
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use primitives::H256;
+	use support::{impl_outer_origin, parameter_types, assert_ok, assert_noop};
+	use sr_primitives::{traits::{BlakeTwo256, IdentityLookup, OnInitialize}, testing::Header};
+	use sr_primitives::weights::Weight;
+	use sr_primitives::Perbill;
+	use crate::mock_items::TestItems;
+
+	impl_outer_origin! {
+		pub enum Origin for Test {}
+	}
+
+	#[derive(Clone, Eq, PartialEq, Debug)]
+	pub struct Test;
+	parameter_types! {
+		pub const BlockHashCount: u64 = 250;
+		pub const MaximumBlockWeight: Weight = 1024;
+		pub const MaximumBlockLength: u32 = 2 * 1024;
+		pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+	}
+	impl system::Trait for Test {
+		type Origin = Origin;
+		type Call = ();
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type Event = ();
+		type BlockHashCount = BlockHashCount;
+		type MaximumBlockWeight = MaximumBlockWeight;
+		type MaximumBlockLength = MaximumBlockLength;
+		type AvailableBlockRatio = AvailableBlockRatio;
+		type Version = ();
+	}
+	parameter_types! {
+		pub const ExistentialDeposit: u64 = 0;
+		pub const TransferFee: u64 = 0;
+		pub const CreationFee: u64 = 0;
+		pub const AirdropModuleId: ModuleId = ModuleId(*b"py/airdr");
+		pub const EpochLength: u64 = 10;
+		pub const MaxRecipients: u32 = 3;
+		pub const MaxPaymentsPerBlock: u32 = 1;
+	}
+	impl balances::Trait for Test {
+		type Balance = u64;
+		type OnFreeBalanceZero = ();
+		type OnNewAccount = ();
+		type Event = ();
+		type TransferPayment = ();
+		type DustRemoval = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type TransferFee = TransferFee;
+		type CreationFee = CreationFee;
+	}
+	impl Trait for Test {
+		type Event = ();
+		type KittyIndex = u32;
+		type Kitties = TestItems;
+		type Currency = balances::Module<Test>;
+		type ModuleId = AirdropModuleId;
+		type EpochLength = EpochLength;
+		type MaxRecipients = MaxRecipients;
+		type MaxPaymentsPerBlock = MaxPaymentsPerBlock;
+	}
+	type Airdrop = Module<Test>;
+	type Balances = balances::Module<Test>;
+	type System = system::Module<Test>;
+
+	fn new_test_ext() -> runtime_io::TestExternalities {
+		system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
+	}
+
+	#[test]
+	fn token_rewards_are_paid_from_the_pot() {
+		new_test_ext().execute_with(|| {
+			let pot = Airdrop::pot_account();
+			assert_noop!(
+				Airdrop::create_airdrop(Origin::ROOT, vec![1, 2], None, 0, AirdropReward::Tokens(50)),
+				"Airdrop pot can not cover the rewards"
+			);
+
+			let _ = Balances::deposit_creating(&pot, 150);
+			assert_ok!(Airdrop::create_airdrop(Origin::ROOT, vec![1, 2, 1], None, 0, AirdropReward::Tokens(50)));
+			assert_eq!(Airdrop::pending_recipients(0), vec![1, 2]);
+			assert_eq!(Airdrop::committed_rewards(), 100);
+			// 已承诺的奖励不能再用于新的空投
+			assert_noop!(
+				Airdrop::create_airdrop(Origin::ROOT, vec![3], None, 0, AirdropReward::Tokens(60)),
+				"Airdrop pot can not cover the rewards"
+			);
+
+			Airdrop::on_initialize(1);
+			assert_eq!(Balances::free_balance(&1), 50);
+			assert_eq!(Airdrop::committed_rewards(), 50);
+			Airdrop::on_initialize(2);
+			assert_eq!(Balances::free_balance(&2), 50);
+			assert_eq!(Balances::free_balance(&pot), 50);
+			assert_eq!(Airdrop::committed_rewards(), 0);
+			assert!(Airdrop::active_airdrops().is_empty());
+			assert_eq!(Airdrop::airdrop(0), None);
+		});
+	}
+
+	#[test]
+	fn recipients_are_snapshotted_by_the_criteria() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(5);
+			<Airdrop as OnBidPlaced<u64, u64>>::on_bid_placed(&1, 10);
+			System::set_block_number(25);
+			<Airdrop as OnBidPlaced<u64, u64>>::on_bid_placed(&2, 10);
+			assert_ok!(TestItems::mint_item(&3));
+			assert_ok!(TestItems::mint_item(&2));
+
+			assert_noop!(
+				Airdrop::create_airdrop(Origin::ROOT, vec![1, 2, 3, 4], None, 0, AirdropReward::Kitty),
+				"Too many candidates"
+			);
+			assert_noop!(
+				Airdrop::create_airdrop(Origin::ROOT, vec![1, 4], Some(1), 0, AirdropReward::Kitty),
+				"No candidate meets the criteria"
+			);
+
+			// 最近一个周期内出价且至少有一只猫
+			assert_ok!(Airdrop::create_airdrop(Origin::ROOT, vec![1, 2, 3], Some(1), 1, AirdropReward::Kitty));
+			assert_eq!(Airdrop::pending_recipients(0), vec![2]);
+			assert_ok!(Airdrop::create_airdrop(Origin::ROOT, vec![1, 2, 3], Some(2), 0, AirdropReward::Kitty));
+			assert_eq!(Airdrop::pending_recipients(1), vec![1, 2]);
+
+			Airdrop::on_initialize(26);
+			Airdrop::on_initialize(27);
+			Airdrop::on_initialize(28);
+			assert_eq!(TestItems::owned_items(&2), vec![1, 2, 4]);
+			assert_eq!(TestItems::owned_items(&1), vec![3]);
+			assert!(Airdrop::active_airdrops().is_empty());
+			assert_eq!(Airdrop::committed_rewards(), 0);
+		});
+	}
+}
@@ -0,0 +1,440 @@
+//! Sale statistics per item class, e.g. floor price and volume of gen-0 kitties.
+//!
+//! Fed by the `OnItemSold` hook of the auction and order book modules. Stats are
+//! kept per epoch, the stats of the previous epoch are kept for comparison.
+//!
+//! The offchain worker of an authority derives a suggested floor price per class
+//! from the stats and submits it signed by its aura key. Other modules can use
+//! the suggested floor as the minimum begin price of an item.
+
+use support::{decl_module, decl_storage, decl_event, ensure, print, Parameter};
+use sr_primitives::weights::SimpleDispatchInfo;
+use support::traits::{Get, Currency};
+use sr_primitives::{RuntimeAppPublic, transaction_validity::{
+	TransactionValidity, TransactionLongevity, ValidTransaction, InvalidTransaction,
+}};
+use sr_primitives::traits::{Member, Zero, Saturating};
+use codec::{Encode, Decode};
+use system::ensure_none;
+use system::offchain::SubmitUnsignedTransaction;
+use rstd::prelude::*;
+use crate::traits::{OnItemSold, ClassifyItem, SuggestedPrice};
+
+pub trait Trait: aura::Trait {
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+	/// A dispatchable call type.
+	type Call: From<Call<Self>>;
+	/// A transaction submitter.
+	type SubmitTransaction: SubmitUnsignedTransaction<Self, <Self as Trait>::Call>;
+	/// Item Id
+	type ItemId: Parameter + Member + Copy;
+	/// Class of items, stats are tracked per class
+	type ItemClass: Parameter + Member + Copy;
+	/// Tell the class of an item
+	type Classifier: ClassifyItem<Self::ItemId, Self::ItemClass>;
+	/// Currency the items are paid with
+	type Currency: Currency<Self::AccountId>;
+	/// Number of blocks in an epoch
+	type EpochLength: Get<Self::BlockNumber>;
+	/// Number of blocks between two suggested floor submissions
+	type OracleInterval: Get<Self::BlockNumber>;
+}
+
+pub type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+type SignatureOf<T> = <<T as aura::Trait>::AuthorityId as RuntimeAppPublic>::Signature;
+
+#[derive(Encode, Decode, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct ClassStats<Balance, BlockNumber> {
+	pub epoch: BlockNumber, // 统计周期序号
+	pub floor_price: Option<Balance>, // 本周期最低成交价
+	pub last_price: Option<Balance>, // 最近成交价
+	pub volume: Balance, // 本周期成交额
+	pub sales: u32, // 本周期成交数
+	pub total_volume: Balance, // 累计成交额
+	pub total_sales: u32, // 累计成交数
+}
+
+pub type ClassStatsOf<T> = ClassStats<BalanceOf<T>, <T as system::Trait>::BlockNumber>;
+
+decl_storage! {
+	trait Store for Module<T: Trait> as Analytics {
+		/// Stats of the epoch in which the class was last sold
+		pub CurrentStats get(fn current_stats): map T::ItemClass => ClassStatsOf<T>;
+		/// Stats of the epoch before
+		pub PreviousStats get(fn previous_stats): map T::ItemClass => Option<ClassStatsOf<T>>;
+		/// Classes sold at least once
+		pub KnownClasses get(fn known_classes): Vec<T::ItemClass>;
+
+		/// Suggested floor price of a class, submitted by the oracle
+		pub SuggestedFloors get(fn suggested_floor): map T::ItemClass => Option<BalanceOf<T>>;
+		/// Block the suggested floors were computed at
+		pub FloorsUpdatedAt get(fn floors_updated_at): T::BlockNumber;
+	}
+}
+
+decl_event!(
+	pub enum Event<T> where
+		<T as system::Trait>::BlockNumber,
+	{
+		/// Suggested floors computed at the block are submitted. (block_number, number of classes)
+		SuggestedFloorsUpdated(BlockNumber, u32),
+	}
+);
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		fn deposit_event() = default;
+
+		// Called by offchain worker
+		#[weight = SimpleDispatchInfo::FixedOperational(10_000)]
+		fn submit_suggested_floors(
+			origin,
+			floors: Vec<(T::ItemClass, BalanceOf<T>)>,
+			at: T::BlockNumber,
+			signature: SignatureOf<T>
+		) {
+			ensure_none(origin)?;
+			ensure!(at > Self::floors_updated_at(), "Suggested floors are stale");
+
+			let key = Self::authority_id().ok_or("Non existent public key.")?;
+			let signature_valid = (&floors, at).using_encoded(|encoded| key.verify(&encoded, &signature));
+			ensure!(signature_valid, "Invalid signature.");
+
+			for (class, floor) in floors.iter() {
+				<SuggestedFloors<T>>::insert(class, floor);
+			}
+			<FloorsUpdatedAt<T>>::put(at);
+			Self::deposit_event(RawEvent::SuggestedFloorsUpdated(at, floors.len() as u32));
+		}
+
+		fn offchain_worker(now: T::BlockNumber) {
+			if runtime_io::is_validator() {
+				Self::offchain(now);
+			}
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	fn current_epoch() -> T::BlockNumber {
+		let length = T::EpochLength::get();
+		if length.is_zero() {
+			return Zero::zero();
+		}
+		<system::Module<T>>::block_number() / length
+	}
+
+	/// Stats of the class in the current epoch, with the epoch figures reset if no sale happened yet
+	pub fn class_stats(class: T::ItemClass) -> ClassStatsOf<T> {
+		let epoch = Self::current_epoch();
+		let stats = Self::current_stats(class);
+		if stats.epoch == epoch {
+			return stats;
+		}
+		ClassStats {
+			epoch: epoch,
+			floor_price: None,
+			last_price: stats.last_price,
+			volume: Zero::zero(),
+			sales: 0,
+			total_volume: stats.total_volume,
+			total_sales: stats.total_sales,
+		}
+	}
+
+	fn record_sale(class: T::ItemClass, price: BalanceOf<T>) {
+		let epoch = Self::current_epoch();
+		let previous = Self::current_stats(class);
+		if previous.epoch != epoch && previous.total_sales > 0 {
+			<PreviousStats<T>>::insert(class, previous);
+		}
+		if previous.total_sales == 0 {
+			<KnownClasses<T>>::mutate(|classes| classes.push(class));
+		}
+
+		let mut stats = Self::class_stats(class);
+		stats.floor_price = Some(stats.floor_price.map_or(price, |floor| floor.min(price)));
+		stats.last_price = Some(price);
+		stats.volume = stats.volume.saturating_add(price);
+		stats.sales = stats.sales.saturating_add(1);
+		stats.total_volume = stats.total_volume.saturating_add(price);
+		stats.total_sales = stats.total_sales.saturating_add(1);
+		<CurrentStats<T>>::insert(class, stats);
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// Returns own authority identifier iff it is part of the current authority set.
+	fn authority_id() -> Option<T::AuthorityId> {
+		let local_keys = T::AuthorityId::all();
+		<aura::Module<T>>::authorities().into_iter().find(|authority| local_keys.contains(authority))
+	}
+
+	/// Suggested floor of a class: the lower floor price of the current and the previous epoch
+	pub fn compute_suggested_floor(class: T::ItemClass) -> Option<BalanceOf<T>> {
+		let current = Self::class_stats(class).floor_price;
+		let previous = Self::previous_stats(class).and_then(|stats| stats.floor_price);
+		match (current, previous) {
+			(Some(c), Some(p)) => Some(c.min(p)),
+			(c, p) => c.or(p),
+		}
+	}
+
+	/// only run by current validator
+	fn offchain(now: T::BlockNumber) {
+		let interval = T::OracleInterval::get();
+		if interval.is_zero() || !(now % interval).is_zero() {
+			return;
+		}
+		let key = match Self::authority_id() {
+			Some(key) => key,
+			None => return,
+		};
+
+		let floors: Vec<(T::ItemClass, BalanceOf<T>)> = Self::known_classes().into_iter()
+			.filter_map(|class| Self::compute_suggested_floor(class).map(|floor| (class, floor)))
+			.collect();
+		if floors.is_empty() {
+			return;
+		}
+
+		let signature = match (&floors, now).using_encoded(|encoded| key.sign(&encoded)) {
+			Some(signature) => signature,
+			None => return print("Offchain error: signing failed!"),
+		};
+		let call = Call::<T>::submit_suggested_floors(floors, now, signature);
+		if T::SubmitTransaction::submit_unsigned(call).is_err() {
+			print("Offchain error: submitting transaction failed!");
+		}
+	}
+}
+
+impl<T: Trait> SuggestedPrice<T::ItemId, BalanceOf<T>> for Module<T> {
+	fn suggested_price(item_id: T::ItemId) -> Option<BalanceOf<T>> {
+		Self::suggested_floor(T::Classifier::class_of(item_id))
+	}
+}
+
+impl<T: Trait> support::unsigned::ValidateUnsigned for Module<T> {
+	type Call = Call<T>;
+
+	fn validate_unsigned(call: &Self::Call) -> TransactionValidity {
+		if let Call::submit_suggested_floors(floors, at, signature) = call {
+			if *at <= Self::floors_updated_at() {
+				return InvalidTransaction::Stale.into();
+			}
+			let authority_id = match Self::authority_id() {
+				Some(id) => id,
+				None => return InvalidTransaction::BadProof.into(),
+			};
+
+			// check signature (this is expensive so we do it last).
+			let signature_valid = (floors, at).using_encoded(|encoded| authority_id.verify(&encoded, &signature));
+			if !signature_valid {
+				return InvalidTransaction::BadProof.into();
+			}
+
+			Ok(ValidTransaction {
+				priority: 0,
+				requires: vec![],
+				provides: vec![(at, authority_id).encode()],
+				longevity: TransactionLongevity::max_value(),
+				propagate: true,
+			})
+		} else {
+			InvalidTransaction::Call.into()
+		}
+	}
+}
+
+impl<T: Trait> OnItemSold<T::AccountId, T::ItemId, BalanceOf<T>> for Module<T> {
+	fn on_item_sold(_seller: &T::AccountId, _buyer: &T::AccountId, item_id: T::ItemId, price: BalanceOf<T>) {
+		Self::record_sale(T::Classifier::class_of(item_id), price);
+	}
+}
+
+/// Runtime api of this module
+pub mod api {
+	use codec::Codec;
+
+	client::decl_runtime_apis! {
+		/// The api to query sale stats of item classes.
+		pub trait AnalyticsApi<ItemClass, Stats, Balance> where
+			ItemClass: Codec,
+			Stats: Codec,
+			Balance: Codec,
+		{
+			/// Stats of the class in the current epoch.
+			fn class_stats(class: ItemClass) -> Stats;
+			/// Stats of the class in the last epoch it was sold before the current one.
+			fn previous_class_stats(class: ItemClass) -> Option<Stats>;
+			/// Suggested floor price of the class submitted by the oracle.
+			fn suggested_floor(class: ItemClass) -> Option<Balance>;
+		}
+	}
+}
+
+/// Tests for Analytics module
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use primitives::H256;
+	use support::{impl_outer_origin, parameter_types, assert_ok, assert_noop};
+	use sr_primitives::{traits::{BlakeTwo256, IdentityLookup}, testing::{Header, TestXt, UintAuthorityId}};
+	use sr_primitives::weights::Weight;
+	use sr_primitives::Perbill;
+	use support::unsigned::ValidateUnsigned;
+
+	impl_outer_origin! {
+		pub enum Origin for Test {}
+	}
+
+	/// Items with an even id are of class 0, the others of class 1
+	pub struct TestClassifier;
+	impl ClassifyItem<u32, u32> for TestClassifier {
+		fn class_of(item_id: u32) -> u32 {
+			item_id % 2
+		}
+	}
+
+	#[derive(Clone, Eq, PartialEq, Debug)]
+	pub struct Test;
+	parameter_types! {
+		pub const BlockHashCount: u64 = 250;
+		pub const MaximumBlockWeight: Weight = 1024;
+		pub const MaximumBlockLength: u32 = 2 * 1024;
+		pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+	}
+	impl system::Trait for Test {
+		type Origin = Origin;
+		type Call = ();
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type Event = ();
+		type BlockHashCount = BlockHashCount;
+		type MaximumBlockWeight = MaximumBlockWeight;
+		type MaximumBlockLength = MaximumBlockLength;
+		type AvailableBlockRatio = AvailableBlockRatio;
+		type Version = ();
+	}
+	parameter_types! {
+		pub const MinimumPeriod: u64 = 5;
+		pub const ExistentialDeposit: u64 = 0;
+		pub const TransferFee: u64 = 0;
+		pub const CreationFee: u64 = 0;
+		pub const EpochLength: u64 = 10;
+		pub const OracleInterval: u64 = 5;
+	}
+	impl timestamp::Trait for Test {
+		type Moment = u64;
+		type OnTimestampSet = aura::Module<Self>;
+		type MinimumPeriod = MinimumPeriod;
+	}
+	impl aura::Trait for Test {
+		type AuthorityId = UintAuthorityId;
+	}
+	impl balances::Trait for Test {
+		type Balance = u64;
+		type OnFreeBalanceZero = ();
+		type OnNewAccount = ();
+		type Event = ();
+		type TransferPayment = ();
+		type DustRemoval = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type TransferFee = TransferFee;
+		type CreationFee = CreationFee;
+	}
+	impl Trait for Test {
+		type Event = ();
+		type Call = Call<Test>;
+		type SubmitTransaction = system::offchain::TransactionSubmitter<(), Call<Test>, TestXt<Call<Test>, ()>>;
+		type ItemId = u32;
+		type ItemClass = u32;
+		type Classifier = TestClassifier;
+		type Currency = balances::Module<Test>;
+		type EpochLength = EpochLength;
+		type OracleInterval = OracleInterval;
+	}
+	type Analytics = Module<Test>;
+	type System = system::Module<Test>;
+
+	fn new_test_ext() -> runtime_io::TestExternalities {
+		let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+		aura::GenesisConfig::<Test> {
+			authorities: vec![UintAuthorityId(1)],
+		}.assimilate_storage(&mut t).unwrap();
+		t.into()
+	}
+
+	#[test]
+	fn sales_are_tracked_per_class_and_epoch() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			Analytics::on_item_sold(&1, &2, 0, 100);
+			Analytics::on_item_sold(&1, &2, 2, 80);
+			Analytics::on_item_sold(&1, &2, 1, 50);
+
+			assert_eq!(Analytics::known_classes(), vec![0, 1]);
+			assert_eq!(Analytics::class_stats(0), ClassStats {
+				epoch: 0,
+				floor_price: Some(80),
+				last_price: Some(80),
+				volume: 180,
+				sales: 2,
+				total_volume: 180,
+				total_sales: 2,
+			});
+			assert_eq!(Analytics::compute_suggested_floor(1), Some(50));
+
+			// 新周期只保留累计数据
+			System::set_block_number(12);
+			assert_eq!(Analytics::class_stats(0), ClassStats {
+				epoch: 1,
+				floor_price: None,
+				last_price: Some(80),
+				volume: 0,
+				sales: 0,
+				total_volume: 180,
+				total_sales: 2,
+			});
+
+			Analytics::on_item_sold(&1, &2, 4, 120);
+			assert_eq!(Analytics::previous_stats(0).map(|stats| stats.floor_price), Some(Some(80)));
+			assert_eq!(Analytics::class_stats(0).floor_price, Some(120));
+			assert_eq!(Analytics::class_stats(0).total_sales, 3);
+			assert_eq!(Analytics::compute_suggested_floor(0), Some(80));
+			assert_eq!(Analytics::known_classes(), vec![0, 1]);
+		});
+	}
+
+	#[test]
+	fn suggested_floors_are_signed_by_an_authority() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(5);
+			let floors = vec![(0, 80)];
+			let signature = UintAuthorityId(1).sign(&(&floors, 5u64).encode()).unwrap();
+			let call = Call::submit_suggested_floors(floors.clone(), 5, signature);
+
+			// 本节点没有验证人密钥
+			assert_eq!(Analytics::validate_unsigned(&call), InvalidTransaction::BadProof.into());
+			assert_noop!(Analytics::submit_suggested_floors(Origin::NONE, floors.clone(), 5, signature), "Non existent public key.");
+
+			UintAuthorityId::set_all_keys(vec![1u64]);
+			assert!(Analytics::validate_unsigned(&call).is_ok());
+			assert_noop!(Analytics::submit_suggested_floors(Origin::NONE, floors.clone(), 5, signature + 1), "Invalid signature.");
+			assert_ok!(Analytics::submit_suggested_floors(Origin::NONE, floors.clone(), 5, signature));
+			assert_eq!(Analytics::suggested_price(2), Some(80));
+			assert_eq!(Analytics::suggested_price(1), None);
+
+			assert_eq!(Analytics::validate_unsigned(&call), InvalidTransaction::Stale.into());
+			assert_noop!(Analytics::submit_suggested_floors(Origin::NONE, floors, 5, signature), "Suggested floors are stale");
+		});
+	}
+}
@@ -0,0 +1,31 @@
+//! Runtime api for querying auctions from outside of the runtime.
+
+use codec::Codec;
+use rstd::vec::Vec;
+
+client::decl_runtime_apis! {
+	/// The api to query auctions and their settlement.
+	pub trait AuctionApi<AuctionId, AccountId, Receipt, BlockNumber, Balance, Dashboard> where
+		AuctionId: Codec,
+		AccountId: Codec,
+		Receipt: Codec,
+		BlockNumber: Codec,
+		Balance: Codec,
+		Dashboard: Codec,
+	{
+		/// Get the settlement receipt of an auction, if it is settled.
+		fn settlement_receipt(auction_id: AuctionId) -> Option<Receipt>;
+
+		/// Get the escrow account which the payments of an auction go through.
+		fn escrow_account(auction_id: AuctionId) -> AccountId;
+
+		/// Get the price snapshots (block, highest bid) of an auction, oldest first.
+		fn price_chart(auction_id: AuctionId) -> Vec<(BlockNumber, Balance)>;
+
+		/// Get the auctions of an owner by status, its pending proceeds, held deposits and locked items.
+		fn owner_dashboard(owner: AccountId) -> Dashboard;
+
+		/// Get the auction of an owner by its sequence number among the owner's auctions.
+		fn auction_by_owner_seq(owner: AccountId, seq: u32) -> Option<AuctionId>;
+	}
+}
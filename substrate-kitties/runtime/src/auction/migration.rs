@@ -0,0 +1,180 @@
+//! Storage migrations of the auction module.
+//!
+//! Migrations run in bounded steps, at most `MIGRATION_BATCH` auctions in one block, walking the
+//! auctions by creation index with `MigrationCursor`. The module is paused until they are done.
+
+use codec::{Encode, Decode};
+use rstd::prelude::*;
+use sr_primitives::traits::{One, Saturating};
+use support::{StorageValue, StorageMap, StorageDoubleMap, storage::unhashed};
+use support::storage::generator::StorageMap as StorageMapGenerator;
+use support::storage::StorageHasher;
+use super::{
+	Module, Trait, Instance, Auction, AuctionStatus, LotPricing, BalanceOf, GenerateAuctionId,
+	PalletStorageVersion, MigrationCursor, Auctions, AuctionItems, AuctionsByIndex, AuctionCreatedAt,
+	OwnerAuctions, OwnerSeqs, OwnerAuctionCount, BidPlacedAt,
+};
+use crate::traits::ItemTransfer;
+
+/// Version of the storage layout, written at genesis and bumped with every migration.
+pub const CURRENT_VERSION: u32 = 2;
+
+/// Max number of auctions migrated in one block.
+pub const MIGRATION_BATCH: u32 = 50;
+
+/// Layout of an auction before the version was tracked.
+#[derive(Encode, Decode)]
+struct AuctionV0<AccountId, AuctionId, ItemId, Balance, Moment> {
+	id: AuctionId,
+	item: Option<ItemId>,
+	owner: AccountId,
+	start_at: Option<Moment>,
+	stop_at: Option<Moment>,
+	wait_period: Option<Moment>,
+	begin_price: Balance,
+	upper_bound_price: Option<Balance>,
+	minimum_step: Balance,
+	latest_participate: Option<(AccountId, Moment)>,
+	status: AuctionStatus,
+}
+
+type AuctionV0Of<T, I> = AuctionV0<
+	<T as system::Trait>::AccountId,
+	<T as Trait<I>>::AuctionId,
+	<T as Trait<I>>::LegacyItemId,
+	BalanceOf<T, I>,
+	<T as timestamp::Trait>::Moment,
+>;
+
+/// Run one bounded step of the pending migrations, returns whether the storage is still migrating
+/// in this block. The version is bumped once a migration has walked all auctions.
+pub fn perform_migrations<T: Trait<I>, I: Instance>() -> bool {
+	let version = <PalletStorageVersion<I>>::get();
+	if version >= CURRENT_VERSION {
+		return false;
+	}
+
+	let next_index = <Module<T, I>>::next_auction_id();
+	let start = <MigrationCursor<T, I>>::get();
+	let mut end = start;
+	let mut count = 0;
+	while end < next_index && count < MIGRATION_BATCH {
+		match version {
+			0 => migrate_to_v1::<T, I>(end),
+			1 => migrate_to_v2::<T, I>(end),
+			_ => {},
+		}
+		end = end.saturating_add(One::one());
+		count += 1;
+	}
+
+	if end >= next_index {
+		<MigrationCursor<T, I>>::kill();
+		<PalletStorageVersion<I>>::put(version + 1);
+	} else {
+		<MigrationCursor<T, I>>::put(end);
+	}
+	true
+}
+
+/// Auctions created before the version was tracked get the fields added since with their defaults,
+/// the item mapping is keyed by the new item id, and the auction is indexed by its creation index.
+fn migrate_to_v1<T: Trait<I>, I: Instance>(index: T::AuctionIndex) {
+	let auction_id = match T::AuctionIds::legacy_auction_id(index) {
+		Some(id) => id,
+		None => return,
+	};
+	let old: AuctionV0Of<T, I> = match unhashed::get(&<Auctions<T, I>>::hashed_key_for(auction_id)) {
+		Some(auction) => auction,
+		None => return,
+	};
+
+	// 旧的物品映射以旧的物品id为键
+	let item = old.item.map(|legacy_item| {
+		let key = legacy_map_key::<AuctionItems<T, I>, T::ItemId, T::AuctionId, _>(&legacy_item);
+		let mapped = unhashed::get::<T::AuctionId>(&key);
+		unhashed::kill(&key);
+		let item: T::ItemId = legacy_item.into();
+		if let Some(mapped) = mapped {
+			<AuctionItems<T, I>>::insert(item, mapped);
+		}
+		item
+	});
+
+	let seq = <Module<T, I>>::owner_auction_count(&old.owner);
+	<OwnerAuctions<T, I>>::insert(&old.owner, seq, auction_id);
+	<OwnerSeqs<T, I>>::insert(auction_id, seq);
+	<OwnerAuctionCount<T, I>>::insert(&old.owner, seq.saturating_add(1));
+	// 旧拍卖的存续期从升级时开始计算
+	<AuctionCreatedAt<T, I>>::insert(auction_id, <aura::Module<T>>::last());
+	<AuctionsByIndex<T, I>>::insert(index, auction_id);
+	<Auctions<T, I>>::insert(auction_id, Auction {
+		id: old.id,
+		title: Vec::new(),
+		description_hash: None,
+		media_cid: None,
+		item: item,
+		lot: Vec::new(),
+		lot_pricing: LotPricing::Uniform,
+		owner: old.owner,
+		start_at: old.start_at,
+		stop_at: old.stop_at,
+		wait_period: old.wait_period,
+		begin_price: old.begin_price,
+		upper_bound_price: old.upper_bound_price,
+		minimum_step: old.minimum_step,
+		step_tiers: Vec::new(),
+		latest_participate: old.latest_participate,
+		invite_only: false,
+		terms_hash: None,
+		payout_split: Vec::new(),
+		charity: None,
+		relist_policy: None,
+		relist_count: 0,
+		raffle: None,
+		round: None,
+		claim_settlement: false,
+		inspection_period: None,
+		vest_proceeds: false,
+		installment_plan: None,
+		collateral_ratio: None,
+		bid_denomination: None,
+		close_jitter: false,
+		status: old.status,
+	});
+}
+
+/// Items listed before the item modules knew about holds are reserved for their auctions,
+/// and bid times stored without the tie-break sequence get sequence 0.
+fn migrate_to_v2<T: Trait<I>, I: Instance>(index: T::AuctionIndex) {
+	let auction = match <Module<T, I>>::auction_by_index(index).and_then(|id| <Module<T, I>>::auctions(id)) {
+		Some(auction) => auction,
+		None => return,
+	};
+	for item in auction.item.iter().chain(auction.lot.iter()) {
+		if <Module<T, I>>::auction_items(item) == Some(auction.id) {
+			// 持有者已变更的拍品无法占用，留待结算时失败
+			let _ = T::AuctionTransfer::reserve_item(&auction.owner, *item);
+		}
+	}
+	for bidder in <Module<T, I>>::auction_participants(auction.id).unwrap_or_default() {
+		let key = <BidPlacedAt<T, I>>::hashed_key_for(auction.id, &bidder);
+		if unhashed::get::<(T::Moment, u32)>(&key).is_none() {
+			if let Some(placed_at) = unhashed::get::<T::Moment>(&key) {
+				<BidPlacedAt<T, I>>::insert(auction.id, &bidder, (placed_at, 0));
+			}
+		}
+	}
+}
+
+/// Storage key of a map entry under a key type the map no longer uses.
+fn legacy_map_key<S, K, V, L>(legacy_key: &L) -> Vec<u8> where
+	S: StorageMapGenerator<K, V>,
+	K: Encode,
+	V: Encode + Decode,
+	L: Encode,
+{
+	let mut final_key = S::prefix().to_vec();
+	legacy_key.encode_to(&mut final_key);
+	S::Hasher::hash(&final_key).as_ref().to_vec()
+}
@@ -1,5 +1,5 @@
-//! Test utilities
-#![cfg(test)]
+//! Test utilities, also used by the simulation harness
+#![cfg(any(test, feature = "simulation"))]
 
 use std::cell::RefCell;
 use {runtime_io, system};
@@ -8,19 +8,19 @@ use primitives::H256;
 use aura_primitives::ed25519::AuthorityId;
 use sr_primitives::{
 	Perbill,
-	testing::{Header, TestXt},
+	testing::{Header, TestXt, TestSignature},
 	traits::{
 		BlakeTwo256, IdentityLookup
 	},
 };
 use support::{
-	impl_outer_origin, impl_outer_dispatch, parameter_types,
+	impl_outer_origin, impl_outer_dispatch, parameter_types, StorageValue,
 	// traits::{Currency},
 	dispatch::Result,
 };
 
 use super::*;
-use crate::traits::ItemTransfer;
+use crate::traits::{ContractCall, ItemTransfer, ItemTransferError};
 
 /// The AccountId alias in this test module.
 pub type AccountId = u64;
@@ -35,12 +35,104 @@ impl_outer_dispatch! {
 	pub enum Call for Test where origin: Origin {
 		balances::Balances,
 		auction::Auctions,
+		auction::CommunityAuctions,
 		aura::Aura,
 	}
 }
 
 thread_local! {
 	pub static VALIDATORS: RefCell<Option<Vec<u64>>> = RefCell::new(Some(vec![1, 2, 3]));
+	pub static EXISTENTIAL_DEPOSIT: RefCell<u64> = RefCell::new(0);
+	pub static OFFCHAIN_TIMING: RefCell<bool> = RefCell::new(true);
+	pub static MAX_BIDS_PER_BLOCK: RefCell<u32> = RefCell::new(0);
+	pub static MAX_AUCTION_BIDS_PER_BLOCK: RefCell<u32> = RefCell::new(0);
+	pub static MAX_AUTO_REFUNDS: RefCell<u32> = RefCell::new(100);
+	pub static CREATION_DEPOSIT: RefCell<Balance> = RefCell::new(0);
+	pub static CHALLENGE_BOND: RefCell<Balance> = RefCell::new(0);
+	pub static CONTRACT_CALLS: RefCell<Vec<(AccountId, AccountId, u64, Vec<u8>)>> = RefCell::new(vec![]);
+	pub static TIE_BREAK: RefCell<super::TieBreak> = RefCell::new(super::TieBreak::FirstWins);
+	pub static FROZEN_ITEMS: RefCell<Vec<ItemId>> = RefCell::new(vec![]);
+	pub static RESERVED_ITEMS: RefCell<Vec<ItemId>> = RefCell::new(vec![]);
+}
+
+/// Existential deposit which tests can raise
+pub struct ExistentialDeposit;
+impl Get<u64> for ExistentialDeposit {
+	fn get() -> u64 {
+		EXISTENTIAL_DEPOSIT.with(|v| *v.borrow())
+	}
+}
+
+/// Offchain timing switch which tests can turn off
+pub struct OffchainTiming;
+impl Get<bool> for OffchainTiming {
+	fn get() -> bool {
+		OFFCHAIN_TIMING.with(|v| *v.borrow())
+	}
+}
+
+/// Per-block bid limit which tests can set, zero by default
+pub struct MaxBidsPerBlock;
+impl Get<u32> for MaxBidsPerBlock {
+	fn get() -> u32 {
+		MAX_BIDS_PER_BLOCK.with(|v| *v.borrow())
+	}
+}
+
+/// Per-block bid cap of an auction which tests can set, zero by default
+pub struct MaxAuctionBidsPerBlock;
+impl Get<u32> for MaxAuctionBidsPerBlock {
+	fn get() -> u32 {
+		MAX_AUCTION_BIDS_PER_BLOCK.with(|v| *v.borrow())
+	}
+}
+
+/// Creation deposit which tests can raise, zero by default
+pub struct CreationDeposit;
+impl Get<Balance> for CreationDeposit {
+	fn get() -> Balance {
+		CREATION_DEPOSIT.with(|v| *v.borrow())
+	}
+}
+
+/// Challenge bond which tests can raise, zero by default
+pub struct ChallengeBond;
+impl Get<Balance> for ChallengeBond {
+	fn get() -> Balance {
+		CHALLENGE_BOND.with(|v| *v.borrow())
+	}
+}
+
+/// Tie-break rule of multi-unit lots which tests can switch, first wins by default
+pub struct TieBreak;
+impl Get<super::TieBreak> for TieBreak {
+	fn get() -> super::TieBreak {
+		TIE_BREAK.with(|v| *v.borrow())
+	}
+}
+
+/// Bidder count above which losers claim their refunds, tests can lower it
+pub struct MaxAutoRefunds;
+impl Get<u32> for MaxAutoRefunds {
+	fn get() -> u32 {
+		MAX_AUTO_REFUNDS.with(|v| *v.borrow())
+	}
+}
+
+/// Contracts which record their calls, a contract 0 always fails
+pub struct MockContracts;
+impl ContractCall<AccountId> for MockContracts {
+	fn is_supported() -> bool {
+		true
+	}
+
+	fn call_contract(caller: &AccountId, contract: &AccountId, gas_limit: u64, input: Vec<u8>) -> Result {
+		if *contract == 0 {
+			return Err("Contract trapped");
+		}
+		CONTRACT_CALLS.with(|v| v.borrow_mut().push((*caller, *contract, gas_limit, input)));
+		Ok(())
+	}
 }
 
 /// An extrinsic type used for tests.
@@ -50,12 +142,40 @@ type SubmitTransaction = system::offchain::TransactionSubmitter<(), Call, Extrin
 /// struct for item transfer 
 pub struct SomeItemModule;
 impl ItemTransfer<AccountId, ItemId> for SomeItemModule {
-	fn is_item_owner(_who: &AccountId, _item_id: ItemId) -> bool {
-		true
+	fn is_item_owner(who: &AccountId, _item_id: ItemId) -> bool {
+		*who == 1
+	}
+	fn transfer_item(source: &AccountId, _dest: &AccountId, item_id: ItemId) -> rstd::result::Result<(), ItemTransferError> {
+		Self::can_transfer(source, item_id)
+	}
+	fn transfer_reserved_item(source: &AccountId, _dest: &AccountId, item_id: ItemId) -> rstd::result::Result<(), ItemTransferError> {
+		Self::can_transfer_reserved(source, item_id)?;
+		Self::unreserve_item(item_id);
+		Ok(())
 	}
-	fn transfer_item(_source: &AccountId, _dest: &AccountId, _item_id: ItemId) -> Result {
+	fn can_transfer(from: &AccountId, item_id: ItemId) -> rstd::result::Result<(), ItemTransferError> {
+		ensure!(*from == 1, ItemTransferError::NotOwner);
+		ensure!(!FROZEN_ITEMS.with(|v| v.borrow().contains(&item_id)), ItemTransferError::Frozen);
+		ensure!(!RESERVED_ITEMS.with(|v| v.borrow().contains(&item_id)), ItemTransferError::Frozen);
 		Ok(())
 	}
+	fn can_transfer_reserved(from: &AccountId, item_id: ItemId) -> rstd::result::Result<(), ItemTransferError> {
+		ensure!(*from == 1, ItemTransferError::NotOwner);
+		ensure!(!FROZEN_ITEMS.with(|v| v.borrow().contains(&item_id)), ItemTransferError::Frozen);
+		ensure!(RESERVED_ITEMS.with(|v| v.borrow().contains(&item_id)), ItemTransferError::Unknown);
+		Ok(())
+	}
+	fn reserve_item(who: &AccountId, item_id: ItemId) -> rstd::result::Result<(), ItemTransferError> {
+		ensure!(*who == 1, ItemTransferError::NotOwner);
+		RESERVED_ITEMS.with(|v| {
+			ensure!(!v.borrow().contains(&item_id), ItemTransferError::Frozen);
+			v.borrow_mut().push(item_id);
+			Ok(())
+		})
+	}
+	fn unreserve_item(item_id: ItemId) {
+		RESERVED_ITEMS.with(|v| v.borrow_mut().retain(|id| *id != item_id));
+	}
 }
 
 // For testing the module, we construct most of a mock runtime. This means
@@ -113,32 +233,180 @@ impl balances::Trait for Test {
 	type Event = ();
 	type TransferPayment = ();
 	type DustRemoval = ();
-	type ExistentialDeposit = ();
+	type ExistentialDeposit = ExistentialDeposit;
 	type TransferFee = TransferFee;
 	type CreationFee = CreationFee;
 }
 
+parameter_types! {
+	pub const RoundLength: u64 = 1000;
+	pub const MaxDueBatchSize: u32 = 10;
+	pub const MaxSettlementsPerBlock: u32 = 2;
+	pub const MaxSettlementQueueLength: u32 = 4;
+	pub const ClaimPeriod: u64 = 1000;
+	pub const RejectionPenalty: Permill = Permill::from_percent(10);
+	pub const RefundPeriod: u64 = 1000;
+	pub const CommentDeposit: Balance = 5;
+	pub const SponsoredBidFee: Balance = 3;
+	pub const MinSponsoredBid: Balance = 100;
+	pub const MinimumBeginPrice: Balance = 10;
+	pub const MinimumBidAmount: Balance = 10;
+	pub const StaleAuctionTimeout: u64 = 1000;
+	pub const StalePurgeBounty: Permill = Permill::from_percent(10);
+	pub const VestingPeriod: u64 = 1000;
+	pub const DefaultWaitPeriod: u64 = 500;
+	pub const RatingWindow: u64 = 1000;
+	pub const AuctionModuleId: ModuleId = ModuleId(*b"py/auctn");
+	pub const BidLockId: LockIdentifier = *b"auction ";
+	pub const VestingLockId: LockIdentifier = *b"aucvest ";
+	pub const ReviewTimeout: u64 = 1000;
+	pub const MaxCallbackGas: u64 = 1_000_000;
+	pub const PriceSnapshotInterval: u64 = 2;
+	pub const MaxPriceSnapshots: u32 = 3;
+	pub const MaxCloseJitter: u64 = 100;
+	pub const MaxAuctionLifetime: u64 = 5000;
+}
+
 impl Trait for Test {
 	type Event = ();
 	type ItemId = ItemId;
+	type LegacyItemId = ItemId;
 	type AuctionId = u32;
+	type AuctionIndex = u32;
+	type AuctionIds = SequentialIds;
 	type Currency = balances::Module<Self>;
 	type OnAuctionPayment = ();
+	type OnItemSold = ();
+	type OnBidPlaced = ();
+	type OnAuctionPenalty = ();
+	type OnAuctionRated = ();
+	type RatingWindow = RatingWindow;
+	type Reputation = ();
+	type Contracts = MockContracts;
+	type MaxCallbackGas = MaxCallbackGas;
 	// Offchain worker
 	type Call = Call;
 	type SubmitTransaction = SubmitTransaction;
 	/// Interface for transfer item
 	type AuctionTransfer = SomeItemModule;
+	type ItemLocks = ();
+	type SuggestedPrice = ();
+	type RoundLength = RoundLength;
+	type MaxDueBatchSize = MaxDueBatchSize;
+	type OffchainTiming = OffchainTiming;
+	type MaxSettlementsPerBlock = MaxSettlementsPerBlock;
+	type MaxSettlementQueueLength = MaxSettlementQueueLength;
+	type ClaimPeriod = ClaimPeriod;
+	type RejectionPenalty = RejectionPenalty;
+	type MaxAutoRefunds = MaxAutoRefunds;
+	type RefundPeriod = RefundPeriod;
+	type CreationDeposit = CreationDeposit;
+	type CommentDeposit = CommentDeposit;
+	type SponsoredBidFee = SponsoredBidFee;
+	type MinSponsoredBid = MinSponsoredBid;
+	type MinimumBeginPrice = MinimumBeginPrice;
+	type MinimumBidAmount = MinimumBidAmount;
+	type StaleAuctionTimeout = StaleAuctionTimeout;
+	type StalePurgeBounty = StalePurgeBounty;
+	type ChallengeBond = ChallengeBond;
+	type ModerationOrigin = system::EnsureRoot<u64>;
+	type ReviewTimeout = ReviewTimeout;
+	type ModuleId = AuctionModuleId;
+	type BidLockId = BidLockId;
+	type VestingLockId = VestingLockId;
+	type VestingPeriod = VestingPeriod;
+	type DefaultWaitPeriod = DefaultWaitPeriod;
+	type TieBreak = TieBreak;
+	type PriceSnapshotInterval = PriceSnapshotInterval;
+	type MaxPriceSnapshots = MaxPriceSnapshots;
+	type MaxCloseJitter = MaxCloseJitter;
+	type MaxBidsPerBlock = MaxBidsPerBlock;
+	type MaxAuctionBidsPerBlock = MaxAuctionBidsPerBlock;
+	type MaxAuctionLifetime = MaxAuctionLifetime;
+	type BidSignature = TestSignature;
+}
+
+parameter_types! {
+	pub const CommunityCreationDeposit: Balance = 20;
+	pub const CommunityModuleId: ModuleId = ModuleId(*b"py/commu");
+	pub const CommunityBidLockId: LockIdentifier = *b"commauc ";
+	pub const CommunityVestingLockId: LockIdentifier = *b"commvest";
+}
+
+/// A second auction house, e.g. a community market next to the official drops,
+/// with its own deposit, module id and locks.
+impl Trait<Instance1> for Test {
+	type Event = ();
+	type ItemId = ItemId;
+	type LegacyItemId = ItemId;
+	type AuctionId = u32;
+	type AuctionIndex = u32;
+	type AuctionIds = SequentialIds;
+	type Currency = balances::Module<Self>;
+	type OnAuctionPayment = ();
+	type OnItemSold = ();
+	type OnBidPlaced = ();
+	type OnAuctionPenalty = ();
+	type OnAuctionRated = ();
+	type RatingWindow = RatingWindow;
+	type Reputation = ();
+	type Contracts = MockContracts;
+	type MaxCallbackGas = MaxCallbackGas;
+	type Call = Call;
+	type SubmitTransaction = SubmitTransaction;
+	type AuctionTransfer = SomeItemModule;
+	type ItemLocks = ();
+	type SuggestedPrice = ();
+	type RoundLength = RoundLength;
+	type MaxDueBatchSize = MaxDueBatchSize;
+	type OffchainTiming = OffchainTiming;
+	type MaxSettlementsPerBlock = MaxSettlementsPerBlock;
+	type MaxSettlementQueueLength = MaxSettlementQueueLength;
+	type ClaimPeriod = ClaimPeriod;
+	type RejectionPenalty = RejectionPenalty;
+	type MaxAutoRefunds = MaxAutoRefunds;
+	type RefundPeriod = RefundPeriod;
+	type CreationDeposit = CommunityCreationDeposit;
+	type CommentDeposit = CommentDeposit;
+	type SponsoredBidFee = SponsoredBidFee;
+	type MinSponsoredBid = MinSponsoredBid;
+	type MinimumBeginPrice = MinimumBeginPrice;
+	type MinimumBidAmount = MinimumBidAmount;
+	type StaleAuctionTimeout = StaleAuctionTimeout;
+	type StalePurgeBounty = StalePurgeBounty;
+	type ChallengeBond = ChallengeBond;
+	type ModerationOrigin = system::EnsureRoot<u64>;
+	type ReviewTimeout = ReviewTimeout;
+	type ModuleId = CommunityModuleId;
+	type BidLockId = CommunityBidLockId;
+	type VestingLockId = CommunityVestingLockId;
+	type VestingPeriod = VestingPeriod;
+	type DefaultWaitPeriod = DefaultWaitPeriod;
+	type TieBreak = TieBreak;
+	type PriceSnapshotInterval = PriceSnapshotInterval;
+	type MaxPriceSnapshots = MaxPriceSnapshots;
+	type MaxCloseJitter = MaxCloseJitter;
+	type MaxBidsPerBlock = MaxBidsPerBlock;
+	type MaxAuctionBidsPerBlock = MaxAuctionBidsPerBlock;
+	type MaxAuctionLifetime = MaxAuctionLifetime;
+	type BidSignature = TestSignature;
 }
 
 pub type Auctions = Module<Test>;
+pub type CommunityAuctions = Module<Test, Instance1>;
 pub type System = system::Module<Test>;
 pub type Balances = balances::Module<Test>;
 pub type Aura = aura::Module<Test>;
 
 pub fn new_test_ext() -> runtime_io::TestExternalities {
 	let t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
-	t.into()
+	let mut ext: runtime_io::TestExternalities = t.into();
+	// a new chain starts with the current storage layout
+	ext.execute_with(|| {
+		<PalletStorageVersion<DefaultInstance>>::put(migration::CURRENT_VERSION);
+		<PalletStorageVersion<Instance1>>::put(migration::CURRENT_VERSION);
+	});
+	ext
 }
 
 pub fn next_block() {
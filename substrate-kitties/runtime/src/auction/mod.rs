@@ -1,36 +1,108 @@
 use codec::{Encode, Decode};
 use rstd::prelude::*;
 use rstd::{result, vec::Vec};
-use sr_primitives::{RuntimeAppPublic, RuntimeDebug};
+use sr_primitives::{RuntimeAppPublic, RuntimeDebug, Permill, ModuleId, generic};
+use sr_primitives::weights::{SimpleDispatchInfo, Weight, WeighData, ClassifyDispatch, DispatchClass};
 use sr_primitives::traits::{
-	SimpleArithmetic, Member, Bounded, Zero, One,
+	SimpleArithmetic, Member, Bounded, Zero, One, UniqueSaturatedInto, Saturating,
 	Printable,
-	CheckedAdd, CheckedSub,
+	CheckedAdd, CheckedSub, CheckedMul, Hash, Verify, AccountIdConversion, EnsureOrigin,
 };
 use sr_primitives::transaction_validity::{
 	TransactionValidity, TransactionLongevity, ValidTransaction, InvalidTransaction,
 };
 use support::dispatch::Result;
 use support::{
-	decl_module, decl_storage, decl_event, Parameter, ensure, print, debug,
+	decl_module, decl_storage, decl_event, decl_error, Parameter, ensure, print, debug,
 	traits::{
 		LockIdentifier, WithdrawReasons, WithdrawReason,
-		LockableCurrency, Currency, ExistenceRequirement,
-		OnUnbalanced,
+		LockableCurrency, ReservableCurrency, Currency, ExistenceRequirement,
+		OnUnbalanced, Randomness, Get, Imbalance,
 	}
 };
-use system::{ensure_none, ensure_signed};
+use system::{ensure_none, ensure_root, ensure_signed};
 use system::offchain::SubmitUnsignedTransaction;
 
-use crate::traits::ItemTransfer;
+use crate::traits::{
+	ItemTransfer, ItemTransferError, IsItemLocked, OnItemSold, OnBidPlaced, OnAuctionPenalty, OnAuctionRated, ReputationScore,
+	SuggestedPrice, ContractCall,
+};
+
+/// Runtime api of this module
+pub mod api;
+/// Storage migrations of this module
+pub mod migration;
 
 // Tests part
 mod mocks;
 mod tests;
+/// Economics simulation on the mock runtime, with the `simulation` feature
+pub mod simulation;
+
+/// Codes of `InvalidTransaction::Custom`, telling why the pool rejected an auction transaction.
+pub mod validity {
+	/// The auctions are unknown or not due to start or stop yet.
+	pub const NOT_DUE: u8 = 1;
+	/// The auctions have already been started.
+	pub const ALREADY_STARTED: u8 = 2;
+	/// The auctions have already been stopped.
+	pub const ALREADY_STOPPED: u8 = 3;
+	/// The bid can not become the highest bid.
+	pub const BID_TOO_LOW: u8 = 5;
+	/// The auction is not open for bids.
+	pub const NOT_BIDDABLE: u8 = 6;
+	/// The batch holds more auctions than allowed.
+	pub const BATCH_TOO_LARGE: u8 = 7;
+	/// The bid does not acknowledge the auction's terms of sale.
+	pub const TERMS_MISMATCH: u8 = 8;
+	/// The bid is not a sponsorable first bid, or the sponsor's budget is exhausted.
+	pub const NOT_SPONSORED: u8 = 9;
+}
 
-const AUCTION_ID: LockIdentifier = *b"auction ";
+/// Weight of calls only touching the auction itself, e.g. pause or setup.
+/// They are far cheaper than bidding and settlement, which keep the default weight.
+const SETUP_CALL_WEIGHT: u32 = 1_000;
+/// Weight of stopping one auction in a batch, as much as a single stop with its settlement.
+const STOP_CALL_WEIGHT: Weight = 10_000;
+/// Max number of auctions an owner stops or cancels in one batch call.
+const MAX_OWNER_BATCH: usize = 50;
+/// Max number of tiers in an auction's minimum step schedule.
+const MAX_STEP_TIERS: usize = 16;
+/// Max number of beneficiaries sharing an auction's proceeds.
+const MAX_PAYOUT_BENEFICIARIES: usize = 8;
+/// Max number of tickets in a raffle.
+const MAX_RAFFLE_TICKETS: u32 = 1_000;
+/// Max length in bytes of an auction title.
+const MAX_TITLE_LEN: usize = 128;
+/// Max length in bytes of a media content identifier, enough for a base32 CIDv1.
+const MAX_CID_LEN: usize = 64;
+/// Max length in bytes of a bid memo.
+const MAX_MEMO_LEN: usize = 32;
+/// Max number of item mappings checked in one `repair_item_mapping` call.
+const MAX_REPAIR_ITEMS: usize = 100;
+/// Max number of units in a multi-unit lot.
+const MAX_LOT_SIZE: usize = 16;
+/// Max number of installments in a payment plan.
+const MAX_INSTALLMENTS: u32 = 12;
+/// Max number of vesting schedules of an account, later proceeds are merged into the last one.
+const MAX_VESTING_SCHEDULES: usize = 16;
+/// Max number of co-managers of an auction.
+const MAX_MANAGERS: usize = 8;
+/// Max number of won auctions kept in the history of an account, the oldest are dropped.
+const MAX_WON_AUCTIONS: usize = 100;
+/// Max number of past auctions kept in the history of an item, the oldest are dropped.
+const MAX_ITEM_HISTORY: usize = 50;
+/// Max number of comments on an auction.
+const MAX_COMMENTS: usize = 50;
+/// Max length in bytes of a comment.
+const MAX_COMMENT_LEN: usize = 256;
+/// Max number of active auctions a block hook walks in one block, the rest are left to later blocks.
+const MAX_SCANNED_AUCTIONS: usize = 64;
+/// Tag of the `DigestItem::Other` listing the auctions closed in a block.
+pub const CLOSED_AUCTIONS_DIGEST: [u8; 4] = *b"aucs";
 
 /// Error which may occur while executing the off-chain code.
+#[cfg(feature = "offchain")]
 #[derive(RuntimeDebug)]
 enum OffchainErr {
 	MissingKey,
@@ -38,6 +110,7 @@ enum OffchainErr {
 	SubmitTransaction,
 }
 
+#[cfg(feature = "offchain")]
 impl Printable for OffchainErr {
 	fn print(&self) {
 		match self {
@@ -48,47 +121,257 @@ impl Printable for OffchainErr {
 	}
 }
 
-/// The module's configuration trait.
-pub trait Trait: timestamp::Trait + aura::Trait {
+/// The module's configuration trait, implemented once for every instance (auction house).
+pub trait Trait<I: Instance = DefaultInstance>: timestamp::Trait + aura::Trait {
 	/// Item Id
 	type ItemId: Parameter
 		+ Member
-		+ SimpleArithmetic
-		+ Bounded
 		+ Default
 		+ Copy;
 
+	/// Item Id used before the current `ItemId`, the item mappings are migrated from it
+	type LegacyItemId: Parameter + Into<Self::ItemId>;
+
 	/// Auction Id
 	type AuctionId: Parameter
+		+ Member
+		+ Default
+		+ Copy;
+
+	/// Creation index of auctions, counting every auction ever created
+	type AuctionIndex: Parameter
 		+ Member
 		+ SimpleArithmetic
 		+ Bounded
 		+ Default
 		+ Copy;
 
+	/// How auction ids are made, e.g. sequential or unguessable hashes.
+	type AuctionIds: GenerateAuctionId<Self, I>;
+
 	/// Currency type for this module.
-	type Currency: LockableCurrency<Self::AccountId, Moment = Self::BlockNumber>;
+	type Currency: LockableCurrency<Self::AccountId, Moment = Self::BlockNumber>
+		+ ReservableCurrency<Self::AccountId>;
 
 	/// The overarching event type.
-	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+	type Event: From<Event<Self, I>> + Into<<Self as system::Trait>::Event>;
 
 	/// A dispatchable call type.
-	type Call: From<Call<Self>>;
+	type Call: From<Call<Self, I>>;
 
 	/// A transaction submitter.
-	type SubmitTransaction: SubmitUnsignedTransaction<Self, <Self as Trait>::Call>;
+	type SubmitTransaction: SubmitUnsignedTransaction<Self, <Self as Trait<I>>::Call>;
 	
 	/// Interface for transfer item
 	type AuctionTransfer: ItemTransfer<Self::AccountId, Self::ItemId>;
 
-	/// Handler for the unbalanced reduction when taking a auction fee.
-	type OnAuctionPayment: OnUnbalanced<NegativeImbalanceOf<Self>>;
+	/// Tell whether an item is locked by other modules, e.g. wrapped into shares
+	type ItemLocks: IsItemLocked<Self::ItemId>;
+
+	/// Suggested minimum begin price of an item, e.g. from a price oracle. Use `()` to not enforce any.
+	type SuggestedPrice: SuggestedPrice<Self::ItemId, BalanceOf<Self, I>>;
+
+	/// Length of an auction round, auctions in a round open and close together.
+	type RoundLength: Get<Self::Moment>;
+
+	/// Max number of due auctions the offchain worker starts or stops in one transaction.
+	type MaxDueBatchSize: Get<u32>;
+
+	/// Whether the offchain worker starts and stops due auctions, else `on_initialize` does.
+	/// A runtime built without the `offchain` feature has no worker and must turn it off.
+	type OffchainTiming: Get<bool>;
+
+	/// Max number of stopped auctions settled in one block, the rest are carried over.
+	type MaxSettlementsPerBlock: Get<u32>;
+
+	/// Max number of stopped auctions waiting in the settlement queue, due auctions beyond it
+	/// are stopped once the queue drains.
+	type MaxSettlementQueueLength: Get<u32>;
+
+	/// How long the winner of a claim-settled auction has to claim the item alone,
+	/// after which anyone can settle it.
+	type ClaimPeriod: Get<Self::Moment>;
+
+	/// Part of the winning bid the winner forfeits to the seller when rejecting the item at inspection.
+	type RejectionPenalty: Get<Permill>;
+
+	/// Max number of losing bids unlocked at settlement, auctions with more bidders
+	/// leave the refunds to be claimed with `claim_refund`.
+	type MaxAutoRefunds: Get<u32>;
+
+	/// How long losing bidders have to claim their refunds before the rest are swept.
+	type RefundPeriod: Get<Self::Moment>;
+
+	/// Deposit reserved from the owner when creating an auction, released when it is stopped.
+	type CreationDeposit: Get<BalanceOf<Self, I>>;
+
+	/// Deposit reserved from the author of a comment, returned when the comments are pruned.
+	type CommentDeposit: Get<BalanceOf<Self, I>>;
+
+	/// Fee taken from the sponsor's budget for each sponsored first bid.
+	type SponsoredBidFee: Get<BalanceOf<Self, I>>;
+
+	/// Lowest sponsored first bid. Accounts are cheap, so a sponsored account has to lock this
+	/// much until the auction ends, which makes draining a budget with fresh accounts costly.
+	type MinSponsoredBid: Get<BalanceOf<Self, I>>;
+
+	/// Lowest begin price of an auction, rejecting dust-level auctions.
+	type MinimumBeginPrice: Get<BalanceOf<Self, I>>;
+
+	/// Lowest price of a bid, so no funds are locked for a negligible value.
+	type MinimumBidAmount: Get<BalanceOf<Self, I>>;
+
+	/// How long an auction may stay pending without moments before it can be purged.
+	type StaleAuctionTimeout: Get<Self::Moment>;
+
+	/// Part of the creation deposit paid to whoever purges a stale auction.
+	type StalePurgeBounty: Get<Permill>;
+
+	/// Bond reserved from whoever challenges an auction for shill bidding.
+	type ChallengeBond: Get<BalanceOf<Self, I>>;
+
+	/// Origin ruling on shill bidding challenges.
+	type ModerationOrigin: EnsureOrigin<Self::Origin>;
+
+	/// How long the moderation origin has to rule on a challenge or report, after which it
+	/// lapses and the reporter gets its bond back.
+	type ReviewTimeout: Get<Self::Moment>;
+
+	/// Id of the module, each auction's escrow account is derived from it.
+	/// Every instance needs its own, it also binds the signed payloads to the instance.
+	type ModuleId: Get<ModuleId>;
+
+	/// Identifier of the lock on bidders' funds, every instance needs its own.
+	type BidLockId: Get<LockIdentifier>;
+
+	/// Identifier of the lock on vesting proceeds, every instance needs its own.
+	type VestingLockId: Get<LockIdentifier>;
+
+	/// How long vested proceeds take to be fully unlocked.
+	type VestingPeriod: Get<Self::Moment>;
+
+	/// Wait period of new auctions, so anti-sniping is on by default. Zero leaves it off.
+	type DefaultWaitPeriod: Get<Self::Moment>;
+
+	/// Which of two equal bids in a multi-unit lot ranks higher.
+	type TieBreak: Get<TieBreak>;
+
+	/// Blocks between two price snapshots of the active auctions. Zero takes no snapshots.
+	type PriceSnapshotInterval: Get<Self::BlockNumber>;
+
+	/// Number of price snapshots kept per auction, the oldest are overwritten.
+	type MaxPriceSnapshots: Get<u32>;
+
+	/// Max random delay added to the close time of auctions opting in to close jitter. Zero disables it.
+	type MaxCloseJitter: Get<Self::Moment>;
+
+	/// Max number of bids one account can place in one auction per block. Zero means no limit.
+	type MaxBidsPerBlock: Get<u32>;
+
+	/// Soft cap on bids processed in one auction per block, so a hot auction can not crowd out
+	/// the rest of the chain. Overflow bids wait in the pool for a later block. Zero means no limit.
+	type MaxAuctionBidsPerBlock: Get<u32>;
+
+	/// Max age of an auction, older ones are expired by the due processing even when paused:
+	/// pending ones are cancelled, started ones are stopped and settled. Zero means no limit.
+	type MaxAuctionLifetime: Get<Self::Moment>;
+
+	/// Handler for the unbalanced reduction when taking a auction fee, used when fees go to `FeeDestination::Treasury`.
+	type OnAuctionPayment: OnUnbalanced<NegativeImbalanceOf<Self, I>>;
+
+	/// Handler for the item being sold at settlement, e.g. sale stats.
+	type OnItemSold: OnItemSold<Self::AccountId, Self::ItemId, BalanceOf<Self, I>>;
+
+	/// Handler for accepted bids, e.g. achievements.
+	type OnBidPlaced: OnBidPlaced<Self::AccountId, BalanceOf<Self, I>>;
+
+	/// Handler for payment defaults and upheld reports, e.g. reputation.
+	type OnAuctionPenalty: OnAuctionPenalty<Self::AccountId>;
+
+	/// Handler for ratings between the seller and the winner, e.g. reputation.
+	type OnAuctionRated: OnAuctionRated<Self::AccountId>;
+
+	/// How long after the settlement the seller and the winner can rate each other.
+	type RatingWindow: Get<Self::Moment>;
+
+	/// Reputation of auction owners, checked against the creation gate.
+	type Reputation: ReputationScore<Self::AccountId>;
+
+	/// Calls the contract an owner registers to be notified of the settlement.
+	type Contracts: ContractCall<Self::AccountId>;
+
+	/// Max gas an owner can give to its settlement callback.
+	type MaxCallbackGas: Get<u64>;
+
+	/// Signature of a bidder over an unsigned bid payload, or of a seller over a relayed creation payload.
+	type BidSignature: Parameter + Verify<Signer = Self::AccountId>;
+}
+
+/// Make the id of a new auction.
+pub trait GenerateAuctionId<T: Trait<I>, I: Instance = DefaultInstance> {
+	/// Id of the auction created by `owner` at creation `index`, None if the ids are used up.
+	fn generate_auction_id(owner: &T::AccountId, index: T::AuctionIndex) -> Option<T::AuctionId>;
+
+	/// Id of the auction created at `index` before ids were generated, when the id was the index.
+	/// None if such an auction can not exist.
+	fn legacy_auction_id(index: T::AuctionIndex) -> Option<T::AuctionId>;
+}
+
+/// Sequential auction ids, the creation index itself.
+pub struct SequentialIds;
+impl<T: Trait<I>, I: Instance> GenerateAuctionId<T, I> for SequentialIds where
+	T::AuctionId: SimpleArithmetic + Bounded,
+{
+	fn generate_auction_id(_owner: &T::AccountId, index: T::AuctionIndex) -> Option<T::AuctionId> {
+		let index: u64 = index.unique_saturated_into();
+		let auction_id: T::AuctionId = index.unique_saturated_into();
+		if auction_id == T::AuctionId::max_value() {
+			return None;
+		}
+		Some(auction_id)
+	}
+
+	fn legacy_auction_id(index: T::AuctionIndex) -> Option<T::AuctionId> {
+		let index: u64 = index.unique_saturated_into();
+		Some(index.unique_saturated_into())
+	}
+}
+
+/// Unguessable auction ids hashed from the owner, the creation index and the random seed,
+/// so they leak no listing volume and can not be enumerated.
+pub struct HashedIds;
+impl<T: Trait<I>, I: Instance> GenerateAuctionId<T, I> for HashedIds where
+	T::AuctionId: From<T::Hash>,
+{
+	fn generate_auction_id(owner: &T::AccountId, index: T::AuctionIndex) -> Option<T::AuctionId> {
+		let seed = <randomness_collective_flip::Module<T>>::random_seed();
+		Some((b"auction/id", owner, index, seed).using_encoded(T::Hashing::hash).into())
+	}
+
+	// hashed ids were never used with the legacy layout
+	fn legacy_auction_id(_index: T::AuctionIndex) -> Option<T::AuctionId> {
+		None
+	}
 }
 
-pub type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
-type NegativeImbalanceOf<T> =
-	<<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::NegativeImbalance;
+pub type BalanceOf<T, I = DefaultInstance> = <<T as Trait<I>>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+type NegativeImbalanceOf<T, I = DefaultInstance> =
+	<<T as Trait<I>>::Currency as Currency<<T as system::Trait>::AccountId>>::NegativeImbalance;
 type SignatureOf<T> = <<T as aura::Trait>::AuthorityId as RuntimeAppPublic>::Signature;
+pub type BidPayloadOf<T, I = DefaultInstance> = BidPayload<
+	<T as system::Trait>::AccountId,
+	<T as Trait<I>>::AuctionId,
+	BalanceOf<T, I>,
+	<T as system::Trait>::Hash,
+	<T as system::Trait>::BlockNumber,
+>;
+pub type OwnerDashboardOf<T, I = DefaultInstance> = OwnerDashboard<<T as Trait<I>>::AuctionId, <T as Trait<I>>::ItemId, BalanceOf<T, I>>;
+pub type CreationPayloadOf<T, I = DefaultInstance> = CreationPayload<
+	<T as system::Trait>::AccountId,
+	<T as Trait<I>>::ItemId,
+	BalanceOf<T, I>,
+	<T as system::Trait>::Hash,
+>;
 
 #[derive(Encode, Decode, Clone, Copy, Eq, PartialEq)]
 #[cfg_attr(feature = "std", derive(Debug))]
@@ -109,21 +392,252 @@ impl AuctionStatus {
 			AuctionStatus::Stopped => "Auction should be stopped.",
 		}
 	}
+
+	/// The status an action moves an auction to. This is the only place where status
+	/// transitions are defined, every change of status goes through it.
+	pub fn transition(self, action: AuctionAction) -> result::Result<AuctionStatus, &'static str> {
+		match (self, action) {
+			(AuctionStatus::PendingStart, AuctionAction::Start) => Ok(AuctionStatus::Active),
+			(AuctionStatus::Active, AuctionAction::Pause) => Ok(AuctionStatus::Paused),
+			(AuctionStatus::Paused, AuctionAction::Resume) => Ok(AuctionStatus::Active),
+			(AuctionStatus::Stopped, AuctionAction::Stop) => Err("Auction is already stopped"),
+			(_, AuctionAction::Stop) => Ok(AuctionStatus::Stopped),
+			(_, AuctionAction::Start) => Err(AuctionStatus::PendingStart.error_str()),
+			(_, AuctionAction::Pause) => Err(AuctionStatus::Active.error_str()),
+			(_, AuctionAction::Resume) => Err(AuctionStatus::Paused.error_str()),
+		}
+	}
+}
+
+/// What moves an auction from one status to another, see `AuctionStatus::transition`.
+#[derive(Encode, Decode, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum AuctionAction {
+	Start,
+	Pause,
+	Resume,
+	Stop,
+}
+
+/// Policy for relisting an auction automatically when it ends with no bids.
+#[derive(Encode, Decode, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct RelistPolicy {
+	max_relists: u32, // 最多重新上架次数
+	price_reduction: Permill, // 每次重新上架时起拍价的降价比例
+}
+
+/// Payment plan of an auction: the winner pays a down payment at settlement
+/// and the rest in equal installments, the item stays in escrow until fully paid.
+#[derive(Encode, Decode, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct InstallmentPlan<Moment> {
+	down_payment: Permill, // 首付比例，违约时不退还
+	installments: u32, // 分期数
+	interval: Moment, // 每期的间隔
+}
+
+/// Progress of a winner paying by installments.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct InstallmentState<AccountId, Balance, Moment> {
+	winner: AccountId, // 中标者
+	remaining: Balance, // 尚未支付的金额
+	paid: Balance, // 卖家从已支付的分期款（不含首付）中实际收到的金额，违约时退还
+	installments_left: u32, // 剩余期数
+	next_due: Moment, // 下一期的截止时间，逾期后卖家可收回拍品
+}
+
+/// Requirement set by governance for creating auctions at or above a begin price.
+#[derive(Encode, Decode, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct CreationGate<Balance> {
+	value_threshold: Balance, // 起拍价达到该值的auction受限制
+	min_reputation: i32, // 创建者需要的最低信誉分
+	low_reputation_deposit: Option<Balance>, // 信誉分不足时可改为预留的更高押金，为空时直接拒绝
+}
+
+/// Bounds set by governance on the wait period owners choose for their auctions.
+#[derive(Encode, Decode, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct WaitPeriodBounds<Moment> {
+	min: Moment, // 最短等待时间
+	max: Moment, // 最长等待时间
+	allow_disable: bool, // 是否允许拍卖者关闭等待时间
+}
+
+/// Outcome of an upheld report against an auction owner.
+#[derive(Encode, Decode, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct ReportRuling {
+	slash_deposit: bool, // 将拍卖者的创建押金赔付给举报人
+	blacklist_item: bool, // 拉黑拍品，之后不能再上架
+	refund_bidders: bool, // 作废本场交易，解锁所有出价
+}
+
+/// Where auction fees go, set by governance.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum FeeDestination<AccountId> {
+	/// Fees are burned.
+	Burn,
+	/// Fees go to `OnAuctionPayment`, which the runtime wires to its treasury.
+	Treasury,
+	/// Fees go to one account.
+	FixedAccount(AccountId),
+	/// Fees are split among accounts by shares summing to 100%.
+	Split(Vec<(AccountId, Permill)>),
+}
+
+impl<AccountId> Default for FeeDestination<AccountId> {
+	fn default() -> Self {
+		FeeDestination::Treasury
+	}
+}
+
+/// Weight of a call on a batch of auctions, the weight of one auction times the batch size.
+pub struct BatchWeight(pub Weight);
+
+impl<'a, Id> WeighData<(&'a Vec<Id>,)> for BatchWeight {
+	fn weigh_data(&self, (ids,): (&'a Vec<Id>,)) -> Weight {
+		self.0.saturating_mul(ids.len() as Weight)
+	}
+}
+
+impl<'a, Id> ClassifyDispatch<(&'a Vec<Id>,)> for BatchWeight {
+	fn classify_dispatch(&self, _: (&'a Vec<Id>,)) -> DispatchClass {
+		DispatchClass::Normal
+	}
+}
+
+/// Weight of setting up a settlement callback, with the gas the callback may use on top,
+/// since the callback runs in the block settling the auction.
+pub struct CallbackWeight(pub Weight);
+
+impl<'a, Id, AccountId> WeighData<(&'a Id, &'a Option<(AccountId, u64)>)> for CallbackWeight {
+	fn weigh_data(&self, (_, callback): (&'a Id, &'a Option<(AccountId, u64)>)) -> Weight {
+		let gas = callback.as_ref().map_or(0, |(_, gas_limit)| *gas_limit);
+		self.0.saturating_add(gas.min(Weight::max_value() as u64) as Weight)
+	}
+}
+
+impl<'a, Id, AccountId> ClassifyDispatch<(&'a Id, &'a Option<(AccountId, u64)>)> for CallbackWeight {
+	fn classify_dispatch(&self, _: (&'a Id, &'a Option<(AccountId, u64)>)) -> DispatchClass {
+		DispatchClass::Normal
+	}
+}
+
+/// Who pays for a sponsored first bid.
+#[derive(Encode, Decode, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum Sponsor {
+	/// The owner of the auction.
+	Owner,
+	/// The sponsor pool account set by governance.
+	Pool,
+}
+
+/// What happens when two bids of a multi-unit lot have the same price.
+///
+/// Bids are strictly ordered by (price, moment, extrinsic index). A single item auction
+/// always requires a higher bid, so equal bids never land there.
+#[derive(Encode, Decode, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum TieBreak {
+	/// The bid recorded first ranks higher.
+	FirstWins,
+	/// The bid recorded later ranks higher.
+	LaterWins,
+	/// A bid equal to an existing one is rejected.
+	RejectEqual,
 }
 
-#[derive(Encode, Decode, Clone, PartialEq, Copy)]
+/// How the winners of a multi-unit lot pay for their units.
+#[derive(Encode, Decode, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum LotPricing {
+	/// Every winner pays the lowest winning bid.
+	Uniform,
+	/// Every winner pays its own bid.
+	PayAsBid,
+}
+
+/// Raffle mode of an auction: tickets are sold at a fixed price and a random
+/// ticket wins the item when the auction is stopped.
+///
+/// The ticket is drawn from the collective flip seed, which the author of the
+/// settling block can bias by withholding blocks. Raffles suit prizes worth
+/// less than a block reward, not items an author would gamble a block on.
+#[derive(Encode, Decode, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct RaffleConfig<Balance> {
+	ticket_price: Balance, // 单张票价
+	max_tickets: u32, // 最多售出票数，售罄后自动停拍
+}
+
+/// A bid signed by the bidder's own key, which anyone can submit as an unsigned transaction.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
 #[cfg_attr(feature = "std", derive(Debug))]
-pub struct Auction<T> where T: Trait {
+pub struct BidPayload<AccountId, AuctionId, Balance, Hash, BlockNumber> {
+	pub bidder: AccountId, // 出价人，其余额被锁定
+	pub nonce: u64, // 出价人的无签名出价序号，防止重放
+	pub expires_at: BlockNumber, // 该区块之后签名失效
+	pub auction_id: AuctionId,
+	pub price: Balance,
+	pub terms_hash: Option<Hash>, // 确认接受的销售条款哈希
+	pub memo: Option<Vec<u8>>, // 出价备注，仅在事件中发出
+}
+
+/// An auction creation signed by the seller's own key, which a relayer submits and pays for.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct CreationPayload<AccountId, ItemId, Balance, Hash> {
+	pub seller: AccountId, // 拍卖者，成为链上的拍卖所有人
+	pub nonce: u64, // 拍卖者的代签创建序号，防止重放
+	pub item: Option<ItemId>, // 拍卖物品，可选
+	pub begin_price: Balance, // 起拍价
+	pub minimum_step: Balance, // 最小加价幅度
+	pub upper_bound_price: Option<Balance>, // 封顶价
+	pub step_tiers: Vec<(Balance, Balance)>, // 阶梯加价表
+	pub title: Vec<u8>, // 标题
+	pub description_hash: Option<Hash>, // 链下描述的哈希
+	pub media_cid: Option<Vec<u8>>, // 拍品媒体的IPFS CID
+}
+
+#[derive(Encode, Decode, Clone, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct Auction<T, I = DefaultInstance> where T: Trait<I>, I: Instance {
 	id: T::AuctionId,
+	title: Vec<u8>, // 标题，最长MAX_TITLE_LEN字节
+	description_hash: Option<T::Hash>, // 链下详细描述的哈希
+	media_cid: Option<Vec<u8>>, // 拍品图片/视频的IPFS CID，最长MAX_CID_LEN字节
 	item: Option<T::ItemId>, // 拍卖物品id
+	lot: Vec<T::ItemId>, // 多件拍品：出价最高的前N位各得一件
+	lot_pricing: LotPricing, // 多件拍品的成交价：统一按最低中标价，或按各自出价
 	owner: T::AccountId, // 拍卖管理账户，可以控制暂停和继续
 	start_at: Option<T::Moment>, // 自动开始时间
 	stop_at: Option<T::Moment>, // 截止时间
 	wait_period: Option<T::Moment>, // 等待时间
-	begin_price: BalanceOf<T>, // 起拍价
-	upper_bound_price: Option<BalanceOf<T>>, // 封顶价（可选）
-	minimum_step: BalanceOf<T>, // 最小加价幅度
+	begin_price: BalanceOf<T, I>, // 起拍价
+	upper_bound_price: Option<BalanceOf<T, I>>, // 封顶价（可选）
+	minimum_step: BalanceOf<T, I>, // 最小加价幅度
+	step_tiers: Vec<(BalanceOf<T, I>, BalanceOf<T, I>)>, // 阶梯加价表（价格阈值, 加价幅度），按阈值升序
 	latest_participate: Option<(T::AccountId, T::Moment)>, // 最后出价人/时间
+	invite_only: bool, // 私密拍卖，出价需附上拍卖者为出价人签发的邀请
+	terms_hash: Option<T::Hash>, // 销售条款哈希，出价人需提交相同哈希以确认接受
+	payout_split: Vec<(T::AccountId, Permill)>, // 成交款分配比例，为空时全部归拍卖者
+	charity: Option<(T::AccountId, Permill)>, // 慈善拍卖：受捐账户及捐赠比例，结算时优先扣除
+	relist_policy: Option<RelistPolicy>, // 流拍后自动重新上架策略
+	relist_count: u32, // 已重新上架次数
+	raffle: Option<RaffleConfig<BalanceOf<T, I>>>, // 抽奖模式，开启后只能购票不能出价
+	round: Option<u32>, // 所属拍卖轮次，同一轮次的拍卖同时开拍和停拍
+	claim_settlement: bool, // 惰性结算：停拍时只记录中标者，由中标者调用claim_item完成付款和物品转移
+	inspection_period: Option<T::Moment>, // 验货期：停拍后中标者可在该时长内拒收拍品，超时视为接受
+	vest_proceeds: bool, // 成交款在VestingPeriod内线性解锁，而不是立即可用
+	installment_plan: Option<InstallmentPlan<T::Moment>>, // 分期付款：中标者先付首付，付清前拍品由拍卖托管
+	collateral_ratio: Option<Permill>, // 保证金出价：出价时只锁定该比例，余款在结算时支付，付不出时保证金赔付给卖家
+	bid_denomination: Option<BalanceOf<T, I>>, // 出价单位：出价必须是该金额的整数倍
+	close_jitter: bool, // 随机截止：到达stop_at后再随机延迟不超过MaxCloseJitter才停拍，防止机器人卡点出价
 	status: AuctionStatus,
 }
 // No need [commented by Tang]
@@ -132,9 +646,80 @@ pub struct Auction<T> where T: Trait {
 // pub struct DetailAuction<T> where T: Trait {
 // 	auction: Auction<T>,//
 // 	is_participate: bool,//是否参与
-// 	participate_price: BalanceOf<T>,//参与的最新出价
+// 	participate_price: BalanceOf<T, I>,//参与的最新出价
 // }
 
+/// Canonical record of a settled auction, kept in state so that it can be
+/// fetched (and proven with a storage proof) by external accounting systems.
+#[derive(Encode, Decode, Clone, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct SettlementReceipt<T, I = DefaultInstance> where T: Trait<I>, I: Instance {
+	auction_id: T::AuctionId,
+	item: T::ItemId, // 拍卖物品id
+	seller: T::AccountId, // 拍卖者
+	winner: T::AccountId, // 中标者
+	price: BalanceOf<T, I>, // 成交价
+	fee: BalanceOf<T, I>, // 拍卖手续费
+	seller_proceeds: BalanceOf<T, I>, // 扣除手续费后的成交款
+	donation: Option<(T::AccountId, BalanceOf<T, I>)>, // 慈善捐赠
+	payouts: Vec<(T::AccountId, BalanceOf<T, I>)>, // 成交款的实际分配
+	settled_at: T::Moment, // 结算时间
+}
+
+/// How an auction of an item ended.
+#[derive(Encode, Decode, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum AuctionOutcome<AccountId, Balance> {
+	/// Sold to the winner. (winner, price)
+	Sold(AccountId, Balance),
+	/// Closed or cancelled without a sale, the item stays with the seller.
+	Unsold,
+}
+
+/// A post on the comment board of an auction.
+#[derive(Encode, Decode, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct Comment<AccountId, Balance, Moment> {
+	pub author: AccountId, // 发言人
+	pub text: Vec<u8>, // 内容，最长MAX_COMMENT_LEN字节
+	pub reply_to: Option<u32>, // 回复的评论序号
+	pub deposit: Balance, // 发言时reserve的押金，清理时退还
+	pub posted_at: Moment, // 发言时间
+}
+
+/// Everything the dashboard of an auction owner shows, gathered in one query.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct OwnerDashboard<AuctionId, ItemId, Balance> {
+	pub pending: Vec<AuctionId>, // 尚未开始的拍卖
+	pub active: Vec<AuctionId>, // 进行中的拍卖
+	pub paused: Vec<AuctionId>, // 已暂停的拍卖
+	pub stopped: Vec<AuctionId>, // 已停拍的拍卖
+	pub pending_proceeds: Balance, // 已停拍尚未付给卖家的成交款：待结算、待领取、验货期中及分期未付部分
+	pub vesting_proceeds: Balance, // 分期释放中仍被锁定的成交款
+	pub deposits_held: Balance, // 被reserve的押金：创建押金、留言押金及赞助预算
+	pub locked_items: Vec<ItemId>, // 仍被拍卖占用的物品
+}
+
+/// An auction won by an account, kept in its purchase history.
+#[derive(Encode, Decode, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct WonAuction<AuctionId, ItemId, Balance, Moment> {
+	pub auction_id: AuctionId,
+	pub item: ItemId, // 获得的物品id
+	pub price: Balance, // 成交价
+	pub won_at: Moment, // 成交时间
+}
+
+/// Money moved by the settlement of an auction, summed over its payers.
+struct Payment<AccountId, Balance> {
+	price: Balance,
+	fee: Balance,
+	seller_proceeds: Balance,
+	donation: Option<(AccountId, Balance)>,
+	payouts: Vec<(AccountId, Balance)>,
+}
+
 // helper enum for auction_ids vec
 enum StoreVecs {
 	PendingVec,
@@ -143,61 +728,451 @@ enum StoreVecs {
 
 // This module's storage items.
 decl_storage! {
-	trait Store for Module<T: Trait> as Auctions {
-		NextAuctionId get(fn next_auction_id): T::AuctionId;
+	trait Store for Module<T: Trait<I>, I: Instance = DefaultInstance> as Auctions {
+		// 存储布局版本，创世时写入当前版本，升级后由migration逐版本迁移
+		PalletStorageVersion get(fn storage_version) build(|_| migration::CURRENT_VERSION): u32;
+		// 分批迁移的进度：下一个待迁移auction的创建序号
+		MigrationCursor get(fn migration_cursor): T::AuctionIndex;
+
+		// 下一个auction的创建序号，以及序号到auction id的索引，用于遍历
+		NextAuctionId get(fn next_auction_id): T::AuctionIndex;
+		AuctionsByIndex get(fn auction_by_index): map T::AuctionIndex => Option<T::AuctionId>;
+		// 拍卖者自己的拍卖序号，不暴露全局创建顺序：(拍卖者, 序号) => auction id，以及auction id => 序号
+		OwnerAuctionCount get(fn owner_auction_count): map T::AccountId => u32;
+		OwnerAuctions get(fn auction_by_owner_seq): double_map T::AccountId, twox_128(u32) => Option<T::AuctionId>;
+		OwnerSeqs get(fn owner_seq_of): map T::AuctionId => Option<u32>;
 		
 		// 记录账户全局lock的余额数量，不同auction中lock的余额汇总在这里
-		AccountLocks get(fn account_locks): map T::AccountId => BalanceOf<T>;
+		AccountLocks get(fn account_locks): map T::AccountId => BalanceOf<T, I>;
 
 		// 物品id映射auctionid，一个物品只能在一个auction中参拍，创建auction后添加映射，auction结束后删除映射
 		AuctionItems get(fn auction_items): map T::ItemId => Option<T::AuctionId>;
-		Auctions get(fn auctions): map T::AuctionId => Option<Auction<T>>;
-		AuctionBids get(fn auction_bids): double_map T::AuctionId, twox_128(T::AccountId) => BalanceOf<T>;
+		Auctions get(fn auctions): map T::AuctionId => Option<Auction<T, I>>;
+		AuctionBids get(fn auction_bids): double_map T::AuctionId, twox_128(T::AccountId) => BalanceOf<T, I>;
+		// 已解锁的出价，避免重复解锁
+		UnlockedBids get(fn bid_unlocked): double_map T::AuctionId, twox_128(T::AccountId) => bool;
+		// 预存的出价额度（已reserve）及其中已用于出价的部分，出价中由额度支持的金额不再锁仓
+		BidCredits get(fn bid_credit): map T::AccountId => BalanceOf<T, I>;
+		CommittedCredits get(fn committed_credit): map T::AccountId => BalanceOf<T, I>;
+		CreditBids get(fn credit_bid): double_map T::AuctionId, twox_128(T::AccountId) => BalanceOf<T, I>;
+		// 保证金出价的auction中实际锁定的保证金，AuctionBids记录完整出价
+		BidCollateral get(fn bid_collateral): double_map T::AuctionId, twox_128(T::AccountId) => BalanceOf<T, I>;
 		AuctionParticipants get(fn auction_participants): map T::AuctionId => Option<Vec<T::AccountId>>;
+		// 出价人最近一次出价的时间和交易序号，多件拍卖按价格、时间、交易序号排序分配
+		BidPlacedAt get(fn bid_placed_at): double_map T::AuctionId, twox_128(T::AccountId) => (T::Moment, u32);
+		// 出价人在该auction中最近出价的区块，及其在该区块内的出价次数，用于限制刷单
+		BidsInBlock get(fn bids_in_block_record): double_map T::AuctionId, twox_128(T::AccountId) => (T::BlockNumber, u32);
+		// 该auction最近有出价的区块，及该区块内全部出价人的出价次数
+		AuctionBidsInBlock get(fn auction_bids_in_block_record): map T::AuctionId => (T::BlockNumber, u32);
+		// auction的创建时间，超过MaxAuctionLifetime后自动过期
+		AuctionCreatedAt get(fn auction_created_at): map T::AuctionId => Option<T::Moment>;
+
+		// 出价人确认接受的销售条款哈希
+		TermsAcceptances get(fn terms_accepted): double_map T::AuctionId, twox_128(T::AccountId) => Option<T::Hash>;
+
+		// 成交凭证，结算时按auction id记录
+		SettlementReceipts get(fn settlement_receipt): map T::AuctionId => Option<SettlementReceipt<T, I>>;
+		// 拍卖的留言板，最多MAX_COMMENTS条，结算后可清理并退还押金
+		AuctionComments get(fn comments): map T::AuctionId => Vec<Comment<T::AccountId, BalanceOf<T, I>, T::Moment>>;
+		// 结算后买卖双方的互评，(auction id, 评价人) => 星级1~5
+		AuctionRatings get(fn auction_rating): double_map T::AuctionId, twox_128(T::AccountId) => Option<u8>;
+		// 物品参加过的拍卖及结果，最多保留MAX_ITEM_HISTORY条，超出时丢弃最旧的
+		ItemAuctionHistory get(fn item_auction_history): map T::ItemId => Vec<(T::AuctionId, AuctionOutcome<T::AccountId, BalanceOf<T, I>>)>;
+		// 赞助人为首次出价预付手续费的预算，资金被reserve
+		SponsorBudgets get(fn sponsor_budget): map T::AccountId => BalanceOf<T, I>;
+		// 治理设置的全局赞助池账户
+		SponsorPoolAccount get(fn sponsor_pool_account): Option<T::AccountId>;
+		// 已获得过赞助的出价人，每个账户只赞助一次
+		SponsoredBidders get(fn is_sponsored): map T::AccountId => bool;
+		// 账户下一次代签创建拍卖应使用的序号
+		CreationNonces get(fn creation_nonce): map T::AccountId => u64;
+		// 账户下一次无签名出价应使用的序号
+		BidNonces get(fn bid_nonce): map T::AccountId => u64;
+		// 账户的中标记录，结算时写入，最多保留MAX_WON_AUCTIONS条，超出时丢弃最旧的
+		WonAuctions get(fn won_auctions): map T::AccountId => Vec<WonAuction<T::AuctionId, T::ItemId, BalanceOf<T, I>, T::Moment>>;
+
+		// 抽奖模式下售出的票，每张票记录一次购票人
+		RaffleTickets get(fn raffle_tickets): map T::AuctionId => Vec<T::AccountId>;
+
+		// 每个轮次中的拍卖
+		RoundAuctions get(fn round_auctions): map u32 => Vec<T::AuctionId>;
 
 		// Auction workinig list
 		PendingAuctions get(fn pending_auctions): Vec<T::AuctionId>; // 尚未开始的auction
 		ActiveAuctions get(fn active_auctions): Vec<T::AuctionId>; // 尚未结束的auction，已经暂停的也在这里
+		// 惰性结算中待领取的auction：中标者及领取截止时间
+		PendingClaims get(fn pending_claim): map T::AuctionId => Option<(T::AccountId, T::Moment)>;
+
+		// 验货中的auction：中标者及验货截止时间，以及按截止时间排序的待处理队列
+		Inspections get(fn inspection): map T::AuctionId => Option<(T::AccountId, T::Moment)>;
+		InspectionQueue get(fn inspection_queue): Vec<(T::AuctionId, T::Moment)>;
+
+		// 分期付款中的auction及中标者的付款进度
+		Installments get(fn installment): map T::AuctionId => Option<InstallmentState<T::AccountId, BalanceOf<T, I>, T::Moment>>;
+
+		// 创建auction时预留的押金及创建时间，停拍时返还
+		CreationDeposits get(fn creation_deposit): map T::AuctionId => Option<(BalanceOf<T, I>, T::Moment)>;
+
+		// 出价人过多时落选者自行领取退款：auction的自动清扫时间、已退款账户、待清扫的auction及进度
+		PullRefunds get(fn pull_refund_deadline): map T::AuctionId => Option<T::Moment>;
+		RefundClaimed get(fn refund_claimed): double_map T::AuctionId, twox_128(T::AccountId) => bool;
+		RefundSweeps get(fn refund_sweeps): Vec<(T::AuctionId, T::Moment, u32)>;
+
+		// 针对auction的围标举报：举报人、保证金及裁决期限，裁决前暂停结算
+		Challenges get(fn challenge): map T::AuctionId => Option<(T::AccountId, BalanceOf<T, I>, T::Moment)>;
+
+		// 针对拍卖者欺诈的举报：举报人、理由哈希、保证金及审核期限，以及待审核的举报队列
+		Reports get(fn report): map T::AuctionId => Option<(T::AccountId, T::Hash, BalanceOf<T, I>, T::Moment)>;
+		ReviewQueue get(fn review_queue): Vec<T::AuctionId>;
+		// 结算时调用的合约地址和gas上限，由拍卖者支付
+		SettlementCallbacks get(fn settlement_callback): map T::AuctionId => Option<(T::AccountId, u64)>;
+
+		// auction的共同管理人，可暂停/恢复拍卖和设置时间，但不能停拍或修改收款去向
+		AuctionManagers get(fn managers_of): map T::AuctionId => Vec<T::AccountId>;
+
+		// 被拉黑的拍品，不能再加入拍卖
+		BlacklistedItems get(fn is_blacklisted): map T::ItemId => bool;
+
+		// 治理设置的高价auction创建门槛
+		CreationGateConfig get(fn creation_gate): Option<CreationGate<BalanceOf<T, I>>>;
+
+		// 治理设置的竞价等待时间范围
+		WaitPeriodLimits get(fn wait_period_bounds): Option<WaitPeriodBounds<T::Moment>>;
+
+		// 治理设置的手续费去向，默认交给OnAuctionPayment
+		FeeDestinationConfig get(fn fee_destination): FeeDestination<T::AccountId>;
+
+		// 治理设置的成交款阈值，单场成交款超过该值时强制分期释放
+		VestingThreshold get(fn vesting_threshold): Option<BalanceOf<T, I>>;
+		// 受益人的分期释放计划（金额, 开始时间），每笔在VestingPeriod内线性解锁
+		ProceedsVesting get(fn proceeds_vesting): map T::AccountId => Vec<(BalanceOf<T, I>, T::Moment)>;
+
+		SettlementQueue get(fn settlement_queue): Vec<T::AuctionId>; // 已停拍待结算的auction，每个区块最多结算MaxSettlementsPerBlock个
+		ClosedAuctions get(fn closed_auctions): Vec<T::AuctionId>; // 本区块停拍的auction，区块结束时汇总公告
+
+		// 价格快照环形缓冲区（区块, 最高出价），最多MaxPriceSnapshots条，写满后覆盖最旧的一条
+		PriceSnapshots get(fn price_snapshots): map T::AuctionId => Vec<(T::BlockNumber, BalanceOf<T, I>)>;
+		// 环形缓冲区中下一条快照写入的位置
+		PriceSnapshotCursor get(fn price_snapshot_cursor): map T::AuctionId => u32;
+		// 进行中的快照轮次下一个要快照的ActiveAuctions下标，每个区块最多快照MAX_SCANNED_AUCTIONS个
+		PriceSnapshotRound get(fn price_snapshot_round): Option<u32>;
+
+		// 随机截止的延迟，在到达stop_at后才抽取，实际截止时间 = stop_at + 延迟
+		CloseOffsets get(fn close_offset): map T::AuctionId => Option<T::Moment>;
+		// 下一个区块开始检查是否需要抽取延迟的ActiveAuctions下标，每个区块最多检查MAX_SCANNED_AUCTIONS个
+		CloseJitterCursor get(fn close_jitter_cursor): u32;
 	}
 }
 
 // add by sunhao 20191023
 decl_event!(
-	pub enum Event<T> where
+	pub enum Event<T, I: Instance = DefaultInstance> where
 		<T as system::Trait>::AccountId,
-		<T as Trait>::AuctionId,
-		Balance = BalanceOf<T>,
+		<T as system::Trait>::Hash,
+		<T as Trait<I>>::AuctionId,
+		<T as Trait<I>>::ItemId,
+		<T as timestamp::Trait>::Moment,
+		Balance = BalanceOf<T, I>,
 	{
+		/// An auction is created. (auction_id, owner, media_cid)
+		AuctionCreated(AuctionId, AccountId, Option<Vec<u8>>),
 		/// A price and/or amount is changed in some auction. 
-		/// (auction_id, latest_bidder, latest_price, remain_amount)
-		BidderUpdated(AuctionId, AccountId, Balance, u32),
+		/// (auction_id, latest_bidder, latest_price, remain_amount, memo)
+		BidderUpdated(AuctionId, AccountId, Balance, u32, Option<Vec<u8>>),
 		/// A auction's status has changed. (auction_id, status_from, status_to)
 		AuctionUpdated(AuctionId, AuctionStatus, AuctionStatus),
+		/// A auction is settled and its receipt is recorded. (auction_id, winner, price)
+		AuctionSettled(AuctionId, AccountId, Balance),
+		/// A beneficiary is paid its share of the proceeds. (auction_id, beneficiary, amount)
+		ProceedsPaid(AuctionId, AccountId, Balance),
+		/// The charity of a charity auction receives its donation. (auction_id, charity, amount)
+		CharityDonated(AuctionId, AccountId, Balance),
+		/// An auction ended with no bids and is relisted. (old_auction_id, new_auction_id, begin_price)
+		AuctionRelisted(AuctionId, AuctionId, Balance),
+		/// Raffle tickets are bought. (auction_id, buyer, count, total_tickets)
+		TicketsBought(AuctionId, AccountId, u32, u32),
+		/// An auction joins a round. (auction_id, round)
+		RoundJoined(AuctionId, u32),
+		/// A claim-settled auction is stopped and waits for its winner. (auction_id, winner, claim_deadline)
+		ClaimPending(AuctionId, AccountId, Moment),
+		/// The winner starts inspecting the item. (auction_id, winner, inspection_deadline)
+		InspectionStarted(AuctionId, AccountId, Moment),
+		/// The winner rejects the item at inspection and forfeits the penalty. (auction_id, winner, penalty)
+		ItemRejected(AuctionId, AccountId, Balance),
+		/// The item can not be settled when the inspection is over, the seller keeps it and the
+		/// winner's bid is unlocked. (auction_id, winner)
+		InspectionFailed(AuctionId, AccountId),
+		/// A batch of due auctions is processed. (started, stopped, purged, skipped)
+		DueAuctionsProcessed(u32, u32, u32, u32),
+		/// An orphaned item mapping is removed. (item_id, auction_id)
+		ItemMappingRepaired(ItemId, AuctionId),
+		/// A stale pending auction is purged. (auction_id, bounty)
+		StaleAuctionPurged(AuctionId, Balance),
+		/// The winner pays the down payment and starts paying by installments. (auction_id, winner, down_payment, remaining)
+		InstallmentsStarted(AuctionId, AccountId, Balance, Balance),
+		/// An installment is paid. (auction_id, winner, amount, remaining)
+		InstallmentPaid(AuctionId, AccountId, Balance, Balance),
+		/// The seller takes back the item of a defaulted winner and refunds its installments. (auction_id, winner, refund)
+		ItemRepossessed(AuctionId, AccountId, Balance),
+		/// An auction is challenged for shill bidding. (auction_id, reporter, bond)
+		ShillChallenged(AuctionId, AccountId, Balance),
+		/// A challenge is upheld, the sale is void and the seller's deposit goes to the reporter. (auction_id, reporter, slashed)
+		ChallengeUpheld(AuctionId, AccountId, Balance),
+		/// A challenge is rejected, the reporter's bond goes to the seller. (auction_id, reporter, slashed)
+		ChallengeRejected(AuctionId, AccountId, Balance),
+		/// A challenge lapses without a ruling, the reporter's bond is returned. (auction_id, reporter)
+		ChallengeExpired(AuctionId, AccountId),
+		/// An auction owner is reported for fraud. (auction_id, reporter, reason_hash)
+		AuctionReported(AuctionId, AccountId, Hash),
+		/// A report is upheld. (auction_id, reporter, ruling)
+		ReportUpheld(AuctionId, AccountId, ReportRuling),
+		/// A report is dismissed, the reporter's bond goes to the owner. (auction_id, reporter, slashed)
+		ReportDismissed(AuctionId, AccountId, Balance),
+		/// A report lapses without a review, the reporter's bond is returned. (auction_id, reporter)
+		ReportExpired(AuctionId, AccountId),
+		/// Dust left in an auction's escrow account is swept to the owner. (auction_id, amount)
+		EscrowSwept(AuctionId, Balance),
+		/// Funds are deposited into the bidding credit. (account, amount)
+		CreditDeposited(AccountId, Balance),
+		/// Funds are withdrawn from the bidding credit. (account, amount)
+		CreditWithdrawn(AccountId, Balance),
+		/// The winner fails to pay the balance of a collateral-backed bid, its collateral goes to the seller. (auction_id, winner, collateral)
+		CollateralSlashed(AuctionId, AccountId, Balance),
+		/// Proceeds are paid under a vesting schedule. (auction_id, beneficiary, amount)
+		ProceedsVested(AuctionId, AccountId, Balance),
+		/// The vesting lock of an account is updated. (account, still_locked)
+		VestingUpdated(AccountId, Balance),
+		/// A unit of a multi-unit lot is allocated. (auction_id, winner, item_id, price)
+		UnitAllocated(AuctionId, AccountId, ItemId, Balance),
+		/// The owner adds a co-manager to the auction. (auction_id, manager)
+		ManagerAdded(AuctionId, AccountId),
+		/// The owner removes a co-manager from the auction. (auction_id, manager)
+		ManagerRemoved(AuctionId, AccountId),
+		/// Auctions closed in this block, also announced in a digest item. (auction_ids)
+		AuctionsClosed(Vec<AuctionId>),
+		/// The settlement callback of an auction is called. (auction_id, contract, success)
+		SettlementCallbackCalled(AuctionId, AccountId, bool),
+		/// A payout too small to create the account goes to the fee destination. (auction_id, account, amount)
+		PayoutRouted(AuctionId, AccountId, Balance),
+		/// Result of stopping an auction in an owner's batch. (auction_id, owner, success)
+		BatchStopped(AuctionId, AccountId, bool),
+		/// Result of cancelling an auction in an owner's batch. (auction_id, owner, success)
+		BatchCancelled(AuctionId, AccountId, bool),
+		/// A sponsor pays the fee of a bidder's first bid. (auction_id, bidder, sponsor, fee)
+		BidSponsored(AuctionId, AccountId, AccountId, Balance),
+		/// Funds are added to a sponsor's budget. (sponsor, amount)
+		SponsorshipFunded(AccountId, Balance),
+		/// Funds are withdrawn from a sponsor's budget. (sponsor, amount)
+		SponsorshipWithdrawn(AccountId, Balance),
+		/// A comment is posted on an auction. (auction_id, author, index, reply_to)
+		CommentPosted(AuctionId, AccountId, u32, Option<u32>),
+		/// The comments of a settled auction are pruned and their deposits returned. (auction_id, count)
+		CommentsPruned(AuctionId, u32),
+		/// A party of a settled auction rates the other. (auction_id, rated, rater, stars)
+		CounterpartyRated(AuctionId, AccountId, AccountId, u8),
+		/// A jittered auction is stopped at its realized close time. (auction_id, close_at)
+		CloseTimeRevealed(AuctionId, Moment),
 	}
 );
 
+decl_error! {
+	/// Errors of the auction module, telling why an item of an auction can not be listed or transferred.
+	pub enum Error {
+		/// The owner of the auction no longer owns the item
+		ItemNotOwned,
+		/// The item is frozen, held or locked by another module
+		ItemFrozen,
+		/// The item does not exist, or could not be transferred for another reason
+		ItemTransferFailed,
+	}
+}
+
+impl From<ItemTransferError> for Error {
+	fn from(err: ItemTransferError) -> Self {
+		match err {
+			ItemTransferError::NotOwner => Error::ItemNotOwned,
+			ItemTransferError::Frozen => Error::ItemFrozen,
+			ItemTransferError::Unknown => Error::ItemTransferFailed,
+		}
+	}
+}
+
 // The module's dispatchable functions.
 decl_module! {
 	/// The module declaration.
-	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
-		// Initializing events
-		fn deposit_event() = default;
+	pub struct Module<T: Trait<I>, I: Instance = DefaultInstance> for enum Call where origin: T::Origin {
+		/// Length of an auction round, auctions in a round open and close together.
+		const RoundLength: T::Moment = T::RoundLength::get();
+		/// Max number of due auctions the offchain worker starts or stops in one transaction.
+		const MaxDueBatchSize: u32 = T::MaxDueBatchSize::get();
+		/// Whether the offchain worker starts and stops due auctions, else `on_initialize` does.
+		const OffchainTiming: bool = T::OffchainTiming::get();
+		/// Max number of stopped auctions settled in one block, the rest are carried over.
+		const MaxSettlementsPerBlock: u32 = T::MaxSettlementsPerBlock::get();
+		/// Max number of stopped auctions waiting in the settlement queue.
+		const MaxSettlementQueueLength: u32 = T::MaxSettlementQueueLength::get();
+		/// How long the winner of a claim-settled auction has to claim the item alone.
+		const ClaimPeriod: T::Moment = T::ClaimPeriod::get();
+		/// Part of the winning bid forfeited to the seller when rejecting the item at inspection.
+		const RejectionPenalty: Permill = T::RejectionPenalty::get();
+		/// Max number of losing bids unlocked at settlement.
+		const MaxAutoRefunds: u32 = T::MaxAutoRefunds::get();
+		/// How long losing bidders have to claim their refunds before the rest are swept.
+		const RefundPeriod: T::Moment = T::RefundPeriod::get();
+		/// Deposit reserved from the owner when creating an auction.
+		const CreationDeposit: BalanceOf<T, I> = T::CreationDeposit::get();
+		/// Deposit reserved from the author of a comment.
+		const CommentDeposit: BalanceOf<T, I> = T::CommentDeposit::get();
+		/// Fee taken from the sponsor's budget for each sponsored first bid.
+		const SponsoredBidFee: BalanceOf<T, I> = T::SponsoredBidFee::get();
+		/// Lowest sponsored first bid.
+		const MinSponsoredBid: BalanceOf<T, I> = T::MinSponsoredBid::get();
+		/// Lowest begin price of an auction.
+		const MinimumBeginPrice: BalanceOf<T, I> = T::MinimumBeginPrice::get();
+		/// Lowest price of a bid.
+		const MinimumBidAmount: BalanceOf<T, I> = T::MinimumBidAmount::get();
+		/// How long an auction may stay pending without moments before it can be purged.
+		const StaleAuctionTimeout: T::Moment = T::StaleAuctionTimeout::get();
+		/// Part of the creation deposit paid to whoever purges a stale auction.
+		const StalePurgeBounty: Permill = T::StalePurgeBounty::get();
+		/// Bond reserved from whoever challenges or reports an auction.
+		const ChallengeBond: BalanceOf<T, I> = T::ChallengeBond::get();
+		/// How long the moderation origin has to rule on a challenge or report.
+		const ReviewTimeout: T::Moment = T::ReviewTimeout::get();
+		/// Id of the module, each auction's escrow account is derived from it.
+		const ModuleId: ModuleId = T::ModuleId::get();
+		/// Identifier of the lock on bidders' funds.
+		const BidLockId: LockIdentifier = T::BidLockId::get();
+		/// Identifier of the lock on vesting proceeds.
+		const VestingLockId: LockIdentifier = T::VestingLockId::get();
+		/// How long vested proceeds take to be fully unlocked.
+		const VestingPeriod: T::Moment = T::VestingPeriod::get();
+		/// How long after the settlement the seller and the winner can rate each other.
+		const RatingWindow: T::Moment = T::RatingWindow::get();
+		/// Wait period given to new auctions, zero leaves anti-sniping off.
+		const DefaultWaitPeriod: T::Moment = T::DefaultWaitPeriod::get();
+		/// Which of two equal bids in a multi-unit lot ranks higher.
+		const TieBreak: TieBreak = T::TieBreak::get();
+		/// Blocks between two price snapshots of the active auctions.
+		const PriceSnapshotInterval: T::BlockNumber = T::PriceSnapshotInterval::get();
+		/// Number of price snapshots kept per auction.
+		const MaxPriceSnapshots: u32 = T::MaxPriceSnapshots::get();
+		/// Max random delay added to the close time of a jittered auction.
+		const MaxCloseJitter: T::Moment = T::MaxCloseJitter::get();
+		/// Max number of bids one account can place in one auction per block.
+		const MaxBidsPerBlock: u32 = T::MaxBidsPerBlock::get();
+		/// Max number of bids processed in one auction per block.
+		const MaxAuctionBidsPerBlock: u32 = T::MaxAuctionBidsPerBlock::get();
+		/// Max age of an auction before it is expired.
+		const MaxAuctionLifetime: T::Moment = T::MaxAuctionLifetime::get();
+		/// Max gas an owner can give to its settlement callback.
+		const MaxCallbackGas: u64 = T::MaxCallbackGas::get();
+
+		/// Max number of tiers in an auction's minimum step schedule.
+		const MaxStepTiers: u32 = MAX_STEP_TIERS as u32;
+		/// Max number of beneficiaries sharing an auction's proceeds.
+		const MaxPayoutBeneficiaries: u32 = MAX_PAYOUT_BENEFICIARIES as u32;
+		/// Max number of tickets in a raffle.
+		const MaxRaffleTickets: u32 = MAX_RAFFLE_TICKETS;
+		/// Max length in bytes of an auction title.
+		const MaxTitleLength: u32 = MAX_TITLE_LEN as u32;
+		/// Max length in bytes of a media content identifier.
+		const MaxCidLength: u32 = MAX_CID_LEN as u32;
+		/// Max length in bytes of a bid memo.
+		const MaxMemoLength: u32 = MAX_MEMO_LEN as u32;
+		/// Max number of units in a multi-unit lot.
+		const MaxLotSize: u32 = MAX_LOT_SIZE as u32;
+		/// Max number of installments in a payment plan.
+		const MaxInstallments: u32 = MAX_INSTALLMENTS;
+		/// Max number of co-managers of an auction.
+		const MaxManagers: u32 = MAX_MANAGERS as u32;
+		/// Max number of won auctions kept in the history of an account.
+		const MaxWonAuctions: u32 = MAX_WON_AUCTIONS as u32;
+		/// Max number of past auctions kept in the history of an item.
+		const MaxItemHistory: u32 = MAX_ITEM_HISTORY as u32;
+		/// Max number of auctions an owner stops or cancels in one batch call.
+		const MaxOwnerBatch: u32 = MAX_OWNER_BATCH as u32;
+		/// Max number of comments on an auction.
+		const MaxComments: u32 = MAX_COMMENTS as u32;
+		/// Max length in bytes of a comment.
+		const MaxCommentLength: u32 = MAX_COMMENT_LEN as u32;
+
+		// Events are deposited with indexed topics by `Module::deposit_event`
 
 		pub fn create_auction(origin,
 			// item: T::ItemId,//竞拍对象
-			begin_price: BalanceOf<T>,//起拍价
-			minimum_step: BalanceOf<T>,//最小加价幅度
-			upper_bound_price: Option<BalanceOf<T>>,//封顶价
+			begin_price: BalanceOf<T, I>,//起拍价
+			minimum_step: BalanceOf<T, I>,//最小加价幅度
+			upper_bound_price: Option<BalanceOf<T, I>>,//封顶价
+			step_tiers: Vec<(BalanceOf<T, I>, BalanceOf<T, I>)>,//阶梯加价表（可为空）
+			title: Vec<u8>,//标题
+			description_hash: Option<T::Hash>,//链下描述的哈希
+			media_cid: Option<Vec<u8>>,//拍品媒体的IPFS CID
 			// start_at: T::Moment,//起拍时间
 			// stop_at: T::Moment,//结束时间
 			// wait_period: T::Moment //竞价等待时间
 		) -> Result {
 			let sender = ensure_signed(origin)?;
 
-			Self::do_create_auction(&sender, begin_price,minimum_step, upper_bound_price)?;
+			Self::do_create_auction(
+				&sender, None, begin_price, minimum_step, upper_bound_price, step_tiers,
+				title, description_hash, media_cid,
+			)?;
+
+			Ok(())
+		}
+
+		// A relayer submits an auction creation pre-signed by the seller, e.g. a custodial marketplace
+		// listing for its users. The seller owns the auction and pays its deposit, the relayer the fee.
+		pub fn create_auction_on_behalf(origin, payload: CreationPayloadOf<T, I>, signature: T::BidSignature) -> Result {
+			let _ = ensure_signed(origin)?;
+
+			ensure!(
+				signature.verify(&Self::signing_payload(&payload)[..], &payload.seller),
+				"Invalid creation signature"
+			);
+			let nonce = Self::creation_nonce(&payload.seller);
+			ensure!(payload.nonce == nonce, "Invalid creation nonce");
+			let next_nonce = nonce.checked_add(1).ok_or("Creation nonce overflow")?;
+			if let Some(item) = payload.item {
+				Self::ensure_item_listable(&payload.seller, item, payload.begin_price, None)?;
+			}
+
+			let CreationPayload {
+				seller, item, begin_price, minimum_step, upper_bound_price, step_tiers,
+				title, description_hash, media_cid, ..
+			} = payload;
+			// the item is held as part of the creation, nothing is written if either fails
+			Self::do_create_auction(
+				&seller, item, begin_price, minimum_step, upper_bound_price, step_tiers,
+				title, description_hash, media_cid,
+			)?;
+			<CreationNonces<T, I>>::insert(&seller, next_nonce);
+
+			Ok(())
+		}
+
+		// Owner can edit the title, description and media before the auction is started.
+		#[weight = SimpleDispatchInfo::FixedNormal(SETUP_CALL_WEIGHT)]
+		pub fn update_description(
+			origin,
+			auction_id: T::AuctionId,
+			title: Vec<u8>,
+			description_hash: Option<T::Hash>,
+			media_cid: Option<Vec<u8>>
+		) -> Result {
+			let sender = ensure_signed(origin)?;
+
+			let mut auction = Self::_ensure_auction_with_status(auction_id, Some(AuctionStatus::PendingStart), Some(&sender))?;
+			Self::ensure_valid_description(&title, &media_cid)?;
+			auction.title = title;
+			auction.description_hash = description_hash;
+			auction.media_cid = media_cid;
+			<Auctions<T, I>>::insert(auction_id, auction);
 
 			Ok(())
 		}
+		#[weight = SimpleDispatchInfo::FixedNormal(SETUP_CALL_WEIGHT)]
 		pub fn add_item(origin,
 			auction_id: T::AuctionId,
 			item: T::ItemId,//竞拍对象
@@ -206,9 +1181,60 @@ decl_module! {
 
 			Self::do_add_item(&sender, auction_id,item)
 		}
+
+		// Owner can list several items as a multi-unit lot instead of a single item, each of the
+		// best bidders gets one unit at the price given by `pricing`. Only allowed before the auction is started.
+		#[weight = SimpleDispatchInfo::FixedNormal(SETUP_CALL_WEIGHT)]
+		pub fn add_lot_items(
+			origin,
+			auction_id: T::AuctionId,
+			items: Vec<T::ItemId>,
+			pricing: LotPricing
+		) -> Result {
+			let sender = ensure_signed(origin)?;
+
+			let mut auction = Self::_ensure_auction_with_status(auction_id, Some(AuctionStatus::PendingStart), Some(&sender))?;
+			ensure!(auction.item.is_none(), "Auction already has a single item");
+			ensure!(auction.raffle.is_none(), "Raffle can not sell a lot");
+			ensure!(!auction.claim_settlement, "Claim settled auction can not sell a lot");
+			ensure!(auction.inspection_period.is_none(), "Inspected auction can not sell a lot");
+			ensure!(auction.installment_plan.is_none(), "Lot can not be paid by installments");
+			ensure!(auction.collateral_ratio.is_none(), "Lot can not take collateral bids");
+			ensure!(items.len() <= MAX_LOT_SIZE, "Too many items in a lot");
+			for (index, item) in items.iter().enumerate() {
+				ensure!(!items[..index].contains(item), "Duplicate item in lot");
+				ensure!(!Self::is_blacklisted(item), "Item is blacklisted");
+				ensure!(T::AuctionTransfer::is_item_owner(&sender, *item), "you should be item's owner.");
+				Self::ensure_can_transfer(&sender, *item, Some(auction_id))?;
+				ensure!(Self::auction_items(item).map(|id| id == auction_id).unwrap_or(true), "Item is already in another auction");
+				ensure!(!T::ItemLocks::is_item_locked(*item), "Item is locked by another module");
+				if let Some(floor) = T::SuggestedPrice::suggested_price(*item) {
+					ensure!(auction.begin_price >= floor, "Begin price is below the suggested floor");
+				}
+			}
+
+			// hold the new items first, then replace the old lot mappings
+			let added: Vec<T::ItemId> = items.iter().filter(|item| !auction.lot.contains(item)).cloned().collect();
+			Self::hold_items(&sender, &added)?;
+			for item in auction.lot.iter() {
+				<AuctionItems<T, I>>::remove(item);
+				if !items.contains(item) {
+					T::AuctionTransfer::unreserve_item(*item);
+				}
+			}
+			for item in items.iter() {
+				<AuctionItems<T, I>>::insert(item, auction_id);
+			}
+			auction.lot = items;
+			auction.lot_pricing = pricing;
+			<Auctions<T, I>>::insert(auction_id, auction);
+
+			Ok(())
+		}
 		// setup start and/or stop Moment, and wait_period after someone's bid
 		// add by sunhao 20191023
 		// separated by Tang 20191024
+		#[weight = SimpleDispatchInfo::FixedNormal(SETUP_CALL_WEIGHT)]
 		pub fn setup_moments(origin,
 			auction_id: T::AuctionId, 
 			start_at: Option<T::Moment>,  //起拍时间
@@ -220,680 +1246,3284 @@ decl_module! {
 			Self::do_setup_moments(&sender, auction_id, start_at, stop_at, wait_period)
 		}
 
-		// Owner can pause the auction when it is in active.
-		// add by sunhao 20191024
-		// separated by Tang 20191024
-		pub fn pause_auction(origin, auction_id: T::AuctionId) -> Result {
+		// Owner can put the auction into a future round instead of setting its moments,
+		// it then opens and closes together with the other auctions of the round.
+		#[weight = SimpleDispatchInfo::FixedNormal(SETUP_CALL_WEIGHT)]
+		pub fn join_round(origin, auction_id: T::AuctionId, round: u32) -> Result {
 			let sender = ensure_signed(origin)?;
 
-			Self::do_pause_auction(&sender, auction_id)
+			let mut auction = Self::_ensure_auction_with_status(auction_id, Some(AuctionStatus::PendingStart), Some(&sender))?;
+			ensure!(auction.round.is_none(), "Auction is already in a round");
+			let (start_at, stop_at) = Self::round_moments(round);
+			ensure!(start_at > <aura::Module<T>>::last(), "Round is already started");
+
+			auction.start_at = Some(start_at);
+			auction.stop_at = Some(stop_at);
+			auction.wait_period = None;
+			auction.round = Some(round);
+			<Auctions<T, I>>::insert(auction_id, auction);
+			<RoundAuctions<T, I>>::mutate(round, |ids| ids.push(auction_id));
+			Self::add_all_to_set(StoreVecs::PendingVec, &vec![auction_id]);
+
+			Self::deposit_event(RawEvent::RoundJoined(auction_id, round));
+			Ok(())
 		}
 
-		// Owner can resume the auction paused before.
-		// add by sunhao 20191024
-		// separated by Tang 20191024
-		pub fn resume_auction(origin, auction_id: T::AuctionId) -> Result {
+		// Owner can make the auction private, bidders should provide the owner's signature
+		// over `invite_payload` of the auction and themselves.
+		// Only allowed before the auction is started.
+		#[weight = SimpleDispatchInfo::FixedNormal(SETUP_CALL_WEIGHT)]
+		pub fn setup_invite(origin, auction_id: T::AuctionId, invite_only: bool) -> Result {
 			let sender = ensure_signed(origin)?;
 
-			Self::do_resume_auction(&sender, auction_id)
+			let mut auction = Self::_ensure_auction_with_status(auction_id, Some(AuctionStatus::PendingStart), Some(&sender))?;
+			auction.invite_only = invite_only;
+			<Auctions<T, I>>::insert(auction_id, auction);
+
+			Ok(())
 		}
 
-		// owner can stop an active or paused auction by his will.
-		// add by sunhao 20191024
-		pub fn stop_auction(
-			origin,
-			auction_id: T::AuctionId
-		) -> Result {
+		// Owner can attach the hash of the terms of sale, which bidders have to acknowledge.
+		// Only allowed before the auction is started.
+		#[weight = SimpleDispatchInfo::FixedNormal(SETUP_CALL_WEIGHT)]
+		pub fn setup_terms(origin, auction_id: T::AuctionId, terms_hash: Option<T::Hash>) -> Result {
 			let sender = ensure_signed(origin)?;
 
-			Self::do_stop_auction(&sender, auction_id)?;
-			
+			let mut auction = Self::_ensure_auction_with_status(auction_id, Some(AuctionStatus::PendingStart), Some(&sender))?;
+			auction.terms_hash = terms_hash;
+			<Auctions<T, I>>::insert(auction_id, auction);
+
 			Ok(())
 		}
 
-		pub fn participate_auction(
+		// Owner can split the proceeds across several beneficiaries, shares should sum to 100%.
+		// An empty split pays everything to the owner. Only allowed before the auction is started.
+		#[weight = SimpleDispatchInfo::FixedNormal(SETUP_CALL_WEIGHT)]
+		pub fn setup_payout_split(
 			origin,
 			auction_id: T::AuctionId,
-			price: BalanceOf<T>
+			payout_split: Vec<(T::AccountId, Permill)>
 		) -> Result {
-			let participant = ensure_signed(origin)?;
-
-			// unwrap auction and ensure its status is Active
-			let auction = Self::_ensure_auction_with_status(auction_id, Some(AuctionStatus::Active), None)?;
-
-			match auction.latest_participate {
-					Some((_account, _moment)) => { // 已经有用户出价
-							let bid_price = <AuctionBids<T>>::get(auction.id, _account);
-							ensure!(price > bid_price + auction.minimum_step, "Increment of bid price less than minimum step ");
-					},
-					_ => {}, // 尚无用户出价
-			};
-
-			let mut delta_price = price;
-			if <AuctionBids<T>>::exists(auction.id, &participant) { // 已经参与过的用户再次出价
-				let prev_bid = <AuctionBids<T>>::get(auction.id, &participant);
-				delta_price = price - prev_bid;
-			}
+			let sender = ensure_signed(origin)?;
 
-			ensure!(delta_price < T::Currency::free_balance(&participant), "No enough balance to lock");
+			let mut auction = Self::_ensure_auction_with_status(auction_id, Some(AuctionStatus::PendingStart), Some(&sender))?;
+			Self::ensure_valid_payout_split(&payout_split)?;
+			auction.payout_split = payout_split;
+			<Auctions<T, I>>::insert(auction_id, auction);
 
-			Self::do_lock_balance(&auction_id, &participant, delta_price)?;
-			Self::do_participate_auction(&auction_id, &participant, price)?;
-			
 			Ok(())
 		}
 
-		// No need [commented by Tang]
-		// //query one auction with auctionId
-		// pub fn query_one_auction(
-		// 	origin,
-		// 	auction: T::AuctionId,
-		// 	) {
-		// 	let sender = ensure_signed(origin)?;
-		// 	Self::do_query_one_auction(auction, sender.clone())?;
-		// }
+		// Owner can mark the auction as a charity auction, the given percent of the proceeds
+		// is donated at settlement. Only allowed before the auction is started.
+		#[weight = SimpleDispatchInfo::FixedNormal(SETUP_CALL_WEIGHT)]
+		pub fn setup_charity(
+			origin,
+			auction_id: T::AuctionId,
+			charity: Option<(T::AccountId, Permill)>
+		) -> Result {
+			let sender = ensure_signed(origin)?;
 
-		// ===== passive method =====
-		// starting auction methods
-		// Called by offchain worker
-		fn start_auctions_passive(
+			let mut auction = Self::_ensure_auction_with_status(auction_id, Some(AuctionStatus::PendingStart), Some(&sender))?;
+			if let Some((_, percent)) = &charity {
+				ensure!(percent.deconstruct() > 0, "Donation percent should not be zero");
+			}
+			auction.charity = charity;
+			<Auctions<T, I>>::insert(auction_id, auction);
+
+			Ok(())
+		}
+
+		// Owner can opt in to relist the item automatically when the auction ends with no bids.
+		// Only allowed before the auction is started.
+		#[weight = SimpleDispatchInfo::FixedNormal(SETUP_CALL_WEIGHT)]
+		pub fn setup_relist_policy(
 			origin,
-			auction_ids: Vec<T::AuctionId>,
-			signature: SignatureOf<T>
+			auction_id: T::AuctionId,
+			relist_policy: Option<RelistPolicy>
 		) -> Result {
-			ensure_none(origin)?;
-			// ensure status
-			ensure!(Self::is_auctions_with_status(&auction_ids, AuctionStatus::PendingStart, false), "auctions should be pending start");
+			let sender = ensure_signed(origin)?;
 
-			// key validating
-			if let Some(key) = Self::authority_id() {
-				let signature_valid = auction_ids.using_encoded(|encoded_auction_ids| {
-					key.verify(&encoded_auction_ids, &signature)
-				});
-				ensure!(signature_valid, "Invalid signature.");
+			let mut auction = Self::_ensure_auction_with_status(auction_id, Some(AuctionStatus::PendingStart), Some(&sender))?;
+			auction.relist_policy = relist_policy;
+			<Auctions<T, I>>::insert(auction_id, auction);
 
-				// set status as active
-				auction_ids.iter().for_each(|auction_id| {
-					Self::_change_auction_status(*auction_id, AuctionStatus::PendingStart, AuctionStatus::Active);
-				});
-				// remove auction_ids from pendings
-				Self::remove_all_from_set(StoreVecs::PendingVec, &auction_ids);
-				// add auction_ids to active_auctions
-				Self::add_all_to_set(StoreVecs::ActiveVec, &auction_ids);
+			Ok(())
+		}
 
-				Ok(())
-			} else {
-				Err("Non existent public key.")?
+		// Owner can turn the auction into a raffle, or back with None.
+		// Only allowed before the auction is started.
+		#[weight = SimpleDispatchInfo::FixedNormal(SETUP_CALL_WEIGHT)]
+		pub fn setup_raffle(
+			origin,
+			auction_id: T::AuctionId,
+			raffle: Option<RaffleConfig<BalanceOf<T, I>>>
+		) -> Result {
+			let sender = ensure_signed(origin)?;
+
+			let mut auction = Self::_ensure_auction_with_status(auction_id, Some(AuctionStatus::PendingStart), Some(&sender))?;
+			if let Some(config) = &raffle {
+				ensure!(!config.ticket_price.is_zero(), "Ticket price should not be zero");
+				ensure!(config.max_tickets > 0 && config.max_tickets <= MAX_RAFFLE_TICKETS, "Invalid max tickets");
 			}
+			ensure!(raffle.is_none() || !auction.claim_settlement, "Raffle can not be claim settled");
+			ensure!(raffle.is_none() || auction.inspection_period.is_none(), "Raffle can not be inspected");
+			ensure!(raffle.is_none() || auction.lot.is_empty(), "Raffle can not sell a lot");
+			ensure!(raffle.is_none() || auction.installment_plan.is_none(), "Raffle can not be paid by installments");
+			ensure!(raffle.is_none() || auction.collateral_ratio.is_none(), "Raffle can not take collateral bids");
+			auction.raffle = raffle;
+			<Auctions<T, I>>::insert(auction_id, auction);
+
+			Ok(())
 		}
 
-		// stoping auction methods
-		// Called by offchain worker
-		fn stop_auctions_passive(
+		// Owner can leave the settlement to the winner, who pays and takes the item with `claim_item`.
+		// Only allowed before the auction is started.
+		#[weight = SimpleDispatchInfo::FixedNormal(SETUP_CALL_WEIGHT)]
+		pub fn setup_claim_settlement(origin, auction_id: T::AuctionId, enabled: bool) -> Result {
+			let sender = ensure_signed(origin)?;
+
+			let mut auction = Self::_ensure_auction_with_status(auction_id, Some(AuctionStatus::PendingStart), Some(&sender))?;
+			ensure!(!enabled || auction.raffle.is_none(), "Raffle can not be claim settled");
+			ensure!(!enabled || auction.inspection_period.is_none(), "Inspected auction can not be claim settled");
+			ensure!(!enabled || auction.lot.is_empty(), "Claim settled auction can not sell a lot");
+			ensure!(!enabled || auction.installment_plan.is_none(), "Claim settled auction can not be paid by installments");
+			auction.claim_settlement = enabled;
+			<Auctions<T, I>>::insert(auction_id, auction);
+
+			Ok(())
+		}
+
+		// Owner can give the winner an inspection period after the close, during which the
+		// winner may reject the item. Only allowed before the auction is started.
+		#[weight = SimpleDispatchInfo::FixedNormal(SETUP_CALL_WEIGHT)]
+		pub fn setup_inspection(origin, auction_id: T::AuctionId, period: Option<T::Moment>) -> Result {
+			let sender = ensure_signed(origin)?;
+
+			let mut auction = Self::_ensure_auction_with_status(auction_id, Some(AuctionStatus::PendingStart), Some(&sender))?;
+			if period.is_some() {
+				ensure!(auction.raffle.is_none(), "Raffle can not be inspected");
+				ensure!(!auction.claim_settlement, "Claim settled auction can not be inspected");
+				ensure!(auction.lot.is_empty(), "Inspected auction can not sell a lot");
+				ensure!(auction.installment_plan.is_none(), "Inspected auction can not be paid by installments");
+			}
+			auction.inspection_period = period;
+			<Auctions<T, I>>::insert(auction_id, auction);
+
+			Ok(())
+		}
+
+		// Owner can let the winner pay by installments, the item is transferred once fully paid.
+		// Only allowed before the auction is started.
+		#[weight = SimpleDispatchInfo::FixedNormal(SETUP_CALL_WEIGHT)]
+		pub fn setup_installments(
 			origin,
-			auction_ids: Vec<T::AuctionId>,
-			signature: SignatureOf<T>
+			auction_id: T::AuctionId,
+			plan: Option<InstallmentPlan<T::Moment>>
 		) -> Result {
-			ensure_none(origin)?;
-			// ensure status
-			ensure!(Self::is_auctions_with_status(&auction_ids, AuctionStatus::Stopped, true), "auctions should be non stopped");
+			let sender = ensure_signed(origin)?;
 
-			// key validating
-			if let Some(key) = Self::authority_id() {
-				let signature_valid = auction_ids.using_encoded(|encoded_auction_ids| {
-					key.verify(&encoded_auction_ids, &signature)
-				});
-				ensure!(signature_valid, "Invalid signature.");
+			let mut auction = Self::_ensure_auction_with_status(auction_id, Some(AuctionStatus::PendingStart), Some(&sender))?;
+			if let Some(plan) = &plan {
+				ensure!(plan.installments > 0 && plan.installments <= MAX_INSTALLMENTS, "Invalid number of installments");
+				ensure!(!plan.interval.is_zero(), "Installment interval should not be zero");
+				ensure!(auction.raffle.is_none(), "Raffle can not be paid by installments");
+				ensure!(auction.lot.is_empty(), "Lot can not be paid by installments");
+				ensure!(!auction.claim_settlement, "Claim settled auction can not be paid by installments");
+				ensure!(auction.inspection_period.is_none(), "Inspected auction can not be paid by installments");
+				ensure!(auction.collateral_ratio.is_none(), "Auction paid by installments can not take collateral bids");
+			}
+			auction.installment_plan = plan;
+			<Auctions<T, I>>::insert(auction_id, auction);
 
-				// set status as active
-				auction_ids.iter().for_each(|auction_id| {
-					if let Some(auction) = Self::auctions(auction_id) {
-						// call settle func if needed.
-						if auction.status != AuctionStatus::PendingStart {
-							match Self::do_settle_auction(&auction) {
-								Err(_) => {}, // DO SOMETHING?
-								Ok(_) => {},
-							}
-						}
-						Self::_change_auction_status(*auction_id, auction.status, AuctionStatus::Stopped);
-					}
-				});
-				// remove auction_ids from active_auctions
-				Self::remove_all_from_set(StoreVecs::ActiveVec, &auction_ids);
+			Ok(())
+		}
 
-				Ok(())
-			} else {
-				Err("Non existent public key.")?
+		// Owner can let bidders lock only a part of their bids as collateral, the balance
+		// is due at settlement. Only allowed before the auction is started.
+		#[weight = SimpleDispatchInfo::FixedNormal(SETUP_CALL_WEIGHT)]
+		pub fn setup_collateral(origin, auction_id: T::AuctionId, ratio: Option<Permill>) -> Result {
+			let sender = ensure_signed(origin)?;
+
+			let mut auction = Self::_ensure_auction_with_status(auction_id, Some(AuctionStatus::PendingStart), Some(&sender))?;
+			if let Some(ratio) = ratio {
+				ensure!(ratio.deconstruct() > 0, "Collateral ratio should not be zero");
+				ensure!(auction.raffle.is_none(), "Raffle can not take collateral bids");
+				ensure!(auction.lot.is_empty(), "Lot can not take collateral bids");
+				ensure!(auction.installment_plan.is_none(), "Auction paid by installments can not take collateral bids");
 			}
+			auction.collateral_ratio = ratio;
+			<Auctions<T, I>>::insert(auction_id, auction);
+
+			Ok(())
 		}
-		
-		// Runs after every block.
-		fn offchain_worker(now: <T as system::Trait>::BlockNumber) {
-			debug::RuntimeLogger::init();
 
-			// Only send messages if we are a potential validator.
-			if runtime_io::is_validator() {
-				Self::offchain(now);
+		// Owner can require bids to be multiples of a denomination, keeping the bid ladder clean.
+		// Only allowed before the auction is started.
+		#[weight = SimpleDispatchInfo::FixedNormal(SETUP_CALL_WEIGHT)]
+		pub fn setup_bid_denomination(origin, auction_id: T::AuctionId, denomination: Option<BalanceOf<T, I>>) -> Result {
+			let sender = ensure_signed(origin)?;
+
+			let mut auction = Self::_ensure_auction_with_status(auction_id, Some(AuctionStatus::PendingStart), Some(&sender))?;
+			if let Some(denomination) = denomination {
+				ensure!(!denomination.is_zero(), "Bid denomination should not be zero");
+				ensure!((auction.begin_price % denomination).is_zero(), "Begin price is not a multiple of the denomination");
 			}
+			auction.bid_denomination = denomination;
+			<Auctions<T, I>>::insert(auction_id, auction);
+
+			Ok(())
 		}
-	}
-}
 
-impl<T: Trait> Module<T> {
-	// ====== exported public methods ======
-	/// Returns own authority identifier iff it is part of the current authority
-	/// set, otherwise this function returns None. The restriction might be
-	/// softened in the future in case a consumer needs to learn own authority
-	/// identifier.
-	pub fn authority_id() -> Option<T::AuthorityId> {
-		let authorities = <aura::Module<T>>::authorities();
+		// Owner can let the auction close at a random moment shortly after stop_at,
+		// so bots can not aim their bids at the exact closing block.
+		// Only allowed before the auction is started.
+		#[weight = SimpleDispatchInfo::FixedNormal(SETUP_CALL_WEIGHT)]
+		pub fn setup_close_jitter(origin, auction_id: T::AuctionId, enabled: bool) -> Result {
+			let sender = ensure_signed(origin)?;
 
-		let local_keys = T::AuthorityId::all();
+			let mut auction = Self::_ensure_auction_with_status(auction_id, Some(AuctionStatus::PendingStart), Some(&sender))?;
+			if enabled {
+				ensure!(!T::MaxCloseJitter::get().is_zero(), "Close jitter is disabled");
+			}
+			auction.close_jitter = enabled;
+			<Auctions<T, I>>::insert(auction_id, auction);
 
-		authorities.into_iter().find_map(|authority| {
-			if local_keys.contains(&authority) {
-				Some(authority)
+			Ok(())
+		}
+
+		// The winner pays the next installment, the last one transfers the item.
+		pub fn pay_installment(origin, auction_id: T::AuctionId) -> Result {
+			let sender = ensure_signed(origin)?;
+
+			let mut state = Self::installment(auction_id).ok_or("Auction is not paid by installments")?;
+			ensure!(sender == state.winner, "Only the winner can pay installments");
+			let auction = Self::auctions(auction_id).ok_or("Auction not exist")?;
+			let plan = auction.installment_plan.ok_or("Auction is not paid by installments")?;
+
+			let amount = if state.installments_left > 1 {
+				state.remaining / state.installments_left.into()
 			} else {
-				None
+				state.remaining
+			};
+			let remaining = state.remaining.checked_sub(&amount).ok_or("balance sub overflow")?;
+			let last = state.installments_left <= 1;
+			// the installment is paid from the reserve, check the winner's locks before unreserving it
+			ensure!(T::Currency::reserved_balance(&sender) >= amount, "Installments are not reserved");
+			T::Currency::ensure_can_withdraw(
+				&sender,
+				amount,
+				WithdrawReason::Transfer.into(),
+				T::Currency::free_balance(&sender),
+			)?;
+			// the last installment hands over the item, check it before any payment
+			if let (true, Some(item_id)) = (last, auction.item) {
+				T::AuctionTransfer::can_transfer_reserved(&auction.owner, item_id).map_err(Error::from)?;
 			}
-		})
-	}
-	pub fn is_auctions_with_status (
-		auction_ids: &Vec<T::AuctionId>,
-		status: AuctionStatus,
-		not_equal: bool
-	) -> bool {
-		auction_ids.iter().all(|&auction_id| {
-			if let Some(auction) = Self::auctions(auction_id) {
-				if not_equal {
-					auction.status != status
-				} else {
-					auction.status == status
+			T::Currency::unreserve(&sender, amount);
+			// the payer is checked before any funds move, a failed payment only takes the reserve back
+			let payment = match Self::collect_payments(&auction, &vec![(sender.clone(), amount)]) {
+				Ok(payment) => payment,
+				Err(e) => {
+					let _ = T::Currency::reserve(&sender, amount);
+					return Err(e);
+				},
+			};
+			// only the owner's share is refunded on default
+			let received = payment.payouts.iter()
+				.filter(|(beneficiary, _)| *beneficiary == auction.owner)
+				.fold(Zero::zero(), |sum: BalanceOf<T, I>, (_, amount)| sum.saturating_add(*amount));
+
+			state.remaining = remaining;
+			state.paid = state.paid.saturating_add(received);
+			state.installments_left = state.installments_left.saturating_sub(1);
+			state.next_due = state.next_due.saturating_add(plan.interval);
+
+			if !last {
+				Self::deposit_event(RawEvent::InstallmentPaid(auction_id, sender.clone(), amount, state.remaining));
+				<Installments<T, I>>::insert(auction_id, state);
+				return Ok(());
+			}
+
+			// hand over the item first, the installment record is only removed once it is done
+			if let Some(item_id) = auction.item {
+				T::AuctionTransfer::transfer_reserved_item(&auction.owner, &sender, item_id).map_err(Error::from)?;
+			}
+			<Installments<T, I>>::remove(auction_id);
+			Self::deposit_event(RawEvent::InstallmentPaid(auction_id, sender.clone(), amount, state.remaining));
+			if let Some(item_id) = auction.item {
+				let price = Self::auction_bids(auction_id, &sender);
+				Self::deposit_event(RawEvent::AuctionSettled(auction_id, sender.clone(), price));
+				T::OnItemSold::on_item_sold(&auction.owner, &sender, item_id, price);
+				Self::record_win(&sender, auction_id, item_id, price);
+				Self::call_settlement_callback(&auction, item_id, &sender, price);
+			}
+			Self::release_item(&auction);
+
+			Ok(())
+		}
+
+		// The owner takes back the item once an installment is overdue, refunding what it
+		// received from the installments so far. The down payment is not refunded.
+		pub fn repossess_item(origin, auction_id: T::AuctionId) -> Result {
+			let sender = ensure_signed(origin)?;
+
+			let state = Self::installment(auction_id).ok_or("Auction is not paid by installments")?;
+			let auction = Self::auctions(auction_id).ok_or("Auction not exist")?;
+			ensure!(sender == auction.owner, "Only owner can call this fn.");
+			ensure!(<aura::Module<T>>::last() > state.next_due, "Installment is not overdue");
+
+			// proceeds still vesting are released for the refund
+			let free = T::Currency::free_balance(&sender);
+			let locked = Self::vesting_locked_total(&sender);
+			ensure!(
+				free >= state.paid && free - state.paid >= locked.saturating_sub(state.paid),
+				"Owner can not refund the installments"
+			);
+			Self::release_vesting(&sender, state.paid);
+			T::Currency::transfer(&sender, &state.winner, state.paid)?;
+			T::Currency::unreserve(&state.winner, state.remaining);
+			<Installments<T, I>>::remove(auction_id);
+			Self::release_item(&auction);
+			T::OnAuctionPenalty::on_payment_default(&state.winner);
+			Self::deposit_event(RawEvent::ItemRepossessed(auction_id, state.winner, state.paid));
+
+			Ok(())
+		}
+
+		// The winner accepts the item during inspection, which settles the auction.
+		pub fn accept_item(origin, auction_id: T::AuctionId) -> Result {
+			let sender = ensure_signed(origin)?;
+
+			let (winner, _) = Self::inspection(auction_id).ok_or("Auction is not in inspection")?;
+			ensure!(sender == winner, "Only the winner can accept the item");
+
+			Self::finish_inspection(auction_id, true)
+		}
+
+		// The winner rejects the item during inspection, forfeiting `RejectionPenalty` of the bid
+		// to the seller who keeps the item.
+		pub fn reject_item(origin, auction_id: T::AuctionId) -> Result {
+			let sender = ensure_signed(origin)?;
+
+			let (winner, deadline) = Self::inspection(auction_id).ok_or("Auction is not in inspection")?;
+			ensure!(sender == winner, "Only the winner can reject the item");
+			ensure!(<aura::Module<T>>::last() <= deadline, "Inspection period is over");
+
+			Self::finish_inspection(auction_id, false)
+		}
+
+		// The winner of a claim-settled auction pays and takes the item.
+		// After the claim deadline anyone can settle it on the winner's behalf.
+		pub fn claim_item(origin, auction_id: T::AuctionId) -> Result {
+			let sender = ensure_signed(origin)?;
+
+			let (winner, deadline) = Self::pending_claim(auction_id).ok_or("No pending claim for the auction")?;
+			ensure!(sender == winner || <aura::Module<T>>::last() > deadline, "Only the winner can claim before the deadline");
+			let auction = Self::auctions(auction_id).ok_or("Auction not exist")?;
+			// check before any write, so a failed claim can be retried
+			Self::ensure_can_settle(&auction, &winner)?;
+
+			Self::do_unlock_winner(&auction_id, &winner)?;
+			Self::do_pay_and_transfer(&auction)?;
+			<PendingClaims<T, I>>::remove(auction_id);
+			Self::release_item(&auction);
+
+			Ok(())
+		}
+
+		// Anyone can purge an auction left pending without moments for `StaleAuctionTimeout`,
+		// for a bounty out of its creation deposit.
+		pub fn purge_stale_auction(origin, auction_id: T::AuctionId) -> Result {
+			let sender = ensure_signed(origin)?;
+
+			let auction = Self::auctions(auction_id).ok_or("Auction not exist")?;
+			ensure!(Self::is_stale(&auction, <aura::Module<T>>::last()), "Auction is not stale");
+			Self::do_purge_stale(&auction, Some(&sender))?;
+
+			Ok(())
+		}
+
+		// Anyone can remove item mappings left behind by auctions which are over,
+		// so the items can be listed again.
+		pub fn repair_item_mapping(origin, items: Vec<T::ItemId>) -> Result {
+			ensure_signed(origin)?;
+			ensure!(items.len() <= MAX_REPAIR_ITEMS, "Too many items to repair");
+
+			for item in items {
+				if let Some(auction_id) = Self::auction_items(item) {
+					if Self::is_orphaned_mapping(item, auction_id) {
+						<AuctionItems<T, I>>::remove(item);
+						T::AuctionTransfer::unreserve_item(item);
+						Self::deposit_event(RawEvent::ItemMappingRepaired(item, auction_id));
+					}
 				}
+			}
+
+			Ok(())
+		}
+
+		// The winner rates the seller or the seller rates the winner, once each and within
+		// `RatingWindow` after the settlement.
+		#[weight = SimpleDispatchInfo::FixedNormal(SETUP_CALL_WEIGHT)]
+		pub fn rate_counterparty(origin, auction_id: T::AuctionId, stars: u8) -> Result {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(stars >= 1 && stars <= 5, "Rating should be from 1 to 5 stars");
+			let receipt = Self::settlement_receipt(auction_id).ok_or("Auction is not settled")?;
+			let rated = if sender == receipt.winner {
+				receipt.seller
+			} else if sender == receipt.seller {
+				receipt.winner
 			} else {
-				false
+				return Err("Only the seller or the winner can rate");
+			};
+			let deadline = receipt.settled_at.saturating_add(T::RatingWindow::get());
+			ensure!(<aura::Module<T>>::last() <= deadline, "Rating window is closed");
+			ensure!(!<AuctionRatings<T, I>>::exists(auction_id, &sender), "Already rated");
+
+			<AuctionRatings<T, I>>::insert(auction_id, &sender, stars);
+			T::OnAuctionRated::on_rated(&rated, stars);
+			Self::deposit_event(RawEvent::CounterpartyRated(auction_id, rated, sender, stars));
+
+			Ok(())
+		}
+
+		// Losing bidders of an auction with many bidders unlock their bids themselves.
+		pub fn claim_refund(origin, auction_id: T::AuctionId) -> Result {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(Self::pull_refund_deadline(auction_id).is_some(), "Auction has no refunds to claim");
+			ensure!(<AuctionBids<T, I>>::exists(auction_id, &sender), "No bid to refund");
+			ensure!(!Self::refund_claimed(auction_id, &sender), "Refund already claimed");
+
+			Self::do_unlock_balance(&auction_id, &sender)?;
+			<RefundClaimed<T, I>>::insert(auction_id, &sender, true);
+
+			Ok(())
+		}
+
+		// Deposit funds into the sender's bidding credit, which backs bids in any auction
+		// without locking funds for each of them.
+		pub fn deposit_credit(origin, amount: BalanceOf<T, I>) -> Result {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(!amount.is_zero(), "Credit amount should not be zero");
+			let credit = Self::bid_credit(&sender).checked_add(&amount).ok_or("balance add overflow")?;
+			T::Currency::reserve(&sender, amount)?;
+			<BidCredits<T, I>>::insert(&sender, credit);
+			Self::deposit_event(RawEvent::CreditDeposited(sender, amount));
+
+			Ok(())
+		}
+
+		// Withdraw the bidding credit which is not backing any bid.
+		pub fn withdraw_credit(origin, amount: BalanceOf<T, I>) -> Result {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(amount <= Self::available_credit(&sender), "Not enough available credit");
+			<BidCredits<T, I>>::mutate(&sender, |credit| *credit = credit.saturating_sub(amount));
+			T::Currency::unreserve(&sender, amount);
+			Self::deposit_event(RawEvent::CreditWithdrawn(sender, amount));
+
+			Ok(())
+		}
+
+		// Owner can have the proceeds vested over `VestingPeriod` instead of paid at once.
+		// Only allowed before the auction is started.
+		#[weight = SimpleDispatchInfo::FixedNormal(SETUP_CALL_WEIGHT)]
+		pub fn setup_proceeds_vesting(origin, auction_id: T::AuctionId, enabled: bool) -> Result {
+			let sender = ensure_signed(origin)?;
+
+			let mut auction = Self::_ensure_auction_with_status(auction_id, Some(AuctionStatus::PendingStart), Some(&sender))?;
+			auction.vest_proceeds = enabled;
+			<Auctions<T, I>>::insert(auction_id, auction);
+
+			Ok(())
+		}
+
+		// Governance can require the proceeds of any auction above the threshold to be vested.
+		pub fn set_vesting_threshold(origin, threshold: Option<BalanceOf<T, I>>) -> Result {
+			ensure_root(origin)?;
+
+			match threshold {
+				Some(threshold) => <VestingThreshold<T, I>>::put(threshold),
+				None => <VestingThreshold<T, I>>::kill(),
+			}
+
+			Ok(())
+		}
+
+		// Anyone can bond `ChallengeBond` to accuse an auction of shill bidding,
+		// which holds its settlement until the moderation origin rules on it.
+		pub fn challenge_auction(origin, auction_id: T::AuctionId) -> Result {
+			let sender = ensure_signed(origin)?;
+
+			let auction = Self::auctions(auction_id).ok_or("Auction not exist")?;
+			ensure!(
+				auction.status == AuctionStatus::Active
+					|| auction.status == AuctionStatus::Paused
+					|| Self::settlement_queue().contains(&auction_id),
+				"Auction can not be challenged now"
+			);
+			ensure!(!<Challenges<T, I>>::exists(auction_id), "Auction is already challenged");
+
+			let bond = T::ChallengeBond::get();
+			T::Currency::reserve(&sender, bond)?;
+			let deadline = <aura::Module<T>>::last().saturating_add(T::ReviewTimeout::get());
+			<Challenges<T, I>>::insert(auction_id, (sender.clone(), bond, deadline));
+			Self::deposit_event(RawEvent::ShillChallenged(auction_id, sender, bond));
+
+			Ok(())
+		}
+
+		// Anyone can lapse a challenge the moderation origin did not rule on within
+		// `ReviewTimeout`, the challenger gets its bond back and the auction settles again.
+		pub fn expire_challenge(origin, auction_id: T::AuctionId) -> Result {
+			ensure_signed(origin)?;
+
+			let (reporter, bond, deadline) = Self::challenge(auction_id).ok_or("Auction is not challenged")?;
+			ensure!(<aura::Module<T>>::last() > deadline, "Challenge is not expired");
+			let auction = Self::auctions(auction_id).ok_or("Auction not exist")?;
+
+			T::Currency::unreserve(&reporter, bond);
+			<Challenges<T, I>>::remove(auction_id);
+			Self::release_held_deposit(&auction);
+			Self::deposit_event(RawEvent::ChallengeExpired(auction_id, reporter));
+
+			Ok(())
+		}
+
+		// The moderation origin upholds a challenge, which voids the sale and pays the seller's
+		// creation deposit to the reporter, or rejects it, which pays the reporter's bond to the seller.
+		pub fn rule_challenge(origin, auction_id: T::AuctionId, upheld: bool) -> Result {
+			T::ModerationOrigin::ensure_origin(origin)?;
+
+			let (reporter, bond, _) = Self::challenge(auction_id).ok_or("Auction is not challenged")?;
+			let auction = Self::auctions(auction_id).ok_or("Auction not exist")?;
+			// 可能失败的步骤都在修改存储之前完成
+			if upheld {
+				let deposit = Self::creation_deposit(auction_id).map_or(Zero::zero(), |(deposit, _)| deposit);
+				Self::ensure_can_receive(&reporter, deposit)?;
+				Self::void_sale(&auction)?;
+
+				<Challenges<T, I>>::remove(auction_id);
+				T::Currency::unreserve(&reporter, bond);
+				let slashed = Self::slash_creation_deposit(&auction, &reporter, deposit);
+				T::OnAuctionPenalty::on_report_upheld(&auction.owner);
+				Self::deposit_event(RawEvent::ChallengeUpheld(auction_id, reporter, slashed));
+			} else {
+				Self::ensure_can_receive(&auction.owner, bond)?;
+
+				<Challenges<T, I>>::remove(auction_id);
+				let _ = T::Currency::repatriate_reserved(&reporter, &auction.owner, bond);
+				Self::deposit_event(RawEvent::ChallengeRejected(auction_id, reporter, bond));
+			}
+			Self::release_held_deposit(&auction);
+
+			Ok(())
+		}
+
+		// Anyone can bond `ChallengeBond` to report an auction owner for fraud, which holds
+		// the auction's settlement until the moderation origin reviews the report.
+		pub fn report_auction(origin, auction_id: T::AuctionId, reason_hash: T::Hash) -> Result {
+			let sender = ensure_signed(origin)?;
+
+			let auction = Self::auctions(auction_id).ok_or("Auction not exist")?;
+			ensure!(
+				auction.status != AuctionStatus::Stopped || Self::settlement_queue().contains(&auction_id),
+				"Auction can not be reported now"
+			);
+			ensure!(!<Reports<T, I>>::exists(auction_id), "Auction is already reported");
+
+			let bond = T::ChallengeBond::get();
+			T::Currency::reserve(&sender, bond)?;
+			let deadline = <aura::Module<T>>::last().saturating_add(T::ReviewTimeout::get());
+			<Reports<T, I>>::insert(auction_id, (sender.clone(), reason_hash, bond, deadline));
+			<ReviewQueue<T, I>>::mutate(|queue| queue.push(auction_id));
+			Self::deposit_event(RawEvent::AuctionReported(auction_id, sender, reason_hash));
+
+			Ok(())
+		}
+
+		// Anyone can lapse a report the moderation origin did not review within
+		// `ReviewTimeout`, the reporter gets its bond back and the auction settles again.
+		pub fn expire_report(origin, auction_id: T::AuctionId) -> Result {
+			ensure_signed(origin)?;
+
+			let (reporter, _, bond, deadline) = Self::report(auction_id).ok_or("Auction is not reported")?;
+			ensure!(<aura::Module<T>>::last() > deadline, "Report is not expired");
+			let auction = Self::auctions(auction_id).ok_or("Auction not exist")?;
+
+			T::Currency::unreserve(&reporter, bond);
+			Self::remove_report(auction_id);
+			Self::release_held_deposit(&auction);
+			Self::deposit_event(RawEvent::ReportExpired(auction_id, reporter));
+
+			Ok(())
+		}
+
+		// The moderation origin upholds a report with a ruling, or dismisses it with None,
+		// which pays the reporter's bond to the owner.
+		pub fn review_report(origin, auction_id: T::AuctionId, ruling: Option<ReportRuling>) -> Result {
+			T::ModerationOrigin::ensure_origin(origin)?;
+
+			let (reporter, _, bond, _) = Self::report(auction_id).ok_or("Auction is not reported")?;
+			let auction = Self::auctions(auction_id).ok_or("Auction not exist")?;
+
+			// 可能失败的步骤都在修改存储之前完成
+			match ruling {
+				Some(ruling) => {
+					let deposit = match Self::creation_deposit(auction_id) {
+						Some((deposit, _)) if ruling.slash_deposit => deposit,
+						_ => Zero::zero(),
+					};
+					Self::ensure_can_receive(&reporter, deposit)?;
+					if ruling.refund_bidders {
+						Self::void_sale(&auction)?;
+					}
+
+					Self::remove_report(auction_id);
+					T::Currency::unreserve(&reporter, bond);
+					if ruling.slash_deposit {
+						Self::slash_creation_deposit(&auction, &reporter, deposit);
+					}
+					if ruling.blacklist_item {
+						for item in auction.item.iter().chain(auction.lot.iter()) {
+							<BlacklistedItems<T, I>>::insert(item, true);
+						}
+					}
+					T::OnAuctionPenalty::on_report_upheld(&auction.owner);
+					Self::deposit_event(RawEvent::ReportUpheld(auction_id, reporter, ruling));
+				},
+				None => {
+					Self::ensure_can_receive(&auction.owner, bond)?;
+
+					Self::remove_report(auction_id);
+					let _ = T::Currency::repatriate_reserved(&reporter, &auction.owner, bond);
+					Self::deposit_event(RawEvent::ReportDismissed(auction_id, reporter, bond));
+				},
+			}
+			Self::release_held_deposit(&auction);
+
+			Ok(())
+		}
+
+		// Governance can require a minimum reputation, or a higher deposit, to create auctions
+		// at or above a begin price.
+		pub fn set_creation_gate(origin, gate: Option<CreationGate<BalanceOf<T, I>>>) -> Result {
+			ensure_root(origin)?;
+
+			match gate {
+				Some(gate) => <CreationGateConfig<T, I>>::put(gate),
+				None => <CreationGateConfig<T, I>>::kill(),
+			}
+
+			Ok(())
+		}
+
+		// Governance can bound the wait period owners set, and whether they may turn it off.
+		pub fn set_wait_period_bounds(origin, bounds: Option<WaitPeriodBounds<T::Moment>>) -> Result {
+			ensure_root(origin)?;
+
+			match bounds {
+				Some(bounds) => {
+					ensure!(bounds.min <= bounds.max, "Invalid wait period bounds");
+					<WaitPeriodLimits<T, I>>::put(bounds);
+				},
+				None => <WaitPeriodLimits<T, I>>::kill(),
+			}
+
+			Ok(())
+		}
+
+		// Governance can change where auction fees go.
+		pub fn set_fee_destination(origin, destination: FeeDestination<T::AccountId>) -> Result {
+			ensure_root(origin)?;
+
+			if let FeeDestination::Split(shares) = &destination {
+				ensure!(!shares.is_empty(), "Fee split should not be empty");
+				Self::ensure_valid_payout_split(shares)?;
+			}
+			<FeeDestinationConfig<T, I>>::put(destination);
+
+			Ok(())
+		}
+
+		// Unlock the vested part of the sender's proceeds.
+		pub fn vest(origin) -> Result {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(<ProceedsVesting<T, I>>::exists(&sender), "No vesting proceeds");
+			Self::update_vesting_lock(&sender);
+
+			Ok(())
+		}
+
+		// Owner can register a contract to be called with (auction_id, item_id, winner, price)
+		// for every item sold by the auction, e.g. to unlock content or mint a certificate.
+		#[weight = CallbackWeight(SETUP_CALL_WEIGHT)]
+		pub fn setup_settlement_callback(
+			origin,
+			auction_id: T::AuctionId,
+			callback: Option<(T::AccountId, u64)>, // (合约地址, gas上限)
+		) -> Result {
+			let sender = ensure_signed(origin)?;
+
+			Self::_ensure_auction_with_status(auction_id, Some(AuctionStatus::PendingStart), Some(&sender))?;
+			match callback {
+				Some((contract, gas_limit)) => {
+					ensure!(T::Contracts::is_supported(), "Contracts are not supported");
+					ensure!(gas_limit <= T::MaxCallbackGas::get(), "Callback gas limit too high");
+					<SettlementCallbacks<T, I>>::insert(auction_id, (contract, gas_limit));
+				},
+				None => <SettlementCallbacks<T, I>>::remove(auction_id),
+			}
+
+			Ok(())
+		}
+
+		// Owner can add a co-manager, who may pause, resume and setup moments of the auction
+		// but not stop it or change its payouts.
+		#[weight = SimpleDispatchInfo::FixedNormal(SETUP_CALL_WEIGHT)]
+		pub fn add_manager(origin, auction_id: T::AuctionId, manager: T::AccountId) -> Result {
+			let sender = ensure_signed(origin)?;
+
+			let auction = Self::_ensure_auction_with_status(auction_id, None, Some(&sender))?;
+			ensure!(auction.status != AuctionStatus::Stopped, "Auction is already stopped");
+			ensure!(manager != sender, "Owner is already a manager");
+			let mut managers = Self::managers_of(auction_id);
+			ensure!(!managers.contains(&manager), "Already a manager");
+			ensure!(managers.len() < MAX_MANAGERS, "Too many managers");
+
+			managers.push(manager.clone());
+			<AuctionManagers<T, I>>::insert(auction_id, managers);
+			Self::deposit_event(RawEvent::ManagerAdded(auction_id, manager));
+
+			Ok(())
+		}
+
+		// Owner can remove a co-manager.
+		#[weight = SimpleDispatchInfo::FixedNormal(SETUP_CALL_WEIGHT)]
+		pub fn remove_manager(origin, auction_id: T::AuctionId, manager: T::AccountId) -> Result {
+			let sender = ensure_signed(origin)?;
+
+			Self::_ensure_auction_with_status(auction_id, None, Some(&sender))?;
+			let mut managers = Self::managers_of(auction_id);
+			let index = managers.iter().position(|m| *m == manager).ok_or("Not a manager")?;
+
+			managers.swap_remove(index);
+			if managers.is_empty() {
+				<AuctionManagers<T, I>>::remove(auction_id);
+			} else {
+				<AuctionManagers<T, I>>::insert(auction_id, managers);
+			}
+			Self::deposit_event(RawEvent::ManagerRemoved(auction_id, manager));
+
+			Ok(())
+		}
+
+		// Anyone can post a comment on an open auction, e.g. bidders ask and the owner answers,
+		// reserving `CommentDeposit` until the comments are pruned.
+		#[weight = SimpleDispatchInfo::FixedNormal(SETUP_CALL_WEIGHT)]
+		pub fn post_comment(origin, auction_id: T::AuctionId, text: Vec<u8>, reply_to: Option<u32>) -> Result {
+			let sender = ensure_signed(origin)?;
+
+			let auction = Self::auctions(auction_id).ok_or("Auction not exist")?;
+			ensure!(auction.status != AuctionStatus::Stopped, "Auction is already stopped");
+			ensure!(!text.is_empty(), "Comment should not be empty");
+			ensure!(text.len() <= MAX_COMMENT_LEN, "Comment too long");
+			let mut comments = Self::comments(auction_id);
+			ensure!(comments.len() < MAX_COMMENTS, "Too many comments");
+			ensure!(reply_to.map_or(true, |index| (index as usize) < comments.len()), "Replied comment does not exist");
+
+			let deposit = T::CommentDeposit::get();
+			T::Currency::reserve(&sender, deposit)?;
+			let index = comments.len() as u32;
+			comments.push(Comment {
+				author: sender.clone(),
+				text: text,
+				reply_to: reply_to,
+				deposit: deposit,
+				posted_at: <aura::Module<T>>::last(),
+			});
+			<AuctionComments<T, I>>::insert(auction_id, comments);
+			Self::deposit_event(RawEvent::CommentPosted(auction_id, sender, index, reply_to));
+
+			Ok(())
+		}
+
+		// Anyone can prune the comments of a settled auction, returning the deposits to their authors.
+		#[weight = SimpleDispatchInfo::FixedNormal(SETUP_CALL_WEIGHT)]
+		pub fn prune_comments(origin, auction_id: T::AuctionId) -> Result {
+			let _ = ensure_signed(origin)?;
+
+			let stopped = Self::auctions(auction_id).map_or(true, |a| a.status == AuctionStatus::Stopped);
+			ensure!(stopped && !Self::settlement_queue().contains(&auction_id), "Auction is not settled");
+			let comments = <AuctionComments<T, I>>::take(auction_id);
+			ensure!(!comments.is_empty(), "No comments to prune");
+
+			for comment in comments.iter() {
+				T::Currency::unreserve(&comment.author, comment.deposit);
+			}
+			Self::deposit_event(RawEvent::CommentsPruned(auction_id, comments.len() as u32));
+
+			Ok(())
+		}
+
+		// Owner can pause the auction when it is in active.
+		// add by sunhao 20191024
+		// separated by Tang 20191024
+		#[weight = SimpleDispatchInfo::FixedNormal(SETUP_CALL_WEIGHT)]
+		pub fn pause_auction(origin, auction_id: T::AuctionId) -> Result {
+			let sender = ensure_signed(origin)?;
+
+			Self::do_pause_auction(&sender, auction_id)
+		}
+
+		// Owner can resume the auction paused before.
+		// add by sunhao 20191024
+		// separated by Tang 20191024
+		#[weight = SimpleDispatchInfo::FixedNormal(SETUP_CALL_WEIGHT)]
+		pub fn resume_auction(origin, auction_id: T::AuctionId) -> Result {
+			let sender = ensure_signed(origin)?;
+
+			Self::do_resume_auction(&sender, auction_id)
+		}
+
+		// owner can stop an active or paused auction by his will.
+		// add by sunhao 20191024
+		pub fn stop_auction(
+			origin,
+			auction_id: T::AuctionId
+		) -> Result {
+			let sender = ensure_signed(origin)?;
+
+			Self::do_stop_auction(&sender, auction_id, true)?;
+			
+			Ok(())
+		}
+
+		// Owner stops many auctions at once, e.g. to wind down its listings. The whole batch is
+		// rejected unless every auction can be stopped, then each is stopped and settled like
+		// `stop_auction`, a failed settlement is reported without undoing the others.
+		#[weight = BatchWeight(STOP_CALL_WEIGHT)]
+		pub fn stop_auctions_batch(origin, auction_ids: Vec<T::AuctionId>) -> Result {
+			let sender = ensure_signed(origin)?;
+
+			Self::ensure_owner_batch(&auction_ids)?;
+			for auction_id in auction_ids.iter() {
+				Self::ensure_stoppable(&sender, *auction_id)?;
+			}
+			for auction_id in auction_ids {
+				let success = Self::do_stop_auction(&sender, auction_id, true).is_ok();
+				Self::deposit_event(RawEvent::BatchStopped(auction_id, sender.clone(), success));
+			}
+
+			Ok(())
+		}
+
+		// Owner cancels many auctions at once, so no item is sold. The whole batch is rejected
+		// unless every auction can be stopped and has no bid.
+		#[weight = BatchWeight(STOP_CALL_WEIGHT)]
+		pub fn cancel_auctions_batch(origin, auction_ids: Vec<T::AuctionId>) -> Result {
+			let sender = ensure_signed(origin)?;
+
+			Self::ensure_owner_batch(&auction_ids)?;
+			for auction_id in auction_ids.iter() {
+				Self::ensure_cancellable(&sender, *auction_id)?;
+			}
+			for auction_id in auction_ids {
+				let success = Self::do_cancel_auction(&sender, auction_id).is_ok();
+				Self::deposit_event(RawEvent::BatchCancelled(auction_id, sender.clone(), success));
+			}
+
+			Ok(())
+		}
+
+		pub fn participate_auction(
+			origin,
+			auction_id: T::AuctionId,
+			price: BalanceOf<T, I>,
+			invite: Option<T::BidSignature>, // 私密拍卖中拍卖者为出价人签发的邀请
+			terms_hash: Option<T::Hash>, // 确认接受的销售条款哈希
+			memo: Option<Vec<u8>> // 出价备注，仅在事件中发出
+		) -> Result {
+			let participant = ensure_signed(origin)?;
+
+			// unwrap auction and ensure its status is Active
+			let auction = Self::ensure_bid_acceptable(auction_id, price)?;
+			Self::ensure_invited(&auction, &participant, invite)?;
+			Self::ensure_terms(&auction, &terms_hash)?;
+
+			Self::do_bid(&auction_id, &participant, price, memo)?;
+			Self::record_terms(&auction, &participant);
+			Ok(())
+		}
+
+		// Feeless bid, the payload is signed by the bidder and the transaction is unsigned,
+		// so anyone can sponsor it while the bidder's own funds are locked.
+		pub fn participate_auction_unsigned(
+			origin,
+			payload: BidPayloadOf<T, I>,
+			signature: T::BidSignature
+		) -> Result {
+			ensure_none(origin)?;
+
+			ensure!(
+				signature.verify(&Self::signing_payload(&payload)[..], &payload.bidder),
+				"Invalid bid signature"
+			);
+			let next_nonce = Self::ensure_bid_payload(&payload)?;
+			let auction = Self::ensure_bid_acceptable(payload.auction_id, payload.price)?;
+			ensure!(!auction.invite_only, "Private auction requires an invite");
+			Self::ensure_terms(&auction, &payload.terms_hash)?;
+
+			Self::do_bid(&payload.auction_id, &payload.bidder, payload.price, payload.memo.clone())?;
+			<BidNonces<T, I>>::insert(&payload.bidder, next_nonce);
+			Self::record_terms(&auction, &payload.bidder);
+			Ok(())
+		}
+
+		// Feeless first bid of a new bidder, whose fee is paid from the budget of the auction
+		// owner or of the sponsor pool. Each account is sponsored once.
+		pub fn participate_auction_sponsored(
+			origin,
+			payload: BidPayloadOf<T, I>,
+			signature: T::BidSignature,
+			sponsor: Sponsor
+		) -> Result {
+			ensure_none(origin)?;
+
+			ensure!(
+				signature.verify(&Self::signing_payload(&payload)[..], &payload.bidder),
+				"Invalid bid signature"
+			);
+			let next_nonce = Self::ensure_bid_payload(&payload)?;
+			let sponsor_account = Self::ensure_sponsorship(payload.auction_id, &payload.bidder, payload.price, sponsor)?;
+			let auction = Self::ensure_bid_acceptable(payload.auction_id, payload.price)?;
+			ensure!(!auction.invite_only, "Private auction requires an invite");
+			Self::ensure_terms(&auction, &payload.terms_hash)?;
+
+			Self::do_bid(&payload.auction_id, &payload.bidder, payload.price, payload.memo.clone())?;
+			<BidNonces<T, I>>::insert(&payload.bidder, next_nonce);
+			Self::record_terms(&auction, &payload.bidder);
+
+			// 预算只扣除实际罚没的金额
+			let (imbalance, _) = T::Currency::slash_reserved(&sponsor_account, T::SponsoredBidFee::get());
+			let fee = imbalance.peek();
+			<SponsorBudgets<T, I>>::mutate(&sponsor_account, |budget| *budget = budget.saturating_sub(fee));
+			<SponsoredBidders<T, I>>::insert(&payload.bidder, true);
+			Self::route_fee(imbalance);
+			Self::deposit_event(RawEvent::BidSponsored(payload.auction_id, payload.bidder.clone(), sponsor_account, fee));
+			Ok(())
+		}
+
+		// Reserve funds as a budget for sponsoring first bids in the sender's auctions,
+		// or in any auction if the sender is the sponsor pool.
+		pub fn fund_sponsorship(origin, amount: BalanceOf<T, I>) -> Result {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(!amount.is_zero(), "Sponsorship amount should not be zero");
+			let budget = Self::sponsor_budget(&sender).checked_add(&amount).ok_or("balance add overflow")?;
+			T::Currency::reserve(&sender, amount)?;
+			<SponsorBudgets<T, I>>::insert(&sender, budget);
+			Self::deposit_event(RawEvent::SponsorshipFunded(sender, amount));
+
+			Ok(())
+		}
+
+		// Withdraw unspent funds from the sender's sponsorship budget.
+		pub fn withdraw_sponsorship(origin, amount: BalanceOf<T, I>) -> Result {
+			let sender = ensure_signed(origin)?;
+
+			let budget = Self::sponsor_budget(&sender).checked_sub(&amount).ok_or("Not enough sponsorship budget")?;
+			T::Currency::unreserve(&sender, amount);
+			if budget.is_zero() {
+				<SponsorBudgets<T, I>>::remove(&sender);
+			} else {
+				<SponsorBudgets<T, I>>::insert(&sender, budget);
+			}
+			Self::deposit_event(RawEvent::SponsorshipWithdrawn(sender, amount));
+
+			Ok(())
+		}
+
+		// Governance sets the account whose budget sponsors first bids in any auction.
+		pub fn set_sponsor_pool(origin, account: Option<T::AccountId>) -> Result {
+			ensure_root(origin)?;
+
+			match account {
+				Some(account) => <SponsorPoolAccount<T, I>>::put(account),
+				None => <SponsorPoolAccount<T, I>>::kill(),
+			}
+
+			Ok(())
+		}
+
+		// Buy tickets of an active raffle, the ticket price is locked like a bid.
+		pub fn buy_tickets(
+			origin,
+			auction_id: T::AuctionId,
+			count: u32,
+			invite: Option<T::BidSignature> // 私密拍卖中拍卖者为买家签发的邀请
+		) -> Result {
+			let buyer = ensure_signed(origin)?;
+
+			let auction = Self::_ensure_auction_with_status(auction_id, Some(AuctionStatus::Active), None)?;
+			let raffle = auction.raffle.ok_or("Auction is not a raffle")?;
+			Self::ensure_invited(&auction, &buyer, invite)?;
+
+			let mut tickets = Self::raffle_tickets(auction_id);
+			ensure!(count > 0, "Should buy at least one ticket");
+			ensure!((tickets.len() as u32).saturating_add(count) <= raffle.max_tickets, "Not enough tickets left");
+
+			let cost = raffle.ticket_price.checked_mul(&count.into()).ok_or("balance mul overflow")?;
+			ensure!(Self::can_lock(&buyer, cost), "No enough balance to lock");
+
+			Self::do_lock_balance(&auction_id, &buyer, cost)?;
+			let spent = Self::auction_bids(auction_id, &buyer);
+			Self::do_participate_auction(&auction_id, &buyer, spent, None)?;
+
+			for _ in 0..count {
+				tickets.push(buyer.clone());
+			}
+			let total = tickets.len() as u32;
+			<RaffleTickets<T, I>>::insert(auction_id, tickets);
+			Self::deposit_event(RawEvent::TicketsBought(auction_id, buyer, count, total));
+
+			Ok(())
+		}
+
+		// No need [commented by Tang]
+		// //query one auction with auctionId
+		// pub fn query_one_auction(
+		// 	origin,
+		// 	auction: T::AuctionId,
+		// 	) {
+		// 	let sender = ensure_signed(origin)?;
+		// 	Self::do_query_one_auction(auction, sender.clone())?;
+		// }
+
+		// ===== passive method =====
+		// starting auction methods
+		// Called by offchain worker
+		#[weight = SimpleDispatchInfo::FixedOperational(10_000)]
+		fn start_auctions_passive(
+			origin,
+			auction_ids: Vec<T::AuctionId>,
+			signature: SignatureOf<T>
+		) -> Result {
+			ensure_none(origin)?;
+			// ensure status
+			ensure!(Self::is_auctions_with_status(&auction_ids, AuctionStatus::PendingStart, false), "auctions should be pending start");
+			ensure!(Self::are_auctions_due(&auction_ids, true).is_ok(), "auctions are not due to start");
+
+			// key validating
+			if let Some(key) = Self::authority_id() {
+				let signature_valid = auction_ids.using_encoded(|encoded_auction_ids| {
+					key.verify(&encoded_auction_ids, &signature)
+				});
+				ensure!(signature_valid, "Invalid signature.");
+
+				Self::do_start_auctions(&auction_ids);
+
+				Ok(())
+			} else {
+				Err("Non existent public key.")?
+			}
+		}
+
+		// stoping auction methods
+		// Called by offchain worker
+		#[weight = SimpleDispatchInfo::FixedOperational(10_000)]
+		fn stop_auctions_passive(
+			origin,
+			auction_ids: Vec<T::AuctionId>,
+			signature: SignatureOf<T>
+		) -> Result {
+			ensure_none(origin)?;
+			// ensure status
+			ensure!(Self::is_auctions_with_status(&auction_ids, AuctionStatus::Stopped, true), "auctions should be non stopped");
+			ensure!(Self::are_auctions_due(&auction_ids, false).is_ok(), "auctions are not due to stop");
+			ensure!(
+				Self::settlement_queue().len().saturating_add(auction_ids.len()) <= T::MaxSettlementQueueLength::get() as usize,
+				"Settlement queue is full"
+			);
+
+			// key validating
+			if let Some(key) = Self::authority_id() {
+				let signature_valid = auction_ids.using_encoded(|encoded_auction_ids| {
+					key.verify(&encoded_auction_ids, &signature)
+				});
+				ensure!(signature_valid, "Invalid signature.");
+
+				Self::do_stop_auctions(&auction_ids);
+
+				Ok(())
+			} else {
+				Err("Non existent public key.")?
+			}
+		}
+
+		// Start and stop a batch of due auctions in one transaction.
+		// Called by offchain worker, auctions which are no longer due are skipped.
+		#[weight = SimpleDispatchInfo::FixedOperational(10_000)]
+		fn process_due_auctions(
+			origin,
+			auction_ids: Vec<T::AuctionId>,
+			signature: SignatureOf<T>
+		) -> Result {
+			ensure_none(origin)?;
+			ensure!(!auction_ids.is_empty(), "No auctions to process");
+			ensure!(auction_ids.len() as u32 <= T::MaxDueBatchSize::get(), "Too many auctions in a batch");
+
+			// key validating
+			let key = Self::authority_id().ok_or("Non existent public key.")?;
+			let signature_valid = auction_ids.using_encoded(|encoded_auction_ids| {
+				key.verify(&encoded_auction_ids, &signature)
+			});
+			ensure!(signature_valid, "Invalid signature.");
+
+			Self::do_process_due_auctions(&auction_ids);
+
+			Ok(())
+		}
+		
+		// Settle queued auctions at the beginning of every block.
+		// Without offchain timing the due auctions are started and stopped here as well.
+		fn on_initialize(now: T::BlockNumber) {
+			// 迁移完成前暂停其余的区块初始化工作
+			if migration::perform_migrations::<T, I>() {
+				return;
+			}
+			Self::draw_close_offsets();
+			if !Self::offchain_timing() {
+				let mut due_auction_ids = Self::due_auction_ids();
+				due_auction_ids.truncate(T::MaxDueBatchSize::get().max(1) as usize);
+				if !due_auction_ids.is_empty() {
+					Self::do_process_due_auctions(&due_auction_ids);
+				}
+			}
+			Self::settle_queued_auctions();
+			Self::finish_expired_inspections();
+			Self::sweep_refunds();
+			Self::take_price_snapshots(now);
+		}
+
+		// Announces the auctions closed in this block, so watchers need not track each deadline.
+		fn on_finalize(_now: T::BlockNumber) {
+			let closed = <ClosedAuctions<T, I>>::take();
+			if !closed.is_empty() {
+				let digest = (CLOSED_AUCTIONS_DIGEST, &closed).encode();
+				<system::Module<T>>::deposit_log(generic::DigestItem::Other(digest));
+				Self::deposit_event(RawEvent::AuctionsClosed(closed));
+			}
+		}
+
+		// Runs after every block, compiled out without the `offchain` feature.
+		fn offchain_worker(now: <T as system::Trait>::BlockNumber) {
+			Self::run_offchain_worker(now);
+		}
+	}
+}
+
+impl<T: Trait<I>, I: Instance> Module<T, I> {
+	// ====== exported public methods ======
+	/// Returns own authority identifier iff it is part of the current authority
+	/// set, otherwise this function returns None. The restriction might be
+	/// softened in the future in case a consumer needs to learn own authority
+	/// identifier.
+	pub fn authority_id() -> Option<T::AuthorityId> {
+		let authorities = <aura::Module<T>>::authorities();
+
+		let local_keys = T::AuthorityId::all();
+
+		authorities.into_iter().find_map(|authority| {
+			if local_keys.contains(&authority) {
+				Some(authority)
+			} else {
+				None
+			}
+		})
+	}
+	pub fn is_auctions_with_status (
+		auction_ids: &Vec<T::AuctionId>,
+		status: AuctionStatus,
+		not_equal: bool
+	) -> bool {
+		auction_ids.iter().all(|&auction_id| {
+			if let Some(auction) = Self::auctions(auction_id) {
+				if not_equal {
+					auction.status != status
+				} else {
+					auction.status == status
+				}
+			} else {
+				false
+			}
+		})
+	}
+
+	// ====== module private methods ======
+	/// Activate pending auctions.
+	fn do_start_auctions(auction_ids: &Vec<T::AuctionId>) {
+		// set status as active, skipping the ones which can not start
+		let started: Vec<T::AuctionId> = auction_ids.iter()
+			.filter(|auction_id| Self::auctions(**auction_id).map_or(false, |a| Self::ensure_items_transferable(&a).is_ok()))
+			.filter(|auction_id| Self::_change_auction_status(**auction_id, AuctionAction::Start).is_ok())
+			.cloned()
+			.collect();
+		// remove auction_ids from pendings
+		Self::remove_all_from_set(StoreVecs::PendingVec, &started);
+		// add auction_ids to active_auctions
+		Self::add_all_to_set(StoreVecs::ActiveVec, &started);
+	}
+
+	/// Stop auctions, the started ones are queued for settlement in the coming blocks.
+	/// Once the queue is full the rest stay due and are stopped in a later block.
+	/// Returns the stopped ones.
+	fn do_stop_auctions(auction_ids: &Vec<T::AuctionId>) -> Vec<T::AuctionId> {
+		let mut queue = Self::settlement_queue();
+		let mut stopped = Vec::new();
+		auction_ids.iter().for_each(|auction_id| {
+			if let Some(auction) = Self::auctions(auction_id) {
+				// 结算队列已满，留待后续区块停拍
+				if auction.status != AuctionStatus::PendingStart
+					&& queue.len() as u32 >= T::MaxSettlementQueueLength::get() {
+					return;
+				}
+				if Self::_change_auction_status(*auction_id, AuctionAction::Stop).is_err() {
+					return;
+				}
+				stopped.push(*auction_id);
+				if let (Some(stop_at), Some(offset)) = (auction.stop_at, Self::close_offset(auction_id)) {
+					Self::deposit_event(RawEvent::CloseTimeRevealed(*auction_id, stop_at.saturating_add(offset)));
+				}
+				// call settle func if needed.
+				if auction.status != AuctionStatus::PendingStart {
+					queue.push(*auction_id);
+				} else {
+					Self::release_item(&auction);
+					// relist if no one bids
+					Self::try_relist(&auction);
+				}
+			}
+		});
+		<SettlementQueue<T, I>>::put(queue);
+		// remove auction_ids from active_auctions
+		Self::remove_all_from_set(StoreVecs::ActiveVec, &stopped);
+		stopped
+	}
+
+	/// Settle at most `MaxSettlementsPerBlock` queued auctions, carrying over the rest.
+	fn settle_queued_auctions() {
+		let mut queue = Self::settlement_queue();
+		if queue.is_empty() {
+			return;
+		}
+		let count = queue.len().min(T::MaxSettlementsPerBlock::get() as usize);
+		let due: Vec<T::AuctionId> = queue.drain(..count).collect();
+		// the due ones leave the queue before they are settled, which returns their deposits
+		<SettlementQueue<T, I>>::put(queue);
+		let mut challenged = Vec::new();
+		for auction_id in due {
+			// challenged or reported auctions wait for the ruling
+			if Self::is_under_review(auction_id) {
+				challenged.push(auction_id);
+				continue;
+			}
+			if let Some(auction) = Self::auctions(auction_id) {
+				match Self::settle_or_defer(&auction) {
+					Ok(false) => continue, // the item stays in the auction until claimed
+					Err(_) => {}, // DO SOMETHING?
+					Ok(true) => {},
+				}
+				Self::release_item(&auction);
+				// relist if no one bids
+				Self::try_relist(&auction);
+			}
+		}
+		if !challenged.is_empty() {
+			<SettlementQueue<T, I>>::mutate(|queue| queue.extend(challenged));
+		}
+	}
+
+	fn get_next_auction_id(owner: &T::AccountId) -> result::Result<T::AuctionId, &'static str> {
+		// new auctions would be taken for legacy ones by the migration
+		ensure!(!Self::is_migrating(), "Auction storage is migrating");
+		let index = Self::next_auction_id();
+		if index == T::AuctionIndex::max_value() {
+			return Err("Auction count overflow");
+		}
+		let auction_id = T::AuctionIds::generate_auction_id(owner, index).ok_or("Auction count overflow")?;
+		ensure!(!<Auctions<T, I>>::exists(auction_id), "Auction id already in use");
+		ensure!(Self::owner_auction_count(owner) < u32::max_value(), "Auction count overflow");
+		Ok(auction_id)
+	}
+
+	// utility method for ensure auction status
+	// add by Tang 20191024
+	fn _ensure_auction_with_status(
+		auction_id: T::AuctionId,
+		status: Option<AuctionStatus>,
+		owner: Option<&T::AccountId>
+	) -> result::Result<Auction<T, I>, &'static str> {
+		// unwrap auction and ensure its status
+		let auction = Self::auctions(auction_id);
+		ensure!(auction.is_some(), "Auction does not exist");
+
+		let auction = auction.unwrap();
+		// check status equel
+		if let Some(s) = status {
+			ensure!(auction.status == s, s.error_str());
+		}
+		// check owner or not
+		if let Some(account) = owner {
+			// ensure only owner can call this
+			ensure!(auction.owner == *account, "Only owner can call this fn.");
+		}
+
+		Ok(auction)
+	}
+
+	/// Like `_ensure_auction_with_status`, but the owner's co-managers may call it as well.
+	fn _ensure_auction_managed_by(
+		auction_id: T::AuctionId,
+		status: Option<AuctionStatus>,
+		who: &T::AccountId,
+	) -> result::Result<Auction<T, I>, &'static str> {
+		let auction = Self::_ensure_auction_with_status(auction_id, status, None)?;
+		ensure!(
+			auction.owner == *who || Self::managers_of(auction_id).contains(who),
+			"Only owner or managers can call this fn."
+		);
+
+		Ok(auction)
+	}
+
+	/// Deposit an event indexed by the auction and the account it concerns.
+	fn deposit_event(event: Event<T, I>) {
+		let topics = Self::event_topics(&event);
+		<system::Module<T>>::deposit_event_indexed(&topics, <T as Trait<I>>::Event::from(event).into());
+	}
+
+	/// Topics of an event, `hash(("auction", auction_id))` and `hash(("account", account))`,
+	/// so light clients can subscribe to their own auctions.
+	pub fn event_topics(event: &Event<T, I>) -> Vec<T::Hash> {
+		let (auction_id, account) = match event {
+			RawEvent::AuctionCreated(id, who, _)
+			| RawEvent::BidderUpdated(id, who, _, _, _)
+			| RawEvent::AuctionSettled(id, who, _)
+			| RawEvent::ProceedsPaid(id, who, _)
+			| RawEvent::CharityDonated(id, who, _)
+			| RawEvent::TicketsBought(id, who, _, _)
+			| RawEvent::ClaimPending(id, who, _)
+			| RawEvent::InspectionStarted(id, who, _)
+			| RawEvent::ItemRejected(id, who, _)
+			| RawEvent::InstallmentsStarted(id, who, _, _)
+			| RawEvent::InstallmentPaid(id, who, _, _)
+			| RawEvent::ItemRepossessed(id, who, _)
+			| RawEvent::ShillChallenged(id, who, _)
+			| RawEvent::ChallengeUpheld(id, who, _)
+			| RawEvent::ChallengeRejected(id, who, _)
+			| RawEvent::ChallengeExpired(id, who)
+			| RawEvent::AuctionReported(id, who, _)
+			| RawEvent::ReportUpheld(id, who, _)
+			| RawEvent::ReportDismissed(id, who, _)
+			| RawEvent::ReportExpired(id, who)
+			| RawEvent::CollateralSlashed(id, who, _)
+			| RawEvent::ProceedsVested(id, who, _)
+			| RawEvent::UnitAllocated(id, who, _, _)
+			| RawEvent::InspectionFailed(id, who)
+			| RawEvent::ManagerAdded(id, who)
+			| RawEvent::ManagerRemoved(id, who)
+			| RawEvent::SettlementCallbackCalled(id, who, _)
+			| RawEvent::PayoutRouted(id, who, _)
+			| RawEvent::CounterpartyRated(id, who, _, _)
+			| RawEvent::CommentPosted(id, who, _, _)
+			| RawEvent::BidSponsored(id, who, _, _)
+			| RawEvent::BatchStopped(id, who, _)
+			| RawEvent::BatchCancelled(id, who, _) => (Some(id), Some(who)),
+			RawEvent::AuctionUpdated(id, _, _)
+			| RawEvent::AuctionRelisted(id, _, _)
+			| RawEvent::RoundJoined(id, _)
+			| RawEvent::ItemMappingRepaired(_, id)
+			| RawEvent::StaleAuctionPurged(id, _)
+			| RawEvent::EscrowSwept(id, _)
+			| RawEvent::CommentsPruned(id, _)
+			| RawEvent::CloseTimeRevealed(id, _) => (Some(id), None),
+			RawEvent::CreditDeposited(who, _)
+			| RawEvent::CreditWithdrawn(who, _)
+			| RawEvent::SponsorshipFunded(who, _)
+			| RawEvent::SponsorshipWithdrawn(who, _)
+			| RawEvent::VestingUpdated(who, _) => (None, Some(who)),
+			RawEvent::DueAuctionsProcessed(..)
+			| RawEvent::AuctionsClosed(..)
+			| RawEvent::PhantomData(_) => (None, None),
+		};
+
+		let mut topics = Vec::new();
+		if let Some(id) = auction_id {
+			topics.push(T::Hashing::hash_of(&(b"auction", id)));
+		}
+		if let Some(who) = account {
+			topics.push(T::Hashing::hash_of(&(b"account", who)));
+		}
+		topics
+	}
+
+	/// add auction ids to a store vec set.
+	/// add by sunhao 20191024
+	/// modified by Tang 20191025
+	fn add_all_to_set(vec_type: StoreVecs, auction_ids: &Vec<T::AuctionId>) {
+		let mut stored_auction_ids = match vec_type {
+			StoreVecs::PendingVec => Self::pending_auctions(),
+			StoreVecs::ActiveVec => Self::active_auctions(),
+		};
+		// add to set
+		for auction_id in auction_ids.iter() {
+			let exists = stored_auction_ids.iter().any(|auc_id| auc_id == auction_id);
+			if !exists {
+				stored_auction_ids.push(*auction_id);
+			}
+		}
+		// save to store
+		match vec_type {
+			StoreVecs::PendingVec => <PendingAuctions<T, I>>::put(stored_auction_ids),
+			StoreVecs::ActiveVec => <ActiveAuctions<T, I>>::put(stored_auction_ids),
+		};
+	}
+
+	/// remove an auction from a store vec set.
+	/// add by sunhao 20191024
+	/// modified by Tang 20191025
+	fn remove_all_from_set(vec_type: StoreVecs, auction_ids: &Vec<T::AuctionId>) {
+		let mut stored_auction_ids = match vec_type {
+			StoreVecs::PendingVec => Self::pending_auctions(),
+			StoreVecs::ActiveVec => Self::active_auctions(),
+		};
+		// remove from set
+		for auction_id in auction_ids.iter() {
+			stored_auction_ids = stored_auction_ids.iter()
+				.filter_map(|auc_id| if auc_id == auction_id {
+					None
+				} else {
+					Some(*auc_id)
+				})
+				.collect();
+		}
+		
+		// save to store
+		match vec_type {
+			StoreVecs::PendingVec => <PendingAuctions<T, I>>::put(stored_auction_ids),
+			StoreVecs::ActiveVec => <ActiveAuctions<T, I>>::put(stored_auction_ids),
+		};
+	}
+
+	fn insert_auction(auction_id: T::AuctionId, auction:Auction<T, I>) {
+		// Create and store kitty
+		let index = Self::next_auction_id();
+		let seq = Self::owner_auction_count(&auction.owner);
+		<OwnerAuctions<T, I>>::insert(&auction.owner, seq, auction_id);
+		<OwnerSeqs<T, I>>::insert(auction_id, seq);
+		<OwnerAuctionCount<T, I>>::insert(&auction.owner, seq.saturating_add(1));
+		<AuctionCreatedAt<T, I>>::insert(auction_id, <aura::Module<T>>::last());
+		<Auctions<T, I>>::insert(auction_id, auction);
+		<AuctionsByIndex<T, I>>::insert(index, auction_id);
+		<NextAuctionId<T, I>>::put(index.saturating_add(One::one()));
+	}
+
+	/// Deposit the owner reserves to create an auction, the creation gate may raise it
+	/// or reject owners with a low reputation.
+	fn creation_deposit_for(
+		owner: &T::AccountId,
+		begin_price: BalanceOf<T, I>,
+	) -> result::Result<BalanceOf<T, I>, &'static str> {
+		let deposit = T::CreationDeposit::get();
+		match Self::creation_gate() {
+			Some(gate) if begin_price >= gate.value_threshold
+				&& T::Reputation::reputation_score(owner) < gate.min_reputation =>
+			{
+				let higher = gate.low_reputation_deposit.ok_or("Reputation too low to create this auction")?;
+				Ok(higher.max(deposit))
+			},
+			_ => Ok(deposit),
+		}
+	}
+
+	/// Create an auction of the owner and hold its item if any, nothing is written on failure.
+	fn do_create_auction(
+		owner: &T::AccountId, 
+		item: Option<T::ItemId>,
+		begin_price: BalanceOf<T, I>,//起拍价
+		minimum_step: BalanceOf<T, I>,//最小加价幅度
+		upper_bound_price: Option<BalanceOf<T, I>>,
+		step_tiers: Vec<(BalanceOf<T, I>, BalanceOf<T, I>)>,//阶梯加价表
+		title: Vec<u8>,
+		description_hash: Option<T::Hash>,
+		media_cid: Option<Vec<u8>>,
+	) -> result::Result<T::AuctionId, &'static str> {
+		ensure!(begin_price >= T::MinimumBeginPrice::get(), "Begin price below the minimum");
+		Self::ensure_valid_step_tiers(minimum_step, &step_tiers)?;
+		Self::ensure_valid_description(&title, &media_cid)?;
+		// 判断id
+		let deposit = Self::creation_deposit_for(owner, begin_price)?;
+		let auction_id = Self::get_next_auction_id(owner)?;
+		T::Currency::reserve(owner, deposit)?;
+		if let Some(item) = item {
+			if let Err(e) = T::AuctionTransfer::reserve_item(owner, item) {
+				T::Currency::unreserve(owner, deposit);
+				return Err(Error::from(e).into());
+			}
+		}
+		<CreationDeposits<T, I>>::insert(auction_id, (deposit, <aura::Module<T>>::last()));
+		let new_auction = Auction {
+			id: auction_id,
+			title: title,
+			description_hash: description_hash,
+			media_cid: media_cid.clone(),
+			item: item, // 拍卖物品id
+			lot: Vec::new(),
+			lot_pricing: LotPricing::Uniform,
+			owner: (*owner).clone(), // 拍卖管理账户，可以控制暂停和继续
+			begin_price: begin_price, // 起拍价
+			minimum_step: minimum_step, // 最小加价幅度
+			step_tiers: step_tiers,
+			status: AuctionStatus::PendingStart,
+			upper_bound_price: upper_bound_price,
+			start_at: None,
+			stop_at:None,
+			wait_period: Self::default_wait_period(),
+			latest_participate: None,
+			invite_only: false,
+			terms_hash: None,
+			payout_split: Vec::new(),
+			charity: None,
+			relist_policy: None,
+			relist_count: 0,
+			raffle: None,
+			round: None,
+			claim_settlement: false,
+			inspection_period: None,
+			vest_proceeds: false,
+			installment_plan: None,
+			collateral_ratio: None,
+			bid_denomination: None,
+			close_jitter: false,
+		};
+		Self::insert_auction(auction_id, new_auction);
+		if let Some(item) = item {
+			<AuctionItems<T, I>>::insert(item, auction_id);
+		}
+		Self::deposit_event(RawEvent::AuctionCreated(auction_id, owner.clone(), media_cid));
+		Ok(auction_id)
+	}
+
+	fn ensure_valid_description(title: &Vec<u8>, media_cid: &Option<Vec<u8>>) -> Result {
+		ensure!(title.len() <= MAX_TITLE_LEN, "Auction title too long");
+		if let Some(cid) = media_cid {
+			ensure!(!cid.is_empty() && cid.len() <= MAX_CID_LEN, "Invalid media CID length");
+		}
+		Ok(())
+	}
+
+	/// Thresholds must be strictly ascending and steps may never drop below
+	/// `minimum_step` or below the step of a lower tier.
+	fn ensure_valid_step_tiers(
+		minimum_step: BalanceOf<T, I>,
+		step_tiers: &Vec<(BalanceOf<T, I>, BalanceOf<T, I>)>
+	) -> Result {
+		ensure!(step_tiers.len() <= MAX_STEP_TIERS, "Too many step tiers");
+
+		let mut prev: Option<&(BalanceOf<T, I>, BalanceOf<T, I>)> = None;
+		for tier in step_tiers.iter() {
+			ensure!(tier.1 >= minimum_step, "Tier step less than minimum step");
+			if let Some(p) = prev {
+				ensure!(tier.0 > p.0, "Tier thresholds should be ascending");
+				ensure!(tier.1 >= p.1, "Tier steps should not decrease");
+			}
+			prev = Some(tier);
+		}
+		Ok(())
+	}
+
+	/// The minimum increment required on top of `current_price`, taken from the
+	/// highest tier whose threshold is reached, or the flat `minimum_step`.
+	pub fn minimum_step_at(auction: &Auction<T, I>, current_price: BalanceOf<T, I>) -> BalanceOf<T, I> {
+		auction.step_tiers.iter()
+			.take_while(|(threshold, _)| *threshold <= current_price)
+			.last()
+			.map(|(_, step)| *step)
+			.unwrap_or(auction.minimum_step)
+	}
+
+	/// Start and stop moment of a round.
+	pub fn round_moments(round: u32) -> (T::Moment, T::Moment) {
+		let length = T::RoundLength::get();
+		let start_at = length.saturating_mul(round.into());
+		(start_at, start_at.saturating_add(length))
+	}
+
+	/// Relist an auction which ended with no bids according to its relist policy.
+	/// The new auction keeps the same settings, starts now and lasts as long as the old one.
+	fn try_relist(auction: &Auction<T, I>) -> Option<T::AuctionId> {
+		let policy = auction.relist_policy?;
+		let item = auction.item?;
+		if auction.latest_participate.is_some() || auction.relist_count >= policy.max_relists {
+			return None;
+		}
+		// the item was released with the old auction, the owner may no longer hold it
+		if !T::AuctionTransfer::is_item_owner(&auction.owner, item)
+			|| T::AuctionTransfer::can_transfer(&auction.owner, item).is_err()
+			|| T::ItemLocks::is_item_locked(item)
+			|| Self::is_blacklisted(&item)
+		{
+			return None;
+		}
+		let new_auction_id = Self::get_next_auction_id(&auction.owner).ok()?;
+
+		let now = <aura::Module<T>>::last();
+		let duration = match (auction.start_at, auction.stop_at) {
+			(Some(start_at), Some(stop_at)) if stop_at > start_at => Some(stop_at - start_at),
+			_ => None,
+		};
+		let mut begin_price = auction.begin_price.saturating_sub(policy.price_reduction * auction.begin_price);
+		if let Some(denomination) = auction.bid_denomination {
+			begin_price -= begin_price % denomination; // 向下取整到出价单位
+		}
+		if begin_price < T::MinimumBeginPrice::get() {
+			return None;
+		}
+		// a relisted auction passes the creation gate and takes a new deposit like a created one
+		let deposit = Self::creation_deposit_for(&auction.owner, begin_price).ok()?;
+		T::Currency::reserve(&auction.owner, deposit).ok()?;
+		if T::AuctionTransfer::reserve_item(&auction.owner, item).is_err() {
+			T::Currency::unreserve(&auction.owner, deposit);
+			return None;
+		}
+
+		let mut new_auction = auction.clone();
+		new_auction.id = new_auction_id;
+		new_auction.begin_price = begin_price;
+		new_auction.start_at = Some(now);
+		new_auction.stop_at = duration.map(|d| now.saturating_add(d)).or(auction.stop_at);
+		new_auction.latest_participate = None;
+		new_auction.relist_count = auction.relist_count.saturating_add(1);
+		new_auction.round = None;
+		new_auction.status = AuctionStatus::PendingStart;
+
+		<CreationDeposits<T, I>>::insert(new_auction_id, (deposit, now));
+		Self::insert_auction(new_auction_id, new_auction);
+		<AuctionItems<T, I>>::insert(item, new_auction_id);
+		Self::add_all_to_set(StoreVecs::PendingVec, &vec![new_auction_id]);
+		Self::deposit_event(RawEvent::AuctionRelisted(auction.id, new_auction_id, begin_price));
+
+		Some(new_auction_id)
+	}
+
+	/// Whether an item mapping points to an auction which no longer holds the item:
+	/// missing, listing another item, or stopped without a pending claim.
+	fn is_orphaned_mapping(item: T::ItemId, auction_id: T::AuctionId) -> bool {
+		match Self::auctions(auction_id) {
+			Some(auction) => (auction.item != Some(item) && !auction.lot.contains(&item))
+				|| (auction.status == AuctionStatus::Stopped
+					&& !<PendingClaims<T, I>>::exists(auction_id)
+					&& !<Inspections<T, I>>::exists(auction_id)
+					&& !<Installments<T, I>>::exists(auction_id)
+					&& !Self::settlement_queue().contains(&auction_id)),
+			None => true,
+		}
+	}
+
+	/// Remove the item -> auction mappings and return the creation deposit once the auction is over.
+	fn release_item(auction: &Auction<T, I>) {
+		for item in auction.item.iter().chain(auction.lot.iter()) {
+			if Self::auction_items(item) == Some(auction.id) {
+				<AuctionItems<T, I>>::remove(item);
+				T::AuctionTransfer::unreserve_item(*item);
+				// 已售出的物品在结算时记录过结果
+				let recorded = Self::item_auction_history(item).last().map_or(false, |(id, _)| *id == auction.id);
+				if !recorded {
+					Self::record_item_outcome(*item, auction.id, AuctionOutcome::Unsold);
+				}
+			}
+		}
+		Self::release_held_deposit(auction);
+	}
+
+	/// Append the outcome of an auction to the history of the item, a sale replaces
+	/// the unsold record of the same auction.
+	fn record_item_outcome(item: T::ItemId, auction_id: T::AuctionId, outcome: AuctionOutcome<T::AccountId, BalanceOf<T, I>>) {
+		<ItemAuctionHistory<T, I>>::mutate(item, |history| {
+			match history.last_mut() {
+				Some(last) if last.0 == auction_id => last.1 = outcome,
+				_ => {
+					if history.len() >= MAX_ITEM_HISTORY {
+						history.remove(0);
+					}
+					history.push((auction_id, outcome));
+				},
+			}
+		});
+	}
+
+	fn do_add_item(
+		sender: &T::AccountId, 
+		auction_id: T::AuctionId,
+		item: T::ItemId,//竞拍对象
+	) -> Result {
+		// ensure item owner
+		ensure!(T::AuctionTransfer::is_item_owner(sender, item), "you should be item's owner.");
+
+		// unwrap auction and ensure its status is PendingStart
+		let mut auction = Self::_ensure_auction_with_status(auction_id, Some(AuctionStatus::PendingStart), Some(sender))?;
+		ensure!(auction.lot.is_empty(), "Auction already has a lot");
+		Self::ensure_item_listable(sender, item, auction.begin_price, Some(auction_id))?;
+
+		// hold the new item, then replace the old item mapping
+		if auction.item != Some(item) {
+			Self::hold_items(sender, &[item])?;
+			if let Some(old_item) = auction.item {
+				<AuctionItems<T, I>>::remove(old_item);
+				T::AuctionTransfer::unreserve_item(old_item);
+			}
+		}
+		<AuctionItems<T, I>>::insert(item, auction_id);
+
+		// change status of auction
+		auction.item = Some(item);
+		<Auctions<T, I>>::insert(auction_id, auction);
+
+		Ok(())
+	}
+
+	/// Place a hold on the items listed by the owner, undoing the holds already placed if one fails.
+	fn hold_items(owner: &T::AccountId, items: &[T::ItemId]) -> Result {
+		for (index, item) in items.iter().enumerate() {
+			if let Err(e) = T::AuctionTransfer::reserve_item(owner, *item) {
+				items[..index].iter().for_each(|held| T::AuctionTransfer::unreserve_item(*held));
+				return Err(Error::from(e).into());
+			}
+		}
+		Ok(())
+	}
+
+	/// Ensure the owner can list the item at the begin price, in the given auction if any.
+	fn ensure_item_listable(
+		owner: &T::AccountId,
+		item: T::ItemId,
+		begin_price: BalanceOf<T, I>,
+		auction_id: Option<T::AuctionId>,
+	) -> Result {
+		ensure!(T::AuctionTransfer::is_item_owner(owner, item), "you should be item's owner.");
+		Self::ensure_can_transfer(owner, item, auction_id)?;
+		ensure!(!Self::is_blacklisted(item), "Item is blacklisted");
+		// one item can only be in one auction
+		ensure!(Self::auction_items(item).map_or(true, |id| Some(id) == auction_id), "Item is already in another auction");
+		ensure!(!T::ItemLocks::is_item_locked(item), "Item is locked by another module");
+		if let Some(floor) = T::SuggestedPrice::suggested_price(item) {
+			ensure!(begin_price >= floor, "Begin price is below the suggested floor");
+		}
+		Ok(())
+	}
+
+	/// Ensure the owner could transfer the item, which is held if it is already in the auction.
+	fn ensure_can_transfer(owner: &T::AccountId, item: T::ItemId, auction_id: Option<T::AuctionId>) -> Result {
+		let transferable = if auction_id.is_some() && Self::auction_items(item) == auction_id {
+			T::AuctionTransfer::can_transfer_reserved(owner, item)
+		} else {
+			T::AuctionTransfer::can_transfer(owner, item)
+		};
+		transferable.map_err(Error::from)?;
+		Ok(())
+	}
+
+	/// Ensure the auction could still transfer every item it holds, so it does not
+	/// start only to fail at settlement.
+	fn ensure_items_transferable(auction: &Auction<T, I>) -> Result {
+		for item in auction.item.iter().chain(auction.lot.iter()) {
+			T::AuctionTransfer::can_transfer_reserved(&auction.owner, *item).map_err(Error::from)?;
+		}
+		Ok(())
+	}
+
+	// real work for do_setup_moments.
+	// separated by Tang 20191024
+	fn do_setup_moments(
+		who: &T::AccountId,
+		auction_id: T::AuctionId,
+		start_at: Option<T::Moment>,  //起拍时间
+		stop_at: Option<T::Moment>,  //结束时间
+		wait_period: Option<T::Moment>  //竞价等待时间
+	) -> Result {
+		// unwrap auction and ensure its status is PendingStart
+		let mut auction = Self::_ensure_auction_managed_by(auction_id, Some(AuctionStatus::PendingStart), who)?;
+		ensure!(auction.round.is_none(), "Moments of a round auction are set by its round");
+
+		// set moments into storage
+		if start_at.is_some() {
+			auction.start_at = start_at;
+		}
+		if stop_at.is_some() {
+			auction.stop_at = stop_at;
+		}
+		// zero turns the wait period off
+		if let Some(period) = wait_period {
+			Self::ensure_wait_period_allowed(period)?;
+			auction.wait_period = if period.is_zero() { None } else { Some(period) };
+		}
+
+		// save to storage
+		<Auctions<T, I>>::insert(auction_id, auction);
+
+		// ensure this auction in pending queue, once owner call this fn.
+		Self::add_all_to_set(StoreVecs::PendingVec,  &vec![auction_id]);
+			
+		Ok(())
+	}
+
+	/// Wait period of new auctions, none if `DefaultWaitPeriod` is zero.
+	fn default_wait_period() -> Option<T::Moment> {
+		Some(T::DefaultWaitPeriod::get()).filter(|period| !period.is_zero())
+	}
+
+	/// Ensure a wait period an owner sets is within the governance bounds, zero turns it off.
+	fn ensure_wait_period_allowed(period: T::Moment) -> Result {
+		if let Some(bounds) = Self::wait_period_bounds() {
+			if period.is_zero() {
+				ensure!(bounds.allow_disable, "Wait period can not be turned off");
+			} else {
+				ensure!(period >= bounds.min && period <= bounds.max, "Wait period out of bounds");
+			}
+		}
+		Ok(())
+	}
+
+	// real work for do_pause_auction
+	// modified by Tang 20191025
+	fn do_pause_auction(
+		who: &T::AccountId,
+		auction_id: T::AuctionId
+	) -> Result {
+		Self::_ensure_auction_managed_by(auction_id, None, who)?;
+
+		// change status of auction, it should be Active
+		Self::_change_auction_status(auction_id, AuctionAction::Pause)
+	}
+
+	// real work for do_resume_auction
+	// separated by Tang 20191024
+	// modified by Tang 20191025
+	fn do_resume_auction(
+		who: &T::AccountId,
+		auction_id: T::AuctionId
+	) -> Result {
+		Self::_ensure_auction_managed_by(auction_id, None, who)?;
+
+		// change status of auction, it should be Paused
+		Self::_change_auction_status(auction_id, AuctionAction::Resume)
+	}
+
+	// storage work for auction status
+	// added by Tang 20191025
+	/// Apply an action to the status of an auction, failing on an illegal transition.
+	fn _change_auction_status(auction_id: T::AuctionId, action: AuctionAction) -> Result {
+		let mut auction = Self::auctions(auction_id).ok_or("Auction not exist")?;
+		let old_status = auction.status;
+		let new_status = old_status.transition(action)?;
+
+		auction.status = new_status;
+		if new_status == AuctionStatus::Stopped {
+			<ClosedAuctions<T, I>>::mutate(|closed| closed.push(auction_id));
+			// managers have nothing left to manage
+			<AuctionManagers<T, I>>::remove(auction_id);
+		}
+		<Auctions<T, I>>::insert(auction_id, auction);
+		// emit event
+		Self::deposit_event(RawEvent::AuctionUpdated(auction_id, old_status, new_status));
+
+		Ok(())
+	}
+
+	// real work for stopping a auction.
+	// added by sunhao 20191024
+	// modified by Tang 20191024
+	fn do_stop_auction(
+		owner: &T::AccountId,
+		auction_id: T::AuctionId,
+		relist: bool
+	) -> Result {
+		let auction = Self::ensure_stoppable(owner, auction_id)?;
+
+		// call settle func if needed.
+		let settled = auction.status == AuctionStatus::PendingStart || Self::settle_or_defer(&auction)?;
+
+		// change status of auction
+		Self::_change_auction_status(auction_id, AuctionAction::Stop)?;
+		if settled {
+			Self::release_item(&auction);
+			// relist if no one bids, a cancelled auction is withdrawn for good
+			if relist {
+				Self::try_relist(&auction);
+			}
+		}
+		
+		// remove from working vecs, a pending auction can be stopped as well
+		Self::remove_all_from_set(StoreVecs::PendingVec, &vec![auction_id]);
+		Self::remove_all_from_set(StoreVecs::ActiveVec, &vec![auction_id]);
+
+		Ok(())
+	}
+
+	/// Ensure the owner can stop the auction: it is not stopped yet nor under review.
+	fn ensure_stoppable(owner: &T::AccountId, auction_id: T::AuctionId) -> result::Result<Auction<T, I>, &'static str> {
+		// unwrap auction and ensure its status is not stopped yet.
+		let auction = Self::_ensure_auction_with_status(auction_id, None, Some(owner))?;
+
+		auction.status.transition(AuctionAction::Stop)?;
+		ensure!(!Self::is_under_review(auction_id), "Auction is under review");
+		Ok(auction)
+	}
+
+	/// Ensure the owner can stop the auction and it has no bid, a raffle without tickets included.
+	fn ensure_cancellable(owner: &T::AccountId, auction_id: T::AuctionId) -> Result {
+		let auction = Self::ensure_stoppable(owner, auction_id)?;
+		let has_bids = auction.latest_participate.is_some()
+			|| Self::auction_participants(auction_id).map_or(false, |p| !p.is_empty());
+		ensure!(!has_bids, "Auction with bids can not be cancelled");
+		Ok(())
+	}
+
+	/// Ensure an owner's batch is small enough and lists every auction once.
+	fn ensure_owner_batch(auction_ids: &Vec<T::AuctionId>) -> Result {
+		ensure!(auction_ids.len() <= MAX_OWNER_BATCH, "Too many auctions in a batch");
+		ensure!(
+			auction_ids.iter().enumerate().all(|(i, id)| !auction_ids[..i].contains(id)),
+			"Duplicate auction in a batch"
+		);
+		Ok(())
+	}
+
+	/// Stop an auction which has no bid, a raffle without tickets included.
+	fn do_cancel_auction(owner: &T::AccountId, auction_id: T::AuctionId) -> Result {
+		Self::ensure_cancellable(owner, auction_id)?;
+		Self::do_stop_auction(owner, auction_id, false)
+	}
+
+	/// Settle a stopped auction, or only mark its winner in claim settlement mode.
+	/// Returns whether it is settled, otherwise the item stays in the auction until claimed.
+	fn settle_or_defer(auction: &Auction<T, I>) -> result::Result<bool, &'static str> {
+		let deferred = auction.claim_settlement
+			|| auction.inspection_period.is_some()
+			|| auction.installment_plan.is_some();
+		let winner = match &auction.latest_participate {
+			Some((winner, _)) if deferred && auction.item.is_some() => winner,
+			_ => {
+				Self::do_settle_auction(auction)?;
+				return Ok(true);
+			},
+		};
+
+		if auction.installment_plan.is_some() {
+			// the winner pays the down payment and reserves the rest at once
+			Self::ensure_can_settle(auction, winner)?;
+		}
+		// losing bids are unlocked at once, the winner's stays locked as escrow
+		Self::unlock_losers(auction, rstd::slice::from_ref(winner))?;
+		let now = <aura::Module<T>>::last();
+		if let Some(plan) = auction.installment_plan {
+			Self::do_unlock_winner(&auction.id, winner)?;
+			Self::start_installments(auction, winner, plan, now)?;
+		} else if let Some(period) = auction.inspection_period {
+			let deadline = now.saturating_add(period);
+			<Inspections<T, I>>::insert(auction.id, (winner.clone(), deadline));
+			<InspectionQueue<T, I>>::mutate(|queue| {
+				let index = queue.iter().position(|(_, d)| *d > deadline).unwrap_or(queue.len());
+				queue.insert(index, (auction.id, deadline));
+			});
+			Self::deposit_event(RawEvent::InspectionStarted(auction.id, winner.clone(), deadline));
+		} else {
+			let deadline = now.saturating_add(T::ClaimPeriod::get());
+			<PendingClaims<T, I>>::insert(auction.id, (winner.clone(), deadline));
+			Self::deposit_event(RawEvent::ClaimPending(auction.id, winner.clone(), deadline));
+		}
+
+		Ok(false)
+	}
+
+	/// Whether the auction is challenged or reported and waits for a ruling.
+	fn is_under_review(auction_id: T::AuctionId) -> bool {
+		<Challenges<T, I>>::exists(auction_id) || <Reports<T, I>>::exists(auction_id)
+	}
+
+	/// Remove a report and take it out of the review queue.
+	fn remove_report(auction_id: T::AuctionId) {
+		<Reports<T, I>>::remove(auction_id);
+		<ReviewQueue<T, I>>::mutate(|queue| queue.retain(|id| *id != auction_id));
+	}
+
+	/// Return the creation deposit once the auction is stopped and out of the settlement queue,
+	/// an auction under review keeps it until the ruling.
+	fn release_held_deposit(auction: &Auction<T, I>) {
+		let stopped = Self::auctions(auction.id).map_or(false, |a| a.status == AuctionStatus::Stopped);
+		if stopped && !Self::is_under_review(auction.id) && !Self::settlement_queue().contains(&auction.id) {
+			if let Some((deposit, _)) = <CreationDeposits<T, I>>::take(auction.id) {
+				T::Currency::unreserve(&auction.owner, deposit);
+			}
+		}
+	}
+
+	/// Ensure an account can be paid a reserved amount, which needs the account to exist.
+	fn ensure_can_receive(beneficiary: &T::AccountId, amount: BalanceOf<T, I>) -> Result {
+		ensure!(
+			amount.is_zero() || !T::Currency::total_balance(beneficiary).is_zero(),
+			"Beneficiary account does not exist"
+		);
+		Ok(())
+	}
+
+	/// Pay the creation deposit of an auction to the reporter of an upheld challenge or report.
+	/// Returns the amount paid, which is less if part of the deposit was slashed before.
+	fn slash_creation_deposit(auction: &Auction<T, I>, reporter: &T::AccountId, deposit: BalanceOf<T, I>) -> BalanceOf<T, I> {
+		<CreationDeposits<T, I>>::remove(auction.id);
+		T::Currency::repatriate_reserved(&auction.owner, reporter, deposit)
+			.map_or(Zero::zero(), |left| deposit.saturating_sub(left))
+	}
+
+	/// Void the sale of an auction found shill bid or fraudulent: all bids are unlocked and the seller keeps the item.
+	fn void_sale(auction: &Auction<T, I>) -> Result {
+		Self::unlock_losers(auction, &[])?;
+		<SettlementQueue<T, I>>::mutate(|queue| queue.retain(|id| *id != auction.id));
+		if auction.status != AuctionStatus::Stopped {
+			Self::_change_auction_status(auction.id, AuctionAction::Stop)?;
+		}
+		Self::remove_all_from_set(StoreVecs::PendingVec, &vec![auction.id]);
+		Self::remove_all_from_set(StoreVecs::ActiveVec, &vec![auction.id]);
+		Self::release_item(auction);
+
+		Ok(())
+	}
+
+	/// Charge the down payment from the winner and schedule the installments,
+	/// the rest of the price is reserved to back them.
+	fn start_installments(
+		auction: &Auction<T, I>,
+		winner: &T::AccountId,
+		plan: InstallmentPlan<T::Moment>,
+		now: T::Moment,
+	) -> Result {
+		let price = Self::auction_bids(auction.id, winner);
+		let down_payment = plan.down_payment * price;
+		Self::collect_payments(auction, &vec![(winner.clone(), down_payment)])?;
+
+		let remaining = price.saturating_sub(down_payment);
+		T::Currency::reserve(winner, remaining)?;
+		<Installments<T, I>>::insert(auction.id, InstallmentState {
+			winner: winner.clone(),
+			remaining: remaining,
+			paid: Zero::zero(),
+			installments_left: plan.installments,
+			next_due: now.saturating_add(plan.interval),
+		});
+		Self::deposit_event(RawEvent::InstallmentsStarted(auction.id, winner.clone(), down_payment, remaining));
+
+		Ok(())
+	}
+
+	/// End the inspection of an auction: settle it if the item is accepted, otherwise
+	/// the winner pays the rejection penalty and the seller keeps the item.
+	fn finish_inspection(auction_id: T::AuctionId, accepted: bool) -> Result {
+		let auction = Self::auctions(auction_id).ok_or("Auction not exist")?;
+		let (winner, _) = Self::inspection(auction_id).ok_or("Auction is not in inspection")?;
+		// check before any write, so a failed settlement can be retried
+		let penalty = T::RejectionPenalty::get() * Self::auction_bids(auction_id, &winner);
+		if accepted {
+			Self::ensure_can_settle(&auction, &winner)?;
+		} else {
+			ensure!(Self::can_pay(&winner, penalty), "Winner can not pay the rejection penalty");
+		}
+
+		if accepted {
+			Self::do_unlock_winner(&auction_id, &winner)?;
+			Self::do_pay_and_transfer(&auction)?;
+		} else {
+			Self::do_unlock_balance(&auction_id, &winner)?;
+			T::Currency::transfer(&winner, &auction.owner, penalty)?;
+			Self::deposit_event(RawEvent::ItemRejected(auction_id, winner, penalty));
+		}
+
+		<Inspections<T, I>>::remove(auction_id);
+		<InspectionQueue<T, I>>::mutate(|queue| queue.retain(|(id, _)| *id != auction_id));
+		Self::release_item(&auction);
+
+		Ok(())
+	}
+
+	/// Settle auctions whose inspection period is over, they are accepted by default.
+	/// At most `MaxSettlementsPerBlock` in one block.
+	fn finish_expired_inspections() {
+		let now = <aura::Module<T>>::last();
+		let expired: Vec<T::AuctionId> = Self::inspection_queue().into_iter()
+			.take_while(|(_, deadline)| *deadline < now)
+			.take(T::MaxSettlementsPerBlock::get() as usize)
+			.map(|(auction_id, _)| auction_id)
+			.collect();
+		for auction_id in expired {
+			if let Err(err) = Self::finish_inspection(auction_id, true) {
+				print(err);
+				Self::abandon_inspection(auction_id);
+			}
+		}
+	}
+
+	/// Give up a sale which can not be settled after the inspection instead of retrying it every
+	/// block. Nothing was written by the failed settlement, the seller keeps the item and the
+	/// winner's bid is unlocked so it is not stuck.
+	fn abandon_inspection(auction_id: T::AuctionId) {
+		if let Some((winner, _)) = <Inspections<T, I>>::take(auction_id) {
+			if let Err(err) = Self::do_unlock_balance(&auction_id, &winner) {
+				print(err);
+			}
+			Self::deposit_event(RawEvent::InspectionFailed(auction_id, winner));
+		}
+		<InspectionQueue<T, I>>::mutate(|queue| queue.retain(|(id, _)| *id != auction_id));
+		if let Some(auction) = Self::auctions(auction_id) {
+			Self::release_item(&auction);
+		}
+	}
+
+	/// Ensure the winner of a deferred settlement can pay and the item can be transferred,
+	/// checked before any write.
+	fn ensure_can_settle(auction: &Auction<T, I>, winner: &T::AccountId) -> Result {
+		Self::ensure_items_transferable(auction)?;
+		let mut due = Self::auction_bids(auction.id, winner).saturating_sub(Self::credit_bid(auction.id, winner));
+		if auction.collateral_ratio.is_some() {
+			// a winner who can not pay the bid defaults with its collateral
+			due = due.min(Self::bid_collateral(auction.id, winner));
+		}
+		ensure!(Self::can_pay(winner, due), "Payer can not pay the bid");
+		Ok(())
+	}
+
+	fn do_settle_auction(auction: &Auction<T, I>) -> Result {
+		// unlock all participents' balance, raffle tickets all pay for the item
+		let winners: Vec<T::AccountId> = if !auction.lot.is_empty() {
+			Self::lot_winners(auction).into_iter().map(|(winner, _)| winner).collect()
+		} else if auction.raffle.is_some() {
+			Self::auction_participants(auction.id).unwrap_or_default()
+		} else {
+			auction.latest_participate.iter().map(|(winner, _)| winner.clone()).collect()
+		};
+		// a defaulting winner's collateral is slashed after the unlock, check it can be paid first
+		if auction.collateral_ratio.is_some() {
+			for winner in winners.iter() {
+				ensure!(
+					Self::can_pay(winner, Self::bid_collateral(auction.id, winner)),
+					"Winner can not pay the collateral"
+				);
+			}
+		}
+		for winner in winners.iter() {
+			Self::do_unlock_winner(&auction.id, winner)?;
+		}
+		Self::unlock_losers(auction, &winners)?;
+
+		Self::do_pay_and_transfer(auction)
+	}
+
+	/// Unlock the bids of all participants but the winners. Auctions with more than
+	/// `MaxAutoRefunds` bidders leave them to `claim_refund`, swept after `RefundPeriod`.
+	fn unlock_losers(auction: &Auction<T, I>, winners: &[T::AccountId]) -> Result {
+		let participants = Self::auction_participants(auction.id).unwrap_or_default();
+		if auction.raffle.is_none() && participants.len() as u32 > T::MaxAutoRefunds::get() {
+			for winner in winners {
+				<RefundClaimed<T, I>>::insert(auction.id, winner, true);
+			}
+			let deadline = <aura::Module<T>>::last().saturating_add(T::RefundPeriod::get());
+			<PullRefunds<T, I>>::insert(auction.id, deadline);
+			<RefundSweeps<T, I>>::mutate(|sweeps| sweeps.push((auction.id, deadline, 0)));
+			return Ok(());
+		}
+
+		participants.iter().filter(|account| !winners.contains(account)).try_for_each(|account| {
+			Self::do_unlock_balance(&auction.id, account)
+		})
+	}
+
+	/// Unlock the unclaimed refunds of the earliest expired auction,
+	/// at most `MaxSettlementsPerBlock` accounts in one block.
+	fn sweep_refunds() {
+		let mut sweeps = Self::refund_sweeps();
+		let (auction_id, deadline, cursor) = match sweeps.first() {
+			Some(sweep) => *sweep,
+			None => return,
+		};
+		if deadline >= <aura::Module<T>>::last() {
+			return;
+		}
+
+		let participants = Self::auction_participants(auction_id).unwrap_or_default();
+		let end = participants.len().min(cursor as usize + T::MaxSettlementsPerBlock::get() as usize);
+		for account in participants.iter().skip(cursor as usize).take(end.saturating_sub(cursor as usize)) {
+			if !Self::refund_claimed(auction_id, account) {
+				match Self::do_unlock_balance(&auction_id, account) {
+					Err(err) => print(err),
+					Ok(_) => {},
+				}
+				<RefundClaimed<T, I>>::insert(auction_id, account, true);
+			}
+		}
+
+		if end >= participants.len() {
+			sweeps.remove(0);
+			<PullRefunds<T, I>>::remove(auction_id);
+			<RefundClaimed<T, I>>::remove_prefix(auction_id);
+		} else {
+			sweeps[0].2 = end as u32;
+		}
+		<RefundSweeps<T, I>>::put(sweeps);
+	}
+
+	/// Pay the proceeds from unlocked winning funds and transfer the item to the winner.
+	fn do_pay_and_transfer(auction: &Auction<T, I>) -> Result {
+		if !auction.lot.is_empty() {
+			return Self::do_settle_lot(auction);
+		}
+
+		// handle winner
+		let owner = &auction.owner;
+		// transfer auction item to winner
+		if let Some(item_id) = auction.item {
+			if let Some((winner, payers)) = Self::winner_and_payers(auction) {
+				if auction.collateral_ratio.is_some() && !Self::can_pay(&winner, Self::auction_bids(auction.id, &winner)) {
+					return Self::slash_collateral(auction, &winner);
+				}
+				let payment = Self::collect_payments(auction, &payers)?;
+
+				// try transfer item
+				T::AuctionTransfer::transfer_reserved_item(owner, &winner, item_id).map_err(Error::from)?;
+
+				// record the receipt
+				let price = payment.price;
+				<SettlementReceipts<T, I>>::insert(auction.id, SettlementReceipt {
+					auction_id: auction.id,
+					item: item_id,
+					seller: owner.clone(),
+					winner: winner.clone(),
+					price: price,
+					fee: payment.fee,
+					seller_proceeds: payment.seller_proceeds,
+					donation: payment.donation,
+					payouts: payment.payouts,
+					settled_at: <aura::Module<T>>::last(),
+				});
+				Self::deposit_event(RawEvent::AuctionSettled(auction.id, winner.clone(), price));
+				T::OnItemSold::on_item_sold(owner, &winner, item_id, price);
+				Self::record_win(&winner, auction.id, item_id, price);
+				Self::call_settlement_callback(auction, item_id, &winner, price);
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Append a settled purchase to the win history of the account and the auction history of the item.
+	fn record_win(winner: &T::AccountId, auction_id: T::AuctionId, item: T::ItemId, price: BalanceOf<T, I>) {
+		Self::record_item_outcome(item, auction_id, AuctionOutcome::Sold(winner.clone(), price));
+		<WonAuctions<T, I>>::mutate(winner, |won| {
+			if won.len() >= MAX_WON_AUCTIONS {
+				won.remove(0);
+			}
+			won.push(WonAuction {
+				auction_id: auction_id,
+				item: item,
+				price: price,
+				won_at: <aura::Module<T>>::last(),
+			});
+		});
+	}
+
+	/// Call the contract registered by the owner, a failing call does not undo the settlement.
+	fn call_settlement_callback(auction: &Auction<T, I>, item_id: T::ItemId, winner: &T::AccountId, price: BalanceOf<T, I>) {
+		if let Some((contract, gas_limit)) = Self::settlement_callback(auction.id) {
+			let input = (auction.id, item_id, winner, price).encode();
+			let success = T::Contracts::call_contract(&auction.owner, &contract, gas_limit, input).is_ok();
+			Self::deposit_event(RawEvent::SettlementCallbackCalled(auction.id, contract, success));
+		}
+	}
+
+	/// Whether the account can lock the amount for a bid on top of its other bids and still keep
+	/// the existential deposit, so paying the bids at settlement does not kill it.
+	pub fn can_lock(who: &T::AccountId, amount: BalanceOf<T, I>) -> bool {
+		amount
+			.saturating_add(Self::account_locks(who))
+			.saturating_add(T::Currency::minimum_balance()) < T::Currency::free_balance(who)
+	}
+
+	/// Whether the account can pay the amount from its free balance.
+	fn can_pay(who: &T::AccountId, amount: BalanceOf<T, I>) -> bool {
+		let free = T::Currency::free_balance(who);
+		free >= amount
+			&& T::Currency::ensure_can_withdraw(who, amount, WithdrawReason::Transfer.into(), free - amount).is_ok()
+	}
+
+	/// The winner of a collateral-backed bid defaults: its unlocked collateral goes to the seller,
+	/// who keeps the item.
+	fn slash_collateral(auction: &Auction<T, I>, winner: &T::AccountId) -> Result {
+		let collateral = Self::bid_collateral(auction.id, winner);
+		T::Currency::transfer(winner, &auction.owner, collateral)?;
+		Self::deposit_event(RawEvent::CollateralSlashed(auction.id, winner.clone(), collateral));
+		T::OnAuctionPenalty::on_payment_default(winner);
+
+		Ok(())
+	}
+
+	/// Settle a multi-unit lot: every winner gets one unit and pays by the lot pricing,
+	/// either the clearing price, which is the lowest winning bid, or its own bid.
+	/// Unsold units stay with the owner.
+	fn do_settle_lot(auction: &Auction<T, I>) -> Result {
+		let winners = Self::lot_winners(auction);
+		let clearing_price = match winners.last() {
+			Some((_, price)) => *price,
+			None => return Ok(()),
+		};
+		let payers: Vec<(T::AccountId, BalanceOf<T, I>)> = winners.into_iter()
+			.map(|(winner, bid)| match auction.lot_pricing {
+				LotPricing::Uniform => (winner, clearing_price),
+				LotPricing::PayAsBid => (winner, bid),
+			})
+			.collect();
+		Self::collect_payments(auction, &payers)?;
+
+		for ((winner, price), item_id) in payers.iter().zip(auction.lot.iter()) {
+			T::AuctionTransfer::transfer_reserved_item(&auction.owner, winner, *item_id).map_err(Error::from)?;
+			Self::deposit_event(RawEvent::UnitAllocated(auction.id, winner.clone(), *item_id, *price));
+			T::OnItemSold::on_item_sold(&auction.owner, winner, *item_id, *price);
+			Self::record_win(winner, auction.id, *item_id, *price);
+			Self::call_settlement_callback(auction, *item_id, winner, *price);
+		}
+
+		Ok(())
+	}
+
+	/// Lock the paid proceeds of a beneficiary under a new vesting schedule.
+	fn add_vesting(who: &T::AccountId, amount: BalanceOf<T, I>) {
+		let now = <aura::Module<T>>::last();
+		let mut schedules = Self::proceeds_vesting(who);
+		schedules.retain(|(_, start)| now < start.saturating_add(T::VestingPeriod::get()));
+		if schedules.len() < MAX_VESTING_SCHEDULES {
+			schedules.push((amount, now));
+		} else if let Some(last) = schedules.last_mut() {
+			// restart the last schedule with its locked part and the new amount
+			*last = (Self::vesting_locked(last, now).saturating_add(amount), now);
+		}
+		<ProceedsVesting<T, I>>::insert(who, schedules);
+		Self::update_vesting_lock(who);
+	}
+
+	/// Proceeds of an account which are still locked.
+	fn vesting_locked_total(who: &T::AccountId) -> BalanceOf<T, I> {
+		let now = <aura::Module<T>>::last();
+		Self::proceeds_vesting(who).iter()
+			.fold(Zero::zero(), |locked, schedule| locked.saturating_add(Self::vesting_locked(schedule, now)))
+	}
+
+	/// Release up to `amount` of the locked proceeds, the newest schedules first.
+	/// A partly released schedule restarts with its remaining locked part.
+	fn release_vesting(who: &T::AccountId, amount: BalanceOf<T, I>) {
+		if amount.is_zero() || !<ProceedsVesting<T, I>>::exists(who) {
+			return;
+		}
+		let now = <aura::Module<T>>::last();
+		let mut left = amount;
+		let mut schedules = Self::proceeds_vesting(who);
+		for schedule in schedules.iter_mut().rev() {
+			if left.is_zero() {
+				break;
 			}
-		})
+			let locked = Self::vesting_locked(schedule, now);
+			let released = locked.min(left);
+			*schedule = (locked.saturating_sub(released), now);
+			left = left.saturating_sub(released);
+		}
+		schedules.retain(|(amount, _)| !amount.is_zero());
+		<ProceedsVesting<T, I>>::insert(who, schedules);
+		Self::update_vesting_lock(who);
 	}
 
-	// ====== module private methods ======
-	fn get_next_auction_id() -> result::Result<T::AuctionId, &'static str> {
-		let auction_id = Self::next_auction_id();
-		if auction_id == T::AuctionId::max_value() {
-			return Err("Auction count overflow");
+	/// Part of a vesting schedule which is still locked at `now`.
+	fn vesting_locked(schedule: &(BalanceOf<T, I>, T::Moment), now: T::Moment) -> BalanceOf<T, I> {
+		let (amount, start) = *schedule;
+		let period = T::VestingPeriod::get();
+		if period.is_zero() {
+			return Zero::zero();
 		}
-		Ok(auction_id)
+		let elapsed = if now > start { (now - start).min(period) } else { Zero::zero() };
+		amount.saturating_sub(Permill::from_rational_approximation(elapsed, period) * amount)
 	}
 
-	// utility method for ensure auction status
-	// add by Tang 20191024
-	fn _ensure_auction_with_status(
-		auction_id: T::AuctionId,
-		status: Option<AuctionStatus>,
-		owner: Option<&T::AccountId>
-	) -> result::Result<Auction<T>, &'static str> {
-		// unwrap auction and ensure its status
-		let auction = Self::auctions(auction_id);
-		ensure!(auction.is_some(), "Auction does not exist");
+	/// Reset the vesting lock of an account to its still locked proceeds,
+	/// dropping the finished schedules.
+	fn update_vesting_lock(who: &T::AccountId) {
+		let now = <aura::Module<T>>::last();
+		let mut locked: BalanceOf<T, I> = Zero::zero();
+		let mut schedules = Self::proceeds_vesting(who);
+		schedules.retain(|schedule| {
+			let schedule_locked = Self::vesting_locked(schedule, now);
+			locked = locked.saturating_add(schedule_locked);
+			!schedule_locked.is_zero()
+		});
 
-		let auction = auction.unwrap();
-		// check status equel
-		if let Some(s) = status {
-			ensure!(auction.status == s, s.error_str());
+		if schedules.is_empty() {
+			<ProceedsVesting<T, I>>::remove(who);
+			T::Currency::remove_lock(T::VestingLockId::get(), who);
+		} else {
+			<ProceedsVesting<T, I>>::insert(who, schedules);
+			T::Currency::set_lock(
+				T::VestingLockId::get(),
+				who,
+				locked,
+				<T as system::Trait>::BlockNumber::max_value(),
+				WithdrawReasons::all());
 		}
-		// check owner or not
-		if let Some(account) = owner {
-			// ensure only owner can call this
-			ensure!(auction.owner == *account, "Only owner can call this fn.");
+		Self::deposit_event(RawEvent::VestingUpdated(who.clone(), locked));
+	}
+
+	/// The winning bids of a multi-unit lot, best first: higher price, then the bid ranked
+	/// higher by `TieBreak`.
+	fn lot_winners(auction: &Auction<T, I>) -> Vec<(T::AccountId, BalanceOf<T, I>)> {
+		let mut bids: Vec<(T::AccountId, BalanceOf<T, I>, (T::Moment, u32))> = Self::auction_participants(auction.id)
+			.unwrap_or_default()
+			.into_iter()
+			.map(|account| {
+				let price = Self::auction_bids(auction.id, &account);
+				let placed_at = Self::bid_placed_at(auction.id, &account);
+				(account, price, placed_at)
+			})
+			.collect();
+		let later_wins = T::TieBreak::get() == TieBreak::LaterWins;
+		bids.sort_by(|a, b| {
+			let by_time = if later_wins { b.2.cmp(&a.2) } else { a.2.cmp(&b.2) };
+			b.1.cmp(&a.1).then(by_time)
+		});
+		bids.into_iter()
+			.take(auction.lot.len())
+			.map(|(account, price, _)| (account, price))
+			.collect()
+	}
+
+	/// The escrow account of an auction, which payments go through at settlement.
+	pub fn escrow_account(auction_id: T::AuctionId) -> T::AccountId {
+		T::ModuleId::get().into_sub_account(auction_id)
+	}
+
+	/// What a bidder or a seller signs for a payload: the payload behind the module id,
+	/// so a signature made for one auction house can not be replayed in another.
+	pub fn signing_payload<P: Encode>(payload: &P) -> Vec<u8> {
+		(T::ModuleId::get().0, payload).encode()
+	}
+
+	/// Pay what is left in the escrow account of an auction to its beneficiaries by the payout split.
+	fn sweep_escrow(auction: &Auction<T, I>, escrow: &T::AccountId) -> Result {
+		let dust = T::Currency::free_balance(escrow);
+		if !dust.is_zero() {
+			let mut rest = T::Currency::withdraw(escrow, dust, WithdrawReason::Transfer, ExistenceRequirement::AllowDeath)?;
+			for (beneficiary, amount) in Self::split_proceeds(&auction.owner, &auction.payout_split, dust) {
+				let (payout, remain) = rest.split(amount);
+				Self::resolve_payout(auction.id, &beneficiary, payout);
+				rest = remain;
+			}
+			Self::route_fee(rest);
+			Self::deposit_event(RawEvent::EscrowSwept(auction.id, dust));
 		}
 
-		Ok(auction)
+		Ok(())
 	}
 
-	/// add auction ids to a store vec set.
-	/// add by sunhao 20191024
-	/// modified by Tang 20191025
-	fn add_all_to_set(vec_type: StoreVecs, auction_ids: &Vec<T::AuctionId>) {
-		let mut stored_auction_ids = match vec_type {
-			StoreVecs::PendingVec => Self::pending_auctions(),
-			StoreVecs::ActiveVec => Self::active_auctions(),
-		};
-		// add to set
-		for auction_id in auction_ids.iter() {
-			let exists = stored_auction_ids.iter().all(|auc_id| auc_id != auction_id);
-			if !exists {
-				stored_auction_ids.push(*auction_id);
+	/// Charge the fee from the payers and pay the rest to the charity and beneficiaries.
+	fn collect_payments(
+		auction: &Auction<T, I>,
+		payers: &Vec<(T::AccountId, BalanceOf<T, I>)>,
+	) -> result::Result<Payment<T::AccountId, BalanceOf<T, I>>, &'static str> {
+		let mut price: BalanceOf<T, I> = Zero::zero();
+		let mut fee: BalanceOf<T, I> = Zero::zero();
+		let mut seller_proceeds: BalanceOf<T, I> = Zero::zero();
+		let mut donated: BalanceOf<T, I> = Zero::zero();
+		let mut payouts: Vec<(T::AccountId, BalanceOf<T, I>)> = Vec::new();
+
+		// payments go through the auction's escrow account
+		let escrow = Self::escrow_account(auction.id);
+		// check every payer before moving any funds, the credit of winners is drawn at unlock
+		for (payer, paid) in payers.iter() {
+			ensure!(Self::can_pay(payer, *paid), "Payer can not pay the bid");
+		}
+		for (payer, paid) in payers.iter() {
+			let (tranfer_value, payer_fee) = Self::_calc_auctino_fee(*paid);
+
+			// the escrow is emptied at once, so it never falls below the existential deposit
+			// in between payouts. A payment too small to create the escrow skips it.
+			let payment = if *paid < T::Currency::minimum_balance() {
+				T::Currency::withdraw(payer, *paid, WithdrawReason::Transfer, ExistenceRequirement::AllowDeath)?
+			} else {
+				T::Currency::transfer(payer, &escrow, *paid)?;
+				T::Currency::withdraw(&escrow, *paid, WithdrawReason::Transfer, ExistenceRequirement::AllowDeath)?
+			};
+
+			// trigger imbalance interface
+			let (fee_imbalance, mut rest) = payment.split(payer_fee);
+			Self::route_fee(fee_imbalance);
+
+			// donate to the charity first
+			let mut proceeds = tranfer_value;
+			if let Some((charity, percent)) = &auction.charity {
+				let amount = (*percent * tranfer_value).min(tranfer_value);
+				let (donation, remain) = rest.split(amount);
+				donated = donated.saturating_add(Self::resolve_payout(auction.id, charity, donation));
+				proceeds = tranfer_value.saturating_sub(amount);
+				rest = remain;
+			}
+
+			// pay the auction balance to the beneficiaries
+			for (beneficiary, amount) in Self::split_proceeds(&auction.owner, &auction.payout_split, proceeds) {
+				let (payout, remain) = rest.split(amount);
+				let amount = Self::resolve_payout(auction.id, &beneficiary, payout);
+				rest = remain;
+				match payouts.iter_mut().find(|(account, _)| *account == beneficiary) {
+					Some(payout) => payout.1 = payout.1.saturating_add(amount),
+					None => payouts.push((beneficiary, amount)),
+				}
 			}
+			Self::route_fee(rest);
+
+			price = price.saturating_add(*paid);
+			fee = fee.saturating_add(payer_fee);
+			seller_proceeds = seller_proceeds.saturating_add(tranfer_value);
 		}
-		// save to store
-		match vec_type {
-			StoreVecs::PendingVec => <PendingAuctions<T>>::put(stored_auction_ids),
-			StoreVecs::ActiveVec => <ActiveAuctions<T>>::put(stored_auction_ids),
-		};
+		Self::sweep_escrow(auction, &escrow)?;
+
+		let donation = auction.charity.as_ref().map(|(charity, _)| (charity.clone(), donated));
+		if let Some((charity, amount)) = &donation {
+			Self::deposit_event(RawEvent::CharityDonated(auction.id, charity.clone(), *amount));
+		}
+		let vested = auction.vest_proceeds
+			|| Self::vesting_threshold().map_or(false, |threshold| seller_proceeds > threshold);
+		for (beneficiary, amount) in payouts.iter() {
+			Self::deposit_event(RawEvent::ProceedsPaid(auction.id, beneficiary.clone(), *amount));
+			if vested {
+				Self::add_vesting(beneficiary, *amount);
+				Self::deposit_event(RawEvent::ProceedsVested(auction.id, beneficiary.clone(), *amount));
+			}
+		}
+
+		Ok(Payment { price, fee, seller_proceeds, donation, payouts })
 	}
 
-	/// remove an auction from a store vec set.
-	/// add by sunhao 20191024
-	/// modified by Tang 20191025
-	fn remove_all_from_set(vec_type: StoreVecs, auction_ids: &Vec<T::AuctionId>) {
-		let mut stored_auction_ids = match vec_type {
-			StoreVecs::PendingVec => Self::pending_auctions(),
-			StoreVecs::ActiveVec => Self::active_auctions(),
-		};
-		// remove from set
-		for auction_id in auction_ids.iter() {
-			stored_auction_ids = stored_auction_ids.iter()
-				.filter_map(|auc_id| if auc_id == auction_id {
-					None
-				} else {
-					Some(*auc_id)
+	/// The winner and the accounts paying for the item with their amounts.
+	/// A raffle is paid by all ticket holders, other auctions by the highest bidder.
+	fn winner_and_payers(auction: &Auction<T, I>) -> Option<(T::AccountId, Vec<(T::AccountId, BalanceOf<T, I>)>)> {
+		if auction.raffle.is_some() {
+			let winner = Self::draw_ticket(auction.id)?;
+			let payers = Self::auction_participants(auction.id).unwrap_or_default().into_iter()
+				.map(|account| {
+					let spent = Self::auction_bids(auction.id, &account);
+					(account, spent)
 				})
 				.collect();
+			Some((winner, payers))
+		} else {
+			let (winner, _) = auction.latest_participate.as_ref()?;
+			Some((winner.clone(), vec![(winner.clone(), Self::auction_bids(auction.id, winner))]))
 		}
-		
-		// save to store
-		match vec_type {
-			StoreVecs::PendingVec => <PendingAuctions<T>>::put(stored_auction_ids),
-			StoreVecs::ActiveVec => <ActiveAuctions<T>>::put(stored_auction_ids),
-		};
-	}
-
-	fn insert_auction(auction_id: T::AuctionId, auction:Auction<T>) {
-		// Create and store kitty
-		<Auctions<T>>::insert(auction_id, auction);
-		<NextAuctionId<T>>::put(auction_id + 1.into());
 	}
 
-	fn do_create_auction(
-		owner: &T::AccountId, 
-		begin_price: BalanceOf<T>,//起拍价
-		minimum_step: BalanceOf<T>,//最小加价幅度
-		upper_bound_price: Option<BalanceOf<T>>
-	) -> result::Result<T::AuctionId, &'static str> {
-		// 判断id
-		let auction_id = Self::get_next_auction_id()?;
-		let new_auction = Auction {
-			id: auction_id,
-			item: None, // 拍卖物品id
-			owner: (*owner).clone(), // 拍卖管理账户，可以控制暂停和继续
-			begin_price: begin_price, // 起拍价
-			minimum_step: minimum_step, // 最小加价幅度
-			status: AuctionStatus::PendingStart,
-			upper_bound_price: upper_bound_price,
-			start_at: None,
-			stop_at:None,
-			wait_period: None,
-			latest_participate: None,
-		};
-		Self::insert_auction(auction_id, new_auction);
-		Ok(auction_id)
+	/// Draw the winning ticket of a raffle.
+	/// NOTE: the collective flip seed can be biased by block authors, see `RaffleConfig`.
+	fn draw_ticket(auction_id: T::AuctionId) -> Option<T::AccountId> {
+		let tickets = Self::raffle_tickets(auction_id);
+		if tickets.is_empty() {
+			return None;
+		}
+		let seed = (
+			b"auction/raffle",
+			auction_id,
+			<randomness_collective_flip::Module<T>>::random_seed(),
+		).using_encoded(T::Hashing::hash);
+		let mut bytes = [0u8; 4];
+		bytes.copy_from_slice(&seed.as_ref()[..4]);
+		let index = u32::from_le_bytes(bytes) as usize % tickets.len();
+		tickets.get(index).cloned()
 	}
 
-	fn do_add_item(
-		sender: &T::AccountId, 
-		auction_id: T::AuctionId,
-		item: T::ItemId,//竞拍对象
-	) -> Result {
-		// ensure item owner
-		ensure!(T::AuctionTransfer::is_item_owner(sender, item), "you should be item's owner.");
-
-		// unwrap auction and ensure its status is PendingStart
-		let mut auction = Self::_ensure_auction_with_status(auction_id, Some(AuctionStatus::PendingStart), Some(sender))?;
-
-		// change status of auction
-		auction.item = Some(item);
-		<Auctions<T>>::insert(auction_id, auction);
+	fn ensure_valid_payout_split(payout_split: &Vec<(T::AccountId, Permill)>) -> Result {
+		if payout_split.is_empty() {
+			return Ok(());
+		}
+		ensure!(payout_split.len() <= MAX_PAYOUT_BENEFICIARIES, "Too many payout beneficiaries");
 
+		let mut total: u32 = 0;
+		for (i, (account, share)) in payout_split.iter().enumerate() {
+			ensure!(share.deconstruct() > 0, "Payout share should not be zero");
+			ensure!(payout_split.iter().skip(i + 1).all(|(a, _)| a != account), "Duplicate payout beneficiary");
+			total = total.saturating_add(share.deconstruct());
+		}
+		ensure!(total == Permill::one().deconstruct(), "Payout shares should sum to 100%");
 		Ok(())
 	}
 
-	// real work for do_setup_moments.
-	// separated by Tang 20191024
-	fn do_setup_moments(
+	/// Split `value` according to `payout_split`, the rounding remainder goes to the last beneficiary.
+	fn split_proceeds(
 		owner: &T::AccountId,
-		auction_id: T::AuctionId,
-		start_at: Option<T::Moment>,  //起拍时间
-		stop_at: Option<T::Moment>,  //结束时间
-		wait_period: Option<T::Moment>  //竞价等待时间
-	) -> Result {
-		// unwrap auction and ensure its status is PendingStart
-		let mut auction = Self::_ensure_auction_with_status(auction_id, Some(AuctionStatus::PendingStart), Some(owner))?;
+		payout_split: &Vec<(T::AccountId, Permill)>,
+		value: BalanceOf<T, I>
+	) -> Vec<(T::AccountId, BalanceOf<T, I>)> {
+		if payout_split.is_empty() {
+			return vec![(owner.clone(), value)];
+		}
+		Self::split_by_shares(value, payout_split)
+	}
 
-		// set moments into storage
-		if start_at.is_some() {
-			auction.start_at = start_at;
+	/// Split the value by the shares: every share gets the floor of its exact amount, so the
+	/// amounts never exceed the value. The units lost to rounding, less than one per share,
+	/// are left to the caller, which sends them to the fee destination.
+	pub fn split_by_shares<K: Clone>(value: BalanceOf<T, I>, shares: &[(K, Permill)]) -> Vec<(K, BalanceOf<T, I>)> {
+		let million: BalanceOf<T, I> = 1_000_000u32.into();
+		let (whole, fraction) = (value / million, value % million);
+
+		shares.iter()
+			.map(|(key, share)| {
+				let parts: BalanceOf<T, I> = share.deconstruct().into();
+				(key.clone(), whole * parts + fraction * parts / million)
+			})
+			.collect()
+	}
+
+	/// Pay out to the account, unless it is too small to create the account. Such a remainder
+	/// goes to the fee destination instead. Returns the amount paid to the account.
+	fn resolve_payout(auction_id: T::AuctionId, who: &T::AccountId, payout: NegativeImbalanceOf<T, I>) -> BalanceOf<T, I> {
+		let amount = payout.peek();
+		if T::Currency::total_balance(who).is_zero() && amount < T::Currency::minimum_balance() {
+			Self::route_fee(payout);
+			if !amount.is_zero() {
+				Self::deposit_event(RawEvent::PayoutRouted(auction_id, who.clone(), amount));
+			}
+			return Zero::zero();
 		}
-		if stop_at.is_some() {
-			auction.stop_at = stop_at;
+		T::Currency::resolve_creating(who, payout);
+		amount
+	}
+
+	/// Send an auction fee to the configured destination.
+	fn route_fee(fee: NegativeImbalanceOf<T, I>) {
+		match Self::fee_destination() {
+			FeeDestination::Burn => drop(fee),
+			FeeDestination::Treasury => T::OnAuctionPayment::on_unbalanced(fee),
+			FeeDestination::FixedAccount(account) => T::Currency::resolve_creating(&account, fee),
+			FeeDestination::Split(shares) => {
+				let mut rest = fee;
+				for (account, amount) in Self::split_by_shares(rest.peek(), &shares) {
+					let (part, remain) = rest.split(amount);
+					T::Currency::resolve_creating(&account, part);
+					rest = remain;
+				}
+				// 取整剩下的零头交给国库
+				T::OnAuctionPayment::on_unbalanced(rest);
+			},
 		}
-		if wait_period.is_some() {
-			auction.wait_period = wait_period;
+	}
+
+	/// FIXME using configable ratio
+	/// return transfer value and fee
+	fn _calc_auctino_fee (
+		price: BalanceOf<T, I>
+	) -> (BalanceOf<T, I>, BalanceOf<T, I>) {
+		let fee = price / 20.into();
+		(price.saturating_sub(fee), fee)
+	}
+
+	fn do_lock_balance(auction: &T::AuctionId, account: &T::AccountId, balance: BalanceOf<T, I>) -> Result {
+		// 账户在auction下锁定一些资产，如果已经锁过，这里会追加锁仓， balance是追价的delta部分
+
+		// 增加全局锁仓
+		let mut global_lock = balance;
+		if <AccountLocks<T, I>>::exists(account) {
+			global_lock = global_lock.checked_add(&Self::account_locks(account)).ok_or("balance add overflow")?;
 		}
 
-		// save to storage
-		<Auctions<T>>::insert(auction_id, auction);
+		// 增加auction下锁仓
+		let mut auction_lock = balance;
+		if <AuctionBids<T, I>>::exists(auction, account) {
+			auction_lock = auction_lock.checked_add(&Self::auction_bids(auction, account)).ok_or("balance add overflow")?;
+		}
+		<AccountLocks<T, I>>::insert(account, global_lock);
+		<AuctionBids<T, I>>::insert(auction, account, auction_lock);
+		<UnlockedBids<T, I>>::remove(auction, account);
 
-		// ensure this auction in pending queue, once owner call this fn.
-		Self::add_all_to_set(StoreVecs::PendingVec,  &vec![auction_id]);
-			
+		// 调用锁仓接口
+		T::Currency::extend_lock(
+			T::BidLockId::get(),
+			account,
+			global_lock,
+			<T as system::Trait>::BlockNumber::max_value(),
+			WithdrawReasons::none());
+	
 		Ok(())
 	}
 
-	// real work for do_pause_auction
-	// modified by Tang 20191025
-	fn do_pause_auction(
-		owner: &T::AccountId,
-		auction_id: T::AuctionId
-	) -> Result {
-		// unwrap auction and ensure its status is Active
-		let auction = Self::_ensure_auction_with_status(auction_id, Some(AuctionStatus::Active), Some(owner))?;
+	/// Unlock the bid of the account in the auction. A bid is unlocked only once,
+	/// unlocking it again does nothing.
+	fn do_unlock_balance(auction: &T::AuctionId, account: &T::AccountId) -> Result {
+		// 解锁账户在auction下锁定的所有资产
 
-		// change status of auction
-		Self::_change_auction_status(auction_id, auction.status, AuctionStatus::Paused);
+		// 获取用户在auction下的锁仓
+		if <AuctionBids<T, I>>::exists(auction, account) && !Self::bid_unlocked(auction, account) {
+			// the credit-backed part of the bid is not locked, only committed
+			let credit = Self::credit_bid(auction, account);
+			// collateral-backed bids only lock the collateral
+			let auction_lock = if <BidCollateral<T, I>>::exists(auction, account) {
+				Self::bid_collateral(auction, account)
+			} else {
+				Self::auction_bids(auction, account).saturating_sub(credit)
+			};
+
+			// 获取用户全局锁仓
+			let global_lock = if auction_lock.is_zero() {
+				None
+			} else {
+				ensure!(<AccountLocks<T, I>>::exists(account), "fatal error, can not find global lock for account");
+				let global_lock = Self::account_locks(account);
+				ensure!(global_lock >= auction_lock, "fatal error, global lock less than auction lock");
+				Some(global_lock - auction_lock)
+			};
 
+			// [No need remove, (commented by Tang)]
+			// <AuctionBids<T, I>>::remove(auction, account);
+			<UnlockedBids<T, I>>::insert(auction, account, true);
+			// 调用锁仓接口
+			match global_lock {
+				None => {},
+				Some(global_lock) if global_lock.is_zero() => {
+					<AccountLocks<T, I>>::remove(account);
+					T::Currency::remove_lock(T::BidLockId::get(), account);
+				},
+				Some(global_lock) => {
+					<AccountLocks<T, I>>::insert(account, global_lock);
+					T::Currency::set_lock(
+						T::BidLockId::get(),
+						account,
+						global_lock,
+						<T as system::Trait>::BlockNumber::max_value(),
+						WithdrawReasons::none());
+				},
+			}
+		}
+		// the credit backing the bid is no longer committed, on every path
+		let credit = <CreditBids<T, I>>::take(auction, account);
+		if !credit.is_zero() {
+			<CommittedCredits<T, I>>::mutate(account, |committed| *committed = committed.saturating_sub(credit));
+		}
 		Ok(())
 	}
 
-	// real work for do_resume_auction
-	// separated by Tang 20191024
-	// modified by Tang 20191025
-	fn do_resume_auction(
-		owner: &T::AccountId,
-		auction_id: T::AuctionId
+	/// Unlock the bid of a winner who pays right after, the credit backing it is drawn
+	/// into free balance to pay with.
+	fn do_unlock_winner(auction_id: &T::AuctionId, winner: &T::AccountId) -> Result {
+		let credit = Self::credit_bid(auction_id, winner);
+		Self::do_unlock_balance(auction_id, winner)?;
+		Self::draw_credit(winner, credit);
+		Ok(())
+	}
+
+	fn do_participate_auction(
+		auction_id: &T::AuctionId,
+		account: &T::AccountId,
+		price: BalanceOf<T, I>,
+		memo: Option<Vec<u8>>,
 	) -> Result {
-		// unwrap auction and ensure its status is Paused
-		let auction = Self::_ensure_auction_with_status(auction_id, Some(AuctionStatus::Paused), Some(owner))?;
+		<Auctions<T, I>>::mutate(auction_id, |a|{
+				if let Some(auc) = a {
+					auc.latest_participate = Option::Some((account.clone(), <aura::Module<T>>::last()));
+				}
+			});
+		let extrinsic_index = <system::Module<T>>::extrinsic_index().unwrap_or_default();
+		<BidPlacedAt<T, I>>::insert(auction_id, account, (<aura::Module<T>>::last(), extrinsic_index));
 
-		// change status of auction
-		Self::_change_auction_status(auction_id, auction.status, AuctionStatus::Active);
+		let mut participants;
+		if let Some(p) = Self::auction_participants(auction_id) {
+			participants = p;
+		} else {
+			participants = Vec::<T::AccountId>::new();
+		}
+
+		if !participants.contains(account) {
+			participants.push(account.clone());
+		}
+
+		<AuctionParticipants<T, I>>::insert(auction_id, participants);
+
+		// emit event
+		Self::deposit_event(RawEvent::BidderUpdated(*auction_id, account.clone(), price, 0, memo));
 
 		Ok(())
 	}
 
-	// storage work for auction status
-	// added by Tang 20191025
-	fn _change_auction_status(
-		auction_id: T::AuctionId,
-		old_status: AuctionStatus,
-		new_status: AuctionStatus,
-	) {
-		<Auctions<T>>::mutate(auction_id, |auc| {
-			if let Some(auction) = auc {
-				auction.status = new_status;
+	/// Ensure the auction is active and the price would make the highest bid.
+	/// Also used to reject losing bids before they are included into a block.
+	pub fn ensure_bid_acceptable(auction_id: T::AuctionId, price: BalanceOf<T, I>) -> result::Result<Auction<T, I>, &'static str> {
+		let auction = Self::_ensure_auction_with_status(auction_id, Some(AuctionStatus::Active), None)?;
+		ensure!(auction.raffle.is_none(), "Raffle only accepts tickets");
+		ensure!(price >= T::MinimumBidAmount::get(), "Bid amount below the minimum");
+		if let Some(denomination) = auction.bid_denomination {
+			ensure!((price % denomination).is_zero(), "Bid is not a multiple of the denomination");
+		}
+
+		// a lot needs to outbid the lowest winning bid once all units are taken
+		if !auction.lot.is_empty() {
+			if T::TieBreak::get() == TieBreak::RejectEqual {
+				let participants = Self::auction_participants(auction_id).unwrap_or_default();
+				ensure!(
+					!participants.iter().any(|account| Self::auction_bids(auction_id, account) == price),
+					"An equal bid already exists"
+				);
 			}
-		});
-		// emit event
-		Self::deposit_event(RawEvent::AuctionUpdated(auction_id, old_status, new_status));
+			if let Some((_, lowest)) = Self::lot_winners(&auction).get(auction.lot.len() - 1) {
+				let step = Self::minimum_step_at(&auction, *lowest);
+				let minimum = lowest.checked_add(&step).ok_or("balance add overflow")?;
+				ensure!(price > minimum, "Increment of bid price less than minimum step ");
+			}
+			return Ok(auction);
+		}
+
+		match &auction.latest_participate {
+			Some((account, _moment)) => { // 已经有用户出价
+				let bid_price = <AuctionBids<T, I>>::get(auction.id, account);
+				let step = Self::minimum_step_at(&auction, bid_price);
+				let minimum = bid_price.checked_add(&step).ok_or("balance add overflow")?;
+				ensure!(price > minimum, "Increment of bid price less than minimum step ");
+			},
+			_ => {}, // 尚无用户出价
+		};
+		Ok(auction)
 	}
 
-	// real work for stopping a auction.
-	// added by sunhao 20191024
-	// modified by Tang 20191024
-	fn do_stop_auction(
-		owner: &T::AccountId,
-		auction_id: T::AuctionId
+	/// Check the nonce and expiry of a bid signed by the bidder, returning the next nonce.
+	fn ensure_bid_payload(payload: &BidPayloadOf<T, I>) -> result::Result<u64, &'static str> {
+		ensure!(payload.expires_at >= <system::Module<T>>::block_number(), "Bid payload expired");
+		let nonce = Self::bid_nonce(&payload.bidder);
+		ensure!(payload.nonce == nonce, "Invalid bid nonce");
+		nonce.checked_add(1).ok_or("Bid nonce overflow")
+	}
+
+	/// Lock the increment of the bidder's price and record the bid.
+	/// The memo is only emitted in the bid event, not stored.
+	fn do_bid(
+		auction_id: &T::AuctionId,
+		participant: &T::AccountId,
+		price: BalanceOf<T, I>,
+		memo: Option<Vec<u8>>,
 	) -> Result {
-		// unwrap auction and ensure its status is not stopped yet.
-		let auction = Self::_ensure_auction_with_status(auction_id, None, Some(owner))?;
+		ensure!(memo.as_ref().map_or(true, |m| m.len() <= MAX_MEMO_LEN), "Bid memo too long");
+		let bids_in_block = Self::bids_in_block(auction_id, participant);
+		let max_bids = T::MaxBidsPerBlock::get();
+		ensure!(max_bids == 0 || bids_in_block < max_bids, "Too many bids in this block");
+		ensure!(!Self::is_auction_saturated(auction_id), "Auction is saturated in this block");
+		if let Some(auction) = Self::auctions(auction_id) {
+			ensure!(!Self::is_seller(&auction, participant), "Owner can not bid in own auction");
+		}
+		let delta = Self::bid_delta(auction_id, participant, price);
+		let collateral = Self::auctions(auction_id).map_or(false, |a| a.collateral_ratio.is_some());
+		if !collateral && !delta.is_zero() && delta <= Self::available_credit(participant) {
+			Self::do_commit_credit(auction_id, participant, delta);
+		} else {
+			ensure!(Self::can_lock(participant, delta), "No enough balance to lock");
+
+			Self::do_lock_balance(auction_id, participant, delta)?;
+			if collateral {
+				// the bid records the full price while only the collateral is locked
+				<BidCollateral<T, I>>::mutate(auction_id, participant, |collateral| *collateral = collateral.saturating_add(delta));
+				<AuctionBids<T, I>>::insert(auction_id, participant, price);
+			}
+		}
+		Self::do_participate_auction(auction_id, participant, price, memo)?;
+		let now = <system::Module<T>>::block_number();
+		<BidsInBlock<T, I>>::insert(auction_id, participant, (now, bids_in_block.saturating_add(1)));
+		<AuctionBidsInBlock<T, I>>::insert(auction_id, (now, Self::auction_bids_in_block(auction_id).saturating_add(1)));
+		T::OnBidPlaced::on_bid_placed(participant, price);
+
+		Ok(())
+	}
 
-		ensure!(auction.status != AuctionStatus::Stopped,
-			"Auction can NOT be stopped now.");
+	/// Number of bids the account has placed in the auction in the current block.
+	pub fn bids_in_block(auction_id: &T::AuctionId, who: &T::AccountId) -> u32 {
+		let (block, count) = Self::bids_in_block_record(auction_id, who);
+		if block == <system::Module<T>>::block_number() { count } else { 0 }
+	}
 
-		// call settle func if needed.
-		if auction.status != AuctionStatus::PendingStart {
-			Self::do_settle_auction(&auction)?;
-		}
+	/// Number of bids placed in the auction in the current block.
+	pub fn auction_bids_in_block(auction_id: &T::AuctionId) -> u32 {
+		let (block, count) = Self::auction_bids_in_block_record(auction_id);
+		if block == <system::Module<T>>::block_number() { count } else { 0 }
+	}
 
-		// change status of auction
-		Self::_change_auction_status(auction_id, auction.status, AuctionStatus::Stopped);
-		
-		// remove from active vecs
-		Self::remove_all_from_set(StoreVecs::ActiveVec, &vec![auction_id]);
+	/// Whether the auction takes no more bids in the current block.
+	pub fn is_auction_saturated(auction_id: &T::AuctionId) -> bool {
+		let max = T::MaxAuctionBidsPerBlock::get();
+		max != 0 && Self::auction_bids_in_block(auction_id) >= max
+	}
 
-		Ok(())
+	/// Whether the account owns the auction or any of its items.
+	fn is_seller(auction: &Auction<T, I>, who: &T::AccountId) -> bool {
+		auction.owner == *who
+			|| auction.item.iter().chain(auction.lot.iter()).any(|item| T::AuctionTransfer::is_item_owner(who, *item))
+	}
+
+	/// Bidding credit which is not backing any bid.
+	pub fn available_credit(who: &T::AccountId) -> BalanceOf<T, I> {
+		Self::bid_credit(who).saturating_sub(Self::committed_credit(who))
 	}
 
-	fn do_settle_auction(auction: &Auction<T>) -> Result {
-		// unlock all participents' balance
-		if let Some(participants) = <AuctionParticipants<T>>::get(auction.id) {
-			participants.iter().try_for_each(|account| {
-				Self::do_unlock_balance(&auction.id, account)
-			})?;
+	/// Back the increment of a bid with the bidder's credit instead of locking funds.
+	fn do_commit_credit(auction_id: &T::AuctionId, participant: &T::AccountId, delta: BalanceOf<T, I>) {
+		<CommittedCredits<T, I>>::mutate(participant, |committed| *committed = committed.saturating_add(delta));
+		<CreditBids<T, I>>::mutate(auction_id, participant, |credit| *credit = credit.saturating_add(delta));
+		<AuctionBids<T, I>>::mutate(auction_id, participant, |bid| *bid = bid.saturating_add(delta));
+		<UnlockedBids<T, I>>::remove(auction_id, participant);
+	}
+
+	/// Turn the credit which backed the payer's bid back into free balance to pay with.
+	fn draw_credit(payer: &T::AccountId, credit: BalanceOf<T, I>) {
+		if !credit.is_zero() {
+			<BidCredits<T, I>>::mutate(payer, |balance| *balance = balance.saturating_sub(credit));
+			T::Currency::unreserve(payer, credit);
 		}
+	}
 
-		// handle winner
-		let owner = &auction.owner;
-		// transfer auction item to winner
-		if let Some(item_id) = auction.item {
-			if let Some((winner, _)) = &auction.latest_participate {
-				let winner_bid = <AuctionBids<T>>::get(&auction.id, winner);
-				let (tranfer_value, fee) = Self::_calc_auctino_fee(winner_bid);
-
-				// FIXME: first ensure then modify store
-				// withdraw imbalance
-				let fee_imbalance = T::Currency::withdraw(
-					winner,
-					fee,
-					WithdrawReason::Fee,
-					ExistenceRequirement::KeepAlive
-				)?;
-				// transfer auction balance
-				T::Currency::transfer(winner, owner, tranfer_value)?;
+	/// Amount to lock when raising the bidder's bid to `price`.
+	fn bid_delta(auction_id: &T::AuctionId, participant: &T::AccountId, price: BalanceOf<T, I>) -> BalanceOf<T, I> {
+		if let Some(ratio) = Self::auctions(auction_id).and_then(|a| a.collateral_ratio) {
+			let collateral = ratio * price;
+			let locked = Self::bid_collateral(auction_id, participant);
+			collateral.saturating_sub(locked)
+		} else if <AuctionBids<T, I>>::exists(auction_id, participant) { // 已经参与过的用户再次出价
+			price.saturating_sub(<AuctionBids<T, I>>::get(auction_id, participant))
+		} else {
+			price
+		}
+	}
 
-				// try transfer item
-				T::AuctionTransfer::transfer_item(owner, winner, item_id)?;
+	/// Ensure the bid is the bidder's first and sponsored once, and the sponsor can pay for it.
+	/// Returns the account paying the fee.
+	pub fn ensure_sponsorship(
+		auction_id: T::AuctionId,
+		bidder: &T::AccountId,
+		price: BalanceOf<T, I>,
+		sponsor: Sponsor,
+	) -> result::Result<T::AccountId, &'static str> {
+		ensure!(!Self::is_sponsored(bidder), "Bidder is already sponsored");
+		ensure!(price >= T::MinSponsoredBid::get(), "Sponsored bid below the minimum");
+		// 只赞助新账户：没有任何锁定的出价和出价额度
+		ensure!(
+			Self::account_locks(bidder).is_zero() && Self::bid_credit(bidder).is_zero(),
+			"Only first bids are sponsored"
+		);
+		let account = match sponsor {
+			Sponsor::Owner => Self::auctions(auction_id).ok_or("Auction not exist")?.owner,
+			Sponsor::Pool => Self::sponsor_pool_account().ok_or("No sponsor pool")?,
+		};
+		ensure!(Self::sponsor_budget(&account) >= T::SponsoredBidFee::get(), "Sponsor budget exhausted");
+		Ok(account)
+	}
 
-				// trigger imbalance interface
-				T::OnAuctionPayment::on_unbalanced(fee_imbalance);
-			}
+	/// Check a bid in the pool, with the `validity` code of the reason it can not win.
+	pub fn check_bid(
+		auction_id: T::AuctionId,
+		price: BalanceOf<T, I>,
+		terms_hash: &Option<T::Hash>,
+	) -> result::Result<(), u8> {
+		match Self::auctions(auction_id) {
+			Some(ref auction) if auction.status == AuctionStatus::Active && auction.raffle.is_none() => {
+				Self::ensure_terms(auction, terms_hash).map_err(|_| validity::TERMS_MISMATCH)?;
+			},
+			_ => return Err(validity::NOT_BIDDABLE),
 		}
+		Self::ensure_bid_acceptable(auction_id, price).map(|_| ()).map_err(|_| validity::BID_TOO_LOW)
+	}
 
+	/// Bids on an auction with terms of sale should carry the same terms hash.
+	fn ensure_terms(auction: &Auction<T, I>, terms_hash: &Option<T::Hash>) -> Result {
+		if auction.terms_hash.is_some() {
+			ensure!(*terms_hash == auction.terms_hash, "Terms hash mismatch");
+		}
 		Ok(())
 	}
 
-	/// FIXME using configable ratio
-	/// return transfer value and fee
-	fn _calc_auctino_fee (
-		price: BalanceOf<T>
-	) -> (BalanceOf<T>, BalanceOf<T>) {
-		let fee = (price / 20.into()).min(One::one());
-		(price - fee, fee)
+	/// Record the bidder's acceptance of the terms of sale.
+	fn record_terms(auction: &Auction<T, I>, bidder: &T::AccountId) {
+		if let Some(terms_hash) = auction.terms_hash {
+			<TermsAcceptances<T, I>>::insert(auction.id, bidder, terms_hash);
+		}
 	}
 
-	fn do_lock_balance(auction: &T::AuctionId, account: &T::AccountId, balance: BalanceOf<T>) -> Result {
-		// 账户在auction下锁定一些资产，如果已经锁过，这里会追加锁仓， balance是追价的delta部分
+	/// What the owner of a private auction signs to invite a bidder.
+	pub fn invite_payload(auction_id: T::AuctionId, bidder: &T::AccountId) -> Vec<u8> {
+		Self::signing_payload(&(b"invite", auction_id, bidder))
+	}
 
-		// 增加全局锁仓
-		let mut global_lock = balance;
-		if <AccountLocks<T>>::exists(account) {
-			global_lock = global_lock.checked_add(&Self::account_locks(account)).ok_or("balance add overflow")?;
-		}
-		<AccountLocks<T>>::insert(account, global_lock);
-		
-		// 增加auction下锁仓
-		let mut auction_lock = balance;
-		if <AuctionBids<T>>::exists(auction, account) {
-			auction_lock = auction_lock.checked_add(&Self::auction_bids(auction, account)).ok_or("balance add overflow")?;
+	/// Private auctions only accept bids carrying the owner's invite for the bidder,
+	/// so an invite seen on chain can not be reused by another account.
+	fn ensure_invited(auction: &Auction<T, I>, bidder: &T::AccountId, invite: Option<T::BidSignature>) -> Result {
+		if auction.invite_only {
+			let invite = invite.ok_or("Invite required for private auction")?;
+			ensure!(invite.verify(&Self::invite_payload(auction.id, bidder)[..], &auction.owner), "Invalid invite");
 		}
-		<AuctionBids<T>>::insert(auction, account, auction_lock);
-
-		// 调用锁仓接口
-		T::Currency::extend_lock(
-			AUCTION_ID,
-			account,
-			global_lock,
-			<T as system::Trait>::BlockNumber::max_value(),
-			WithdrawReasons::none());
-	
 		Ok(())
 	}
 
-	fn do_unlock_balance(auction: &T::AuctionId, account: &T::AccountId) -> Result {
-		// 解锁账户在auction下锁定的所有资产
+	// ====== offchain worker related methods ======
+	/// Whether the offchain worker drives auction timing, otherwise due auctions are processed
+	/// in `on_initialize`.
+	pub fn offchain_timing() -> bool {
+		T::OffchainTiming::get()
+	}
 
-		// 获取用户在auction下的锁仓
-		if <AuctionBids<T>>::exists(auction, account) {
-			let auction_lock = Self::auction_bids(auction, account);
+	/// only run by current validator
+	#[cfg(feature = "offchain")]
+	fn run_offchain_worker(now: T::BlockNumber) {
+		debug::RuntimeLogger::init();
 
-			// 获取用户全局锁仓
-			ensure!(<AccountLocks<T>>::exists(account), "fatal error, can not find global lock for account");
-			let mut global_lock = Self::account_locks(account);
-			ensure!(global_lock >= auction_lock, "fatal error, global lock less than auction lock");
-			
-			// [No need remove, (commented by Tang)]
-			// <AuctionBids<T>>::remove(auction, account);
-			global_lock = global_lock.checked_sub(&auction_lock).ok_or("balance sub overflow")?;
-			// 调用锁仓接口
-			if global_lock == Zero::zero() {
-				<AccountLocks<T>>::remove(account);
-				T::Currency::remove_lock(AUCTION_ID, account);
-			} else {
-				<AccountLocks<T>>::insert(account, global_lock);
-				T::Currency::set_lock(
-					AUCTION_ID,
-					account,
-					global_lock,
-					<T as system::Trait>::BlockNumber::max_value(),
-					WithdrawReasons::none());
-			}
+		// Only send messages if we are a potential validator.
+		if Self::offchain_timing() && runtime_io::is_validator() {
+			Self::offchain(now);
 		}
-		Ok(())
 	}
 
-	fn do_participate_auction(auction_id: &T::AuctionId, account: &T::AccountId, price: BalanceOf<T>) -> Result {
-		<Auctions<T>>::mutate(auction_id, |a|{
-				if let Some(auc) = a {
-					auc.latest_participate = Option::Some((account.clone(), <aura::Module<T>>::last()));
-				}
+	#[cfg(not(feature = "offchain"))]
+	fn run_offchain_worker(_now: T::BlockNumber) {}
+
+	/// Auctions due to start or to stop, and stale pending auctions to purge.
+	fn due_auction_ids() -> Vec<T::AuctionId> {
+		let last_timestamp = <aura::Module<T>>::last();
+
+		// check auction start, stale pending auctions are purged
+		let starting_auction_ids = <PendingAuctions<T, I>>::get().into_iter()
+			.filter(|auction_id| {
+				Self::auctions(auction_id).map_or(false, |auction| {
+					Self::is_due_to_start(&auction, last_timestamp)
+						|| Self::is_stale(&auction, last_timestamp)
+						|| Self::is_expired(&auction, last_timestamp)
+				})
 			});
+		// check auction end
+		let stoping_auction_ids = <ActiveAuctions<T, I>>::get().into_iter()
+			.filter(|auction_id| {
+				Self::auctions(auction_id).map_or(false, |auction| Self::is_due_to_stop(&auction, last_timestamp))
+			});
+		starting_auction_ids.chain(stoping_auction_ids).collect()
+	}
 
-		let mut participants;
-		if let Some(p) = Self::auction_participants(auction_id) {
-			participants = p;
-		} else {
-			participants = Vec::<T::AccountId>::new();
+	/// Start, stop or purge the auctions which are due, skipping the others.
+	fn do_process_due_auctions(auction_ids: &Vec<T::AuctionId>) {
+		let now = <aura::Module<T>>::last();
+		let mut starting = Vec::new();
+		let mut stopping = Vec::new();
+		let mut purging = Vec::new();
+		for auction_id in auction_ids.iter() {
+			match Self::auctions(auction_id) {
+				Some(ref auction) if Self::is_due_to_start(auction, now) => starting.push(*auction_id),
+				Some(ref auction) if Self::is_due_to_stop(auction, now) => stopping.push(*auction_id),
+				Some(auction) => if Self::is_stale(&auction, now) || Self::is_expired(&auction, now) {
+					purging.push(auction)
+				},
+				_ => {},
+			}
 		}
+		Self::do_start_auctions(&starting);
+		let stopping = Self::do_stop_auctions(&stopping);
+		// the whole deposit goes back to the owner, no one to pay a bounty
+		purging.retain(|auction| Self::do_purge_stale(auction, None).is_ok());
+		let skipped = auction_ids.len() - starting.len() - stopping.len() - purging.len();
+		Self::deposit_event(RawEvent::DueAuctionsProcessed(
+			starting.len() as u32, stopping.len() as u32, purging.len() as u32, skipped as u32,
+		));
+	}
 
-		if !participants.contains(account) {
-			participants.push(account.clone());
+	#[cfg(feature = "offchain")]
+	pub(crate) fn offchain(_now: T::BlockNumber) {
+		// send the due auctions in batches, only matched ones
+		let due_auction_ids = Self::due_auction_ids();
+		let batch_size = T::MaxDueBatchSize::get().max(1) as usize;
+		for batch in due_auction_ids.chunks(batch_size) {
+			match Self::_send_process_due_tx(batch.to_vec()) {
+				Ok(_) => {},
+				Err(err) => print(err),
+			}
 		}
+	}
 
-		<AuctionParticipants<T>>::insert(auction_id, participants);
+	/// Whether a pending auction is due to start at the moment.
+	pub fn is_due_to_start(auction: &Auction<T, I>, now: T::Moment) -> bool {
+		// ensure now is pending start
+		if auction.status != AuctionStatus::PendingStart || Self::is_expired(auction, now) {
+			return false;
+		}
+		// an item frozen meanwhile keeps the auction pending
+		if Self::ensure_items_transferable(auction).is_err() {
+			return false;
+		}
+		// Condition: start_at < now
+		auction.start_at.map_or(false, |start_at| start_at < now)
+	}
 
-		// emit event
-		Self::deposit_event(RawEvent::BidderUpdated(*auction_id, account.clone(), price, 0));
+	/// Whether an active or paused auction is due to stop at the moment.
+	pub fn is_due_to_stop(auction: &Auction<T, I>, now: T::Moment) -> bool {
+		// ensure now auction is not Stopped
+		if auction.status == AuctionStatus::Stopped || auction.status == AuctionStatus::PendingStart {
+			return false;
+		}
+		// Condition D: older than MaxAuctionLifetime, paused or not
+		if Self::is_expired(auction, now) {
+			return true;
+		}
+		// auctions of a round close together at the end of the round
+		if auction.round.is_some() && auction.stop_at.map_or(true, |t| t >= now) {
+			return false;
+		}
+		// Condition C: all raffle tickets are sold
+		if let Some(raffle) = &auction.raffle {
+			if Self::raffle_tickets(auction.id).len() as u32 >= raffle.max_tickets {
+				return true;
+			}
+		}
+		let mut stop_at = match auction.stop_at {
+			Some(t) => t,
+			None => return false,
+		};
+		// a jittered auction closes at stop_at + offset, the offset is only drawn after stop_at
+		if auction.close_jitter {
+			match Self::close_offset(auction.id) {
+				Some(offset) => stop_at = stop_at.saturating_add(offset),
+				None => return false,
+			}
+		}
+		// Condition A: stop_at < now
+		if stop_at < now {
+			return true;
+		}
+		// Condition B: reach upper_bound_price
+		let upper_bound_price = match auction.upper_bound_price {
+			Some(v) => v,
+			None => return false,
+		};
+		// get last participate price
+		if let Some((account_id, last_moment)) = &auction.latest_participate {
+			let last_price = <AuctionBids<T, I>>::get(&auction.id, account_id);
+			// price end condition
+			if last_price >= upper_bound_price {
+				return true;
+			}
+			// period end condition
+			if let Some(wait_period) = auction.wait_period {
+				if now.saturating_sub(*last_moment) > wait_period {
+					return true;
+				}
+			}
+		}
+		false
+	}
 
+	/// Ensure all the auctions are due to start, or to stop, else the `validity` code of the
+	/// first one which is not. An empty list is never due.
+	fn are_auctions_due(auction_ids: &Vec<T::AuctionId>, to_start: bool) -> result::Result<(), u8> {
+		let now = <aura::Module<T>>::last();
+		ensure!(!auction_ids.is_empty(), validity::NOT_DUE);
+		for auction_id in auction_ids.iter() {
+			let auction = Self::auctions(auction_id).ok_or(validity::NOT_DUE)?;
+			if to_start {
+				ensure!(auction.status == AuctionStatus::PendingStart, validity::ALREADY_STARTED);
+				ensure!(Self::is_due_to_start(&auction, now), validity::NOT_DUE);
+			} else {
+				ensure!(auction.status != AuctionStatus::Stopped, validity::ALREADY_STOPPED);
+				ensure!(Self::is_due_to_stop(&auction, now), validity::NOT_DUE);
+			}
+		}
 		Ok(())
 	}
 
-	// ====== offchain worker related methods ======
-	/// only run by current validator
-	pub(crate) fn offchain(_now: T::BlockNumber) {
-		// check auction start
-		let pending_auctions = <PendingAuctions<T>>::get();
-		let last_timestamp = <aura::Module<T>>::last();
-
-		let starting_auction_ids: Vec<T::AuctionId> = pending_auctions.into_iter()
-			.filter_map(|auction_id| {
-				let auction = match <Auctions<T>>::get(auction_id) {
-					Some(a) => a,
-					None => return None,
-				};
-				// ensure now is pending start
-				if auction.status != AuctionStatus::PendingStart {
-					return None;
-				}
-				let start_at = match auction.start_at {
-					Some(t) => t,
-					None => return None,
-				};
-				// Condition: start_at < now
-				if start_at < last_timestamp {
-					Some(auction.id)
-				} else {
-					None
-				}
-			})
-			.collect();
-		// only start matched
-		match Self::_send_auction_start_tx(starting_auction_ids) {
-			Ok(_) => {},
-			Err(err) => print(err),
+	/// Whether an auction was left pending without moments for `StaleAuctionTimeout`.
+	pub fn is_stale(auction: &Auction<T, I>, now: T::Moment) -> bool {
+		if auction.status != AuctionStatus::PendingStart || auction.start_at.is_some() {
+			return false;
 		}
+		// auctions without a creation deposit are stale as well
+		Self::auction_created_at(auction.id)
+			.map_or(false, |created_at| created_at.saturating_add(T::StaleAuctionTimeout::get()) < now)
+	}
 
-		// check auction end
-		let active_auctions = <ActiveAuctions<T>>::get();
+	/// Whether the storage is not yet migrated to the current layout.
+	pub fn is_migrating() -> bool {
+		Self::storage_version() < migration::CURRENT_VERSION
+	}
 
-		let stoping_auction_ids: Vec<T::AuctionId> = active_auctions.into_iter()
-			.filter_map(|auction_id| {
-				let auction = match <Auctions<T>>::get(auction_id) {
-					Some(a) => a,
-					None => return None,
-				};
-				// ensure now auction is not Stopped
-				if auction.status == AuctionStatus::Stopped {
-					return None;
-				}
-				let stop_at = match auction.stop_at {
-					Some(t) => t,
-					None => return None,
-				};
-				// Condition A: stop_at < now
-				if stop_at < last_timestamp {
-					return Some(auction.id);
-				}
-				// Condition B: reach upper_bound_price
-				let upper_bound_price = match auction.upper_bound_price {
-					Some(v) => v,
-					None => return None,
-				};
-				// get last participate price
-				if let Some((account_id, last_moment)) = auction.latest_participate {
-					let last_price = <AuctionBids<T>>::get(&auction.id, account_id);
-					// price end condition
-					if last_price >= upper_bound_price {
-						return Some(auction.id);
-					}
-					// period end condition
-					if let Some(wait_period) = auction.wait_period {
-						if last_timestamp - last_moment > wait_period {
-							return Some(auction.id);
-						}
-					}
-				}
-				None
-			})
-			.collect();
-		// only stop matched
-		match Self::_send_auction_stop_tx(stoping_auction_ids) {
-			Ok(_) => {},
-			Err(err) => print(err),
+	/// Whether an auction not yet stopped is older than `MaxAuctionLifetime`.
+	pub fn is_expired(auction: &Auction<T, I>, now: T::Moment) -> bool {
+		let lifetime = T::MaxAuctionLifetime::get();
+		if lifetime.is_zero() || auction.status == AuctionStatus::Stopped {
+			return false;
 		}
+		Self::auction_created_at(auction.id).map_or(false, |created_at| created_at.saturating_add(lifetime) < now)
 	}
 
-	fn _send_auction_start_tx(
-		auction_ids: Vec<T::AuctionId>
-	) -> result::Result<(), OffchainErr> {
-		let signature = Self::_sign_unchecked_payload(&auction_ids.encode())?;
-		let call = Call::<T>::start_auctions_passive(auction_ids, signature);
-		
-		T::SubmitTransaction::submit_unsigned(call)
-			.map_err(|_| OffchainErr::SubmitTransaction)?;
+	/// Stop a stale or expired pending auction and release its item, paying a bounty to the purger if any.
+	fn do_purge_stale(auction: &Auction<T, I>, purger: Option<&T::AccountId>) -> Result {
+		auction.status.transition(AuctionAction::Stop)?;
+		let mut bounty = Zero::zero();
+		if let (Some(purger), Some((deposit, created_at))) = (purger, Self::creation_deposit(auction.id)) {
+			let amount = T::StalePurgeBounty::get() * deposit;
+			if T::Currency::repatriate_reserved(&auction.owner, purger, amount).is_ok() {
+				bounty = amount;
+			}
+			<CreationDeposits<T, I>>::insert(auction.id, (deposit.saturating_sub(bounty), created_at));
+		}
+		Self::_change_auction_status(auction.id, AuctionAction::Stop)?;
+		Self::release_item(auction);
+		Self::remove_all_from_set(StoreVecs::PendingVec, &vec![auction.id]);
+		Self::deposit_event(RawEvent::StaleAuctionPurged(auction.id, bounty));
+
+		Ok(())
+	}
+
+	/// Ensure a batch of auctions is due to start or stop as a whole, else the `validity` code
+	/// of the first auction which is not.
+	fn is_batch_due(auction_ids: &Vec<T::AuctionId>) -> result::Result<(), u8> {
+		let now = <aura::Module<T>>::last();
+		ensure!(!auction_ids.is_empty(), validity::NOT_DUE);
+		ensure!(auction_ids.len() as u32 <= T::MaxDueBatchSize::get(), validity::BATCH_TOO_LARGE);
+		for auction_id in auction_ids.iter() {
+			let auction = Self::auctions(auction_id).ok_or(validity::NOT_DUE)?;
+			ensure!(auction.status != AuctionStatus::Stopped, validity::ALREADY_STOPPED);
+			ensure!(
+				Self::is_due_to_start(&auction, now) || Self::is_due_to_stop(&auction, now)
+					|| Self::is_stale(&auction, now) || Self::is_expired(&auction, now),
+				validity::NOT_DUE
+			);
+		}
 		Ok(())
 	}
 
-	fn _send_auction_stop_tx(
+	#[cfg(feature = "offchain")]
+	fn _send_process_due_tx(
 		auction_ids: Vec<T::AuctionId>
 	) -> result::Result<(), OffchainErr> {
 		let signature = Self::_sign_unchecked_payload(&auction_ids.encode())?;
-		let call = Call::<T>::stop_auctions_passive(auction_ids, signature);
-		
+		let call = Call::<T, I>::process_due_auctions(auction_ids, signature);
+
 		T::SubmitTransaction::submit_unsigned(call)
 			.map_err(|_| OffchainErr::SubmitTransaction)?;
 		Ok(())
 	}
 
 	/// Sign for unchecked transaction
+	#[cfg(feature = "offchain")]
 	fn _sign_unchecked_payload(payload: &Vec<u8>) -> result::Result<SignatureOf<T>, OffchainErr> {
 		let key = Self::authority_id();
 		if key.is_none() {
@@ -931,22 +4561,320 @@ impl<T: Trait> Module<T> {
 	// 	}
 	// 	Err("query fail")
 	// }
+
+	/// Current highest bid of a single item auction, zero before any bid.
+	pub fn highest_bid(auction_id: T::AuctionId) -> BalanceOf<T, I> {
+		Self::auctions(auction_id)
+			.and_then(|auction| auction.latest_participate)
+			.map_or(Zero::zero(), |(account, _)| Self::auction_bids(auction_id, &account))
+	}
+
+	/// Record the highest bid of every active auction with bids, every `PriceSnapshotInterval` blocks.
+	/// A round walks at most `MAX_SCANNED_AUCTIONS` auctions per block and goes on in the next blocks.
+	fn take_price_snapshots(now: T::BlockNumber) {
+		let interval = T::PriceSnapshotInterval::get();
+		let max = T::MaxPriceSnapshots::get();
+		if interval.is_zero() || max == 0 {
+			return;
+		}
+		// 每个间隔开始新一轮，未完成的上一轮不再继续
+		let start = if (now % interval).is_zero() {
+			0
+		} else {
+			match Self::price_snapshot_round() {
+				Some(start) => start as usize,
+				None => return,
+			}
+		};
+
+		let active = Self::active_auctions();
+		let start = start.min(active.len());
+		let end = active.len().min(start + MAX_SCANNED_AUCTIONS);
+		for auction_id in active[start..end].iter().cloned() {
+			let has_bids = Self::auctions(auction_id).map_or(false, |a| a.latest_participate.is_some());
+			if has_bids {
+				Self::push_price_snapshot(auction_id, now, Self::highest_bid(auction_id), max);
+			}
+		}
+		if end < active.len() {
+			<PriceSnapshotRound<T, I>>::put(end as u32);
+		} else {
+			<PriceSnapshotRound<T, I>>::kill();
+		}
+	}
+
+	/// Write a snapshot at the cursor of the ring buffer, overwriting the oldest once it is full.
+	fn push_price_snapshot(auction_id: T::AuctionId, now: T::BlockNumber, price: BalanceOf<T, I>, max: u32) {
+		let mut snapshots = Self::price_snapshots(auction_id);
+		// 缓冲区上限调小后，多出的快照一并丢弃
+		snapshots.truncate(max as usize);
+		let cursor = (Self::price_snapshot_cursor(auction_id) as usize).min(snapshots.len());
+		if snapshots.len() < max as usize {
+			snapshots.insert(cursor, (now, price));
+		} else {
+			snapshots[cursor % max as usize] = (now, price);
+		}
+		<PriceSnapshots<T, I>>::insert(auction_id, snapshots);
+		<PriceSnapshotCursor<T, I>>::insert(auction_id, (cursor as u32).saturating_add(1) % max);
+	}
+
+	/// Draw the close offset of every jittered active auction whose stop_at has just passed.
+	/// The offset is unknown until then, so the realized close time can not be targeted.
+	/// At most `MAX_SCANNED_AUCTIONS` auctions are checked per block, from a cursor rotating
+	/// through the active auctions.
+	fn draw_close_offsets() {
+		let active = Self::active_auctions();
+		if active.is_empty() {
+			return;
+		}
+		let max: u64 = T::MaxCloseJitter::get().unique_saturated_into();
+		let now = <aura::Module<T>>::last();
+		let start = Self::close_jitter_cursor() as usize % active.len();
+		let count = active.len().min(MAX_SCANNED_AUCTIONS);
+		<CloseJitterCursor<T, I>>::put(((start + count) % active.len()) as u32);
+		for auction_id in active.iter().cycle().skip(start).take(count).cloned() {
+			let due = Self::auctions(auction_id).map_or(false, |auction| {
+				auction.close_jitter && auction.stop_at.map_or(false, |t| t < now)
+			});
+			if !due || <CloseOffsets<T, I>>::exists(auction_id) {
+				continue;
+			}
+			let seed = (
+				b"auction/jitter",
+				auction_id,
+				<randomness_collective_flip::Module<T>>::random_seed(),
+			).using_encoded(T::Hashing::hash);
+			let mut bytes = [0u8; 8];
+			bytes.copy_from_slice(&seed.as_ref()[..8]);
+			let offset = u64::from_le_bytes(bytes) % max.saturating_add(1);
+			<CloseOffsets<T, I>>::insert(auction_id, offset.unique_saturated_into());
+		}
+	}
+
+	/// The auctions of an owner grouped by status, with its pending proceeds, held deposits
+	/// and locked items. Walks all auctions, only meant for runtime api queries.
+	pub fn owner_dashboard(owner: &T::AccountId) -> OwnerDashboardOf<T, I> {
+		let mut dashboard = OwnerDashboard::default();
+		let settlement_queue = Self::settlement_queue();
+		let next_index = Self::next_auction_id();
+		let mut index: T::AuctionIndex = Zero::zero();
+		while index < next_index {
+			let auction = Self::auction_by_index(index).and_then(|id| Self::auctions(id));
+			index = index.saturating_add(One::one());
+			let auction = match auction {
+				Some(auction) => auction,
+				None => continue,
+			};
+			let id = auction.id;
+			for comment in Self::comments(id).iter().filter(|c| c.author == *owner) {
+				dashboard.deposits_held = dashboard.deposits_held.saturating_add(comment.deposit);
+			}
+			if auction.owner != *owner {
+				continue;
+			}
+
+			match auction.status {
+				AuctionStatus::PendingStart => dashboard.pending.push(id),
+				AuctionStatus::Active => dashboard.active.push(id),
+				AuctionStatus::Paused => dashboard.paused.push(id),
+				AuctionStatus::Stopped => dashboard.stopped.push(id),
+			}
+			if let Some((deposit, _)) = Self::creation_deposit(id) {
+				dashboard.deposits_held = dashboard.deposits_held.saturating_add(deposit);
+			}
+			for item in auction.item.iter().chain(auction.lot.iter()) {
+				if Self::auction_items(item) == Some(id) {
+					dashboard.locked_items.push(*item);
+				}
+			}
+
+			let pending = if let Some(state) = Self::installment(id) {
+				state.remaining
+			} else if let Some((winner, _)) = Self::pending_claim(id).or_else(|| Self::inspection(id)) {
+				Self::auction_bids(id, &winner)
+			} else if settlement_queue.contains(&id) {
+				Self::highest_bid(id)
+			} else {
+				Zero::zero()
+			};
+			dashboard.pending_proceeds = dashboard.pending_proceeds.saturating_add(pending);
+		}
+
+		let now = <aura::Module<T>>::last();
+		dashboard.vesting_proceeds = Self::proceeds_vesting(owner).iter()
+			.fold(Zero::zero(), |total: BalanceOf<T, I>, schedule| total.saturating_add(Self::vesting_locked(schedule, now)));
+		dashboard.deposits_held = dashboard.deposits_held.saturating_add(Self::sponsor_budget(owner));
+		dashboard
+	}
+
+	/// Price snapshots of an auction ordered from the oldest, for charting.
+	pub fn price_chart(auction_id: T::AuctionId) -> Vec<(T::BlockNumber, BalanceOf<T, I>)> {
+		let mut snapshots = Self::price_snapshots(auction_id);
+		let cursor = Self::price_snapshot_cursor(auction_id) as usize;
+		if cursor < snapshots.len() {
+			snapshots.rotate_left(cursor);
+		}
+		snapshots
+	}
+}
+
+/// Storage sanity checks, run by the tests and by the `try-runtime` tooling before upgrades.
+#[cfg(any(feature = "try-runtime", test))]
+impl<T: Trait<I>, I: Instance> Module<T, I> {
+	/// Walk the auction storage and check that the auctions, their items, the status
+	/// indexes and the escrow accounts agree with each other.
+	pub fn do_try_state() -> Result {
+		let pending = Self::pending_auctions();
+		let active = Self::active_auctions();
+		for (i, auction_id) in pending.iter().chain(active.iter()).enumerate() {
+			ensure!(
+				!pending.iter().chain(active.iter()).skip(i + 1).any(|id| id == auction_id),
+				"Auction listed twice in the status indexes"
+			);
+		}
+
+		let mut index: T::AuctionIndex = Zero::zero();
+		while index < Self::next_auction_id() {
+			let auction_id = Self::auction_by_index(index).ok_or("Auction index without auction")?;
+			let auction = Self::auctions(auction_id).ok_or("Indexed auction not exist")?;
+			ensure!(auction.id == auction_id, "Auction stored under another id");
+
+			match auction.status {
+				AuctionStatus::PendingStart => ensure!(pending.contains(&auction_id), "Pending auction not indexed"),
+				AuctionStatus::Active | AuctionStatus::Paused => {
+					ensure!(active.contains(&auction_id), "Active auction not indexed")
+				},
+				AuctionStatus::Stopped => ensure!(
+					!pending.contains(&auction_id) && !active.contains(&auction_id),
+					"Stopped auction still indexed"
+				),
+			}
+			if auction.status != AuctionStatus::Stopped {
+				for item in auction.item.iter().chain(auction.lot.iter()) {
+					ensure!(Self::auction_items(item) == Some(auction_id), "Item of an open auction not held by it");
+				}
+			}
+			// escrows are emptied within the settlement which fills them
+			ensure!(
+				T::Currency::free_balance(&Self::escrow_account(auction_id)).is_zero(),
+				"Escrow account not empty"
+			);
+
+			index = index.saturating_add(One::one());
+		}
+		Ok(())
+	}
+}
+
+/// Instrumentation for the invariant tests, rebuilding the books from the bids.
+#[cfg(test)]
+impl<T: Trait<I>, I: Instance> Module<T, I> {
+	/// Funds the account should have locked for its bids in the auctions, skipping the
+	/// stopped ones whose bids are unlocked at settlement.
+	pub fn locked_by_bids(who: &T::AccountId, auction_ids: &[T::AuctionId]) -> BalanceOf<T, I> {
+		auction_ids.iter()
+			.filter(|auction_id| Self::auctions(*auction_id).map_or(false, |a| a.status != AuctionStatus::Stopped))
+			.filter(|auction_id| <AuctionBids<T, I>>::exists(*auction_id, who))
+			.fold(Zero::zero(), |total: BalanceOf<T, I>, auction_id| {
+				let locked = if <BidCollateral<T, I>>::exists(auction_id, who) {
+					Self::bid_collateral(auction_id, who)
+				} else {
+					Self::auction_bids(auction_id, who).saturating_sub(Self::credit_bid(auction_id, who))
+				};
+				total.saturating_add(locked)
+			})
+	}
+}
+
+impl<T: Trait<I>, I: Instance> IsItemLocked<T::ItemId> for Module<T, I> {
+	fn is_item_locked(item_id: T::ItemId) -> bool {
+		<AuctionItems<T, I>>::exists(item_id)
+	}
 }
 
-impl<T: Trait> support::unsigned::ValidateUnsigned for Module<T> {
-	type Call = Call<T>;
+impl<T: Trait<I>, I: Instance> support::unsigned::ValidateUnsigned for Module<T, I> {
+	type Call = Call<T, I>;
 
 	fn validate_unsigned(call: &Self::Call) -> TransactionValidity {
+		// bids are signed by the bidder rather than an authority
+		let (payload, signature, sponsor) = match call {
+			Call::participate_auction_unsigned(payload, signature) => (payload, signature, None),
+			Call::participate_auction_sponsored(payload, signature, sponsor) => (payload, signature, Some(*sponsor)),
+			// the offchain worker's calls, signed by an authority
+			_ => return <Module<T, I>>::validate_offchain_call(call),
+		};
+		if !signature.verify(&<Module<T, I>>::signing_payload(payload)[..], &payload.bidder) {
+			return InvalidTransaction::BadProof.into();
+		}
+		let now = <system::Module<T>>::block_number();
+		if payload.expires_at < now {
+			return InvalidTransaction::Stale.into();
+		}
+		let nonce = <Module<T, I>>::bid_nonce(&payload.bidder);
+		if payload.nonce < nonce {
+			return InvalidTransaction::Stale.into();
+		}
+		if payload.nonce > nonce {
+			return InvalidTransaction::Future.into();
+		}
+		if let Err(code) = <Module<T, I>>::check_bid(payload.auction_id, payload.price, &payload.terms_hash) {
+			return InvalidTransaction::Custom(code).into();
+		}
+		// 热门拍卖本区块出价已满，留待之后的区块
+		if <Module<T, I>>::is_auction_saturated(&payload.auction_id) {
+			return InvalidTransaction::ExhaustsResources.into();
+		}
+		let private = <Module<T, I>>::auctions(payload.auction_id).map_or(true, |a| a.invite_only);
+		if private {
+			return InvalidTransaction::Custom(validity::NOT_BIDDABLE).into();
+		}
+		let delta = <Module<T, I>>::bid_delta(&payload.auction_id, &payload.bidder, payload.price);
+		if !<Module<T, I>>::can_lock(&payload.bidder, delta) && delta > <Module<T, I>>::available_credit(&payload.bidder) {
+			return InvalidTransaction::Payment.into();
+		}
+
+		// 标签带上模块id，不同拍卖行的同号拍卖在交易池中互不替换
+		let module_id = T::ModuleId::get().0;
+		// 出价人的每个序号只有一笔出价能进入交易池
+		let mut provides = vec![(module_id, &payload.bidder, payload.nonce).encode()];
+		if let Some(sponsor) = sponsor {
+			if <Module<T, I>>::ensure_sponsorship(payload.auction_id, &payload.bidder, payload.price, sponsor).is_err() {
+				return InvalidTransaction::Custom(validity::NOT_SPONSORED).into();
+			}
+			// 同一出价人只能有一笔赞助出价在交易池中
+			provides.push((module_id, b"sponsored", &payload.bidder).encode());
+		}
+
+		// 签名过期后交易随之失效
+		let longevity: TransactionLongevity = payload.expires_at.saturating_sub(now).unique_saturated_into();
+		Ok(ValidTransaction {
+			priority: 0,
+			requires: vec![],
+			provides: provides,
+			longevity: longevity.saturating_add(1),
+			propagate: true,
+		})
+	}
+}
+
+impl<T: Trait<I>, I: Instance> Module<T, I> {
+	/// Validate the unsigned calls of the offchain worker, rejected without offchain timing.
+	#[cfg(feature = "offchain")]
+	fn validate_offchain_call(call: &Call<T, I>) -> TransactionValidity {
+		if !Self::offchain_timing() {
+			return InvalidTransaction::Call.into();
+		}
+
 		// verify that the incoming (unverified) pubkey is actually an authority id
-		let authority_id = match <Module<T>>::authority_id() {
+		let authority_id = match <Module<T, I>>::authority_id() {
 			Some(id) => id,
 			None => return InvalidTransaction::BadProof.into(),
 		};
 
 		if let Call::start_auctions_passive(auction_ids, signature) = call {
-			if !<Module<T>>::is_auctions_with_status(&auction_ids, AuctionStatus::PendingStart, false) {
-				// all auction ids should be pending start
-				return InvalidTransaction::Stale.into();
+			// all auction ids should be pending start and due to start, only due calls are free
+			if let Err(code) = <Module<T, I>>::are_auctions_due(&auction_ids, true) {
+				return InvalidTransaction::Custom(code).into();
 			}
 			
 			// check signature (this is expensive so we do it last).
@@ -961,14 +4889,36 @@ impl<T: Trait> support::unsigned::ValidateUnsigned for Module<T> {
 			Ok(ValidTransaction {
 				priority: 0,
 				requires: vec![],
-				provides: vec![(auction_ids, authority_id).encode()],
+				provides: vec![(T::ModuleId::get().0, auction_ids, authority_id).encode()],
 				longevity: TransactionLongevity::max_value(),
 				propagate: true,
 			})
 		} else if let Call::stop_auctions_passive(auction_ids, signature) = call {			
-			if !<Module<T>>::is_auctions_with_status(&auction_ids, AuctionStatus::Stopped, true) {
-				// all auction ids should be active start
-				return InvalidTransaction::Stale.into();
+			// all auction ids should be active and due to stop, only due calls are free
+			if let Err(code) = <Module<T, I>>::are_auctions_due(&auction_ids, false) {
+				return InvalidTransaction::Custom(code).into();
+			}
+
+			// check signature (this is expensive so we do it last).
+			let signature_valid = auction_ids.using_encoded(|encoded_auction_ids| {
+				authority_id.verify(&encoded_auction_ids, &signature)
+			});
+
+			if !signature_valid {
+				return InvalidTransaction::BadProof.into();
+			}
+
+			Ok(ValidTransaction {
+				priority: 0,
+				requires: vec![],
+				provides: vec![(T::ModuleId::get().0, auction_ids, authority_id).encode()],
+				longevity: TransactionLongevity::max_value(),
+				propagate: true,
+			})
+		} else if let Call::process_due_auctions(auction_ids, signature) = call {
+			// only batches of due auctions are free
+			if let Err(code) = <Module<T, I>>::is_batch_due(&auction_ids) {
+				return InvalidTransaction::Custom(code).into();
 			}
 
 			// check signature (this is expensive so we do it last).
@@ -983,7 +4933,7 @@ impl<T: Trait> support::unsigned::ValidateUnsigned for Module<T> {
 			Ok(ValidTransaction {
 				priority: 0,
 				requires: vec![],
-				provides: vec![(auction_ids, authority_id).encode()],
+				provides: vec![(T::ModuleId::get().0, auction_ids, authority_id).encode()],
 				longevity: TransactionLongevity::max_value(),
 				propagate: true,
 			})
@@ -991,4 +4941,9 @@ impl<T: Trait> support::unsigned::ValidateUnsigned for Module<T> {
 			InvalidTransaction::Call.into()
 		}
 	}
+
+	#[cfg(not(feature = "offchain"))]
+	fn validate_offchain_call(_call: &Call<T, I>) -> TransactionValidity {
+		InvalidTransaction::Call.into()
+	}
 }
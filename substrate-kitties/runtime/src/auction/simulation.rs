@@ -0,0 +1,175 @@
+//! Economics simulation of auctions, running synthetic auctions with scripted bidders
+//! against the real module logic on the mock runtime.
+//!
+//! Built for tests and with the `simulation` feature, it reports the revenue and fee
+//! outcomes of a configuration, e.g. to tune increments and fees before deployment.
+#![cfg(any(test, feature = "simulation"))]
+
+use std::fmt;
+use sr_primitives::traits::OnFinalize;
+use support::traits::Currency;
+use crate::auction::mocks::*;
+use super::FeeDestination;
+
+/// The seller of every simulated auction, the only owner of items in the mock runtime
+const SELLER: AccountId = 1;
+/// The account collecting the fees
+const FEE_ACCOUNT: AccountId = 999;
+/// Account of the first bidder, the others follow
+const FIRST_BIDDER: AccountId = 100;
+/// Item put up in every auction
+const ITEM: ItemId = 7;
+
+/// How a synthetic bidder bids, never above its valuation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BidderStrategy {
+	/// Outbid the highest bid by the minimum step in every round.
+	Incremental,
+	/// Bid the whole valuation at once.
+	Jump,
+	/// Outbid the highest bid by the minimum step in the last round only.
+	Sniper,
+}
+
+/// Settings of a simulation.
+#[derive(Clone, Debug)]
+pub struct SimulationConfig {
+	pub auctions: u32, // 模拟的拍卖场数
+	pub rounds: u32, // 每场拍卖的出价轮数，每轮每位出价人最多出价一次
+	pub begin_price: Balance, // 起拍价
+	pub minimum_step: Balance, // 最小加价幅度
+	pub step_tiers: Vec<(Balance, Balance)>, // 阶梯加价表
+	pub bidders: Vec<(BidderStrategy, Balance)>, // 出价人的策略及基础估值
+	pub valuation_spread: Balance, // 每场拍卖中估值的随机浮动上限
+	pub seed: u64, // 随机种子，相同配置和种子的结果相同
+}
+
+/// Outcomes of a simulation.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SimulationReport {
+	pub auctions: u32, // 模拟的拍卖场数
+	pub sold: u32, // 成交场数
+	pub bids: u32, // 成功的出价次数
+	pub revenue: Balance, // 卖家收入总额
+	pub fees: Balance, // 手续费总额
+	pub highest_price: Balance, // 最高成交价
+	pub wins: Vec<u32>, // 各出价人的中标场数
+}
+
+impl SimulationReport {
+	/// Average price of the sold auctions, fees included.
+	pub fn average_price(&self) -> Balance {
+		if self.sold == 0 {
+			return 0;
+		}
+		(self.revenue + self.fees) / Balance::from(self.sold)
+	}
+}
+
+impl fmt::Display for SimulationReport {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(
+			f,
+			"{} auctions, {} sold, {} bids, revenue {}, fees {}, average price {}, highest price {}, wins {:?}",
+			self.auctions, self.sold, self.bids, self.revenue, self.fees,
+			self.average_price(), self.highest_price, self.wins,
+		)
+	}
+}
+
+/// Deterministic pseudo random numbers, so a seed replays a simulation.
+struct Rng(u64);
+
+impl Rng {
+	fn next(&mut self, bound: u64) -> u64 {
+		self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+		if bound == 0 { 0 } else { (self.0 >> 33) % bound }
+	}
+}
+
+/// Run the auctions of the configuration one by one, each on a fresh mock chain.
+pub fn run(config: &SimulationConfig) -> SimulationReport {
+	let mut rng = Rng(config.seed);
+	let mut report = SimulationReport {
+		auctions: config.auctions,
+		wins: vec![0; config.bidders.len()],
+		..Default::default()
+	};
+	for _ in 0..config.auctions {
+		let valuations: Vec<Balance> = config.bidders.iter()
+			.map(|(_, valuation)| valuation + rng.next(config.valuation_spread + 1))
+			.collect();
+		new_test_ext().execute_with(|| run_auction(config, &valuations, &mut rng, &mut report));
+	}
+	report
+}
+
+/// Run one auction and add its outcome to the report.
+fn run_auction(config: &SimulationConfig, valuations: &[Balance], rng: &mut Rng, report: &mut SimulationReport) {
+	let bidders: Vec<AccountId> = (0..valuations.len() as u64).map(|i| FIRST_BIDDER + i).collect();
+	for (bidder, valuation) in bidders.iter().zip(valuations.iter()) {
+		let _ = Balances::deposit_creating(bidder, valuation * 2 + 1);
+	}
+	let setup = Auctions::set_fee_destination(Origin::ROOT, FeeDestination::FixedAccount(FEE_ACCOUNT))
+		.and_then(|_| Auctions::create_auction(
+			Origin::signed(SELLER), config.begin_price, config.minimum_step, None,
+			config.step_tiers.clone(), vec![], None, None,
+		))
+		.and_then(|_| Auctions::add_item(Origin::signed(SELLER), 0, ITEM));
+	if setup.is_err() {
+		return;
+	}
+	Auctions::do_start_auctions(&vec![0]);
+
+	for round in 0..config.rounds {
+		let last_round = round + 1 == config.rounds;
+		// a random bidder acts first in every round
+		let first = rng.next(bidders.len() as u64) as usize;
+		for i in 0..bidders.len() {
+			let index = (first + i) % bidders.len();
+			if let Some(price) = next_bid(config.bidders[index].0, valuations[index], &bidders[index], last_round) {
+				if Auctions::participate_auction(Origin::signed(bidders[index]), 0, price, None, None, None).is_ok() {
+					report.bids += 1;
+				}
+			}
+		}
+	}
+
+	if Auctions::stop_auction(Origin::signed(SELLER), 0).is_err() {
+		return;
+	}
+	Auctions::on_finalize(1);
+
+	if let Some((winner, _)) = Auctions::auctions(0).and_then(|auction| auction.latest_participate) {
+		let price = Auctions::auction_bids(0, &winner);
+		report.sold += 1;
+		report.highest_price = report.highest_price.max(price);
+		if let Some(index) = bidders.iter().position(|bidder| *bidder == winner) {
+			report.wins[index] += 1;
+		}
+	}
+	report.revenue += Balances::free_balance(&SELLER);
+	report.fees += Balances::free_balance(&FEE_ACCOUNT);
+}
+
+/// The bid a bidder places now, if any.
+fn next_bid(strategy: BidderStrategy, valuation: Balance, bidder: &AccountId, last_round: bool) -> Option<Balance> {
+	let auction = Auctions::auctions(0)?;
+	let minimum = match &auction.latest_participate {
+		Some((leader, _)) if leader == bidder => return None,
+		Some((leader, _)) => {
+			let highest = Auctions::auction_bids(0, leader);
+			highest + Auctions::minimum_step_at(&auction, highest) + 1
+		},
+		None => auction.begin_price,
+	};
+	if minimum > valuation {
+		return None;
+	}
+	match strategy {
+		BidderStrategy::Incremental => Some(minimum),
+		BidderStrategy::Jump => Some(valuation),
+		BidderStrategy::Sniper if last_round => Some(minimum),
+		BidderStrategy::Sniper => None,
+	}
+}
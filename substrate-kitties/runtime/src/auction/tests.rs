@@ -2,19 +2,2694 @@
 #![cfg(test)]
 
 use crate::auction::mocks::*;
-use support::{assert_ok};
+use super::{Error, RawEvent, AuctionStatus, AuctionAction, CreationGate, FeeDestination, InstallmentPlan, LotPricing, ReportRuling, RelistPolicy, RaffleConfig, AuctionItems, AuctionBids, AuctionParticipants, BidCollateral, BidCredits, BidPlacedAt};
+use sr_primitives::{Permill, generic::DigestItem, traits::{Hash, OnFinalize}};
+use codec::Encode;
+use support::{assert_ok, assert_noop, StorageMap, StorageDoubleMap, traits::Get};
+use sr_primitives::weights::GetDispatchInfo;
 
 #[test]
 fn it_works_for_create_auction() {
   new_test_ext().execute_with(|| {
-    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None));
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
     assert_eq!(Auctions::next_auction_id(), 1);
   });
 }
 
+#[test]
+fn step_tiers_are_validated_and_applied() {
+  new_test_ext().execute_with(|| {
+    assert_noop!(
+      Auctions::create_auction(Origin::signed(1), 100, 5, None, vec![(200, 10), (150, 20)], vec![], None, None),
+      "Tier thresholds should be ascending"
+    );
+    assert_noop!(
+      Auctions::create_auction(Origin::signed(1), 100, 5, None, vec![(200, 4)], vec![], None, None),
+      "Tier step less than minimum step"
+    );
+    assert_noop!(
+      Auctions::create_auction(Origin::signed(1), 100, 5, None, vec![(200, 20), (300, 10)], vec![], None, None),
+      "Tier steps should not decrease"
+    );
+
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 5, None, vec![(200, 10), (500, 50)], vec![], None, None));
+    let auction = Auctions::auctions(0).unwrap();
+    assert_eq!(Auctions::minimum_step_at(&auction, 150), 5);
+    assert_eq!(Auctions::minimum_step_at(&auction, 200), 10);
+    assert_eq!(Auctions::minimum_step_at(&auction, 499), 10);
+    assert_eq!(Auctions::minimum_step_at(&auction, 800), 50);
+  });
+}
+
+#[test]
+fn private_auction_requires_an_invite_signed_by_the_owner() {
+  new_test_ext().execute_with(|| {
+    use sr_primitives::testing::TestSignature;
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_noop!(Auctions::setup_invite(Origin::signed(2), 0, true), "Only owner can call this fn.");
+    assert_ok!(Auctions::setup_invite(Origin::signed(1), 0, true));
+
+    let auction = Auctions::auctions(0).unwrap();
+    let invite = TestSignature(1, Auctions::invite_payload(0, &2));
+    assert_noop!(Auctions::ensure_invited(&auction, &2, None), "Invite required for private auction");
+    assert_noop!(
+      Auctions::ensure_invited(&auction, &2, Some(TestSignature(3, Auctions::invite_payload(0, &2)))),
+      "Invalid invite"
+    );
+    assert_ok!(Auctions::ensure_invited(&auction, &2, Some(invite.clone())));
+    // 邀请绑定出价人，链上看到的邀请不能被其他账户复用
+    assert_noop!(Auctions::ensure_invited(&auction, &3, Some(invite)), "Invalid invite");
+  });
+}
+
+#[test]
+fn private_auction_bids_need_an_invite_for_the_bidder() {
+  new_test_ext().execute_with(|| {
+    use sr_primitives::testing::TestSignature;
+    let _ = Balances::deposit_creating(&2, 1000);
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    assert_ok!(Auctions::setup_invite(Origin::signed(1), 0, true));
+    Auctions::do_start_auctions(&vec![0]);
+
+    let invite = TestSignature(1, Auctions::invite_payload(0, &2));
+    assert_noop!(
+      Auctions::participate_auction(Origin::signed(2), 0, 200, None, None, None),
+      "Invite required for private auction"
+    );
+    assert_noop!(
+      Auctions::participate_auction(Origin::signed(3), 0, 200, Some(invite.clone()), None, None),
+      "Invalid invite"
+    );
+    assert_ok!(Auctions::participate_auction(Origin::signed(2), 0, 200, Some(invite), None, None));
+    assert_eq!(Auctions::highest_bid(0), 200);
+    assert_eq!(Auctions::auction_bids(0, 2), 200);
+  });
+}
+
+#[test]
+fn settlement_records_a_receipt() {
+  new_test_ext().execute_with(|| {
+    use super::SettlementReceipt;
+    let _ = Balances::deposit_creating(&2, 1000);
+    assert_ok!(Auctions::set_fee_destination(Origin::ROOT, FeeDestination::FixedAccount(99)));
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    Auctions::do_start_auctions(&vec![0]);
+    assert_ok!(Auctions::participate_auction(Origin::signed(2), 0, 200, None, None, None));
+    assert!(Auctions::settlement_receipt(0).is_none());
+
+    assert_ok!(Auctions::stop_auction(Origin::signed(1), 0));
+    Auctions::settle_queued_auctions();
+    assert_eq!(Auctions::settlement_receipt(0), Some(SettlementReceipt {
+      auction_id: 0,
+      item: 7,
+      seller: 1,
+      winner: 2,
+      price: 200,
+      fee: 10,
+      seller_proceeds: 190,
+      donation: None,
+      payouts: vec![(1, 190)],
+      settled_at: 0,
+    }));
+    assert_eq!(Balances::free_balance(&1), 190);
+    assert_eq!(Balances::free_balance(&99), 10);
+  });
+}
+
+#[test]
+fn payout_split_is_validated_and_conserves_proceeds() {
+  new_test_ext().execute_with(|| {
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_noop!(
+      Auctions::setup_payout_split(Origin::signed(1), 0, vec![(1, Permill::from_percent(70)), (2, Permill::from_percent(20))]),
+      "Payout shares should sum to 100%"
+    );
+    assert_noop!(
+      Auctions::setup_payout_split(Origin::signed(1), 0, vec![(1, Permill::from_percent(50)), (1, Permill::from_percent(50))]),
+      "Duplicate payout beneficiary"
+    );
+    let split = vec![(1, Permill::from_percent(70)), (2, Permill::from_percent(20)), (3, Permill::from_percent(10))];
+    assert_ok!(Auctions::setup_payout_split(Origin::signed(1), 0, split.clone()));
+
+    assert_eq!(Auctions::split_proceeds(&1, &split, 1000), vec![(1, 700), (2, 200), (3, 100)]);
+    assert_eq!(Auctions::split_proceeds(&1, &vec![], 1001), vec![(1, 1001)]);
+  });
+}
+
+#[test]
+fn payments_are_checked_before_any_payer_is_charged() {
+  new_test_ext().execute_with(|| {
+    let _ = Balances::deposit_creating(&2, 1000);
+    let _ = Balances::deposit_creating(&3, 50);
+    assert_ok!(Auctions::set_fee_destination(Origin::ROOT, FeeDestination::FixedAccount(99)));
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    let split = vec![(1, Permill::from_percent(50)), (4, Permill::from_percent(50))];
+    assert_ok!(Auctions::setup_payout_split(Origin::signed(1), 0, split));
+    let auction = Auctions::auctions(0).unwrap();
+
+    // 第二个付款人余额不足，第一个付款人也不应被扣款
+    assert_eq!(
+      Auctions::collect_payments(&auction, &vec![(2, 100), (3, 100)]).err(),
+      Some("Payer can not pay the bid")
+    );
+    assert_eq!(Balances::free_balance(&2), 1000);
+    assert_eq!(Balances::free_balance(&3), 50);
+    assert_eq!(Balances::free_balance(&1), 0);
+    assert_eq!(Balances::free_balance(&4), 0);
+  });
+}
+
+#[test]
+fn charity_is_only_set_by_owner_before_start() {
+  new_test_ext().execute_with(|| {
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_noop!(
+      Auctions::setup_charity(Origin::signed(1), 0, Some((9, Permill::zero()))),
+      "Donation percent should not be zero"
+    );
+    assert_ok!(Auctions::setup_charity(Origin::signed(1), 0, Some((9, Permill::from_percent(15)))));
+    assert_eq!(Auctions::auctions(0).unwrap().charity, Some((9, Permill::from_percent(15))));
+
+    assert_ok!(Auctions::_change_auction_status(0, AuctionAction::Start));
+    assert_noop!(
+      Auctions::setup_charity(Origin::signed(1), 0, None),
+      AuctionStatus::PendingStart.error_str()
+    );
+  });
+}
+
+#[test]
+fn charity_is_not_paid_when_the_payment_fails() {
+  new_test_ext().execute_with(|| {
+    let _ = Balances::deposit_creating(&2, 150);
+    assert_ok!(Auctions::set_fee_destination(Origin::ROOT, FeeDestination::FixedAccount(99)));
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::setup_charity(Origin::signed(1), 0, Some((9, Permill::from_percent(50)))));
+    let auction = Auctions::auctions(0).unwrap();
+
+    assert_eq!(Auctions::collect_payments(&auction, &vec![(2, 200)]).err(), Some("Payer can not pay the bid"));
+    assert_eq!(Balances::free_balance(&9), 0);
+    assert_eq!(Balances::free_balance(&2), 150);
+
+    // 捐赠、成交款和手续费都出自同一笔付款
+    let payment = Auctions::collect_payments(&auction, &vec![(2, 100)]).unwrap();
+    assert_eq!(payment.donation, Some((9, 47)));
+    assert_eq!(Balances::free_balance(&9), 47);
+    assert_eq!(Balances::free_balance(&1), 48);
+    assert_eq!(Balances::free_balance(&99), 5);
+    assert_eq!(Balances::free_balance(&2), 50);
+  });
+}
+
+#[test]
+fn unsold_auction_is_relisted_with_reduced_price() {
+  new_test_ext().execute_with(|| {
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    let policy = RelistPolicy { max_relists: 2, price_reduction: Permill::from_percent(10) };
+    assert_ok!(Auctions::setup_relist_policy(Origin::signed(1), 0, Some(policy)));
+    Auctions::do_start_auctions(&vec![0]);
+
+    // 拍卖者手动停拍也会重新上架
+    assert_ok!(Auctions::stop_auction(Origin::signed(1), 0));
+    let relisted = Auctions::auctions(1).unwrap();
+    assert_eq!(relisted.begin_price, 90);
+    assert_eq!(relisted.item, Some(7));
+    assert_eq!(relisted.relist_count, 1);
+    assert_eq!(Auctions::auction_items(7), Some(1));
+    assert!(Auctions::pending_auctions().contains(&1));
+
+    // 取消的拍卖不再重新上架
+    assert_ok!(Auctions::cancel_auctions_batch(Origin::signed(1), vec![1]));
+    assert!(Auctions::auctions(2).is_none());
+    assert_eq!(Auctions::auction_items(7), None);
+  });
+}
+
+#[test]
+fn relist_is_skipped_when_the_item_can_not_be_listed() {
+  new_test_ext().execute_with(|| {
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    let policy = RelistPolicy { max_relists: 1, price_reduction: Permill::from_percent(10) };
+    assert_ok!(Auctions::setup_relist_policy(Origin::signed(1), 0, Some(policy)));
+    Auctions::do_start_auctions(&vec![0]);
+
+    // 拍品在停拍前被冻结
+    FROZEN_ITEMS.with(|v| *v.borrow_mut() = vec![7]);
+    assert_ok!(Auctions::stop_auction(Origin::signed(1), 0));
+    assert!(Auctions::auctions(1).is_none());
+    assert_eq!(Auctions::auction_items(7), None);
+    assert!(RESERVED_ITEMS.with(|v| v.borrow().is_empty()));
+  });
+}
+
+#[test]
+fn raffle_only_sells_tickets() {
+  new_test_ext().execute_with(|| {
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_noop!(
+      Auctions::setup_raffle(Origin::signed(1), 0, Some(RaffleConfig { ticket_price: 10, max_tickets: 0 })),
+      "Invalid max tickets"
+    );
+    assert_ok!(Auctions::setup_raffle(Origin::signed(1), 0, Some(RaffleConfig { ticket_price: 10, max_tickets: 5 })));
+    assert_ok!(Auctions::_change_auction_status(0, AuctionAction::Start));
+
+    assert_noop!(
+      Auctions::participate_auction(Origin::signed(2), 0, 200, None, None, None),
+      "Raffle only accepts tickets"
+    );
+    assert_noop!(
+      Auctions::buy_tickets(Origin::signed(2), 0, 6, None),
+      "Not enough tickets left"
+    );
+    assert_eq!(Auctions::draw_ticket(0), None);
+
+    // buying tickets is only allowed in raffles
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::_change_auction_status(1, AuctionAction::Start));
+    assert_noop!(
+      Auctions::buy_tickets(Origin::signed(2), 1, 1, None),
+      "Auction is not a raffle"
+    );
+  });
+}
+
+#[test]
+fn raffle_winner_is_drawn_from_the_tickets() {
+  new_test_ext().execute_with(|| {
+    use super::SettlementReceipt;
+    let _ = Balances::deposit_creating(&2, 100);
+    let _ = Balances::deposit_creating(&3, 100);
+    assert_ok!(Auctions::set_fee_destination(Origin::ROOT, FeeDestination::FixedAccount(99)));
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    assert_ok!(Auctions::setup_raffle(Origin::signed(1), 0, Some(RaffleConfig { ticket_price: 10, max_tickets: 5 })));
+    Auctions::do_start_auctions(&vec![0]);
+
+    assert_ok!(Auctions::buy_tickets(Origin::signed(2), 0, 2, None));
+    assert_ok!(Auctions::buy_tickets(Origin::signed(3), 0, 1, None));
+    assert_eq!(Auctions::raffle_tickets(0), vec![2, 2, 3]);
+
+    // the draw only depends on the auction and the seed of the block
+    let winner = Auctions::draw_ticket(0).unwrap();
+    assert!(winner == 2 || winner == 3);
+    assert_eq!(Auctions::draw_ticket(0), Some(winner));
+
+    assert_ok!(Auctions::stop_auction(Origin::signed(1), 0));
+    Auctions::settle_queued_auctions();
+    assert_eq!(Auctions::settlement_receipt(0), Some(SettlementReceipt {
+      auction_id: 0,
+      item: 7,
+      seller: 1,
+      winner: winner,
+      price: 30,
+      fee: 1,
+      seller_proceeds: 29,
+      donation: None,
+      payouts: vec![(1, 29)],
+      settled_at: 0,
+    }));
+    // every ticket is paid, won or not
+    assert_eq!(Balances::free_balance(&2), 80);
+    assert_eq!(Balances::free_balance(&3), 90);
+    assert_eq!(Balances::free_balance(&1), 29);
+    assert_eq!(Balances::free_balance(&99), 1);
+  });
+}
+
+#[test]
+fn auctions_join_future_rounds() {
+  new_test_ext().execute_with(|| {
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::create_auction(Origin::signed(2), 100, 1, None, vec![], vec![], None, None));
+    assert_noop!(Auctions::join_round(Origin::signed(1), 0, 0), "Round is already started");
+    assert_noop!(Auctions::join_round(Origin::signed(2), 0, 1), "Only owner can call this fn.");
+
+    assert_ok!(Auctions::join_round(Origin::signed(1), 0, 1));
+    assert_ok!(Auctions::join_round(Origin::signed(2), 1, 1));
+    assert_eq!(Auctions::round_auctions(1), vec![0, 1]);
+
+    let auction = Auctions::auctions(0).unwrap();
+    assert_eq!((auction.start_at, auction.stop_at), (Some(1000), Some(2000)));
+    assert!(Auctions::pending_auctions().contains(&0));
+
+    assert_noop!(
+      Auctions::setup_moments(Origin::signed(1), 0, Some(10), None, None),
+      "Moments of a round auction are set by its round"
+    );
+  });
+}
+
+#[test]
+fn raffle_can_not_be_claim_settled() {
+  new_test_ext().execute_with(|| {
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::setup_claim_settlement(Origin::signed(1), 0, true));
+    let raffle = RaffleConfig { ticket_price: 10, max_tickets: 5 };
+    assert_noop!(
+      Auctions::setup_raffle(Origin::signed(1), 0, Some(raffle)),
+      "Raffle can not be claim settled"
+    );
+    assert_noop!(Auctions::claim_item(Origin::signed(1), 0), "No pending claim for the auction");
+  });
+}
+
+#[test]
+fn settlements_are_spread_over_blocks() {
+  new_test_ext().execute_with(|| {
+    for _ in 0..3 {
+      assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    }
+    let auction_ids = vec![0, 1, 2];
+    Auctions::do_start_auctions(&auction_ids);
+    Auctions::do_stop_auctions(&auction_ids);
+
+    assert_eq!(Auctions::auctions(2).unwrap().status, AuctionStatus::Stopped);
+    assert_eq!(Auctions::settlement_queue(), vec![0, 1, 2]);
+    assert!(Auctions::active_auctions().is_empty());
+
+    // at most two settlements per block
+    Auctions::settle_queued_auctions();
+    assert_eq!(Auctions::settlement_queue(), vec![2]);
+    Auctions::settle_queued_auctions();
+    assert!(Auctions::settlement_queue().is_empty());
+  });
+}
+
+#[test]
+fn settlement_queue_is_bounded() {
+  new_test_ext().execute_with(|| {
+    let max = <Test as super::Trait>::MaxSettlementQueueLength::get();
+    let auction_ids: Vec<u32> = (0..max + 2).collect();
+    for _ in auction_ids.iter() {
+      assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    }
+    Auctions::do_start_auctions(&auction_ids);
+
+    // 队列已满时多出的场次保持进行中，待队列消化后再停拍
+    assert_eq!(Auctions::do_stop_auctions(&auction_ids).len() as u32, max);
+    assert_eq!(Auctions::settlement_queue().len() as u32, max);
+    assert_eq!(Auctions::auctions(max).unwrap().status, AuctionStatus::Active);
+    assert_eq!(Auctions::active_auctions(), vec![max, max + 1]);
+
+    Auctions::settle_queued_auctions();
+    assert_eq!(Auctions::do_stop_auctions(&vec![max, max + 1]), vec![max, max + 1]);
+    assert_eq!(Auctions::settlement_queue().len() as u32, max);
+    assert!(Auctions::active_auctions().is_empty());
+  });
+}
+
+#[test]
+fn item_mapping_is_released_and_repaired() {
+  new_test_ext().execute_with(|| {
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    assert_eq!(Auctions::auction_items(7), Some(0));
+
+    // stopping a pending auction releases its item
+    assert_ok!(Auctions::stop_auction(Origin::signed(1), 0));
+    assert_eq!(Auctions::auction_items(7), None);
+    assert!(Auctions::pending_auctions().is_empty());
+
+    // orphaned mappings left behind are removed by anyone
+    <AuctionItems<Test>>::insert(7, 0);
+    <AuctionItems<Test>>::insert(8, 5);
+    assert_ok!(Auctions::repair_item_mapping(Origin::signed(2), vec![7, 8]));
+    assert_eq!(Auctions::auction_items(7), None);
+    assert_eq!(Auctions::auction_items(8), None);
+  });
+}
+
+#[test]
+fn bids_should_acknowledge_terms() {
+  new_test_ext().execute_with(|| {
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    let terms = <Test as system::Trait>::Hashing::hash(b"terms of sale");
+    assert_ok!(Auctions::setup_terms(Origin::signed(1), 0, Some(terms)));
+    assert_ok!(Auctions::_change_auction_status(0, AuctionAction::Start));
+
+    assert_noop!(Auctions::participate_auction(Origin::signed(2), 0, 200, None, None, None), "Terms hash mismatch");
+    assert_eq!(Auctions::check_bid(0, 200, &None), Err(super::validity::TERMS_MISMATCH));
+  });
+}
+
+#[test]
+fn inspection_excludes_raffle_and_claim_settlement() {
+  new_test_ext().execute_with(|| {
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::setup_inspection(Origin::signed(1), 0, Some(100)));
+    assert_noop!(
+      Auctions::setup_claim_settlement(Origin::signed(1), 0, true),
+      "Inspected auction can not be claim settled"
+    );
+    let raffle = RaffleConfig { ticket_price: 10, max_tickets: 5 };
+    assert_noop!(Auctions::setup_raffle(Origin::signed(1), 0, Some(raffle)), "Raffle can not be inspected");
+    assert_noop!(Auctions::reject_item(Origin::signed(2), 0), "Auction is not in inspection");
+  });
+}
+
+#[test]
+fn inspected_items_are_accepted_after_a_failed_try() {
+  new_test_ext().execute_with(|| {
+    let _ = Balances::deposit_creating(&2, 1000);
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    assert_ok!(Auctions::setup_inspection(Origin::signed(1), 0, Some(100)));
+    Auctions::do_start_auctions(&vec![0]);
+    assert_ok!(Auctions::participate_auction(Origin::signed(2), 0, 200, None, None, None));
+    assert_ok!(Auctions::stop_auction(Origin::signed(1), 0));
+    assert_eq!(Auctions::inspection(0), Some((2, 100)));
+
+    // 结算失败时不解锁出价，可以重试
+    FROZEN_ITEMS.with(|v| *v.borrow_mut() = vec![7]);
+    assert_noop!(Auctions::accept_item(Origin::signed(2), 0), <&'static str>::from(Error::ItemFrozen));
+    assert_eq!(Auctions::account_locks(2), 200);
+    assert_noop!(Auctions::accept_item(Origin::signed(3), 0), "Only the winner can accept the item");
+
+    FROZEN_ITEMS.with(|v| v.borrow_mut().clear());
+    assert_ok!(Auctions::accept_item(Origin::signed(2), 0));
+    assert_eq!(Auctions::inspection(0), None);
+    assert!(Auctions::inspection_queue().is_empty());
+    assert_eq!(Auctions::account_locks(2), 0);
+    assert_eq!(Auctions::auction_items(7), None);
+    assert_eq!(Balances::free_balance(&2), 800);
+    assert_eq!(Balances::free_balance(&1), 190);
+  });
+}
+
+#[test]
+fn rejected_items_cost_the_penalty() {
+  new_test_ext().execute_with(|| {
+    use support::traits::OnTimestampSet;
+    let _ = Balances::deposit_creating(&2, 1000);
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    assert_ok!(Auctions::setup_inspection(Origin::signed(1), 0, Some(100)));
+    Auctions::do_start_auctions(&vec![0]);
+    assert_ok!(Auctions::participate_auction(Origin::signed(2), 0, 200, None, None, None));
+    assert_ok!(Auctions::stop_auction(Origin::signed(1), 0));
+
+    Aura::on_timestamp_set(101);
+    assert_noop!(Auctions::reject_item(Origin::signed(2), 0), "Inspection period is over");
+    Aura::on_timestamp_set(100);
+    assert_ok!(Auctions::reject_item(Origin::signed(2), 0));
+    assert_eq!(Auctions::inspection(0), None);
+    assert_eq!(Auctions::account_locks(2), 0);
+    assert_eq!(Auctions::auction_items(7), None);
+    assert_eq!(Balances::free_balance(&2), 980);
+    assert_eq!(Balances::free_balance(&1), 20);
+  });
+}
+
+#[test]
+fn failed_settlement_after_inspection_releases_the_bid() {
+  new_test_ext().execute_with(|| {
+    use support::traits::OnTimestampSet;
+    let _ = Balances::deposit_creating(&2, 1000);
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    assert_ok!(Auctions::setup_inspection(Origin::signed(1), 0, Some(100)));
+    Auctions::do_start_auctions(&vec![0]);
+    assert_ok!(Auctions::participate_auction(Origin::signed(2), 0, 200, None, None, None));
+    assert_ok!(Auctions::stop_auction(Origin::signed(1), 0));
+
+    // 到期自动接受失败时，卖家保留拍品，中标者的出价解锁
+    FROZEN_ITEMS.with(|v| *v.borrow_mut() = vec![7]);
+    Aura::on_timestamp_set(101);
+    Auctions::finish_expired_inspections();
+    assert_eq!(Auctions::inspection(0), None);
+    assert!(Auctions::inspection_queue().is_empty());
+    assert_eq!(Auctions::account_locks(2), 0);
+    assert_eq!(Auctions::auction_items(7), None);
+    assert_eq!(Balances::free_balance(&2), 1000);
+    assert_eq!(Balances::free_balance(&1), 0);
+    assert!(Auctions::settlement_receipt(0).is_none());
+  });
+}
+
+#[test]
+fn lot_is_allocated_by_price_then_time() {
+  new_test_ext().execute_with(|| {
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_noop!(Auctions::add_lot_items(Origin::signed(1), 0, vec![7, 7], LotPricing::Uniform), "Duplicate item in lot");
+    assert_ok!(Auctions::add_lot_items(Origin::signed(1), 0, vec![7, 8], LotPricing::PayAsBid));
+    assert_eq!(Auctions::auction_items(8), Some(0));
+    assert_noop!(Auctions::add_item(Origin::signed(1), 0, 9), "Auction already has a lot");
+
+    <AuctionParticipants<Test>>::insert(0, vec![2, 3, 4]);
+    <AuctionBids<Test>>::insert(0, 2, 150);
+    <AuctionBids<Test>>::insert(0, 3, 200);
+    <AuctionBids<Test>>::insert(0, 4, 150);
+    <BidPlacedAt<Test>>::insert(0, 2, (20, 0));
+    <BidPlacedAt<Test>>::insert(0, 4, (10, 0));
+
+    // the earlier of two equal bids wins the last unit, and sets the clearing price
+    let auction = Auctions::auctions(0).unwrap();
+    assert_eq!(Auctions::lot_winners(&auction), vec![(3, 200), (4, 150)]);
+
+    // within a block the extrinsic index breaks the tie
+    <BidPlacedAt<Test>>::insert(0, 2, (10, 1));
+    <BidPlacedAt<Test>>::insert(0, 4, (10, 2));
+    assert_eq!(Auctions::lot_winners(&auction), vec![(3, 200), (2, 150)]);
+  });
+}
+
+#[test]
+fn later_bids_win_ties_when_configured() {
+  new_test_ext().execute_with(|| {
+    TIE_BREAK.with(|v| *v.borrow_mut() = super::TieBreak::LaterWins);
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_lot_items(Origin::signed(1), 0, vec![7, 8], LotPricing::PayAsBid));
+
+    <AuctionParticipants<Test>>::insert(0, vec![2, 3, 4]);
+    <AuctionBids<Test>>::insert(0, 2, 150);
+    <AuctionBids<Test>>::insert(0, 3, 200);
+    <AuctionBids<Test>>::insert(0, 4, 150);
+    <BidPlacedAt<Test>>::insert(0, 2, (20, 0));
+    <BidPlacedAt<Test>>::insert(0, 4, (10, 0));
+
+    // 价格相同时后出价者获得最后一件
+    let auction = Auctions::auctions(0).unwrap();
+    assert_eq!(Auctions::lot_winners(&auction), vec![(3, 200), (2, 150)]);
+
+    <BidPlacedAt<Test>>::insert(0, 2, (10, 1));
+    <BidPlacedAt<Test>>::insert(0, 4, (10, 2));
+    assert_eq!(Auctions::lot_winners(&auction), vec![(3, 200), (4, 150)]);
+  });
+}
+
+#[test]
+fn equal_bids_are_rejected_when_configured() {
+  new_test_ext().execute_with(|| {
+    TIE_BREAK.with(|v| *v.borrow_mut() = super::TieBreak::RejectEqual);
+    for bidder in 2..5 {
+      let _ = Balances::deposit_creating(&bidder, 1000);
+    }
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_lot_items(Origin::signed(1), 0, vec![7, 8], LotPricing::PayAsBid));
+    Auctions::do_start_auctions(&vec![0]);
+    assert_ok!(Auctions::participate_auction(Origin::signed(2), 0, 150, None, None, None));
+    assert_noop!(
+      Auctions::participate_auction(Origin::signed(3), 0, 150, None, None, None),
+      "An equal bid already exists"
+    );
+    assert_ok!(Auctions::participate_auction(Origin::signed(3), 0, 151, None, None, None));
+    assert_eq!(Auctions::auction_bids(0, 3), 151);
+  });
+}
+
+#[test]
+fn lot_settles_at_the_clearing_price() {
+  new_test_ext().execute_with(|| {
+    for bidder in 2..5 {
+      let _ = Balances::deposit_creating(&bidder, 1000);
+    }
+    assert_ok!(Auctions::set_fee_destination(Origin::ROOT, FeeDestination::FixedAccount(99)));
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_lot_items(Origin::signed(1), 0, vec![7, 8], LotPricing::Uniform));
+    Auctions::do_start_auctions(&vec![0]);
+    assert_ok!(Auctions::participate_auction(Origin::signed(2), 0, 150, None, None, None));
+    assert_ok!(Auctions::participate_auction(Origin::signed(3), 0, 200, None, None, None));
+    // 拍品已满时需高于最低中标价加最小加价幅度
+    assert!(Auctions::participate_auction(Origin::signed(4), 0, 151, None, None, None).is_err());
+    assert_ok!(Auctions::participate_auction(Origin::signed(4), 0, 160, None, None, None));
+    assert_ok!(Auctions::stop_auction(Origin::signed(1), 0));
+
+    // 中标者都按最低中标价160成交，多锁定的部分退回
+    assert_eq!(Auctions::account_locks(2), 0);
+    assert_eq!(Auctions::account_locks(3), 0);
+    assert_eq!(Balances::free_balance(&2), 1000);
+    assert_eq!(Balances::free_balance(&3), 840);
+    assert_eq!(Balances::free_balance(&4), 840);
+    assert_eq!(Balances::free_balance(&1), 304);
+    assert_eq!(Balances::free_balance(&99), 16);
+    assert_eq!(Auctions::auction_items(7), None);
+    assert_eq!(Auctions::auction_items(8), None);
+  });
+}
+
+#[test]
+fn lot_winners_pay_their_own_bids() {
+  new_test_ext().execute_with(|| {
+    for bidder in 2..5 {
+      let _ = Balances::deposit_creating(&bidder, 1000);
+    }
+    assert_ok!(Auctions::set_fee_destination(Origin::ROOT, FeeDestination::FixedAccount(99)));
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_lot_items(Origin::signed(1), 0, vec![7, 8], LotPricing::PayAsBid));
+    Auctions::do_start_auctions(&vec![0]);
+    assert_ok!(Auctions::participate_auction(Origin::signed(2), 0, 150, None, None, None));
+    assert_ok!(Auctions::participate_auction(Origin::signed(3), 0, 200, None, None, None));
+    assert_ok!(Auctions::participate_auction(Origin::signed(4), 0, 160, None, None, None));
+    assert_ok!(Auctions::stop_auction(Origin::signed(1), 0));
+
+    assert_eq!(Balances::free_balance(&2), 1000);
+    assert_eq!(Balances::free_balance(&3), 800);
+    assert_eq!(Balances::free_balance(&4), 840);
+    assert_eq!(Balances::free_balance(&1), 342);
+    assert_eq!(Balances::free_balance(&99), 18);
+  });
+}
+
+#[test]
+fn proceeds_above_the_threshold_vest() {
+  new_test_ext().execute_with(|| {
+    use support::traits::OnTimestampSet;
+    let _ = Balances::deposit_creating(&2, 1000);
+    assert_ok!(Auctions::set_vesting_threshold(Origin::ROOT, Some(150)));
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    Auctions::do_start_auctions(&vec![0]);
+    assert_ok!(Auctions::participate_auction(Origin::signed(2), 0, 210, None, None, None));
+    assert_ok!(Auctions::stop_auction(Origin::signed(1), 0));
+
+    // 成交款200超过阈值，在VestingPeriod内线性解锁
+    assert_eq!(Balances::free_balance(&1), 200);
+    assert_eq!(Auctions::proceeds_vesting(1), vec![(200, 0)]);
+    assert!(Balances::transfer(Origin::signed(1), 5, 1).is_err());
+    assert_noop!(Auctions::vest(Origin::signed(2)), "No vesting proceeds");
+
+    Aura::on_timestamp_set(500);
+    assert_ok!(Auctions::vest(Origin::signed(1)));
+    assert_ok!(Balances::transfer(Origin::signed(1), 5, 100));
+    assert!(Balances::transfer(Origin::signed(1), 5, 1).is_err());
+
+    Aura::on_timestamp_set(1000);
+    assert_ok!(Auctions::vest(Origin::signed(1)));
+    assert!(Auctions::proceeds_vesting(1).is_empty());
+    assert_ok!(Balances::transfer(Origin::signed(1), 5, 100));
+  });
+}
+
+#[test]
+fn proceeds_vest_linearly() {
+  new_test_ext().execute_with(|| {
+    assert!(Auctions::set_vesting_threshold(Origin::signed(1), Some(500)).is_err());
+    assert_ok!(Auctions::set_vesting_threshold(Origin::ROOT, Some(500)));
+    assert_eq!(Auctions::vesting_threshold(), Some(500));
+
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_noop!(Auctions::setup_proceeds_vesting(Origin::signed(2), 0, true), "Only owner can call this fn.");
+    assert_ok!(Auctions::setup_proceeds_vesting(Origin::signed(1), 0, true));
+
+    // vesting period is 1000 in the mock
+    assert_eq!(Auctions::vesting_locked(&(1000, 100), 100), 1000);
+    assert_eq!(Auctions::vesting_locked(&(1000, 100), 350), 750);
+    assert_eq!(Auctions::vesting_locked(&(1000, 100), 1100), 0);
+  });
+}
+
+#[test]
+fn installment_plan_is_validated() {
+  new_test_ext().execute_with(|| {
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    let plan = |installments, interval| InstallmentPlan { down_payment: Permill::from_percent(20), installments, interval };
+    assert_noop!(
+      Auctions::setup_installments(Origin::signed(1), 0, Some(plan(0, 100))),
+      "Invalid number of installments"
+    );
+    assert_noop!(
+      Auctions::setup_installments(Origin::signed(1), 0, Some(plan(3, 0))),
+      "Installment interval should not be zero"
+    );
+    assert_ok!(Auctions::setup_installments(Origin::signed(1), 0, Some(plan(3, 100))));
+    assert_noop!(
+      Auctions::setup_claim_settlement(Origin::signed(1), 0, true),
+      "Claim settled auction can not be paid by installments"
+    );
+    assert_noop!(Auctions::pay_installment(Origin::signed(2), 0), "Auction is not paid by installments");
+  });
+}
+
+#[test]
+fn installments_are_reserved_and_repossessed() {
+  new_test_ext().execute_with(|| {
+    use support::traits::OnTimestampSet;
+    let _ = Balances::deposit_creating(&2, 1000);
+    assert_ok!(Auctions::set_fee_destination(Origin::ROOT, FeeDestination::FixedAccount(99)));
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    let plan = InstallmentPlan { down_payment: Permill::from_percent(20), installments: 2, interval: 100 };
+    assert_ok!(Auctions::setup_installments(Origin::signed(1), 0, Some(plan)));
+    Auctions::do_start_auctions(&vec![0]);
+    assert_ok!(Auctions::participate_auction(Origin::signed(2), 0, 200, None, None, None));
+    assert_ok!(Auctions::stop_auction(Origin::signed(1), 0));
+
+    // 首付40，其余160被reserve
+    assert_eq!(Balances::free_balance(&2), 800);
+    assert_eq!(Balances::reserved_balance(&2), 160);
+    assert_eq!(Balances::free_balance(&1), 38);
+
+    assert_noop!(Auctions::pay_installment(Origin::signed(3), 0), "Only the winner can pay installments");
+    assert_ok!(Auctions::pay_installment(Origin::signed(2), 0));
+    assert_eq!(Balances::free_balance(&2), 800);
+    assert_eq!(Balances::reserved_balance(&2), 80);
+    assert_eq!(Balances::free_balance(&1), 114);
+    assert_eq!(Auctions::installment(0).unwrap().paid, 76);
+
+    assert_noop!(Auctions::repossess_item(Origin::signed(1), 0), "Installment is not overdue");
+    Aura::on_timestamp_set(201);
+    assert_noop!(Auctions::repossess_item(Origin::signed(2), 0), "Only owner can call this fn.");
+    assert_ok!(Auctions::repossess_item(Origin::signed(1), 0));
+
+    // 只退还卖家实际收到的分期款，手续费不退
+    assert_eq!(Balances::free_balance(&1), 38);
+    assert_eq!(Balances::free_balance(&2), 956);
+    assert_eq!(Balances::reserved_balance(&2), 0);
+    assert_eq!(Balances::free_balance(&99), 6);
+    assert!(Auctions::installment(0).is_none());
+    assert_eq!(Auctions::auction_items(7), None);
+  });
+}
+
+#[test]
+fn installments_are_paid_off() {
+  new_test_ext().execute_with(|| {
+    let _ = Balances::deposit_creating(&2, 1000);
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    let plan = InstallmentPlan { down_payment: Permill::from_percent(20), installments: 2, interval: 100 };
+    assert_ok!(Auctions::setup_installments(Origin::signed(1), 0, Some(plan)));
+    Auctions::do_start_auctions(&vec![0]);
+    assert_ok!(Auctions::participate_auction(Origin::signed(2), 0, 200, None, None, None));
+    assert_ok!(Auctions::stop_auction(Origin::signed(1), 0));
+
+    assert_ok!(Auctions::pay_installment(Origin::signed(2), 0));
+    assert_ok!(Auctions::pay_installment(Origin::signed(2), 0));
+    assert_eq!(Balances::free_balance(&2), 800);
+    assert_eq!(Balances::reserved_balance(&2), 0);
+    assert!(Auctions::installment(0).is_none());
+    assert_noop!(Auctions::pay_installment(Origin::signed(2), 0), "Auction is not paid by installments");
+  });
+}
+
+#[test]
+fn last_installment_is_not_taken_when_the_item_can_not_be_handed_over() {
+  new_test_ext().execute_with(|| {
+    let _ = Balances::deposit_creating(&2, 1000);
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    let plan = InstallmentPlan { down_payment: Permill::from_percent(20), installments: 2, interval: 100 };
+    assert_ok!(Auctions::setup_installments(Origin::signed(1), 0, Some(plan)));
+    Auctions::do_start_auctions(&vec![0]);
+    assert_ok!(Auctions::participate_auction(Origin::signed(2), 0, 200, None, None, None));
+    assert_ok!(Auctions::stop_auction(Origin::signed(1), 0));
+    assert_ok!(Auctions::pay_installment(Origin::signed(2), 0));
+
+    // 最后一期无法交付拍品时不扣款，分期记录保留
+    FROZEN_ITEMS.with(|v| *v.borrow_mut() = vec![7]);
+    assert_noop!(Auctions::pay_installment(Origin::signed(2), 0), <&'static str>::from(Error::ItemFrozen));
+    assert_eq!(Balances::reserved_balance(&2), 80);
+    assert_eq!(Auctions::installment(0).unwrap().installments_left, 1);
+
+    FROZEN_ITEMS.with(|v| v.borrow_mut().clear());
+    assert_ok!(Auctions::pay_installment(Origin::signed(2), 0));
+    assert!(Auctions::installment(0).is_none());
+    assert_eq!(Auctions::auction_items(7), None);
+  });
+}
+
+#[test]
+fn repossessed_installments_release_vested_proceeds() {
+  new_test_ext().execute_with(|| {
+    use support::traits::OnTimestampSet;
+    let _ = Balances::deposit_creating(&2, 1000);
+    assert_ok!(Auctions::set_fee_destination(Origin::ROOT, FeeDestination::FixedAccount(99)));
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    assert_ok!(Auctions::setup_proceeds_vesting(Origin::signed(1), 0, true));
+    let plan = InstallmentPlan { down_payment: Permill::from_percent(20), installments: 2, interval: 1 };
+    assert_ok!(Auctions::setup_installments(Origin::signed(1), 0, Some(plan)));
+    Auctions::do_start_auctions(&vec![0]);
+    assert_ok!(Auctions::participate_auction(Origin::signed(2), 0, 200, None, None, None));
+    assert_ok!(Auctions::stop_auction(Origin::signed(1), 0));
+    assert_ok!(Auctions::pay_installment(Origin::signed(2), 0));
+    assert_eq!(Auctions::proceeds_vesting(1), vec![(38, 0), (76, 0)]);
+
+    // 分期款仍在锁仓中，退还时先解锁
+    Aura::on_timestamp_set(3);
+    assert_ok!(Auctions::repossess_item(Origin::signed(1), 0));
+    assert_eq!(Balances::free_balance(&1), 38);
+    assert_eq!(Auctions::proceeds_vesting(1), vec![(38, 0)]);
+    assert!(Balances::transfer(Origin::signed(1), 5, 1).is_err());
+    assert_eq!(Balances::free_balance(&2), 956);
+  });
+}
+
+#[test]
+fn collateral_bids_only_lock_the_collateral() {
+  new_test_ext().execute_with(|| {
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_noop!(
+      Auctions::setup_collateral(Origin::signed(1), 0, Some(Permill::zero())),
+      "Collateral ratio should not be zero"
+    );
+    assert_ok!(Auctions::setup_collateral(Origin::signed(1), 0, Some(Permill::from_percent(20))));
+    assert_eq!(Auctions::bid_delta(&0, &2, 100), 20);
+
+    // raising the bid only locks the extra collateral
+    <BidCollateral<Test>>::insert(0, 2, 20);
+    <AuctionBids<Test>>::insert(0, 2, 100);
+    assert_eq!(Auctions::bid_delta(&0, &2, 150), 10);
+  });
+}
+
+#[test]
+fn defaulting_winners_lose_their_collateral() {
+  new_test_ext().execute_with(|| {
+    let _ = Balances::deposit_creating(&2, 100);
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    assert_ok!(Auctions::setup_collateral(Origin::signed(1), 0, Some(Permill::from_percent(20))));
+    Auctions::do_start_auctions(&vec![0]);
+    assert_ok!(Auctions::participate_auction(Origin::signed(2), 0, 200, None, None, None));
+    assert_eq!(Auctions::bid_collateral(0, 2), 40);
+
+    // 保证金不足时不解锁出价，可补足后重试
+    assert_ok!(Balances::transfer(Origin::signed(2), 5, 70));
+    assert_noop!(Auctions::stop_auction(Origin::signed(1), 0), "Winner can not pay the collateral");
+    assert!(!Auctions::bid_unlocked(0, 2));
+
+    assert_ok!(Balances::transfer(Origin::signed(5), 2, 70));
+    assert_ok!(Auctions::stop_auction(Origin::signed(1), 0));
+    assert_eq!(Balances::free_balance(&1), 40);
+    assert_eq!(Balances::free_balance(&2), 60);
+    assert!(Auctions::bid_unlocked(0, 2));
+    assert_eq!(Auctions::auction_items(7), None);
+  });
+}
+
+#[test]
+fn committed_credit_is_not_withdrawable() {
+  new_test_ext().execute_with(|| {
+    <BidCredits<Test>>::insert(2, 100);
+    Auctions::do_commit_credit(&0, &2, 30);
+    assert_eq!(Auctions::available_credit(&2), 70);
+    assert_eq!(Auctions::auction_bids(0, 2), 30);
+    assert_noop!(Auctions::withdraw_credit(Origin::signed(2), 80), "Not enough available credit");
+
+    // unlocking the bid releases the committed credit
+    assert_ok!(Auctions::do_unlock_balance(&0, &2));
+    assert_eq!(Auctions::available_credit(&2), 100);
+    assert_eq!(Auctions::credit_bid(0, 2), 0);
+    assert_ok!(Auctions::do_unlock_balance(&0, &2));
+    assert_eq!(Auctions::committed_credit(&2), 0);
+  });
+}
+
+#[test]
+fn winners_pay_with_their_credit() {
+  new_test_ext().execute_with(|| {
+    let _ = Balances::deposit_creating(&2, 300);
+    let _ = Balances::deposit_creating(&3, 300);
+    assert_ok!(Auctions::deposit_credit(Origin::signed(2), 200));
+    assert_ok!(Auctions::deposit_credit(Origin::signed(3), 150));
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    Auctions::do_start_auctions(&vec![0]);
+    assert_ok!(Auctions::participate_auction(Origin::signed(3), 0, 150, None, None, None));
+    assert_ok!(Auctions::participate_auction(Origin::signed(2), 0, 200, None, None, None));
+    assert_eq!(Auctions::credit_bid(0, 2), 200);
+    assert_ok!(Auctions::stop_auction(Origin::signed(1), 0));
+
+    // 中标者的出价从信用额度中支付
+    assert_eq!(Balances::free_balance(&2), 100);
+    assert_eq!(Balances::reserved_balance(&2), 0);
+    assert_eq!(Auctions::bid_credit(&2), 0);
+    assert_eq!(Auctions::committed_credit(&2), 0);
+    assert_eq!(Auctions::credit_bid(0, 2), 0);
+    assert_noop!(Auctions::withdraw_credit(Origin::signed(2), 1), "Not enough available credit");
+
+    // 落选者的信用额度重新可用
+    assert_eq!(Auctions::credit_bid(0, 3), 0);
+    assert_eq!(Auctions::available_credit(&3), 150);
+    assert_ok!(Auctions::withdraw_credit(Origin::signed(3), 150));
+    assert_eq!(Balances::free_balance(&3), 300);
+  });
+}
+
+#[test]
+fn fee_destination_is_set_by_governance() {
+  new_test_ext().execute_with(|| {
+    assert_eq!(Auctions::fee_destination(), FeeDestination::Treasury);
+    assert!(Auctions::set_fee_destination(Origin::signed(1), FeeDestination::Burn).is_err());
+    assert_noop!(
+      Auctions::set_fee_destination(Origin::ROOT, FeeDestination::Split(vec![(1, Permill::from_percent(60))])),
+      "Payout shares should sum to 100%"
+    );
+    let split = FeeDestination::Split(vec![(1, Permill::from_percent(60)), (2, Permill::from_percent(40))]);
+    assert_ok!(Auctions::set_fee_destination(Origin::ROOT, split.clone()));
+    assert_eq!(Auctions::fee_destination(), split);
+  });
+}
+
+#[test]
+fn auction_fee_is_a_twentieth_of_the_price() {
+  new_test_ext().execute_with(|| {
+    // 手续费为成交价的5%，向下取整，不设上限
+    assert_eq!(Auctions::_calc_auctino_fee(19), (19, 0));
+    assert_eq!(Auctions::_calc_auctino_fee(200), (190, 10));
+    assert_eq!(Auctions::_calc_auctino_fee(10_000), (9_500, 500));
+  });
+}
+
+#[test]
+fn fees_are_routed_to_the_destination() {
+  new_test_ext().execute_with(|| {
+    let _ = Balances::deposit_creating(&2, 1000);
+    assert_ok!(Auctions::set_fee_destination(Origin::ROOT, FeeDestination::Burn));
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    Auctions::do_start_auctions(&vec![0]);
+    assert_ok!(Auctions::participate_auction(Origin::signed(2), 0, 200, None, None, None));
+    assert_ok!(Auctions::stop_auction(Origin::signed(1), 0));
+    // 手续费被销毁
+    assert_eq!(Balances::free_balance(&1), 190);
+    assert_eq!(Balances::total_issuance(), 990);
+
+    let split = FeeDestination::Split(vec![(5, Permill::from_percent(60)), (6, Permill::from_percent(40))]);
+    assert_ok!(Auctions::set_fee_destination(Origin::ROOT, split));
+    Auctions::route_fee(Balances::issue(101));
+    assert_eq!(Balances::free_balance(&5), 60);
+    assert_eq!(Balances::free_balance(&6), 40);
+    // 零头交给国库，mock中的国库直接销毁
+    assert_eq!(Balances::total_issuance(), 1090);
+
+    assert_ok!(Auctions::set_fee_destination(Origin::ROOT, FeeDestination::FixedAccount(99)));
+    Auctions::route_fee(Balances::issue(10));
+    assert_eq!(Balances::free_balance(&99), 10);
+  });
+}
+
+#[test]
+fn sellers_can_not_bid() {
+  new_test_ext().execute_with(|| {
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    Auctions::do_start_auctions(&vec![0]);
+
+    assert_noop!(
+      Auctions::participate_auction(Origin::signed(1), 0, 200, None, None, None),
+      "Owner can not bid in own auction"
+    );
+    // other bidders pass the check
+    assert_noop!(
+      Auctions::participate_auction(Origin::signed(2), 0, 200, None, None, None),
+      "No enough balance to lock"
+    );
+  });
+}
+
+#[test]
+fn upheld_challenge_voids_the_sale() {
+  new_test_ext().execute_with(|| {
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    assert_noop!(Auctions::challenge_auction(Origin::signed(2), 0), "Auction can not be challenged now");
+
+    Auctions::do_start_auctions(&vec![0]);
+    assert_ok!(Auctions::challenge_auction(Origin::signed(2), 0));
+    assert_noop!(Auctions::challenge_auction(Origin::signed(3), 0), "Auction is already challenged");
+    assert_noop!(Auctions::stop_auction(Origin::signed(1), 0), "Auction is under review");
+    assert!(Auctions::rule_challenge(Origin::signed(1), 0, true).is_err());
+
+    assert_ok!(Auctions::rule_challenge(Origin::ROOT, 0, true));
+    assert_eq!(Auctions::auctions(0).unwrap().status, AuctionStatus::Stopped);
+    assert_eq!(Auctions::auction_items(7), None);
+    assert!(Auctions::active_auctions().is_empty());
+  });
+}
+
+#[test]
+fn upheld_challenge_slashes_the_creation_deposit() {
+  new_test_ext().execute_with(|| {
+    CREATION_DEPOSIT.with(|v| *v.borrow_mut() = 30);
+    CHALLENGE_BOND.with(|v| *v.borrow_mut() = 50);
+    let _ = Balances::deposit_creating(&1, 100);
+    let _ = Balances::deposit_creating(&2, 100);
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    Auctions::do_start_auctions(&vec![0]);
+    assert_ok!(Auctions::challenge_auction(Origin::signed(2), 0));
+    assert_eq!(Balances::reserved_balance(&2), 50);
+
+    // 围标成立，举报人取回保证金并获得卖家的创建押金
+    assert_ok!(Auctions::rule_challenge(Origin::ROOT, 0, true));
+    assert_eq!(Balances::free_balance(&2), 130);
+    assert_eq!(Balances::reserved_balance(&2), 0);
+    assert_eq!(Balances::free_balance(&1), 70);
+    assert_eq!(Balances::reserved_balance(&1), 0);
+  });
+}
+
+#[test]
+fn queued_auctions_keep_the_creation_deposit_for_a_challenge() {
+  new_test_ext().execute_with(|| {
+    CREATION_DEPOSIT.with(|v| *v.borrow_mut() = 30);
+    CHALLENGE_BOND.with(|v| *v.borrow_mut() = 50);
+    let _ = Balances::deposit_creating(&1, 100);
+    let _ = Balances::deposit_creating(&2, 100);
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    Auctions::do_start_auctions(&vec![0, 1]);
+    Auctions::do_stop_auctions(&vec![0, 1]);
+    assert_eq!(Auctions::settlement_queue(), vec![0, 1]);
+    // 停拍待结算时押金仍然保留
+    assert_eq!(Balances::reserved_balance(&1), 60);
+
+    assert_ok!(Auctions::challenge_auction(Origin::signed(2), 0));
+    Auctions::settle_queued_auctions();
+    assert_eq!(Auctions::settlement_queue(), vec![0]);
+    assert_eq!(Balances::reserved_balance(&1), 30);
+
+    assert_ok!(Auctions::rule_challenge(Origin::ROOT, 0, true));
+    assert!(Auctions::settlement_queue().is_empty());
+    assert_eq!(Balances::free_balance(&2), 130);
+    assert_eq!(Balances::reserved_balance(&1), 0);
+    assert_eq!(Balances::free_balance(&1), 70);
+  });
+}
+
+#[test]
+fn rejected_challenge_pays_the_bond_to_the_seller() {
+  new_test_ext().execute_with(|| {
+    CHALLENGE_BOND.with(|v| *v.borrow_mut() = 50);
+    let _ = Balances::deposit_creating(&1, 100);
+    let _ = Balances::deposit_creating(&2, 100);
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    Auctions::do_start_auctions(&vec![0]);
+    assert_ok!(Auctions::challenge_auction(Origin::signed(2), 0));
+    assert_noop!(Auctions::challenge_auction(Origin::signed(3), 0), "Auction is already challenged");
+
+    assert_ok!(Auctions::rule_challenge(Origin::ROOT, 0, false));
+    assert_eq!(Balances::free_balance(&1), 150);
+    assert_eq!(Balances::free_balance(&2), 50);
+    assert_eq!(Balances::reserved_balance(&2), 0);
+    assert_eq!(Auctions::challenge(0), None);
+    assert_noop!(Auctions::rule_challenge(Origin::ROOT, 0, false), "Auction is not challenged");
+    // 驳回后拍卖照常进行
+    assert_eq!(Auctions::auctions(0).unwrap().status, AuctionStatus::Active);
+    assert_ok!(Auctions::stop_auction(Origin::signed(1), 0));
+  });
+}
+
+#[test]
+fn unruled_challenges_lapse() {
+  new_test_ext().execute_with(|| {
+    use support::traits::OnTimestampSet;
+    CHALLENGE_BOND.with(|v| *v.borrow_mut() = 50);
+    let _ = Balances::deposit_creating(&2, 100);
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    Auctions::do_start_auctions(&vec![0]);
+    assert_ok!(Auctions::challenge_auction(Origin::signed(2), 0));
+    assert_eq!(Auctions::challenge(0), Some((2, 50, 1000)));
+
+    // ReviewTimeout in the mock is 1000
+    Aura::on_timestamp_set(1000);
+    assert_noop!(Auctions::expire_challenge(Origin::signed(3), 0), "Challenge is not expired");
+    Aura::on_timestamp_set(1001);
+    assert_ok!(Auctions::expire_challenge(Origin::signed(3), 0));
+    assert_eq!(Balances::free_balance(&2), 100);
+    assert_eq!(Balances::reserved_balance(&2), 0);
+    assert_noop!(Auctions::expire_challenge(Origin::signed(3), 0), "Auction is not challenged");
+    assert_ok!(Auctions::stop_auction(Origin::signed(1), 0));
+  });
+}
+
+#[test]
+fn upheld_report_blacklists_the_item() {
+  new_test_ext().execute_with(|| {
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    let reason_hash = <Test as system::Trait>::Hashing::hash(b"counterfeit");
+    assert_ok!(Auctions::report_auction(Origin::signed(2), 0, reason_hash));
+    assert_eq!(Auctions::review_queue(), vec![0]);
+
+    let ruling = ReportRuling { slash_deposit: true, blacklist_item: true, refund_bidders: true };
+    assert!(Auctions::review_report(Origin::signed(1), 0, Some(ruling)).is_err());
+    assert_ok!(Auctions::review_report(Origin::ROOT, 0, Some(ruling)));
+    assert!(Auctions::review_queue().is_empty());
+    assert_eq!(Auctions::auctions(0).unwrap().status, AuctionStatus::Stopped);
+
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_noop!(Auctions::add_item(Origin::signed(1), 1, 7), "Item is blacklisted");
+  });
+}
+
+#[test]
+fn upheld_report_on_a_queued_auction_slashes_the_deposit() {
+  new_test_ext().execute_with(|| {
+    CREATION_DEPOSIT.with(|v| *v.borrow_mut() = 30);
+    CHALLENGE_BOND.with(|v| *v.borrow_mut() = 50);
+    let _ = Balances::deposit_creating(&1, 100);
+    let _ = Balances::deposit_creating(&2, 100);
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    Auctions::do_start_auctions(&vec![0]);
+    Auctions::do_stop_auctions(&vec![0]);
+    let reason_hash = <Test as system::Trait>::Hashing::hash(b"counterfeit");
+    assert_ok!(Auctions::report_auction(Origin::signed(2), 0, reason_hash));
+
+    let ruling = ReportRuling { slash_deposit: true, blacklist_item: false, refund_bidders: true };
+    assert_ok!(Auctions::review_report(Origin::ROOT, 0, Some(ruling)));
+    assert!(Auctions::settlement_queue().is_empty());
+    assert!(Auctions::review_queue().is_empty());
+    assert_eq!(Auctions::auction_items(7), None);
+    assert_eq!(Balances::free_balance(&2), 130);
+    assert_eq!(Balances::free_balance(&1), 70);
+    assert_eq!(Balances::reserved_balance(&1), 0);
+  });
+}
+
+#[test]
+fn dismissed_report_pays_the_bond_to_the_owner() {
+  new_test_ext().execute_with(|| {
+    CHALLENGE_BOND.with(|v| *v.borrow_mut() = 50);
+    let _ = Balances::deposit_creating(&1, 100);
+    let _ = Balances::deposit_creating(&2, 100);
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    let reason_hash = <Test as system::Trait>::Hashing::hash(b"counterfeit");
+    assert_ok!(Auctions::report_auction(Origin::signed(2), 0, reason_hash));
+    assert_noop!(Auctions::report_auction(Origin::signed(3), 0, reason_hash), "Auction is already reported");
+    assert_eq!(Balances::reserved_balance(&2), 50);
+
+    assert_ok!(Auctions::review_report(Origin::ROOT, 0, None));
+    assert_eq!(Balances::free_balance(&1), 150);
+    assert_eq!(Balances::free_balance(&2), 50);
+    assert_eq!(Balances::reserved_balance(&2), 0);
+    assert!(Auctions::review_queue().is_empty());
+    // 驳回后拍品照常上架
+    assert_eq!(Auctions::auctions(0).unwrap().status, AuctionStatus::PendingStart);
+    assert_eq!(Auctions::auction_items(7), Some(0));
+  });
+}
+
+#[test]
+fn unreviewed_reports_lapse() {
+  new_test_ext().execute_with(|| {
+    use support::traits::OnTimestampSet;
+    CHALLENGE_BOND.with(|v| *v.borrow_mut() = 50);
+    let _ = Balances::deposit_creating(&2, 100);
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    Auctions::do_start_auctions(&vec![0]);
+    let reason_hash = <Test as system::Trait>::Hashing::hash(b"counterfeit");
+    assert_ok!(Auctions::report_auction(Origin::signed(2), 0, reason_hash));
+    assert_noop!(Auctions::stop_auction(Origin::signed(1), 0), "Auction is under review");
+
+    Aura::on_timestamp_set(1000);
+    assert_noop!(Auctions::expire_report(Origin::signed(3), 0), "Report is not expired");
+    Aura::on_timestamp_set(1001);
+    assert_ok!(Auctions::expire_report(Origin::signed(3), 0));
+    assert_eq!(Balances::free_balance(&2), 100);
+    assert_eq!(Balances::reserved_balance(&2), 0);
+    assert!(Auctions::review_queue().is_empty());
+    assert_ok!(Auctions::stop_auction(Origin::signed(1), 0));
+  });
+}
+
+#[test]
+fn creation_gate_rejects_low_reputation() {
+  new_test_ext().execute_with(|| {
+    let gate = CreationGate { value_threshold: 1_000, min_reputation: 10, low_reputation_deposit: None };
+    assert_ok!(Auctions::set_creation_gate(Origin::ROOT, Some(gate)));
+
+    // below the threshold anyone can create
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_noop!(
+      Auctions::create_auction(Origin::signed(1), 1_000, 1, None, vec![], vec![], None, None),
+      "Reputation too low to create this auction"
+    );
+  });
+}
+
+#[test]
+fn low_reputation_owners_reserve_the_higher_deposit() {
+  new_test_ext().execute_with(|| {
+    CREATION_DEPOSIT.with(|v| *v.borrow_mut() = 10);
+    let _ = Balances::deposit_creating(&1, 100);
+    let gate = CreationGate { value_threshold: 100, min_reputation: 10, low_reputation_deposit: Some(50) };
+    assert_ok!(Auctions::set_creation_gate(Origin::ROOT, Some(gate)));
+
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_eq!(Auctions::creation_deposit(0), Some((50, 0)));
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 50, 1, None, vec![], vec![], None, None));
+    assert_eq!(Auctions::creation_deposit(1), Some((10, 0)));
+    assert_eq!(Balances::reserved_balance(&1), 60);
+  });
+}
+
+#[test]
+fn relisting_passes_the_creation_gate() {
+  new_test_ext().execute_with(|| {
+    CREATION_DEPOSIT.with(|v| *v.borrow_mut() = 10);
+    let _ = Balances::deposit_creating(&1, 60);
+    let gate = CreationGate { value_threshold: 90, min_reputation: 10, low_reputation_deposit: Some(50) };
+    assert_ok!(Auctions::set_creation_gate(Origin::ROOT, Some(gate)));
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    let policy = RelistPolicy { max_relists: 2, price_reduction: Permill::from_percent(10) };
+    assert_ok!(Auctions::setup_relist_policy(Origin::signed(1), 0, Some(policy)));
+    Auctions::do_start_auctions(&vec![0]);
+
+    // 降价后起拍价90仍达到门槛，重新上架需要更高的押金
+    assert_ok!(Auctions::stop_auction(Origin::signed(1), 0));
+    assert_eq!(Auctions::auctions(1).unwrap().begin_price, 90);
+    assert_eq!(Auctions::creation_deposit(1), Some((50, 0)));
+    assert_eq!(Balances::reserved_balance(&1), 50);
+
+    // 门槛拒绝低信誉分时不再重新上架
+    let gate = CreationGate { value_threshold: 80, min_reputation: 10, low_reputation_deposit: None };
+    assert_ok!(Auctions::set_creation_gate(Origin::ROOT, Some(gate)));
+    Auctions::do_start_auctions(&vec![1]);
+    assert_ok!(Auctions::stop_auction(Origin::signed(1), 1));
+    assert!(Auctions::auctions(2).is_none());
+    assert_eq!(Auctions::auction_items(7), None);
+    assert_eq!(Balances::reserved_balance(&1), 0);
+  });
+}
+
+#[test]
+fn relisting_is_skipped_without_the_deposit() {
+  new_test_ext().execute_with(|| {
+    CREATION_DEPOSIT.with(|v| *v.borrow_mut() = 10);
+    let _ = Balances::deposit_creating(&1, 10);
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    let policy = RelistPolicy { max_relists: 2, price_reduction: Permill::from_percent(10) };
+    assert_ok!(Auctions::setup_relist_policy(Origin::signed(1), 0, Some(policy)));
+    Auctions::do_start_auctions(&vec![0]);
+    // 押金在停拍时退还，余额仍可支付新的押金
+    assert_ok!(Auctions::stop_auction(Origin::signed(1), 0));
+    assert_eq!(Auctions::creation_deposit(1), Some((10, 0)));
+
+    // 余额不足以支付押金时不重新上架
+    Auctions::do_start_auctions(&vec![1]);
+    let _ = Balances::slash_reserved(&1, 10);
+    assert_ok!(Auctions::stop_auction(Origin::signed(1), 1));
+    assert!(Auctions::auctions(2).is_none());
+    assert_eq!(Auctions::auction_items(7), None);
+    assert!(RESERVED_ITEMS.with(|v| v.borrow().is_empty()));
+  });
+}
+
+#[test]
+fn managers_can_pause_but_not_stop() {
+  new_test_ext().execute_with(|| {
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_noop!(Auctions::add_manager(Origin::signed(2), 0, 3), "Only owner can call this fn.");
+    assert_ok!(Auctions::add_manager(Origin::signed(1), 0, 3));
+    assert_noop!(Auctions::add_manager(Origin::signed(1), 0, 3), "Already a manager");
+
+    assert_ok!(Auctions::setup_moments(Origin::signed(3), 0, Some(10), Some(20), None));
+    Auctions::do_start_auctions(&vec![0]);
+    assert_ok!(Auctions::pause_auction(Origin::signed(3), 0));
+    assert_ok!(Auctions::resume_auction(Origin::signed(3), 0));
+    assert_noop!(Auctions::pause_auction(Origin::signed(2), 0), "Only owner or managers can call this fn.");
+    assert_noop!(Auctions::stop_auction(Origin::signed(3), 0), "Only owner can call this fn.");
+
+    assert_ok!(Auctions::remove_manager(Origin::signed(1), 0, 3));
+    assert_noop!(Auctions::pause_auction(Origin::signed(3), 0), "Only owner or managers can call this fn.");
+  });
+}
+
+#[test]
+fn managers_are_removed_when_the_auction_ends() {
+  new_test_ext().execute_with(|| {
+    use support::traits::OnTimestampSet;
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_manager(Origin::signed(1), 0, 3));
+    Auctions::do_start_auctions(&vec![0]);
+    assert_ok!(Auctions::stop_auction(Origin::signed(1), 0));
+    assert!(Auctions::managers_of(0).is_empty());
+    assert!(!<super::AuctionManagers<Test>>::exists(0));
+
+    // 过期清理的拍卖同样删除
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_manager(Origin::signed(1), 1, 3));
+    Aura::on_timestamp_set(1001);
+    assert_ok!(Auctions::purge_stale_auction(Origin::signed(3), 1));
+    assert!(!<super::AuctionManagers<Test>>::exists(1));
+  });
+}
+
+#[test]
+fn events_are_indexed_by_auction_and_account() {
+  new_test_ext().execute_with(|| {
+    let auction_topic = <Test as system::Trait>::Hashing::hash_of(&(b"auction", 0u32));
+    let account_topic = <Test as system::Trait>::Hashing::hash_of(&(b"account", 2u64));
+
+    assert_eq!(
+      Auctions::event_topics(&RawEvent::BidderUpdated(0, 2, 100, 0, None)),
+      vec![auction_topic, account_topic]
+    );
+    assert_eq!(Auctions::event_topics(&RawEvent::EscrowSwept(0, 1)), vec![auction_topic]);
+    assert_eq!(Auctions::event_topics(&RawEvent::CreditDeposited(2, 10)), vec![account_topic]);
+  });
+}
+
+#[test]
+fn closed_auctions_are_announced_in_digest() {
+  new_test_ext().execute_with(|| {
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    Auctions::do_start_auctions(&vec![0]);
+    assert_ok!(Auctions::stop_auction(Origin::signed(1), 0));
+    assert_eq!(Auctions::closed_auctions(), vec![0]);
+
+    Auctions::on_finalize(1);
+    assert!(Auctions::closed_auctions().is_empty());
+    let digest = DigestItem::Other((super::CLOSED_AUCTIONS_DIGEST, vec![0u32]).encode());
+    assert!(System::digest().logs.contains(&digest));
+  });
+}
+
+#[test]
+fn settlement_callback_gas_is_bounded() {
+  new_test_ext().execute_with(|| {
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_noop!(
+      Auctions::setup_settlement_callback(Origin::signed(1), 0, Some((9, 2_000_000))),
+      "Callback gas limit too high"
+    );
+    assert_noop!(
+      Auctions::setup_settlement_callback(Origin::signed(2), 0, Some((9, 1_000))),
+      "Only owner can call this fn."
+    );
+    assert_ok!(Auctions::setup_settlement_callback(Origin::signed(1), 0, Some((9, 1_000))));
+    assert_eq!(Auctions::settlement_callback(0), Some((9, 1_000)));
+
+    // 回调的gas计入登记调用的权重
+    let call = super::Call::<Test>::setup_settlement_callback(0, Some((9, 1_000)));
+    let cleared = super::Call::<Test>::setup_settlement_callback(0, None);
+    assert_eq!(call.get_dispatch_info().weight, cleared.get_dispatch_info().weight + 1_000);
+  });
+}
+
+#[test]
+fn settlement_callback_is_called_for_the_sale() {
+  new_test_ext().execute_with(|| {
+    let _ = Balances::deposit_creating(&2, 1000);
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    assert_ok!(Auctions::setup_settlement_callback(Origin::signed(1), 0, Some((9, 1_000))));
+    Auctions::do_start_auctions(&vec![0]);
+    assert_ok!(Auctions::participate_auction(Origin::signed(2), 0, 200, None, None, None));
+    assert!(CONTRACT_CALLS.with(|v| v.borrow().is_empty()));
+    assert_ok!(Auctions::stop_auction(Origin::signed(1), 0));
+
+    // 以拍卖者的身份调用合约，输入为(auction_id, item_id, winner, price)
+    assert_eq!(
+      CONTRACT_CALLS.with(|v| v.borrow().clone()),
+      vec![(1, 9, 1_000, (0u32, 7u32, 2u64, 200u64).encode())]
+    );
+  });
+}
+
+#[test]
+fn failing_settlement_callback_does_not_undo_the_sale() {
+  new_test_ext().execute_with(|| {
+    let _ = Balances::deposit_creating(&2, 1000);
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    assert_ok!(Auctions::setup_settlement_callback(Origin::signed(1), 0, Some((0, 1_000))));
+    Auctions::do_start_auctions(&vec![0]);
+    assert_ok!(Auctions::participate_auction(Origin::signed(2), 0, 200, None, None, None));
+    assert_ok!(Auctions::stop_auction(Origin::signed(1), 0));
+
+    assert!(CONTRACT_CALLS.with(|v| v.borrow().is_empty()));
+    assert_eq!(Auctions::auction_items(7), None);
+    assert_eq!(Auctions::account_locks(2), 0);
+    assert_eq!(Balances::free_balance(&2), 800);
+  });
+}
+
+#[test]
+fn bids_can_not_lock_the_same_balance_twice() {
+  new_test_ext().execute_with(|| {
+    let _ = Balances::deposit_creating(&2, 1000);
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    Auctions::do_start_auctions(&vec![0, 1]);
+    assert_ok!(Auctions::participate_auction(Origin::signed(2), 0, 600, None, None, None));
+
+    // 已锁定的600不能再用于其他拍卖的出价
+    assert!(Auctions::can_lock(&2, 399));
+    assert!(!Auctions::can_lock(&2, 400));
+    assert_noop!(
+      Auctions::participate_auction(Origin::signed(2), 1, 500, None, None, None),
+      "No enough balance to lock"
+    );
+    assert_ok!(Auctions::participate_auction(Origin::signed(2), 1, 300, None, None, None));
+    assert_eq!(Auctions::account_locks(2), 900);
+  });
+}
+
+#[test]
+fn payouts_are_safe_for_fresh_and_near_empty_accounts() {
+  new_test_ext().execute_with(|| {
+    EXISTENTIAL_DEPOSIT.with(|v| *v.borrow_mut() = 10);
+    let _ = Balances::deposit_creating(&2, 1000);
+    let _ = Balances::deposit_creating(&3, 10);
+    // a bid has to leave the existential deposit
+    assert!(Auctions::can_lock(&2, 989));
+    assert!(!Auctions::can_lock(&2, 990));
+
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    let split = vec![(1, Permill::from_percent(90)), (9, Permill::from_percent(5)), (3, Permill::from_percent(5))];
+    assert_ok!(Auctions::setup_payout_split(Origin::signed(1), 0, split));
+    let auction = Auctions::auctions(0).unwrap();
+
+    // fee 5, the fresh account 1 gets 90, the fresh account 9 can not take 5, account 3 can
+    let payment = Auctions::collect_payments(&auction, &vec![(2, 105)]).unwrap();
+    assert_eq!(payment.payouts, vec![(1, 90), (9, 0), (3, 5)]);
+    assert_eq!(Balances::free_balance(&1), 90);
+    assert_eq!(Balances::free_balance(&9), 0);
+    assert_eq!(Balances::free_balance(&3), 15);
+    assert_eq!(Balances::free_balance(&Auctions::escrow_account(0)), 0);
+  });
+}
+
+#[test]
+fn escrow_leftovers_follow_the_payout_split() {
+  new_test_ext().execute_with(|| {
+    let _ = Balances::deposit_creating(&2, 1000);
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    let split = vec![(1, Permill::from_percent(60)), (3, Permill::from_percent(40))];
+    assert_ok!(Auctions::setup_payout_split(Origin::signed(1), 0, split));
+    let auction = Auctions::auctions(0).unwrap();
+    // 有人直接向托管账户转账
+    let _ = Balances::deposit_creating(&Auctions::escrow_account(0), 10);
+
+    let payment = Auctions::collect_payments(&auction, &vec![(2, 105)]).unwrap();
+    assert_eq!(payment.payouts, vec![(1, 60), (3, 40)]);
+    assert_eq!(Balances::free_balance(&1), 66);
+    assert_eq!(Balances::free_balance(&3), 44);
+    assert_eq!(Balances::free_balance(&Auctions::escrow_account(0)), 0);
+  });
+}
+
+#[test]
+fn split_dust_goes_to_the_fee_destination() {
+  new_test_ext().execute_with(|| {
+    let _ = Balances::deposit_creating(&2, 1000);
+    assert_ok!(Auctions::set_fee_destination(Origin::ROOT, FeeDestination::FixedAccount(99)));
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    let third = Permill::from_parts(333_333);
+    let split = vec![(1, third), (3, third), (4, Permill::from_parts(333_334))];
+    assert_ok!(Auctions::setup_payout_split(Origin::signed(1), 0, split));
+    let auction = Auctions::auctions(0).unwrap();
+
+    // 手续费5，成交款100按三等分各得33，零头1计入手续费
+    let payment = Auctions::collect_payments(&auction, &vec![(2, 105)]).unwrap();
+    assert_eq!(payment.payouts, vec![(1, 33), (3, 33), (4, 33)]);
+    assert_eq!(Balances::free_balance(&99), 6);
+    assert_eq!(Balances::free_balance(&2), 895);
+  });
+}
+
+#[test]
+fn escrow_leftovers_go_to_the_owner_without_a_split() {
+  new_test_ext().execute_with(|| {
+    let _ = Balances::deposit_creating(&2, 1000);
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    let auction = Auctions::auctions(0).unwrap();
+    let _ = Balances::deposit_creating(&Auctions::escrow_account(0), 10);
+
+    assert_ok!(Auctions::collect_payments(&auction, &vec![(2, 105)]));
+    assert_eq!(Balances::free_balance(&1), 110);
+    assert_eq!(Balances::free_balance(&Auctions::escrow_account(0)), 0);
+  });
+}
+
+#[test]
+fn share_splits_round_down_and_leave_the_dust() {
+  new_test_ext().execute_with(|| {
+    let third = Permill::from_parts(333_333);
+    let thirds = vec![(1, third), (2, third), (3, Permill::from_parts(333_334))];
+    // 每份向下取整，零头不分配
+    assert_eq!(Auctions::split_by_shares(100, &thirds), vec![(1, 33), (2, 33), (3, 33)]);
+    assert_eq!(Auctions::split_by_shares(2, &thirds), vec![(1, 0), (2, 0), (3, 0)]);
+    assert_eq!(Auctions::split_by_shares(3_000_000, &thirds), vec![(1, 999_999), (2, 999_999), (3, 1_000_002)]);
+
+    // 伪随机的金额与比例，每份都是精确值向下取整，零头少于份数
+    let mut seed: u64 = 42;
+    let mut next = |bound: u64| {
+      seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+      (seed >> 33) % bound
+    };
+    for _ in 0..500 {
+      let count = next(6) + 1;
+      let mut rest = 1_000_000u32;
+      let mut shares = Vec::new();
+      for i in 0..count {
+        let parts = if i == count - 1 { rest } else { next(rest as u64 + 1) as u32 };
+        rest -= parts;
+        shares.push((i, Permill::from_parts(parts)));
+      }
+      let value = next(1 << 40);
+      let amounts = Auctions::split_by_shares(value, &shares);
+      let dust = value - amounts.iter().map(|(_, a)| a).sum::<u64>();
+      assert!(dust < count);
+      for ((_, amount), (_, share)) in amounts.iter().zip(shares.iter()) {
+        let exact = value as u128 * share.deconstruct() as u128;
+        assert_eq!(*amount as u128, exact / 1_000_000);
+      }
+    }
+  });
+}
+
+#[test]
+fn price_math_reports_overflow_instead_of_panicking() {
+  new_test_ext().execute_with(|| {
+    let _ = Balances::deposit_creating(&2, 1000);
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, u64::max_value(), None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    Auctions::do_start_auctions(&vec![0]);
+    assert_ok!(Auctions::participate_auction(Origin::signed(2), 0, 200, None, None, None));
+
+    // 当前出价加最小加价幅度溢出
+    assert_eq!(Auctions::ensure_bid_acceptable(0, 300).err(), Some("balance add overflow"));
+  });
+}
+
+#[test]
+fn random_bidding_keeps_locks_prices_and_issuance_consistent() {
+  let bidders = [2u64, 3, 4];
+  let mut seed: u64 = 7;
+  let mut next = |bound: u64| {
+    seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+    (seed >> 33) % bound
+  };
+  for _ in 0..50 {
+    new_test_ext().execute_with(|| {
+      for bidder in bidders.iter() {
+        let _ = Balances::deposit_creating(bidder, 5_000);
+      }
+      assert_ok!(Auctions::set_fee_destination(Origin::ROOT, FeeDestination::FixedAccount(99)));
+      assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 5, None, vec![], vec![], None, None));
+      assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+      Auctions::do_start_auctions(&vec![0]);
+      let issuance = Balances::total_issuance();
+
+      let mut highest: u64 = 0;
+      for _ in 0..30 {
+        // 随机出价，可能低于加价幅度或超出余额而失败
+        let bidder = bidders[next(3) as usize];
+        let price = highest.max(100) + next(200);
+        let _ = Auctions::participate_auction(Origin::signed(bidder), 0, price, None, None, None);
+
+        // 最高出价不降低
+        assert!(Auctions::highest_bid(0) >= highest);
+        highest = Auctions::highest_bid(0);
+        // 锁定资金等于未结出价
+        for bidder in bidders.iter() {
+          let locked = Auctions::locked_by_bids(bidder, &[0]);
+          assert_eq!(Auctions::account_locks(bidder), locked);
+          let lock = Balances::locks(bidder).into_iter().find(|l| l.id == BidLockId::get()).map_or(0, |l| l.amount);
+          assert_eq!(lock, locked);
+        }
+      }
+
+      assert_ok!(Auctions::stop_auction(Origin::signed(1), 0));
+      Auctions::on_finalize(1);
+      // 结算后全部解锁，总发行量不变
+      assert_eq!(Balances::total_issuance(), issuance);
+      for bidder in bidders.iter() {
+        assert_eq!(Auctions::account_locks(bidder), 0);
+        assert!(Balances::locks(bidder).iter().all(|l| l.id != BidLockId::get()));
+      }
+    });
+  }
+}
+
+#[test]
+fn try_state_catches_inconsistent_storage() {
+  new_test_ext().execute_with(|| {
+    assert_ok!(Auctions::do_try_state());
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    Auctions::do_start_auctions(&vec![0]);
+    assert_ok!(Auctions::stop_auction(Origin::signed(1), 1));
+    assert_ok!(Auctions::do_try_state());
+
+    <AuctionItems<Test>>::remove(7);
+    assert_eq!(Auctions::do_try_state(), Err("Item of an open auction not held by it"));
+    <AuctionItems<Test>>::insert(7, 0);
+
+    let _ = Balances::deposit_creating(&Auctions::escrow_account(1), 10);
+    assert_eq!(Auctions::do_try_state(), Err("Escrow account not empty"));
+  });
+}
+
+#[test]
+fn status_changes_follow_the_transition_table() {
+  new_test_ext().execute_with(|| {
+    use super::AuctionAction;
+    assert_eq!(AuctionStatus::PendingStart.transition(AuctionAction::Start), Ok(AuctionStatus::Active));
+    assert_eq!(AuctionStatus::Paused.transition(AuctionAction::Stop), Ok(AuctionStatus::Stopped));
+    assert!(AuctionStatus::Active.transition(AuctionAction::Start).is_err());
+    assert!(AuctionStatus::Stopped.transition(AuctionAction::Resume).is_err());
+
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_noop!(Auctions::resume_auction(Origin::signed(1), 0), "Auction should be paused.");
+    // 已开始的拍卖不会被重复开始
+    Auctions::do_start_auctions(&vec![0]);
+    Auctions::do_start_auctions(&vec![0]);
+    assert_eq!(Auctions::active_auctions(), vec![0]);
+    assert_ok!(Auctions::pause_auction(Origin::signed(1), 0));
+    assert_ok!(Auctions::stop_auction(Origin::signed(1), 0));
+    assert_noop!(Auctions::stop_auction(Origin::signed(1), 0), "Auction is already stopped");
+  });
+}
+
+#[test]
+fn simulation_reports_revenue_and_fees() {
+  use super::simulation::{self, BidderStrategy, SimulationConfig};
+  let config = SimulationConfig {
+    auctions: 200,
+    rounds: 3,
+    begin_price: 100,
+    minimum_step: 5,
+    step_tiers: vec![],
+    bidders: vec![(BidderStrategy::Incremental, 500), (BidderStrategy::Jump, 1000), (BidderStrategy::Sniper, 300)],
+    valuation_spread: 0,
+    seed: 1,
+  };
+  let report = simulation::run(&config);
+  // 估值最高的出价人每场都以估值成交
+  assert_eq!(report.sold, 200);
+  assert_eq!(report.wins, vec![0, 200, 0]);
+  assert_eq!(report.revenue + report.fees, 200 * 1000);
+  assert_eq!(report.average_price(), 1000);
+  // 相同种子的结果相同
+  assert_eq!(simulation::run(&config), report);
+}
+
+#[test]
+fn due_auctions_start_on_chain_without_offchain_timing() {
+  new_test_ext().execute_with(|| {
+    use sr_primitives::traits::OnInitialize;
+    use support::traits::OnTimestampSet;
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::setup_moments(Origin::signed(1), 0, Some(10), Some(20), None));
+    Aura::on_timestamp_set(15);
+
+    // 由链下工作机负责时，区块初始化不处理到期拍卖
+    Auctions::on_initialize(1);
+    assert_eq!(Auctions::auctions(0).unwrap().status, AuctionStatus::PendingStart);
+
+    OFFCHAIN_TIMING.with(|v| *v.borrow_mut() = false);
+    assert!(!Auctions::offchain_timing());
+    Auctions::on_initialize(2);
+    assert_eq!(Auctions::auctions(0).unwrap().status, AuctionStatus::Active);
+    assert_eq!(Auctions::active_auctions(), vec![0]);
+  });
+}
+
+#[test]
+fn wait_period_defaults_on_and_respects_governance_bounds() {
+  new_test_ext().execute_with(|| {
+    use super::WaitPeriodBounds;
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_eq!(Auctions::auctions(0).unwrap().wait_period, Some(500));
+
+    let bounds = WaitPeriodBounds { min: 100, max: 1000, allow_disable: false };
+    assert_noop!(
+      Auctions::set_wait_period_bounds(Origin::ROOT, Some(WaitPeriodBounds { min: 10, max: 5, allow_disable: true })),
+      "Invalid wait period bounds"
+    );
+    assert_ok!(Auctions::set_wait_period_bounds(Origin::ROOT, Some(bounds)));
+    assert_noop!(Auctions::setup_moments(Origin::signed(1), 0, None, None, Some(50)), "Wait period out of bounds");
+    assert_noop!(Auctions::setup_moments(Origin::signed(1), 0, None, None, Some(0)), "Wait period can not be turned off");
+    assert_ok!(Auctions::setup_moments(Origin::signed(1), 0, None, None, Some(800)));
+    assert_eq!(Auctions::auctions(0).unwrap().wait_period, Some(800));
+
+    // 允许关闭时，传入0关闭等待时间
+    assert_ok!(Auctions::set_wait_period_bounds(Origin::ROOT, Some(WaitPeriodBounds { allow_disable: true, ..bounds })));
+    assert_ok!(Auctions::setup_moments(Origin::signed(1), 0, None, None, Some(0)));
+    assert_eq!(Auctions::auctions(0).unwrap().wait_period, None);
+  });
+}
+
+#[test]
+fn dust_begin_prices_and_bids_are_rejected() {
+  new_test_ext().execute_with(|| {
+    assert_noop!(
+      Auctions::create_auction(Origin::signed(1), 9, 1, None, vec![], vec![], None, None),
+      "Begin price below the minimum"
+    );
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 10, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    Auctions::do_start_auctions(&vec![0]);
+    assert_eq!(Auctions::ensure_bid_acceptable(0, 9).err(), Some("Bid amount below the minimum"));
+    assert!(Auctions::ensure_bid_acceptable(0, 10).is_ok());
+  });
+}
+
+#[test]
+fn bids_must_be_multiples_of_the_denomination() {
+  new_test_ext().execute_with(|| {
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_noop!(
+      Auctions::setup_bid_denomination(Origin::signed(1), 0, Some(0)),
+      "Bid denomination should not be zero"
+    );
+    assert_noop!(
+      Auctions::setup_bid_denomination(Origin::signed(1), 0, Some(30)),
+      "Begin price is not a multiple of the denomination"
+    );
+    assert_ok!(Auctions::setup_bid_denomination(Origin::signed(1), 0, Some(50)));
+    assert_eq!(Auctions::auctions(0).unwrap().bid_denomination, Some(50));
+
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    Auctions::do_start_auctions(&vec![0]);
+    assert_eq!(Auctions::ensure_bid_acceptable(0, 120).err(), Some("Bid is not a multiple of the denomination"));
+    assert!(Auctions::ensure_bid_acceptable(0, 150).is_ok());
+  });
+}
+
+#[test]
+fn price_snapshot_rounds_are_spread_over_blocks() {
+  new_test_ext().execute_with(|| {
+    use sr_primitives::traits::OnInitialize;
+    use support::StorageValue;
+    let _ = Balances::deposit_creating(&2, 1000);
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    Auctions::do_start_auctions(&vec![0]);
+    assert_ok!(Auctions::participate_auction(Origin::signed(2), 0, 200, None, None, None));
+    // 排在前面的场次占满本区块的快照额度
+    let active: Vec<u32> = (100..170).chain(Some(0)).collect();
+    <super::ActiveAuctions<Test>>::put(active);
+
+    Auctions::on_initialize(2);
+    assert!(Auctions::price_snapshots(0).is_empty());
+    assert_eq!(Auctions::price_snapshot_round(), Some(64));
+    Auctions::on_initialize(3);
+    assert_eq!(Auctions::price_snapshots(0), vec![(3, 200)]);
+    assert_eq!(Auctions::price_snapshot_round(), None);
+    Auctions::on_initialize(5);
+    assert_eq!(Auctions::price_snapshots(0), vec![(3, 200)]);
+  });
+}
+
+#[test]
+fn price_snapshots_keep_the_latest_samples_in_a_ring_buffer() {
+  new_test_ext().execute_with(|| {
+    use sr_primitives::traits::OnInitialize;
+    let _ = Balances::deposit_creating(&2, 1000);
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    Auctions::do_start_auctions(&vec![0]);
+
+    // 尚无出价时不记录快照
+    Auctions::on_initialize(2);
+    assert!(Auctions::price_chart(0).is_empty());
+
+    assert_ok!(Auctions::participate_auction(Origin::signed(2), 0, 200, None, None, None));
+    Auctions::on_initialize(3);
+    assert!(Auctions::price_chart(0).is_empty());
+    for (block, price) in &[(4, 200), (6, 300), (8, 400), (10, 500)] {
+      <AuctionBids<Test>>::insert(0, 2, price);
+      Auctions::on_initialize(*block);
+    }
+
+    // 最多保留3条，最旧的一条被覆盖
+    assert_eq!(Auctions::price_snapshots(0), vec![(10, 500), (6, 300), (8, 400)]);
+    assert_eq!(Auctions::price_chart(0), vec![(6, 300), (8, 400), (10, 500)]);
+  });
+}
+
+#[test]
+fn settled_purchases_are_recorded_in_the_win_history() {
+  new_test_ext().execute_with(|| {
+    use super::WonAuction;
+    let _ = Balances::deposit_creating(&2, 1000);
+    assert_ok!(Auctions::set_fee_destination(Origin::ROOT, FeeDestination::FixedAccount(99)));
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    Auctions::do_start_auctions(&vec![0]);
+    assert_ok!(Auctions::participate_auction(Origin::signed(2), 0, 200, None, None, None));
+    assert!(Auctions::won_auctions(2).is_empty());
+
+    assert_ok!(Auctions::stop_auction(Origin::signed(1), 0));
+    Auctions::settle_queued_auctions();
+    assert_eq!(Auctions::won_auctions(2), vec![WonAuction { auction_id: 0, item: 7, price: 200, won_at: 0 }]);
+  });
+}
+
+#[test]
+fn seller_and_winner_rate_each_other_once_within_the_window() {
+  new_test_ext().execute_with(|| {
+    use support::traits::OnTimestampSet;
+    let _ = Balances::deposit_creating(&2, 1000);
+    assert_ok!(Auctions::set_fee_destination(Origin::ROOT, FeeDestination::FixedAccount(99)));
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    Auctions::do_start_auctions(&vec![0]);
+    assert_ok!(Auctions::participate_auction(Origin::signed(2), 0, 200, None, None, None));
+    assert_noop!(Auctions::rate_counterparty(Origin::signed(2), 0, 5), "Auction is not settled");
+
+    assert_ok!(Auctions::stop_auction(Origin::signed(1), 0));
+    Auctions::settle_queued_auctions();
+    assert_noop!(Auctions::rate_counterparty(Origin::signed(2), 0, 6), "Rating should be from 1 to 5 stars");
+    assert_noop!(Auctions::rate_counterparty(Origin::signed(3), 0, 5), "Only the seller or the winner can rate");
+    assert_ok!(Auctions::rate_counterparty(Origin::signed(2), 0, 5));
+    assert_noop!(Auctions::rate_counterparty(Origin::signed(2), 0, 4), "Already rated");
+    assert_eq!(Auctions::auction_rating(0, 2), Some(5));
+
+    // 超过评价期限后卖家不能再评价
+    Aura::on_timestamp_set(1001);
+    assert_noop!(Auctions::rate_counterparty(Origin::signed(1), 0, 4), "Rating window is closed");
+  });
+}
+
+#[test]
+fn comments_are_bounded_and_pruned_after_settlement() {
+  new_test_ext().execute_with(|| {
+    let _ = Balances::deposit_creating(&2, 1000);
+    let _ = Balances::deposit_creating(&1, 1000);
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_noop!(Auctions::post_comment(Origin::signed(2), 0, vec![], None), "Comment should not be empty");
+    assert_noop!(Auctions::post_comment(Origin::signed(2), 0, vec![b'a'; 257], None), "Comment too long");
+    assert_noop!(Auctions::post_comment(Origin::signed(2), 0, b"shipping?".to_vec(), Some(0)), "Replied comment does not exist");
+    assert_ok!(Auctions::post_comment(Origin::signed(2), 0, b"shipping?".to_vec(), None));
+    assert_ok!(Auctions::post_comment(Origin::signed(1), 0, b"worldwide".to_vec(), Some(0)));
+    assert_eq!(Auctions::comments(0).len(), 2);
+    assert_eq!(Balances::reserved_balance(&2), 5);
+
+    assert_noop!(Auctions::prune_comments(Origin::signed(3), 0), "Auction is not settled");
+    assert_ok!(Auctions::stop_auction(Origin::signed(1), 0));
+    assert_noop!(Auctions::post_comment(Origin::signed(2), 0, b"late".to_vec(), None), "Auction is already stopped");
+    Auctions::settle_queued_auctions();
+
+    // 清理后押金退还给发言人
+    assert_ok!(Auctions::prune_comments(Origin::signed(3), 0));
+    assert!(Auctions::comments(0).is_empty());
+    assert_eq!(Balances::reserved_balance(&2), 0);
+    assert_eq!(Balances::reserved_balance(&1), 0);
+  });
+}
+
+#[test]
+fn item_history_lists_past_auctions_and_outcomes() {
+  new_test_ext().execute_with(|| {
+    use super::AuctionOutcome;
+    let _ = Balances::deposit_creating(&2, 1000);
+    assert_ok!(Auctions::set_fee_destination(Origin::ROOT, FeeDestination::FixedAccount(99)));
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    Auctions::do_start_auctions(&vec![0]);
+    assert_ok!(Auctions::participate_auction(Origin::signed(2), 0, 200, None, None, None));
+    assert_ok!(Auctions::stop_auction(Origin::signed(1), 0));
+    Auctions::settle_queued_auctions();
+    assert_eq!(Auctions::item_auction_history(7), vec![(0, AuctionOutcome::Sold(2, 200))]);
+
+    // 再次拍卖流拍
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 1, 7));
+    assert_ok!(Auctions::stop_auction(Origin::signed(1), 1));
+    Auctions::settle_queued_auctions();
+    assert_eq!(
+      Auctions::item_auction_history(7),
+      vec![(0, AuctionOutcome::Sold(2, 200)), (1, AuctionOutcome::Unsold)]
+    );
+  });
+}
+
+#[test]
+fn relayer_creates_auctions_from_seller_signed_payloads() {
+  new_test_ext().execute_with(|| {
+    use super::CreationPayload;
+    use sr_primitives::testing::TestSignature;
+    let payload = CreationPayload {
+      seller: 1,
+      nonce: 0,
+      item: Some(7),
+      begin_price: 100,
+      minimum_step: 1,
+      upper_bound_price: None,
+      step_tiers: vec![],
+      title: vec![],
+      description_hash: None,
+      media_cid: None,
+    };
+    assert_noop!(
+      Auctions::create_auction_on_behalf(Origin::signed(5), payload.clone(), TestSignature(2, Auctions::signing_payload(&payload))),
+      "Invalid creation signature"
+    );
+    // 拍品已被占用时不创建拍卖，押金和序号都不变
+    CREATION_DEPOSIT.with(|v| *v.borrow_mut() = 10);
+    let _ = Balances::deposit_creating(&1, 100);
+    RESERVED_ITEMS.with(|v| v.borrow_mut().push(7));
+    assert_noop!(
+      Auctions::create_auction_on_behalf(Origin::signed(5), payload.clone(), TestSignature(1, Auctions::signing_payload(&payload))),
+      <&'static str>::from(Error::ItemFrozen)
+    );
+    assert_eq!(Balances::reserved_balance(&1), 0);
+    RESERVED_ITEMS.with(|v| v.borrow_mut().clear());
+    assert_ok!(Auctions::create_auction_on_behalf(Origin::signed(5), payload.clone(), TestSignature(1, Auctions::signing_payload(&payload))));
+    let auction = Auctions::auctions(0).unwrap();
+    assert_eq!(auction.owner, 1);
+    assert_eq!(auction.item, Some(7));
+    assert_eq!(Auctions::auction_items(7), Some(0));
+    assert_eq!(RESERVED_ITEMS.with(|v| v.borrow().clone()), vec![7]);
+    assert_eq!(Balances::reserved_balance(&1), 10);
+    assert_eq!(Auctions::creation_nonce(1), 1);
+
+    // 同一签名不能重放
+    assert_noop!(
+      Auctions::create_auction_on_behalf(Origin::signed(5), payload.clone(), TestSignature(1, Auctions::signing_payload(&payload))),
+      "Invalid creation nonce"
+    );
+  });
+}
+
+#[test]
+fn first_bids_are_sponsored_once_from_the_owner_budget() {
+  new_test_ext().execute_with(|| {
+    use super::{BidPayload, Sponsor};
+    use sr_primitives::testing::TestSignature;
+    let _ = Balances::deposit_creating(&1, 100);
+    let _ = Balances::deposit_creating(&2, 1000);
+    assert_ok!(Auctions::set_fee_destination(Origin::ROOT, FeeDestination::FixedAccount(99)));
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    Auctions::do_start_auctions(&vec![0]);
+
+    let bid = |price, nonce| {
+      let payload = BidPayload { bidder: 2, nonce: nonce, expires_at: 10, auction_id: 0, price: price, terms_hash: None, memo: None };
+      let signature = TestSignature(2, Auctions::signing_payload(&payload));
+      (payload, signature)
+    };
+    // 赞助出价需锁定不少于 MinSponsoredBid 的金额
+    let (payload, signature) = bid(99, 0);
+    assert_noop!(
+      Auctions::participate_auction_sponsored(Origin::NONE, payload, signature, Sponsor::Owner),
+      "Sponsored bid below the minimum"
+    );
+    let (payload, signature) = bid(200, 0);
+    assert_noop!(
+      Auctions::participate_auction_sponsored(Origin::NONE, payload.clone(), signature.clone(), Sponsor::Owner),
+      "Sponsor budget exhausted"
+    );
+    assert_noop!(
+      Auctions::participate_auction_sponsored(Origin::NONE, payload.clone(), signature.clone(), Sponsor::Pool),
+      "No sponsor pool"
+    );
+
+    assert_ok!(Auctions::fund_sponsorship(Origin::signed(1), 5));
+    assert_ok!(Auctions::participate_auction_sponsored(Origin::NONE, payload, signature, Sponsor::Owner));
+    assert_eq!(Auctions::auction_bids(0, 2), 200);
+    assert_eq!(Auctions::sponsor_budget(1), 2);
+    assert_eq!(Balances::free_balance(&99), 3);
+
+    // 每个账户只赞助一次
+    let (payload, signature) = bid(300, 1);
+    assert_noop!(
+      Auctions::participate_auction_sponsored(Origin::NONE, payload, signature, Sponsor::Owner),
+      "Bidder is already sponsored"
+    );
+    assert_ok!(Auctions::withdraw_sponsorship(Origin::signed(1), 2));
+    assert_eq!(Balances::reserved_balance(&1), 0);
+  });
+}
+
+#[test]
+fn sponsor_budget_is_charged_what_was_slashed() {
+  new_test_ext().execute_with(|| {
+    use super::{BidPayload, Sponsor};
+    use sr_primitives::testing::TestSignature;
+    use support::traits::ReservableCurrency;
+    let _ = Balances::deposit_creating(&1, 100);
+    let _ = Balances::deposit_creating(&2, 1000);
+    assert_ok!(Auctions::set_fee_destination(Origin::ROOT, FeeDestination::FixedAccount(99)));
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    Auctions::do_start_auctions(&vec![0]);
+    assert_ok!(Auctions::fund_sponsorship(Origin::signed(1), 5));
+    // 预算对应的reserve被其他模块释放了一部分
+    Balances::unreserve(&1, 4);
+
+    let payload = BidPayload { bidder: 2, nonce: 0, expires_at: 10, auction_id: 0, price: 200, terms_hash: None, memo: None };
+    let signature = TestSignature(2, Auctions::signing_payload(&payload));
+    assert_ok!(Auctions::participate_auction_sponsored(Origin::NONE, payload, signature, Sponsor::Owner));
+    assert_eq!(Balances::free_balance(&99), 1);
+    assert_eq!(Auctions::sponsor_budget(1), 4);
+  });
+}
+
+#[test]
+fn unsigned_bids_can_not_be_replayed() {
+  new_test_ext().execute_with(|| {
+    use super::BidPayload;
+    use sr_primitives::testing::TestSignature;
+    use sr_primitives::transaction_validity::InvalidTransaction;
+    use support::unsigned::ValidateUnsigned;
+    let _ = Balances::deposit_creating(&2, 1000);
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    Auctions::do_start_auctions(&vec![0]);
+    System::set_block_number(5);
+
+    let bid = |price, nonce, expires_at| {
+      let payload = BidPayload { bidder: 2, nonce: nonce, expires_at: expires_at, auction_id: 0, price: price, terms_hash: None, memo: None };
+      let signature = TestSignature(2, Auctions::signing_payload(&payload));
+      (payload, signature)
+    };
+    let (payload, signature) = bid(200, 0, 4);
+    let call = super::Call::participate_auction_unsigned(payload.clone(), signature.clone());
+    assert_eq!(Auctions::validate_unsigned(&call), InvalidTransaction::Stale.into());
+    assert_noop!(Auctions::participate_auction_unsigned(Origin::NONE, payload, signature), "Bid payload expired");
+
+    let (payload, signature) = bid(200, 1, 10);
+    let call = super::Call::participate_auction_unsigned(payload.clone(), signature.clone());
+    assert_eq!(Auctions::validate_unsigned(&call), InvalidTransaction::Future.into());
+    assert_noop!(Auctions::participate_auction_unsigned(Origin::NONE, payload, signature), "Invalid bid nonce");
+
+    // 交易在签名过期的区块之后失效
+    let (payload, signature) = bid(200, 0, 10);
+    let call = super::Call::participate_auction_unsigned(payload.clone(), signature.clone());
+    assert_eq!(Auctions::validate_unsigned(&call).map(|v| v.longevity), Ok(6));
+    assert_ok!(Auctions::participate_auction_unsigned(Origin::NONE, payload.clone(), signature.clone()));
+    assert_eq!(Auctions::bid_nonce(2), 1);
+
+    assert_eq!(Auctions::validate_unsigned(&call), InvalidTransaction::Stale.into());
+    assert_noop!(Auctions::participate_auction_unsigned(Origin::NONE, payload, signature), "Invalid bid nonce");
+    let (payload, signature) = bid(300, 1, 10);
+    assert_ok!(Auctions::participate_auction_unsigned(Origin::NONE, payload, signature));
+    assert_eq!(Auctions::auction_bids(0, 2), 300);
+  });
+}
+
+#[test]
+fn owners_cancel_and_stop_auctions_in_batches() {
+  new_test_ext().execute_with(|| {
+    use super::BatchWeight;
+    use sr_primitives::weights::WeighData;
+    let _ = Balances::deposit_creating(&2, 1000);
+    for _ in 0..3 {
+      assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    }
+    assert_ok!(Auctions::add_item(Origin::signed(1), 1, 7));
+    Auctions::do_start_auctions(&vec![0, 1, 2]);
+    assert_ok!(Auctions::participate_auction(Origin::signed(2), 1, 200, None, None, None));
+    assert_noop!(Auctions::stop_auctions_batch(Origin::signed(1), vec![0; 51]), "Too many auctions in a batch");
+    assert_noop!(Auctions::stop_auctions_batch(Origin::signed(1), vec![1, 2, 1]), "Duplicate auction in a batch");
+
+    // 有出价的拍卖不能取消，整批都被拒绝
+    assert_noop!(
+      Auctions::cancel_auctions_batch(Origin::signed(1), vec![0, 1]),
+      "Auction with bids can not be cancelled"
+    );
+    assert_eq!(Auctions::auctions(0).unwrap().status, AuctionStatus::Active);
+    assert_ok!(Auctions::cancel_auctions_batch(Origin::signed(1), vec![0]));
+    assert_eq!(Auctions::auctions(0).unwrap().status, AuctionStatus::Stopped);
+
+    // 不存在、他人的或已停拍的场次都会使整批被拒绝
+    assert_noop!(Auctions::stop_auctions_batch(Origin::signed(3), vec![1, 2]), "Only owner can call this fn.");
+    assert_noop!(Auctions::stop_auctions_batch(Origin::signed(1), vec![1, 9]), "Auction does not exist");
+    assert!(Auctions::stop_auctions_batch(Origin::signed(1), vec![0, 1]).is_err());
+    assert_eq!(Auctions::auctions(1).unwrap().status, AuctionStatus::Active);
+    assert_ok!(Auctions::stop_auctions_batch(Origin::signed(1), vec![1, 2]));
+    assert_eq!(Auctions::auctions(1).unwrap().status, AuctionStatus::Stopped);
+    assert_eq!(Auctions::auctions(2).unwrap().status, AuctionStatus::Stopped);
+    assert_eq!(Auctions::account_locks(2), 0);
+
+    assert_eq!(BatchWeight(10).weigh_data((&vec![1u32, 2, 3],)), 30);
+  });
+}
+
+#[test]
+fn owner_dashboard_gathers_auctions_proceeds_deposits_and_items() {
+  new_test_ext().execute_with(|| {
+    let _ = Balances::deposit_creating(&1, 1000);
+    let _ = Balances::deposit_creating(&2, 1000);
+    for _ in 0..3 {
+      assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    }
+    assert_ok!(Auctions::add_item(Origin::signed(1), 1, 7));
+    assert_ok!(Auctions::setup_claim_settlement(Origin::signed(1), 1, true));
+    Auctions::do_start_auctions(&vec![1, 2]);
+    assert_ok!(Auctions::pause_auction(Origin::signed(1), 2));
+    assert_ok!(Auctions::participate_auction(Origin::signed(2), 1, 200, None, None, None));
+    assert_ok!(Auctions::stop_auction(Origin::signed(1), 1));
+    assert_ok!(Auctions::post_comment(Origin::signed(1), 0, b"soon".to_vec(), None));
+    assert_ok!(Auctions::fund_sponsorship(Origin::signed(1), 20));
+
+    let dashboard = Auctions::owner_dashboard(&1);
+    assert_eq!(dashboard.pending, vec![0]);
+    assert!(dashboard.active.is_empty());
+    assert_eq!(dashboard.paused, vec![2]);
+    assert_eq!(dashboard.stopped, vec![1]);
+    // 等待中标者领取的成交款，拍品仍由拍卖占用
+    assert_eq!(dashboard.pending_proceeds, 200);
+    assert_eq!(dashboard.locked_items, vec![7]);
+    assert_eq!(dashboard.deposits_held, 25);
+    assert_eq!(Auctions::owner_dashboard(&2), Default::default());
+  });
+}
+
+#[test]
+fn owners_look_up_auctions_by_their_own_sequence_numbers() {
+  new_test_ext().execute_with(|| {
+    let _ = Balances::deposit_creating(&2, 1000);
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::create_auction(Origin::signed(2), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+
+    // 各拍卖者的序号独立从0开始
+    assert_eq!(Auctions::auction_by_owner_seq(1, 0), Some(0));
+    assert_eq!(Auctions::auction_by_owner_seq(1, 1), Some(2));
+    assert_eq!(Auctions::auction_by_owner_seq(2, 0), Some(1));
+    assert_eq!(Auctions::auction_by_owner_seq(2, 1), None);
+    assert_eq!(Auctions::owner_seq_of(2), Some(1));
+    assert_eq!(Auctions::owner_auction_count(1), 2);
+  });
+}
+
+#[test]
+fn jittered_auctions_close_after_a_random_offset() {
+  new_test_ext().execute_with(|| {
+    use sr_primitives::traits::OnInitialize;
+    use support::traits::OnTimestampSet;
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    assert_ok!(Auctions::setup_moments(Origin::signed(1), 0, Some(10), Some(20), None));
+    assert_ok!(Auctions::setup_close_jitter(Origin::signed(1), 0, true));
+    Auctions::do_start_auctions(&vec![0]);
+
+    // 到达stop_at前不抽取延迟
+    Auctions::on_initialize(1);
+    assert_eq!(Auctions::close_offset(0), None);
+
+    Aura::on_timestamp_set(25);
+    let auction = Auctions::auctions(0).unwrap();
+    assert!(!Auctions::is_due_to_stop(&auction, 25));
+    Auctions::on_initialize(3);
+    let offset = Auctions::close_offset(0).unwrap();
+    assert!(offset <= 100);
+    // 再次初始化不会重新抽取
+    Auctions::on_initialize(5);
+    assert_eq!(Auctions::close_offset(0), Some(offset));
+    assert!(!Auctions::is_due_to_stop(&auction, 20 + offset));
+    assert!(Auctions::is_due_to_stop(&auction, 21 + offset));
+  });
+}
+
+#[test]
+fn close_offsets_are_drawn_from_a_rotating_cursor() {
+  new_test_ext().execute_with(|| {
+    use support::{StorageValue, traits::OnTimestampSet};
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    assert_ok!(Auctions::setup_moments(Origin::signed(1), 0, Some(10), Some(20), None));
+    assert_ok!(Auctions::setup_close_jitter(Origin::signed(1), 0, true));
+    Auctions::do_start_auctions(&vec![0]);
+    let active: Vec<u32> = (100..170).chain(Some(0)).collect();
+    <super::ActiveAuctions<Test>>::put(active);
+    Aura::on_timestamp_set(25);
+
+    // 每个区块最多检查64场，其余的留到之后的区块
+    Auctions::draw_close_offsets();
+    assert_eq!(Auctions::close_offset(0), None);
+    assert_eq!(Auctions::close_jitter_cursor(), 64);
+    Auctions::draw_close_offsets();
+    assert!(Auctions::close_offset(0).is_some());
+    assert_eq!(Auctions::close_jitter_cursor(), 57);
+  });
+}
+
+#[test]
+fn bids_per_block_are_limited_per_account() {
+  new_test_ext().execute_with(|| {
+    MAX_BIDS_PER_BLOCK.with(|v| *v.borrow_mut() = 2);
+    let _ = Balances::deposit_creating(&2, 1000);
+    let _ = Balances::deposit_creating(&3, 1000);
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    Auctions::do_start_auctions(&vec![0]);
+
+    assert_ok!(Auctions::participate_auction(Origin::signed(2), 0, 110, None, None, None));
+    assert_ok!(Auctions::participate_auction(Origin::signed(2), 0, 120, None, None, None));
+    assert_noop!(
+      Auctions::participate_auction(Origin::signed(2), 0, 130, None, None, None),
+      "Too many bids in this block"
+    );
+    // 其他账户不受影响
+    assert_ok!(Auctions::participate_auction(Origin::signed(3), 0, 130, None, None, None));
+    assert_eq!(Auctions::bids_in_block(&0, &2), 2);
+
+    // 下一个区块重新计数
+    System::set_block_number(2);
+    assert_eq!(Auctions::bids_in_block(&0, &2), 0);
+    assert_ok!(Auctions::participate_auction(Origin::signed(2), 0, 140, None, None, None));
+  });
+}
+
+#[test]
+fn hot_auctions_take_limited_bids_per_block() {
+  new_test_ext().execute_with(|| {
+    MAX_AUCTION_BIDS_PER_BLOCK.with(|v| *v.borrow_mut() = 2);
+    let _ = Balances::deposit_creating(&2, 1000);
+    let _ = Balances::deposit_creating(&3, 1000);
+    let _ = Balances::deposit_creating(&4, 1000);
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    Auctions::do_start_auctions(&vec![0]);
+
+    assert_ok!(Auctions::participate_auction(Origin::signed(2), 0, 110, None, None, None));
+    assert_ok!(Auctions::participate_auction(Origin::signed(3), 0, 120, None, None, None));
+    assert!(Auctions::is_auction_saturated(&0));
+    assert_noop!(
+      Auctions::participate_auction(Origin::signed(4), 0, 130, None, None, None),
+      "Auction is saturated in this block"
+    );
+
+    // 下一个区块恢复出价
+    System::set_block_number(2);
+    assert!(!Auctions::is_auction_saturated(&0));
+    assert_ok!(Auctions::participate_auction(Origin::signed(4), 0, 130, None, None, None));
+  });
+}
+
+#[test]
+fn auctions_expire_after_max_lifetime_even_when_paused() {
+  new_test_ext().execute_with(|| {
+    use support::traits::OnTimestampSet;
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    assert_ok!(Auctions::setup_moments(Origin::signed(1), 0, Some(6000), Some(7000), None));
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 1, 8));
+    Auctions::do_start_auctions(&vec![1]);
+    assert_ok!(Auctions::pause_auction(Origin::signed(1), 1));
+    assert_eq!(Auctions::auction_created_at(1), Some(0));
+
+    Aura::on_timestamp_set(5001);
+    let pending = Auctions::auctions(0).unwrap();
+    let paused = Auctions::auctions(1).unwrap();
+    assert!(Auctions::is_expired(&pending, 5001));
+    assert!(!Auctions::is_due_to_start(&pending, 6001));
+    assert!(Auctions::is_due_to_stop(&paused, 5001));
+    assert_eq!(Auctions::due_auction_ids(), vec![0, 1]);
+
+    // 未开拍的被取消，已开拍（含暂停）的停拍结算
+    Auctions::do_process_due_auctions(&vec![0, 1]);
+    assert_eq!(Auctions::auctions(0).unwrap().status, AuctionStatus::Stopped);
+    assert_eq!(Auctions::auction_items(7), None);
+    assert_eq!(Auctions::auctions(1).unwrap().status, AuctionStatus::Stopped);
+    assert_eq!(Auctions::settlement_queue(), vec![1]);
+  });
+}
+
+#[test]
+fn frozen_items_can_not_be_listed_or_started() {
+  new_test_ext().execute_with(|| {
+    FROZEN_ITEMS.with(|v| *v.borrow_mut() = vec![8]);
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_noop!(Auctions::add_item(Origin::signed(1), 0, 8), <&'static str>::from(Error::ItemFrozen));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    assert_ok!(Auctions::setup_moments(Origin::signed(1), 0, Some(10), Some(20), None));
+
+    // 上架后被冻结的拍品不会开拍
+    FROZEN_ITEMS.with(|v| *v.borrow_mut() = vec![7]);
+    let auction = Auctions::auctions(0).unwrap();
+    assert!(!Auctions::is_due_to_start(&auction, 11));
+    Auctions::do_start_auctions(&vec![0]);
+    assert_eq!(Auctions::auctions(0).unwrap().status, AuctionStatus::PendingStart);
+
+    FROZEN_ITEMS.with(|v| v.borrow_mut().clear());
+    assert!(Auctions::is_due_to_start(&auction, 11));
+    Auctions::do_start_auctions(&vec![0]);
+    assert_eq!(Auctions::auctions(0).unwrap().status, AuctionStatus::Active);
+  });
+}
+
+#[test]
+fn listed_items_are_held_until_released() {
+  new_test_ext().execute_with(|| {
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    assert_eq!(RESERVED_ITEMS.with(|v| v.borrow().clone()), vec![7]);
+
+    // 替换拍品时释放旧拍品
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 8));
+    assert_eq!(RESERVED_ITEMS.with(|v| v.borrow().clone()), vec![8]);
+
+    // 已被其他模块占用的拍品不能上架
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    RESERVED_ITEMS.with(|v| v.borrow_mut().push(9));
+    assert_noop!(Auctions::add_item(Origin::signed(1), 1, 9), <&'static str>::from(Error::ItemFrozen));
+    assert_noop!(
+      Auctions::add_lot_items(Origin::signed(1), 1, vec![7, 9], LotPricing::Uniform),
+      <&'static str>::from(Error::ItemFrozen)
+    );
+    assert_eq!(RESERVED_ITEMS.with(|v| v.borrow().clone()), vec![8, 9]);
+
+    assert_ok!(Auctions::stop_auction(Origin::signed(1), 0));
+    assert_eq!(RESERVED_ITEMS.with(|v| v.borrow().clone()), vec![9]);
+  });
+}
+
+#[test]
+fn failed_settlement_transfers_report_the_reason() {
+  new_test_ext().execute_with(|| {
+    let _ = Balances::deposit_creating(&2, 1000);
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    Auctions::do_start_auctions(&vec![0]);
+    assert_ok!(Auctions::participate_auction(Origin::signed(2), 0, 200, None, None, None));
+
+    // 开拍后拍品被冻结，结算时给出具体原因
+    FROZEN_ITEMS.with(|v| *v.borrow_mut() = vec![7]);
+    assert_eq!(Auctions::stop_auction(Origin::signed(1), 0), Err(<&'static str>::from(Error::ItemFrozen)));
+  });
+}
+
+#[test]
+fn auction_houses_are_independent_instances() {
+  new_test_ext().execute_with(|| {
+    use super::BidPayload;
+    use sr_primitives::testing::TestSignature;
+    let _ = Balances::deposit_creating(&1, 100);
+    let _ = Balances::deposit_creating(&2, 1000);
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(CommunityAuctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    // 两个拍卖行各自编号，押金按各自的配置预留
+    assert_eq!(Auctions::auctions(0).unwrap().owner, 1);
+    assert_eq!(CommunityAuctions::auctions(0).unwrap().owner, 1);
+    assert_eq!(Balances::reserved_balance(&1), 20);
+    assert_ne!(Auctions::escrow_account(0), CommunityAuctions::escrow_account(0));
+
+    // 已在一个拍卖行上架的物品被占用，不能再在另一个拍卖行上架
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    assert_noop!(CommunityAuctions::add_item(Origin::signed(1), 0, 7), <&'static str>::from(Error::ItemFrozen));
+    assert_ok!(CommunityAuctions::add_item(Origin::signed(1), 0, 8));
+
+    Auctions::do_start_auctions(&vec![0]);
+    CommunityAuctions::do_start_auctions(&vec![0]);
+    assert_ok!(Auctions::participate_auction(Origin::signed(2), 0, 200, None, None, None));
+    assert_ok!(CommunityAuctions::participate_auction(Origin::signed(2), 0, 300, None, None, None));
+    assert_eq!(Auctions::auction_bids(0, 2), 200);
+    assert_eq!(CommunityAuctions::auction_bids(0, 2), 300);
+    let locks: Vec<_> = Balances::locks(&2).into_iter().map(|l| (l.id, l.amount)).collect();
+    assert_eq!(locks, vec![(BidLockId::get(), 200), (CommunityBidLockId::get(), 300)]);
+
+    // 为一个拍卖行签名的出价不能在另一个拍卖行重放
+    let payload = BidPayload { bidder: 2, nonce: 0, expires_at: 10, auction_id: 0, price: 400, terms_hash: None, memo: None };
+    let signature = TestSignature(2, Auctions::signing_payload(&payload));
+    assert_noop!(
+      CommunityAuctions::participate_auction_unsigned(Origin::NONE, payload.clone(), signature.clone()),
+      "Invalid bid signature"
+    );
+    assert_ok!(Auctions::participate_auction_unsigned(Origin::NONE, payload, signature));
+    assert_eq!(Auctions::auction_bids(0, 2), 400);
+    assert_eq!(CommunityAuctions::auction_bids(0, 2), 300);
+  });
+}
+
+#[test]
+fn new_ids_are_added_to_the_status_indexes() {
+  new_test_ext().execute_with(|| {
+    use super::StoreVecs;
+    Auctions::add_all_to_set(StoreVecs::PendingVec, &vec![0, 1]);
+    Auctions::add_all_to_set(StoreVecs::PendingVec, &vec![1, 2]);
+    // 已在集合中的id不重复添加
+    assert_eq!(Auctions::pending_auctions(), vec![0, 1, 2]);
+    Auctions::remove_all_from_set(StoreVecs::PendingVec, &vec![1]);
+    assert_eq!(Auctions::pending_auctions(), vec![0, 2]);
+
+    Auctions::add_all_to_set(StoreVecs::ActiveVec, &vec![1]);
+    assert_eq!(Auctions::active_auctions(), vec![1]);
+  });
+}
+
 #[test]
 fn it_works_for_tmp() {
   new_test_ext().execute_with(|| {
     next_block();
   });
-}
\ No newline at end of file
+}
+#[test]
+fn due_auction_batches_mix_starts_and_stops() {
+  new_test_ext().execute_with(|| {
+    use sr_primitives::traits::OnInitialize;
+    use support::traits::OnTimestampSet;
+    use super::validity;
+    OFFCHAIN_TIMING.with(|v| *v.borrow_mut() = false);
+    for item_id in 0..3 {
+      assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+      assert_ok!(Auctions::add_item(Origin::signed(1), item_id, item_id + 10));
+    }
+    // 0到期开拍，1到期停拍，2尚未到期
+    assert_ok!(Auctions::setup_moments(Origin::signed(1), 0, Some(10), Some(100), None));
+    assert_ok!(Auctions::setup_moments(Origin::signed(1), 1, Some(10), Some(20), None));
+    assert_ok!(Auctions::setup_moments(Origin::signed(1), 2, Some(1000), Some(2000), None));
+    Auctions::do_start_auctions(&vec![1]);
+    Aura::on_timestamp_set(50);
+
+    assert_eq!(Auctions::due_auction_ids(), vec![0, 1]);
+    assert_eq!(Auctions::is_batch_due(&vec![0, 1]), Ok(()));
+    assert_eq!(Auctions::is_batch_due(&vec![]), Err(validity::NOT_DUE));
+    assert_eq!(Auctions::is_batch_due(&vec![0, 2]), Err(validity::NOT_DUE));
+    assert_eq!(Auctions::is_batch_due(&vec![0; 11]), Err(validity::BATCH_TOO_LARGE));
+
+    // 同一批次中开拍与停拍一起处理，未到期的被跳过
+    Auctions::do_process_due_auctions(&vec![0, 1, 2]);
+    assert_eq!(Auctions::auctions(0).unwrap().status, AuctionStatus::Active);
+    assert_eq!(Auctions::auctions(1).unwrap().status, AuctionStatus::Stopped);
+    assert_eq!(Auctions::auctions(2).unwrap().status, AuctionStatus::PendingStart);
+    assert_eq!(Auctions::is_batch_due(&vec![1]), Err(validity::ALREADY_STOPPED));
+
+    // 批次大小受上限约束
+    let signature: super::SignatureOf<Test> = codec::Decode::decode(&mut &[0u8; 64][..]).unwrap();
+    assert_noop!(Auctions::process_due_auctions(Origin::NONE, vec![0; 11], signature.clone()), "Too many auctions in a batch");
+    assert_noop!(Auctions::process_due_auctions(Origin::NONE, vec![], signature.clone()), "No auctions to process");
+    assert_noop!(Auctions::process_due_auctions(Origin::NONE, vec![2], signature), "Non existent public key.");
+
+    Aura::on_timestamp_set(1001);
+    Auctions::on_initialize(2);
+    assert_eq!(Auctions::auctions(2).unwrap().status, AuctionStatus::Active);
+  });
+}
+
+#[test]
+fn due_auctions_on_chain_are_capped_per_block() {
+  new_test_ext().execute_with(|| {
+    use sr_primitives::traits::OnInitialize;
+    use support::traits::OnTimestampSet;
+    OFFCHAIN_TIMING.with(|v| *v.borrow_mut() = false);
+    let batch_size = <Test as super::Trait>::MaxDueBatchSize::get();
+    for auction_id in 0..batch_size + 2 {
+      assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+      assert_ok!(Auctions::setup_moments(Origin::signed(1), auction_id, Some(10), Some(100), None));
+    }
+    Aura::on_timestamp_set(50);
+
+    // 每个区块最多处理一批，剩余的留到下个区块
+    Auctions::on_initialize(1);
+    assert_eq!(Auctions::active_auctions().len() as u32, batch_size);
+    assert_eq!(Auctions::due_auction_ids(), vec![batch_size, batch_size + 1]);
+    Auctions::on_initialize(2);
+    assert_eq!(Auctions::active_auctions().len() as u32, batch_size + 2);
+    assert!(Auctions::due_auction_ids().is_empty());
+  });
+}
+
+#[test]
+fn failed_claims_can_be_retried() {
+  new_test_ext().execute_with(|| {
+    let _ = Balances::deposit_creating(&2, 1000);
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    assert_ok!(Auctions::setup_claim_settlement(Origin::signed(1), 0, true));
+    Auctions::do_start_auctions(&vec![0]);
+    assert_ok!(Auctions::participate_auction(Origin::signed(2), 0, 200, None, None, None));
+    assert_ok!(Auctions::stop_auction(Origin::signed(1), 0));
+    assert_eq!(Auctions::pending_claim(0).map(|(winner, _)| winner), Some(2));
+
+    // 领取失败时不解锁出价，可以重试
+    FROZEN_ITEMS.with(|v| *v.borrow_mut() = vec![7]);
+    assert_noop!(Auctions::claim_item(Origin::signed(2), 0), <&'static str>::from(Error::ItemFrozen));
+    assert_eq!(Auctions::account_locks(2), 200);
+    assert!(!Auctions::bid_unlocked(0, 2));
+
+    FROZEN_ITEMS.with(|v| v.borrow_mut().clear());
+    assert_ok!(Auctions::claim_item(Origin::signed(2), 0));
+    assert_eq!(Auctions::pending_claim(0), None);
+    assert_eq!(Auctions::account_locks(2), 0);
+    assert_eq!(Balances::free_balance(&2), 800);
+    assert_eq!(Balances::free_balance(&1), 190);
+    assert_noop!(Auctions::claim_item(Origin::signed(2), 0), "No pending claim for the auction");
+  });
+}
+
+#[test]
+fn bids_are_unlocked_only_once() {
+  new_test_ext().execute_with(|| {
+    let _ = Balances::deposit_creating(&2, 1000);
+    for item_id in 0..2 {
+      assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+      assert_ok!(Auctions::add_item(Origin::signed(1), item_id, item_id + 7));
+    }
+    Auctions::do_start_auctions(&vec![0, 1]);
+    assert_ok!(Auctions::participate_auction(Origin::signed(2), 0, 200, None, None, None));
+    assert_ok!(Auctions::participate_auction(Origin::signed(2), 1, 150, None, None, None));
+    assert_eq!(Auctions::account_locks(2), 350);
+
+    // 重复解锁同一出价不影响其他场次的锁仓
+    assert_ok!(Auctions::do_unlock_balance(&0, &2));
+    assert_ok!(Auctions::do_unlock_balance(&0, &2));
+    assert!(Auctions::bid_unlocked(0, 2));
+    assert_eq!(Auctions::account_locks(2), 150);
+    assert_eq!(Auctions::auction_bids(0, 2), 200);
+  });
+}
+
+#[test]
+fn losers_of_crowded_auctions_claim_their_refunds() {
+  new_test_ext().execute_with(|| {
+    use support::traits::OnTimestampSet;
+    MAX_AUTO_REFUNDS.with(|v| *v.borrow_mut() = 1);
+    for bidder in 2..5 {
+      let _ = Balances::deposit_creating(&bidder, 1000);
+    }
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    Auctions::do_start_auctions(&vec![0]);
+    assert_ok!(Auctions::participate_auction(Origin::signed(2), 0, 200, None, None, None));
+    assert_ok!(Auctions::participate_auction(Origin::signed(3), 0, 300, None, None, None));
+    assert_ok!(Auctions::participate_auction(Origin::signed(4), 0, 400, None, None, None));
+    assert_ok!(Auctions::stop_auction(Origin::signed(1), 0));
+
+    // 落选者的出价在结算时不解锁
+    assert_eq!(Auctions::pull_refund_deadline(0), Some(1000));
+    assert_eq!(Auctions::account_locks(2), 200);
+    assert_eq!(Auctions::account_locks(3), 300);
+    assert_eq!(Auctions::account_locks(4), 0);
+    assert_eq!(Balances::free_balance(&4), 600);
+
+    assert_noop!(Auctions::claim_refund(Origin::signed(4), 0), "Refund already claimed");
+    assert_noop!(Auctions::claim_refund(Origin::signed(5), 0), "No bid to refund");
+    assert_ok!(Auctions::claim_refund(Origin::signed(2), 0));
+    assert_eq!(Auctions::account_locks(2), 0);
+    assert_noop!(Auctions::claim_refund(Origin::signed(2), 0), "Refund already claimed");
+
+    // 到期前不清扫
+    Auctions::sweep_refunds();
+    assert_eq!(Auctions::account_locks(3), 300);
+
+    // 到期后每个区块最多清扫MaxSettlementsPerBlock个账户
+    Aura::on_timestamp_set(1001);
+    Auctions::sweep_refunds();
+    assert_eq!(Auctions::account_locks(3), 0);
+    assert_eq!(Auctions::refund_sweeps(), vec![(0, 1000, 2)]);
+    Auctions::sweep_refunds();
+    assert!(Auctions::refund_sweeps().is_empty());
+    assert_eq!(Auctions::pull_refund_deadline(0), None);
+    assert!(!Auctions::refund_claimed(0, 2));
+    assert_noop!(Auctions::claim_refund(Origin::signed(3), 0), "Auction has no refunds to claim");
+  });
+}
+
+#[test]
+fn stale_auctions_are_purged_for_a_bounty() {
+  new_test_ext().execute_with(|| {
+    use support::traits::OnTimestampSet;
+    CREATION_DEPOSIT.with(|v| *v.borrow_mut() = 50);
+    let _ = Balances::deposit_creating(&1, 1000);
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_ok!(Auctions::add_item(Origin::signed(1), 0, 7));
+    assert_eq!(Balances::reserved_balance(&1), 50);
+    assert_eq!(Auctions::creation_deposit(0), Some((50, 0)));
+
+    assert_noop!(Auctions::purge_stale_auction(Origin::signed(3), 0), "Auction is not stale");
+    Aura::on_timestamp_set(1001);
+    assert!(Auctions::is_stale(&Auctions::auctions(0).unwrap(), 1001));
+
+    // 清理者获得赏金，其余押金返还创建者
+    assert_ok!(Auctions::purge_stale_auction(Origin::signed(3), 0));
+    assert_eq!(Auctions::auctions(0).unwrap().status, AuctionStatus::Stopped);
+    assert_eq!(Auctions::auction_items(7), None);
+    assert_eq!(Auctions::creation_deposit(0), None);
+    assert_eq!(Balances::free_balance(&3), 5);
+    assert_eq!(Balances::reserved_balance(&1), 0);
+    assert_eq!(Balances::free_balance(&1), 995);
+  });
+}
+
+#[test]
+fn stale_auctions_without_a_deposit_are_purged() {
+  new_test_ext().execute_with(|| {
+    use support::traits::OnTimestampSet;
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    <super::CreationDeposits<Test>>::remove(0);
+
+    Aura::on_timestamp_set(1001);
+    assert!(Auctions::is_stale(&Auctions::auctions(0).unwrap(), 1001));
+    assert_ok!(Auctions::purge_stale_auction(Origin::signed(3), 0));
+    assert_eq!(Auctions::auctions(0).unwrap().status, AuctionStatus::Stopped);
+    assert!(Auctions::pending_auctions().is_empty());
+  });
+}
+
+#[test]
+fn legacy_storage_is_migrated_in_batches() {
+  new_test_ext().execute_with(|| {
+    use super::{migration, Auctions as AuctionStore, NextAuctionId, PalletStorageVersion, DefaultInstance};
+    use support::{StorageValue, storage::unhashed};
+    let batch = migration::MIGRATION_BATCH;
+    // 升级前的布局：(id, item, owner, start_at, stop_at, wait_period, begin_price, upper_bound_price, minimum_step, latest_participate, status)
+    for id in 0..batch + 1 {
+      let old = (id, Some(id + 100), 1u64, Some(10u64), Some(20u64), None::<u64>, 100u64, None::<u64>, 5u64, Some((2u64, 15u64)), AuctionStatus::Active);
+      unhashed::put_raw(&<AuctionStore<Test>>::hashed_key_for(id), &old.encode());
+      <AuctionItems<Test>>::insert(id + 100, id);
+    }
+    <NextAuctionId<Test>>::put(batch + 1);
+    <AuctionParticipants<Test>>::insert(0, vec![2]);
+    unhashed::put(&<BidPlacedAt<Test>>::hashed_key_for(0, 2), &15u64);
+    <PalletStorageVersion<DefaultInstance>>::put(0);
+
+    assert!(Auctions::is_migrating());
+    assert_noop!(
+      Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None),
+      "Auction storage is migrating"
+    );
+
+    // 每个区块最多迁移MIGRATION_BATCH个场次
+    assert!(migration::perform_migrations::<Test, DefaultInstance>());
+    assert_eq!((Auctions::storage_version(), Auctions::migration_cursor()), (0, batch));
+    assert!(migration::perform_migrations::<Test, DefaultInstance>());
+    assert_eq!((Auctions::storage_version(), Auctions::migration_cursor()), (1, 0));
+
+    let auction = Auctions::auctions(0).unwrap();
+    assert_eq!((auction.owner, auction.item, auction.begin_price, auction.minimum_step), (1, Some(100), 100, 5));
+    assert_eq!((auction.start_at, auction.stop_at, auction.latest_participate), (Some(10), Some(20), Some((2, 15))));
+    assert_eq!(auction.status, AuctionStatus::Active);
+    assert!(auction.title.is_empty() && auction.lot.is_empty() && !auction.claim_settlement);
+    assert_eq!(Auctions::auctions(batch).map(|a| a.item), Some(Some(batch + 100)));
+    assert_eq!(Auctions::auction_by_index(batch), Some(batch));
+    assert_eq!(Auctions::auction_by_owner_seq(1, 0), Some(0));
+    assert_eq!(Auctions::auction_items(100), Some(0));
+    assert_eq!(Auctions::auction_created_at(0), Some(0));
+
+    assert!(migration::perform_migrations::<Test, DefaultInstance>());
+    assert!(migration::perform_migrations::<Test, DefaultInstance>());
+    assert_eq!(Auctions::storage_version(), migration::CURRENT_VERSION);
+    assert!(!migration::perform_migrations::<Test, DefaultInstance>());
+    assert_eq!(Auctions::bid_placed_at(0, 2), (15, 0));
+    assert!(RESERVED_ITEMS.with(|v| v.borrow().contains(&(batch + 100))));
+
+    assert_ok!(Auctions::create_auction(Origin::signed(1), 100, 1, None, vec![], vec![], None, None));
+    assert_eq!(Auctions::auction_by_index(batch + 1), Some(batch + 1));
+  });
+}
@@ -0,0 +1,231 @@
+//! Soulbound achievement badges, awarded by hooks of the market and kitties modules.
+//!
+//! Badges can not be transferred and are awarded at most once per account.
+
+use support::{decl_module, decl_storage, decl_event};
+use support::traits::{Get, Currency};
+use codec::{Encode, Decode};
+use rstd::prelude::*;
+use crate::traits::{OnItemSold, OnBidPlaced, OnKittyBred};
+
+pub trait Trait: system::Trait {
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+	/// Currency the items are paid with
+	type Currency: Currency<Self::AccountId>;
+	/// Number of bids for the `ActiveBidder` badge
+	type BidsForBadge: Get<u32>;
+}
+
+pub type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+
+#[derive(Encode, Decode, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum Badge {
+	/// Sold the first item
+	FirstSale,
+	/// Bought the first item
+	FirstPurchase,
+	/// Placed `BidsForBadge` bids in auctions
+	ActiveBidder,
+	/// Sold an item at the highest price ever
+	RecordPrice,
+	/// Bred the first kitty
+	FirstBreed,
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as Badges {
+		/// Badges of an account with the block they were awarded at
+		pub AccountBadges get(fn badges_of): map T::AccountId => Vec<(Badge, T::BlockNumber)>;
+		/// Number of auction bids placed by an account
+		pub BidCounts get(fn bid_count): map T::AccountId => u32;
+		/// Highest price an item was ever sold at
+		pub RecordSalePrice get(fn record_sale_price): Option<BalanceOf<T>>;
+	}
+}
+
+decl_event!(
+	pub enum Event<T> where
+		<T as system::Trait>::AccountId,
+	{
+		/// A badge is awarded to an account. (account, badge)
+		BadgeAwarded(AccountId, Badge),
+	}
+);
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		fn deposit_event() = default;
+	}
+}
+
+impl<T: Trait> Module<T> {
+	pub fn has_badge(who: &T::AccountId, badge: Badge) -> bool {
+		Self::badges_of(who).iter().any(|(b, _)| *b == badge)
+	}
+
+	/// Award the badge to the account, unless it already has one
+	fn award(who: &T::AccountId, badge: Badge) {
+		if Self::has_badge(who, badge) {
+			return;
+		}
+		let now = <system::Module<T>>::block_number();
+		<AccountBadges<T>>::mutate(who, |badges| badges.push((badge, now)));
+		Self::deposit_event(RawEvent::BadgeAwarded(who.clone(), badge));
+	}
+}
+
+impl<T: Trait, ItemId> OnItemSold<T::AccountId, ItemId, BalanceOf<T>> for Module<T> {
+	fn on_item_sold(seller: &T::AccountId, buyer: &T::AccountId, _item_id: ItemId, price: BalanceOf<T>) {
+		Self::award(seller, Badge::FirstSale);
+		Self::award(buyer, Badge::FirstPurchase);
+
+		if Self::record_sale_price().map_or(true, |record| price > record) {
+			<RecordSalePrice<T>>::put(price);
+			Self::award(seller, Badge::RecordPrice);
+		}
+	}
+}
+
+impl<T: Trait> OnBidPlaced<T::AccountId, BalanceOf<T>> for Module<T> {
+	fn on_bid_placed(bidder: &T::AccountId, _price: BalanceOf<T>) {
+		let count = Self::bid_count(bidder).saturating_add(1);
+		<BidCounts<T>>::insert(bidder, count);
+		if count >= T::BidsForBadge::get() {
+			Self::award(bidder, Badge::ActiveBidder);
+		}
+	}
+}
+
+impl<T: Trait, KittyIndex> OnKittyBred<T::AccountId, KittyIndex> for Module<T> {
+	fn on_kitty_bred(owner: &T::AccountId, _kitty_id: KittyIndex) {
+		Self::award(owner, Badge::FirstBreed);
+	}
+}
+
+/// Runtime api of this module
+pub mod api {
+	use codec::Codec;
+	use rstd::vec::Vec;
+
+	client::decl_runtime_apis! {
+		/// The api to query badges of accounts.
+		pub trait BadgesApi<AccountId, Badge, BlockNumber> where
+			AccountId: Codec,
+			Badge: Codec,
+			BlockNumber: Codec,
+		{
+			/// Badges of the account with the block they were awarded at.
+			fn badges_of(who: AccountId) -> Vec<(Badge, BlockNumber)>;
+		}
+	}
+}
+
+/// Tests for Badges module
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use primitives::H256;
+	use support::{impl_outer_origin, parameter_types};
+	use sr_primitives::{traits::{BlakeTwo256, IdentityLookup}, testing::Header};
+	use sr_primitives::weights::Weight;
+	use sr_primitives::Perbill;
+
+	impl_outer_origin! {
+		pub enum Origin for Test {}
+	}
+
+	#[derive(Clone, Eq, PartialEq, Debug)]
+	pub struct Test;
+	parameter_types! {
+		pub const BlockHashCount: u64 = 250;
+		pub const MaximumBlockWeight: Weight = 1024;
+		pub const MaximumBlockLength: u32 = 2 * 1024;
+		pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+	}
+	impl system::Trait for Test {
+		type Origin = Origin;
+		type Call = ();
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type Event = ();
+		type BlockHashCount = BlockHashCount;
+		type MaximumBlockWeight = MaximumBlockWeight;
+		type MaximumBlockLength = MaximumBlockLength;
+		type AvailableBlockRatio = AvailableBlockRatio;
+		type Version = ();
+	}
+	parameter_types! {
+		pub const ExistentialDeposit: u64 = 0;
+		pub const TransferFee: u64 = 0;
+		pub const CreationFee: u64 = 0;
+		pub const BidsForBadge: u32 = 3;
+	}
+	impl balances::Trait for Test {
+		type Balance = u64;
+		type OnFreeBalanceZero = ();
+		type OnNewAccount = ();
+		type Event = ();
+		type TransferPayment = ();
+		type DustRemoval = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type TransferFee = TransferFee;
+		type CreationFee = CreationFee;
+	}
+	impl Trait for Test {
+		type Event = ();
+		type Currency = balances::Module<Test>;
+		type BidsForBadge = BidsForBadge;
+	}
+	type Badges = Module<Test>;
+	type System = system::Module<Test>;
+
+	fn new_test_ext() -> runtime_io::TestExternalities {
+		system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
+	}
+
+	#[test]
+	fn sales_award_badges_once() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			<Badges as OnItemSold<u64, u32, u64>>::on_item_sold(&1, &2, 0, 100);
+			assert_eq!(Badges::badges_of(&1), vec![(Badge::FirstSale, 1), (Badge::RecordPrice, 1)]);
+			assert_eq!(Badges::badges_of(&2), vec![(Badge::FirstPurchase, 1)]);
+
+			// 未超过最高成交价不再授予
+			System::set_block_number(2);
+			<Badges as OnItemSold<u64, u32, u64>>::on_item_sold(&3, &2, 1, 100);
+			assert_eq!(Badges::badges_of(&3), vec![(Badge::FirstSale, 2)]);
+			assert_eq!(Badges::badges_of(&2), vec![(Badge::FirstPurchase, 1)]);
+
+			<Badges as OnItemSold<u64, u32, u64>>::on_item_sold(&1, &3, 2, 101);
+			assert_eq!(Badges::record_sale_price(), Some(101));
+			assert_eq!(Badges::badges_of(&1), vec![(Badge::FirstSale, 1), (Badge::RecordPrice, 1)]);
+			assert!(Badges::has_badge(&3, Badge::FirstPurchase));
+		});
+	}
+
+	#[test]
+	fn bids_and_breeding_award_badges() {
+		new_test_ext().execute_with(|| {
+			for _ in 0..2 {
+				<Badges as OnBidPlaced<u64, u64>>::on_bid_placed(&1, 10);
+			}
+			assert!(!Badges::has_badge(&1, Badge::ActiveBidder));
+			<Badges as OnBidPlaced<u64, u64>>::on_bid_placed(&1, 10);
+			assert!(Badges::has_badge(&1, Badge::ActiveBidder));
+			<Badges as OnBidPlaced<u64, u64>>::on_bid_placed(&1, 10);
+			assert_eq!(Badges::bid_count(&1), 4);
+			assert_eq!(Badges::badges_of(&1).len(), 1);
+
+			<Badges as OnKittyBred<u64, u32>>::on_kitty_bred(&2, 5);
+			assert_eq!(Badges::badges_of(&2), vec![(Badge::FirstBreed, 0)]);
+		});
+	}
+}
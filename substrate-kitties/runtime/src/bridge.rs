@@ -0,0 +1,254 @@
+//! Bridge kitties to an Ethereum ERC-721 contract.
+//!
+//! Bridging out locks the kitty on this chain and asks the relayers to mint a
+//! mirrored token on Ethereum. When the mirrored token is burned on Ethereum, the
+//! relayers attest the burn and the kitty is released to the given account once
+//! `RelayerThreshold` of the current relayers attested the same burn. Bridged out
+//! kitties are locked, so they can not be auctioned.
+//!
+//! A burn is bound to the Ethereum transaction and to the nonce of the bridge
+//! out request, so it can neither be relayed twice nor release the kitty of a
+//! later bridge out.
+
+use support::{decl_module, decl_storage, decl_event, ensure, Parameter};
+use support::traits::Get;
+use sr_primitives::traits::{Member, EnsureOrigin, Hash};
+use primitives::{H160, H256};
+use codec::Encode;
+use system::ensure_signed;
+use rstd::prelude::*;
+use crate::traits::{ItemTransfer, IsItemLocked};
+
+pub trait Trait: system::Trait {
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+	/// Kitty index
+	type KittyIndex: Parameter + Member + Copy;
+	/// Interface for transferring kitties
+	type Kitties: ItemTransfer<Self::AccountId, Self::KittyIndex>;
+	/// Tell whether a kitty is locked by other modules, e.g. in an auction
+	type KittyLocks: IsItemLocked<Self::KittyIndex>;
+	/// Number of relayers which have to attest a burn, at least one
+	type RelayerThreshold: Get<u32>;
+	/// Origin managing the relayers
+	type BridgeAdminOrigin: EnsureOrigin<Self::Origin>;
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as Bridge {
+		/// Accounts allowed to relay Ethereum events
+		pub Relayers get(fn relayers): Vec<T::AccountId>;
+		/// Bridged out kitties with the holder on this chain, the recipient on Ethereum and the request nonce
+		pub BridgedOut get(fn bridged_out): map T::KittyIndex => Option<(T::AccountId, H160, u64)>;
+		/// Relayers which attested a burn, by the hash of the burn
+		pub Attestations get(fn attestations): map T::Hash => Vec<T::AccountId>;
+		/// Ethereum transactions already relayed
+		pub ProcessedTxs get(fn is_processed): map H256 => bool;
+		/// Nonce of the next bridge out request
+		pub BridgeNonce get(fn bridge_nonce): u64;
+	}
+}
+
+decl_event!(
+	pub enum Event<T> where
+		<T as system::Trait>::AccountId,
+		<T as Trait>::KittyIndex,
+	{
+		/// A kitty is locked to be minted on Ethereum. (nonce, kitty_id, holder, eth_recipient)
+		BridgedOut(u64, KittyIndex, AccountId, H160),
+		/// A relayer attested a burn. (relayer, kitty_id, eth_tx_hash, attestations)
+		BurnAttested(AccountId, KittyIndex, H256, u32),
+		/// A kitty is released after its mirrored token is burned. (kitty_id, recipient, eth_tx_hash)
+		BridgedIn(KittyIndex, AccountId, H256),
+		/// A relayer is added or removed. (relayer, added)
+		RelayerUpdated(AccountId, bool),
+	}
+);
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		fn deposit_event() = default;
+
+		/// Number of relayers which have to attest a burn
+		const RelayerThreshold: u32 = T::RelayerThreshold::get();
+
+		pub fn set_relayer(origin, relayer: T::AccountId, enabled: bool) {
+			T::BridgeAdminOrigin::ensure_origin(origin)?;
+
+			let mut relayers = Self::relayers();
+			relayers.retain(|r| *r != relayer);
+			if enabled {
+				relayers.push(relayer.clone());
+			}
+			<Relayers<T>>::put(relayers);
+			Self::deposit_event(RawEvent::RelayerUpdated(relayer, enabled));
+		}
+
+		/// Lock an owned kitty to be minted on Ethereum for the recipient
+		pub fn bridge_out(origin, kitty_id: T::KittyIndex, eth_recipient: H160) {
+			let sender = ensure_signed(origin)?;
+			ensure!(T::Kitties::is_item_owner(&sender, kitty_id), "Not owner of kitty");
+			ensure!(!T::KittyLocks::is_item_locked(kitty_id), "Kitty is locked");
+
+			let nonce = Self::bridge_nonce();
+			BridgeNonce::put(nonce.wrapping_add(1));
+			<BridgedOut<T>>::insert(kitty_id, (sender.clone(), eth_recipient, nonce));
+
+			Self::deposit_event(RawEvent::BridgedOut(nonce, kitty_id, sender, eth_recipient));
+		}
+
+		/// Attest the burn of a mirrored token on Ethereum. The kitty is released to the
+		/// recipient once enough relayers attested the same burn.
+		pub fn bridge_in(origin, kitty_id: T::KittyIndex, recipient: T::AccountId, eth_tx_hash: H256, nonce: u64) {
+			let sender = ensure_signed(origin)?;
+			let relayers = Self::relayers();
+			ensure!(relayers.contains(&sender), "Not a relayer");
+			ensure!(!Self::is_processed(eth_tx_hash), "Transaction already relayed");
+			let (holder, _, bridged_nonce) = Self::bridged_out(kitty_id).ok_or("Kitty is not bridged out")?;
+			ensure!(nonce == bridged_nonce, "Burn is not of the current bridge out");
+
+			let burn = (eth_tx_hash, nonce, kitty_id, &recipient).using_encoded(T::Hashing::hash);
+			let mut attesters = Self::attestations(burn);
+			ensure!(!attesters.contains(&sender), "Burn already attested");
+			attesters.push(sender.clone());
+			// 只统计仍在任的中继
+			let count = attesters.iter().filter(|a| relayers.contains(a)).count() as u32;
+
+			if count < T::RelayerThreshold::get().max(1) {
+				<Attestations<T>>::insert(burn, attesters);
+				Self::deposit_event(RawEvent::BurnAttested(sender, kitty_id, eth_tx_hash, count));
+				return Ok(());
+			}
+
+			ensure!(T::Kitties::is_item_owner(&holder, kitty_id), "Bridged kitty is missing");
+			if holder != recipient {
+				T::Kitties::transfer_item(&holder, &recipient, kitty_id)?;
+			}
+			ProcessedTxs::insert(eth_tx_hash, true);
+			<Attestations<T>>::remove(burn);
+			<BridgedOut<T>>::remove(kitty_id);
+
+			Self::deposit_event(RawEvent::BurnAttested(sender, kitty_id, eth_tx_hash, count));
+			Self::deposit_event(RawEvent::BridgedIn(kitty_id, recipient, eth_tx_hash));
+		}
+	}
+}
+
+impl<T: Trait> IsItemLocked<T::KittyIndex> for Module<T> {
+	fn is_item_locked(kitty_id: T::KittyIndex) -> bool {
+		<BridgedOut<T>>::exists(kitty_id)
+	}
+}
+
+/// Tests for Bridge module
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use support::{impl_outer_origin, parameter_types, assert_ok, assert_noop};
+	use sr_primitives::{traits::{BlakeTwo256, IdentityLookup}, testing::Header};
+	use sr_primitives::weights::Weight;
+	use sr_primitives::Perbill;
+	use crate::mock_items::TestItems;
+
+	impl_outer_origin! {
+		pub enum Origin for Test {}
+	}
+
+	#[derive(Clone, Eq, PartialEq, Debug)]
+	pub struct Test;
+	parameter_types! {
+		pub const BlockHashCount: u64 = 250;
+		pub const MaximumBlockWeight: Weight = 1024;
+		pub const MaximumBlockLength: u32 = 2 * 1024;
+		pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+		pub const RelayerThreshold: u32 = 2;
+	}
+	impl system::Trait for Test {
+		type Origin = Origin;
+		type Call = ();
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type Event = ();
+		type BlockHashCount = BlockHashCount;
+		type MaximumBlockWeight = MaximumBlockWeight;
+		type MaximumBlockLength = MaximumBlockLength;
+		type AvailableBlockRatio = AvailableBlockRatio;
+		type Version = ();
+	}
+	impl Trait for Test {
+		type Event = ();
+		type KittyIndex = u32;
+		type Kitties = TestItems;
+		type KittyLocks = ();
+		type RelayerThreshold = RelayerThreshold;
+		type BridgeAdminOrigin = system::EnsureRoot<u64>;
+	}
+	type Bridge = Module<Test>;
+
+	fn new_test_ext() -> runtime_io::TestExternalities {
+		let mut ext: runtime_io::TestExternalities = system::GenesisConfig::default().build_storage::<Test>().unwrap().into();
+		ext.execute_with(|| {
+			for relayer in [10, 11, 12].iter() {
+				assert_ok!(Bridge::set_relayer(Origin::ROOT, *relayer, true));
+			}
+		});
+		TestItems::set_owners(vec![(1, 0)]);
+		ext
+	}
+
+	#[test]
+	fn burns_are_released_by_the_relayer_threshold() {
+		new_test_ext().execute_with(|| {
+			let tx = H256::repeat_byte(1);
+			assert_noop!(Bridge::bridge_out(Origin::signed(2), 0, H160::zero()), "Not owner of kitty");
+			assert_ok!(Bridge::bridge_out(Origin::signed(1), 0, H160::zero()));
+			assert!(Bridge::is_item_locked(0));
+
+			assert_noop!(Bridge::bridge_in(Origin::signed(2), 0, 3, tx, 0), "Not a relayer");
+			assert_noop!(Bridge::bridge_in(Origin::signed(10), 0, 3, tx, 1), "Burn is not of the current bridge out");
+			assert_ok!(Bridge::bridge_in(Origin::signed(10), 0, 3, tx, 0));
+			assert_noop!(Bridge::bridge_in(Origin::signed(10), 0, 3, tx, 0), "Burn already attested");
+			assert!(TestItems::is_item_owner(&1, 0));
+
+			// 不同的接收人是另一笔销毁
+			assert_ok!(Bridge::bridge_in(Origin::signed(11), 0, 4, tx, 0));
+			assert!(TestItems::is_item_owner(&1, 0));
+
+			assert_ok!(Bridge::bridge_in(Origin::signed(11), 0, 3, tx, 0));
+			assert!(TestItems::is_item_owner(&3, 0));
+			assert!(!Bridge::is_item_locked(0));
+			assert!(Bridge::is_processed(tx));
+			assert_noop!(Bridge::bridge_in(Origin::signed(12), 0, 4, tx, 0), "Transaction already relayed");
+		});
+	}
+
+	#[test]
+	fn burns_of_an_earlier_bridge_out_are_rejected() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Bridge::bridge_out(Origin::signed(1), 0, H160::zero()));
+			assert_ok!(Bridge::bridge_in(Origin::signed(10), 0, 1, H256::repeat_byte(1), 0));
+			assert_ok!(Bridge::bridge_in(Origin::signed(11), 0, 1, H256::repeat_byte(1), 0));
+			assert!(TestItems::is_item_owner(&1, 0));
+
+			assert_ok!(Bridge::bridge_out(Origin::signed(1), 0, H160::zero()));
+			assert_eq!(Bridge::bridged_out(0), Some((1, H160::zero(), 1)));
+			assert_noop!(
+				Bridge::bridge_in(Origin::signed(10), 0, 2, H256::repeat_byte(2), 0),
+				"Burn is not of the current bridge out"
+			);
+
+			// 被移除的中继的背书不再计数
+			assert_ok!(Bridge::bridge_in(Origin::signed(10), 0, 2, H256::repeat_byte(2), 1));
+			assert_ok!(Bridge::set_relayer(Origin::ROOT, 10, false));
+			assert_ok!(Bridge::bridge_in(Origin::signed(11), 0, 2, H256::repeat_byte(2), 1));
+			assert!(TestItems::is_item_owner(&1, 0));
+			assert_ok!(Bridge::bridge_in(Origin::signed(12), 0, 2, H256::repeat_byte(2), 1));
+			assert!(TestItems::is_item_owner(&2, 0));
+		});
+	}
+}
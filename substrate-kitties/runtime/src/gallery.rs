@@ -0,0 +1,287 @@
+//! Galleries, accounts owned by a set of signers (e.g. gallery partners).
+//!
+//! A gallery account is derived from the module id and has no key. Calls are
+//! dispatched on its behalf once a threshold of its signers approved them, so
+//! auctions created by a gallery need that threshold for every owner action.
+//! Only the calls the runtime's `CallFilter` allows can be dispatched this way.
+
+use support::{decl_module, decl_storage, decl_event, ensure, Parameter};
+use support::traits::Get;
+use sr_primitives::ModuleId;
+use sr_primitives::traits::{Dispatchable, Hash, AccountIdConversion, Saturating};
+use sr_primitives::weights::{Weight, WeighData, ClassifyDispatch, DispatchClass, GetDispatchInfo};
+use codec::{Encode, Decode};
+use system::ensure_signed;
+use rstd::prelude::*;
+use crate::traits::CallFilter;
+
+/// Max number of signers of a gallery
+const MAX_SIGNERS: usize = 16;
+
+pub trait Trait: system::Trait {
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+	/// The call type which can be dispatched on behalf of a gallery
+	type Call: Parameter + Dispatchable<Origin = <Self as system::Trait>::Origin> + GetDispatchInfo;
+	/// The calls a gallery may dispatch
+	type CallFilter: CallFilter<<Self as Trait>::Call>;
+	/// Id the gallery accounts are derived from
+	type ModuleId: Get<ModuleId>;
+}
+
+/// Weight of an approval, with the weight of the call it may dispatch on top.
+pub struct ApprovalWeight(Weight);
+
+impl<'a, AccountId, Call: GetDispatchInfo> WeighData<(&'a AccountId, &'a Box<Call>)> for ApprovalWeight {
+	fn weigh_data(&self, (_, call): (&'a AccountId, &'a Box<Call>)) -> Weight {
+		self.0.saturating_add(call.get_dispatch_info().weight)
+	}
+}
+
+impl<'a, AccountId, Call> ClassifyDispatch<(&'a AccountId, &'a Box<Call>)> for ApprovalWeight {
+	fn classify_dispatch(&self, _: (&'a AccountId, &'a Box<Call>)) -> DispatchClass {
+		DispatchClass::Normal
+	}
+}
+
+#[derive(Encode, Decode, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct Gallery<AccountId> {
+	signers: Vec<AccountId>, // 签名人列表，已排序
+	threshold: u16, // 执行操作需要的批准人数
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as Gallery {
+		/// Signers of a gallery account
+		pub Galleries get(fn gallery): map T::AccountId => Option<Gallery<T::AccountId>>;
+		/// Index the next gallery account is derived from
+		pub NextGalleryIndex get(fn next_gallery_index): u32;
+		/// Pending actions, (gallery, call hash) => signers which approved it
+		pub Approvals get(fn approvals): double_map T::AccountId, twox_128(T::Hash) => Vec<T::AccountId>;
+	}
+}
+
+decl_event!(
+	pub enum Event<T> where
+		<T as system::Trait>::AccountId,
+		<T as system::Trait>::Hash,
+	{
+		/// A gallery is created. (gallery)
+		GalleryCreated(AccountId),
+		/// A signer approves an action of a gallery. (gallery, signer, call_hash)
+		ActionApproved(AccountId, AccountId, Hash),
+		/// A signer withdraws its approval. (gallery, signer, call_hash)
+		ApprovalCancelled(AccountId, AccountId, Hash),
+		/// An approved action is dispatched on behalf of a gallery. (gallery, call_hash, result)
+		ActionDispatched(AccountId, Hash, bool),
+	}
+);
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		fn deposit_event() = default;
+
+		/// Create a gallery account owned by the signers, the sender must be one of them
+		pub fn create_gallery(origin, signers: Vec<T::AccountId>, threshold: u16) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(signers.len() <= MAX_SIGNERS, "Too many signers");
+			ensure!(threshold >= 1 && threshold as usize <= signers.len(), "Invalid threshold");
+
+			let mut sorted_signers = signers;
+			sorted_signers.sort();
+			ensure!(sorted_signers.windows(2).all(|w| w[0] != w[1]), "Duplicate signers");
+			ensure!(sorted_signers.binary_search(&sender).is_ok(), "Sender must be a signer");
+
+			let index = Self::next_gallery_index();
+			let next_index = index.checked_add(1).ok_or("Gallery index overflow")?;
+			let gallery = Self::gallery_account(index);
+
+			<NextGalleryIndex>::put(next_index);
+			<Galleries<T>>::insert(&gallery, Gallery {
+				signers: sorted_signers,
+				threshold: threshold,
+			});
+			Self::deposit_event(RawEvent::GalleryCreated(gallery));
+		}
+
+		/// Approve a call of the gallery, it is dispatched as the gallery once the threshold is reached
+		#[weight = ApprovalWeight(10_000)]
+		pub fn approve_as_gallery(origin, gallery: T::AccountId, call: Box<<T as Trait>::Call>) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(T::CallFilter::allows(&call), "Call is not allowed for galleries");
+			let config = Self::gallery(&gallery).ok_or("Gallery does not exist")?;
+			ensure!(config.signers.binary_search(&sender).is_ok(), "Not a signer of the gallery");
+			let call_hash = T::Hashing::hash_of(&call);
+			let mut approvals = Self::approvals(&gallery, &call_hash);
+			ensure!(!approvals.contains(&sender), "Already approved");
+
+			approvals.push(sender.clone());
+			Self::deposit_event(RawEvent::ActionApproved(gallery.clone(), sender, call_hash));
+
+			if approvals.len() >= config.threshold as usize {
+				<Approvals<T>>::remove(&gallery, &call_hash);
+				let res = call.dispatch(system::RawOrigin::Signed(gallery.clone()).into()).is_ok();
+				Self::deposit_event(RawEvent::ActionDispatched(gallery, call_hash, res));
+			} else {
+				<Approvals<T>>::insert(&gallery, &call_hash, approvals);
+			}
+		}
+
+		/// Withdraw an approval of a call which is not dispatched yet
+		pub fn cancel_approval(origin, gallery: T::AccountId, call_hash: T::Hash) {
+			let sender = ensure_signed(origin)?;
+
+			let mut approvals = Self::approvals(&gallery, &call_hash);
+			let index = approvals.iter().position(|a| *a == sender).ok_or("Not approved")?;
+
+			approvals.swap_remove(index);
+			if approvals.is_empty() {
+				<Approvals<T>>::remove(&gallery, &call_hash);
+			} else {
+				<Approvals<T>>::insert(&gallery, &call_hash, approvals);
+			}
+			Self::deposit_event(RawEvent::ApprovalCancelled(gallery, sender, call_hash));
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// The account of the gallery with the given index
+	pub fn gallery_account(index: u32) -> T::AccountId {
+		T::ModuleId::get().into_sub_account(index)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::gallery;
+
+	use primitives::H256;
+	use support::{impl_outer_origin, impl_outer_dispatch, parameter_types, assert_ok, assert_noop};
+	use sr_primitives::{traits::{BlakeTwo256, IdentityLookup}, testing::Header};
+	use sr_primitives::Perbill;
+
+	impl_outer_origin! {
+		pub enum Origin for Test {}
+	}
+
+	impl_outer_dispatch! {
+		pub enum Call for Test where origin: Origin {
+			balances::Balances,
+			gallery::Gallery,
+		}
+	}
+
+	#[derive(Clone, Eq, PartialEq, Debug)]
+	pub struct Test;
+	parameter_types! {
+		pub const BlockHashCount: u64 = 250;
+		pub const MaximumBlockWeight: Weight = 1024;
+		pub const MaximumBlockLength: u32 = 2 * 1024;
+		pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+	}
+	impl system::Trait for Test {
+		type Origin = Origin;
+		type Call = Call;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type Event = ();
+		type BlockHashCount = BlockHashCount;
+		type MaximumBlockWeight = MaximumBlockWeight;
+		type MaximumBlockLength = MaximumBlockLength;
+		type AvailableBlockRatio = AvailableBlockRatio;
+		type Version = ();
+	}
+	parameter_types! {
+		pub const ExistentialDeposit: u64 = 0;
+		pub const TransferFee: u64 = 0;
+		pub const CreationFee: u64 = 0;
+		pub const GalleryModuleId: ModuleId = ModuleId(*b"py/gallr");
+	}
+	impl balances::Trait for Test {
+		type Balance = u64;
+		type OnFreeBalanceZero = ();
+		type OnNewAccount = ();
+		type Event = ();
+		type TransferPayment = ();
+		type DustRemoval = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type TransferFee = TransferFee;
+		type CreationFee = CreationFee;
+	}
+	/// Galleries may only move balances in the tests
+	pub struct BalanceCalls;
+	impl CallFilter<Call> for BalanceCalls {
+		fn allows(call: &Call) -> bool {
+			match call {
+				Call::Balances(_) => true,
+				_ => false,
+			}
+		}
+	}
+	impl Trait for Test {
+		type Event = ();
+		type Call = Call;
+		type CallFilter = BalanceCalls;
+		type ModuleId = GalleryModuleId;
+	}
+	type Gallery = Module<Test>;
+	type Balances = balances::Module<Test>;
+
+	fn new_test_ext() -> runtime_io::TestExternalities {
+		system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
+	}
+
+	fn transfer(dest: u64, value: u64) -> Box<Call> {
+		Box::new(Call::Balances(balances::Call::transfer(dest, value)))
+	}
+
+	#[test]
+	fn approved_calls_are_dispatched_as_the_gallery() {
+		new_test_ext().execute_with(|| {
+			use support::traits::Currency;
+			assert_ok!(Gallery::create_gallery(Origin::signed(1), vec![2, 1], 2));
+			let gallery = Gallery::gallery_account(0);
+			let _ = Balances::deposit_creating(&gallery, 100);
+
+			assert_noop!(Gallery::approve_as_gallery(Origin::signed(3), gallery, transfer(5, 30)), "Not a signer of the gallery");
+			assert_ok!(Gallery::approve_as_gallery(Origin::signed(1), gallery, transfer(5, 30)));
+			assert_noop!(Gallery::approve_as_gallery(Origin::signed(1), gallery, transfer(5, 30)), "Already approved");
+			assert_eq!(Balances::free_balance(&5), 0);
+
+			assert_ok!(Gallery::approve_as_gallery(Origin::signed(2), gallery, transfer(5, 30)));
+			assert_eq!(Balances::free_balance(&5), 30);
+			assert_eq!(Balances::free_balance(&gallery), 70);
+		});
+	}
+
+	#[test]
+	fn only_allowed_calls_can_be_approved() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Gallery::create_gallery(Origin::signed(1), vec![1, 2], 1));
+			let gallery = Gallery::gallery_account(0);
+			let nested = Box::new(Call::Gallery(gallery::Call::create_gallery(vec![gallery], 1)));
+			assert_noop!(Gallery::approve_as_gallery(Origin::signed(1), gallery, nested), "Call is not allowed for galleries");
+		});
+	}
+
+	#[test]
+	fn approvals_weigh_the_inner_call() {
+		new_test_ext().execute_with(|| {
+			let inner = transfer(5, 30);
+			let approval = Call::Gallery(gallery::Call::approve_as_gallery(0, inner.clone()));
+			assert_eq!(
+				approval.get_dispatch_info().weight,
+				10_000 + inner.get_dispatch_info().weight
+			);
+		});
+	}
+}
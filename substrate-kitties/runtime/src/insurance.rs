@@ -0,0 +1,347 @@
+//! Insurance for kitties.
+//!
+//! Owners pay premiums per period into a pool to cover a kitty. When a covered
+//! kitty is lost to an exploit or a forced cancellation, the holder files a claim
+//! which is adjudicated by the moderation origin and paid from the pool.
+//!
+//! The pool is the pot account of the module, holding the premiums paid. A claim
+//! can only be filed and approved while the holder no longer owns the kitty.
+
+use support::{decl_module, decl_storage, decl_event, ensure, Parameter};
+use support::traits::{Get, Currency};
+use sr_primitives::{Permill, ModuleId};
+use sr_primitives::traits::{Member, Zero, Saturating, EnsureOrigin, AccountIdConversion};
+use codec::{Encode, Decode};
+use system::ensure_signed;
+use crate::traits::ItemTransfer;
+
+pub trait Trait: system::Trait {
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+	/// Kitty index
+	type KittyIndex: Parameter + Member + Copy;
+	/// Interface for checking kitty ownership
+	type Kitties: ItemTransfer<Self::AccountId, Self::KittyIndex>;
+	/// Currency of premiums and payouts
+	type Currency: Currency<Self::AccountId>;
+	/// Number of blocks a premium covers
+	type PremiumPeriod: Get<Self::BlockNumber>;
+	/// Premium of a period as a part of the coverage
+	type PremiumRate: Get<Permill>;
+	/// Max coverage of a kitty
+	type MaxCoverage: Get<BalanceOf<Self>>;
+	/// Origin adjudicating claims
+	type ModerationOrigin: EnsureOrigin<Self::Origin>;
+	/// Id of the module, premiums are kept in its pot account
+	type ModuleId: Get<ModuleId>;
+}
+
+pub type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum ClaimReason {
+	/// Lost to a verified exploit
+	Exploit,
+	/// Lost to a forced cancellation, e.g. of an auction
+	ForcedCancellation,
+}
+
+#[derive(Encode, Decode, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct Policy<AccountId, Balance, BlockNumber> {
+	holder: AccountId, // 投保人
+	coverage: Balance, // 保额
+	paid_until: BlockNumber, // 保费已缴至的区块
+}
+
+#[derive(Encode, Decode, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct Claim<Hash, BlockNumber> {
+	reason: ClaimReason, // 理赔原因
+	evidence: Hash, // 证据哈希
+	filed_at: BlockNumber, // 申请区块
+}
+
+type PolicyOf<T> = Policy<<T as system::Trait>::AccountId, BalanceOf<T>, <T as system::Trait>::BlockNumber>;
+
+decl_storage! {
+	trait Store for Module<T: Trait> as Insurance {
+		/// Insurance policy of a kitty
+		pub Policies get(fn policy): map T::KittyIndex => Option<PolicyOf<T>>;
+		/// Pending claim of a kitty
+		pub Claims get(fn claim): map T::KittyIndex => Option<Claim<T::Hash, T::BlockNumber>>;
+	}
+}
+
+decl_event!(
+	pub enum Event<T> where
+		<T as system::Trait>::AccountId,
+		<T as system::Trait>::BlockNumber,
+		<T as Trait>::KittyIndex,
+		Balance = BalanceOf<T>,
+	{
+		/// A kitty is insured. (holder, kitty_id, coverage)
+		Insured(AccountId, KittyIndex, Balance),
+		/// Premiums are paid. (holder, kitty_id, amount, paid_until)
+		PremiumPaid(AccountId, KittyIndex, Balance, BlockNumber),
+		/// A policy is cancelled. (kitty_id)
+		PolicyCancelled(KittyIndex),
+		/// A claim is filed. (holder, kitty_id, reason)
+		ClaimFiled(AccountId, KittyIndex, ClaimReason),
+		/// A claim is approved and paid. (holder, kitty_id, payout)
+		ClaimPaid(AccountId, KittyIndex, Balance),
+		/// A claim is rejected. (kitty_id)
+		ClaimRejected(KittyIndex),
+	}
+);
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		fn deposit_event() = default;
+
+		/// Id of the module, premiums are kept in its pot account
+		const ModuleId: ModuleId = T::ModuleId::get();
+
+		/// Insure an owned kitty, paying the premium of the first period
+		pub fn insure_kitty(origin, kitty_id: T::KittyIndex, coverage: BalanceOf<T>) {
+			let sender = ensure_signed(origin)?;
+			ensure!(T::Kitties::is_item_owner(&sender, kitty_id), "Not owner of kitty");
+			ensure!(!coverage.is_zero() && coverage <= T::MaxCoverage::get(), "Invalid coverage");
+			if let Some(policy) = Self::policy(kitty_id) {
+				ensure!(policy.paid_until < <system::Module<T>>::block_number(), "Kitty is already insured");
+			}
+
+			let now = <system::Module<T>>::block_number();
+			Self::collect_premium(&sender, coverage, 1)?;
+			let paid_until = now + T::PremiumPeriod::get();
+			<Policies<T>>::insert(kitty_id, Policy { holder: sender.clone(), coverage: coverage, paid_until: paid_until });
+
+			Self::deposit_event(RawEvent::Insured(sender, kitty_id, coverage));
+		}
+
+		/// Pay the premiums of more periods
+		pub fn pay_premium(origin, kitty_id: T::KittyIndex, periods: u32) {
+			let sender = ensure_signed(origin)?;
+			let mut policy = Self::policy(kitty_id).ok_or("Kitty is not insured")?;
+			ensure!(policy.holder == sender, "Not holder of policy");
+			ensure!(periods > 0, "Should pay at least one period");
+
+			let now = <system::Module<T>>::block_number();
+			let amount = Self::collect_premium(&sender, policy.coverage, periods)?;
+			let start = if policy.paid_until > now { policy.paid_until } else { now };
+			policy.paid_until = start.saturating_add(T::PremiumPeriod::get().saturating_mul(periods.into()));
+			let paid_until = policy.paid_until;
+			<Policies<T>>::insert(kitty_id, policy);
+
+			Self::deposit_event(RawEvent::PremiumPaid(sender, kitty_id, amount, paid_until));
+		}
+
+		/// Cancel a policy, paid premiums are not refunded
+		pub fn cancel_policy(origin, kitty_id: T::KittyIndex) {
+			let sender = ensure_signed(origin)?;
+			let policy = Self::policy(kitty_id).ok_or("Kitty is not insured")?;
+			ensure!(policy.holder == sender, "Not holder of policy");
+			ensure!(!<Claims<T>>::exists(kitty_id), "Claim is pending");
+
+			<Policies<T>>::remove(kitty_id);
+			Self::deposit_event(RawEvent::PolicyCancelled(kitty_id));
+		}
+
+		/// File a claim for a lost kitty while the policy is paid
+		pub fn file_claim(origin, kitty_id: T::KittyIndex, reason: ClaimReason, evidence: T::Hash) {
+			let sender = ensure_signed(origin)?;
+			let policy = Self::policy(kitty_id).ok_or("Kitty is not insured")?;
+			ensure!(policy.holder == sender, "Not holder of policy");
+			let now = <system::Module<T>>::block_number();
+			ensure!(policy.paid_until >= now, "Policy is expired");
+			ensure!(!<Claims<T>>::exists(kitty_id), "Claim is pending");
+			ensure!(!T::Kitties::is_item_owner(&sender, kitty_id), "Kitty is not lost");
+
+			<Claims<T>>::insert(kitty_id, Claim { reason: reason, evidence: evidence, filed_at: now });
+			Self::deposit_event(RawEvent::ClaimFiled(sender, kitty_id, reason));
+		}
+
+		/// Approve or reject a claim. An approved claim is paid up to the pool balance and closes the policy
+		pub fn adjudicate_claim(origin, kitty_id: T::KittyIndex, approved: bool) {
+			T::ModerationOrigin::ensure_origin(origin)?;
+			ensure!(<Claims<T>>::exists(kitty_id), "No claim for kitty");
+			let policy = Self::policy(kitty_id).ok_or("Kitty is not insured")?;
+
+			if !approved {
+				<Claims<T>>::remove(kitty_id);
+				Self::deposit_event(RawEvent::ClaimRejected(kitty_id));
+				return Ok(());
+			}
+
+			// 索赔期间取回猫咪的不予赔付
+			ensure!(!T::Kitties::is_item_owner(&policy.holder, kitty_id), "Kitty is not lost");
+			let payout = policy.coverage.min(Self::pool_balance());
+			T::Currency::transfer(&Self::pool_account(), &policy.holder, payout)?;
+			<Claims<T>>::remove(kitty_id);
+			<Policies<T>>::remove(kitty_id);
+
+			Self::deposit_event(RawEvent::ClaimPaid(policy.holder, kitty_id, payout));
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// The account holding the premiums
+	pub fn pool_account() -> T::AccountId {
+		T::ModuleId::get().into_account()
+	}
+
+	/// Premiums collected and not paid out yet
+	pub fn pool_balance() -> BalanceOf<T> {
+		T::Currency::free_balance(&Self::pool_account())
+	}
+
+	/// Premium of one period for the coverage
+	pub fn premium_of(coverage: BalanceOf<T>) -> BalanceOf<T> {
+		T::PremiumRate::get() * coverage
+	}
+
+	/// Transfer the premiums of the periods into the pool
+	fn collect_premium(who: &T::AccountId, coverage: BalanceOf<T>, periods: u32) -> Result<BalanceOf<T>, &'static str> {
+		let amount = Self::premium_of(coverage).saturating_mul(periods.into());
+		T::Currency::transfer(who, &Self::pool_account(), amount)?;
+		Ok(amount)
+	}
+}
+
+/// Tests for Insurance module
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use primitives::H256;
+	use support::{impl_outer_origin, parameter_types, assert_ok, assert_noop};
+	use sr_primitives::{traits::{BlakeTwo256, IdentityLookup}, testing::Header};
+	use sr_primitives::weights::Weight;
+	use sr_primitives::Perbill;
+	use crate::mock_items::TestItems;
+
+	impl_outer_origin! {
+		pub enum Origin for Test {}
+	}
+
+	#[derive(Clone, Eq, PartialEq, Debug)]
+	pub struct Test;
+	parameter_types! {
+		pub const BlockHashCount: u64 = 250;
+		pub const MaximumBlockWeight: Weight = 1024;
+		pub const MaximumBlockLength: u32 = 2 * 1024;
+		pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+	}
+	impl system::Trait for Test {
+		type Origin = Origin;
+		type Call = ();
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type Event = ();
+		type BlockHashCount = BlockHashCount;
+		type MaximumBlockWeight = MaximumBlockWeight;
+		type MaximumBlockLength = MaximumBlockLength;
+		type AvailableBlockRatio = AvailableBlockRatio;
+		type Version = ();
+	}
+	parameter_types! {
+		pub const ExistentialDeposit: u64 = 0;
+		pub const TransferFee: u64 = 0;
+		pub const CreationFee: u64 = 0;
+		pub const PremiumPeriod: u64 = 10;
+		pub const PremiumRate: Permill = Permill::from_percent(10);
+		pub const MaxCoverage: u64 = 1000;
+		pub const InsuranceModuleId: ModuleId = ModuleId(*b"py/insur");
+	}
+	impl balances::Trait for Test {
+		type Balance = u64;
+		type OnFreeBalanceZero = ();
+		type OnNewAccount = ();
+		type Event = ();
+		type TransferPayment = ();
+		type DustRemoval = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type TransferFee = TransferFee;
+		type CreationFee = CreationFee;
+	}
+	impl Trait for Test {
+		type Event = ();
+		type KittyIndex = u32;
+		type Kitties = TestItems;
+		type Currency = balances::Module<Test>;
+		type PremiumPeriod = PremiumPeriod;
+		type PremiumRate = PremiumRate;
+		type MaxCoverage = MaxCoverage;
+		type ModerationOrigin = system::EnsureRoot<u64>;
+		type ModuleId = InsuranceModuleId;
+	}
+	type Insurance = Module<Test>;
+	type Balances = balances::Module<Test>;
+	type System = system::Module<Test>;
+
+	fn new_test_ext() -> runtime_io::TestExternalities {
+		let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+		balances::GenesisConfig::<Test> {
+			balances: vec![(1, 1000), (2, 1000)],
+			vesting: vec![],
+		}.assimilate_storage(&mut t).unwrap();
+		TestItems::set_owners(vec![(1, 0), (2, 1)]);
+		t.into()
+	}
+
+	#[test]
+	fn approved_claims_are_paid_from_the_premiums() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(Insurance::insure_kitty(Origin::signed(1), 1, 100), "Not owner of kitty");
+			assert_noop!(Insurance::insure_kitty(Origin::signed(1), 0, 1001), "Invalid coverage");
+			assert_ok!(Insurance::insure_kitty(Origin::signed(1), 0, 100));
+			assert_ok!(Insurance::pay_premium(Origin::signed(1), 0, 5));
+			assert_ok!(Insurance::insure_kitty(Origin::signed(2), 1, 1000));
+			assert_eq!(Insurance::pool_balance(), 160);
+			assert_eq!(Balances::free_balance(&1), 940);
+			assert_eq!(Insurance::policy(0).map(|policy| policy.paid_until), Some(60));
+
+			// 猫咪仍在投保人手中时不能索赔
+			assert_noop!(Insurance::file_claim(Origin::signed(1), 0, ClaimReason::Exploit, H256::zero()), "Kitty is not lost");
+			assert_ok!(TestItems::transfer_item(&1, &3, 0));
+			assert_ok!(Insurance::file_claim(Origin::signed(1), 0, ClaimReason::Exploit, H256::zero()));
+			assert_noop!(Insurance::cancel_policy(Origin::signed(1), 0), "Claim is pending");
+
+			assert!(Insurance::adjudicate_claim(Origin::signed(2), 0, true).is_err());
+			assert_ok!(Insurance::adjudicate_claim(Origin::ROOT, 0, true));
+			assert_eq!(Balances::free_balance(&1), 1040);
+			assert_eq!(Insurance::pool_balance(), 60);
+			assert_eq!(Insurance::policy(0), None);
+			assert_eq!(Insurance::claim(0), None);
+		});
+	}
+
+	#[test]
+	fn recovered_or_rejected_claims_are_not_paid() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Insurance::insure_kitty(Origin::signed(1), 0, 100));
+			assert_ok!(TestItems::transfer_item(&1, &3, 0));
+			assert_ok!(Insurance::file_claim(Origin::signed(1), 0, ClaimReason::ForcedCancellation, H256::zero()));
+			assert_noop!(Insurance::file_claim(Origin::signed(1), 0, ClaimReason::Exploit, H256::zero()), "Claim is pending");
+
+			// 索赔期间取回猫咪
+			assert_ok!(TestItems::transfer_item(&3, &1, 0));
+			assert_noop!(Insurance::adjudicate_claim(Origin::ROOT, 0, true), "Kitty is not lost");
+			assert_ok!(Insurance::adjudicate_claim(Origin::ROOT, 0, false));
+			assert_eq!(Insurance::claim(0), None);
+			assert!(Insurance::policy(0).is_some());
+			assert_eq!(Insurance::pool_balance(), 10);
+			assert_eq!(Balances::free_balance(&1), 990);
+
+			System::set_block_number(11);
+			assert_ok!(TestItems::transfer_item(&1, &3, 0));
+			assert_noop!(Insurance::file_claim(Origin::signed(1), 0, ClaimReason::Exploit, H256::zero()), "Policy is expired");
+		});
+	}
+}
@@ -1,16 +1,62 @@
 use support::{decl_module, decl_storage, decl_event, ensure,Parameter};
-use support::traits::{Randomness};
-use sr_primitives::traits::{SimpleArithmetic, Bounded, Member};
+use rstd::vec::Vec;
+use support::traits::{Randomness, Get, Currency, WithdrawReason, ExistenceRequirement, OnUnbalanced};
+use sr_primitives::traits::{SimpleArithmetic, Bounded, Member, Zero, Saturating, SaturatedConversion, IntegerSquareRoot};
 use codec::{Encode, Decode, EncodeLike, Output, Input};
 use runtime_io::blake2_128;
 use system::ensure_signed;
 use rstd::result;
 use crate::linked_item::{LinkedList, LinkedItem};
-use crate::traits::ItemTransfer;
+use crate::traits::{ItemTransfer, ItemTransferError, IsItemLocked, OwnedItems, ItemMint, OnItemBurned, OnKittyBred, OnItemTransferred};
+
+/// Max length of the memo attached to a gift
+const MAX_MEMO_LENGTH: usize = 256;
+/// Min length of a kitty name
+const MIN_NAME_LENGTH: usize = 3;
+/// Max length of a kitty name
+const MAX_NAME_LENGTH: usize = 32;
 
 pub trait Trait: system::Trait {
 	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
 	type KittyIndex: Parameter + Member + SimpleArithmetic + Bounded + Default + Copy;
+	/// Tell whether a kitty is locked by other modules, e.g. in an auction
+	type ItemLocks: IsItemLocked<Self::KittyIndex>;
+	/// Currency spent on feeding kitties
+	type Currency: Currency<Self::AccountId>;
+	/// Price of one experience point, the fed tokens are burned
+	type ExperiencePrice: Get<BalanceOf<Self>>;
+	/// Fee unit for claiming a name, shorter names cost more units. The fee is burned
+	type NameFee: Get<BalanceOf<Self>>;
+	/// Number of blocks both parents have to wait before breeding again
+	type BreedingCooldown: Get<Self::BlockNumber>;
+	/// Handler for kitties being burned, e.g. by fusion
+	type OnKittyBurned: OnItemBurned<Self::AccountId, Self::KittyIndex>;
+	/// Handler for kitties being bred, e.g. achievements
+	type OnKittyBred: OnKittyBred<Self::AccountId, Self::KittyIndex>;
+	/// Handler for kitties being minted or changing hands, e.g. provenance
+	type OnKittyTransferred: OnItemTransferred<Self::AccountId, Self::KittyIndex>;
+	/// Price of the first generation-0 kitties
+	type Gen0BasePrice: Get<BalanceOf<Self>>;
+	/// The gen-0 price goes up by `Gen0BasePrice` every time this many gen-0 kitties are created
+	type Gen0PriceStep: Get<u32>;
+	/// Handler for the gen-0 minting payment
+	type OnMintPayment: OnUnbalanced<NegativeImbalanceOf<Self>>;
+}
+
+pub type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+type NegativeImbalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::NegativeImbalance;
+
+/// Experience needed for the first level, level `n` needs `n * n * EXPERIENCE_PER_LEVEL`
+const EXPERIENCE_PER_LEVEL: u64 = 100;
+
+/// Public attributes of a kitty
+#[derive(Encode, Decode, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct KittyAttributes {
+	pub dna: [u8; 16],
+	pub experience: u64,
+	pub level: u32,
+	pub strength: u32,
 }
 
 pub struct Kitty(pub [u8; 16]);
@@ -37,11 +83,30 @@ decl_storage! {
 		pub Kitties get(kitty): map T::KittyIndex => Option<Kitty>;
 		/// Stores the total number of kitties. i.e. the next kitty index
 		pub KittiesCount get(kitties_count): T::KittyIndex;
+		/// Number of generation-0 kitties ever created
+		pub Gen0Count get(gen0_count): u32;
+		/// Whether a kitty is of generation 0, i.e. created rather than bred
+		pub Gen0Kitties get(is_gen0): map T::KittyIndex => bool;
 
 		pub OwnedKitties get(owned_kitties): map (T::AccountId, Option<T::KittyIndex>) => Option<KittyLinkedItem<T>>;
 
 		/// Get kitty owner
 		pub KittyOwners get(kitty_owner): map T::KittyIndex => Option<T::AccountId>;
+
+		/// Experience gained by feeding the kitty
+		pub KittyExperience get(kitty_experience): map T::KittyIndex => u64;
+
+		/// Unique name of a kitty, names are stored in lower case
+		pub KittyNames get(kitty_name): map T::KittyIndex => Option<Vec<u8>>;
+		/// Lookup kitty by name
+		pub NamedKitties get(kitty_by_name): map Vec<u8> => Option<T::KittyIndex>;
+
+		/// Block number until which a kitty can not breed
+		pub BreedingCooldowns get(breeding_cooldown): map T::KittyIndex => T::BlockNumber;
+		/// Kitties publicly listed as studs, with the posted breeding fee
+		pub StudListings get(stud_fee): map T::KittyIndex => Option<BalanceOf<T>>;
+		/// Kitties held by another module, e.g. listed in an auction
+		pub ReservedKitties get(is_reserved): map T::KittyIndex => bool;
 	}
 }
 
@@ -49,11 +114,26 @@ decl_event!(
 	pub enum Event<T> where
 		<T as system::Trait>::AccountId,
 		<T as Trait>::KittyIndex,
+		Balance = BalanceOf<T>,
 	{
 		/// A kitty is created. (owner, kitty_id)
 		Created(AccountId, KittyIndex),
 		/// A kitty is transferred. (from, to, kitty_id)
 		Transferred(AccountId, AccountId, KittyIndex),
+		/// A kitty is gifted with a memo. (from, to, kitty_id, memo)
+		Gifted(AccountId, AccountId, KittyIndex, Vec<u8>),
+		/// A kitty is fed. (owner, kitty_id, spent, experience, level)
+		Fed(AccountId, KittyIndex, Balance, u64, u32),
+		/// A name is claimed for a kitty. (owner, kitty_id, name, fee)
+		NameClaimed(AccountId, KittyIndex, Vec<u8>, Balance),
+		/// A kitty's name is released. (kitty_id, name)
+		NameReleased(KittyIndex, Vec<u8>),
+		/// A kitty is listed as stud, or unlisted with None. (owner, kitty_id, fee)
+		StudListed(AccountId, KittyIndex, Option<Balance>),
+		/// A stud is hired for breeding. (breeder, stud_owner, stud_id, fee, new_kitty_id)
+		StudHired(AccountId, AccountId, KittyIndex, Balance, KittyIndex),
+		/// Two kitties are burned and fused into a new one. (owner, kitty_id_1, kitty_id_2, new_kitty_id)
+		Fused(AccountId, KittyIndex, KittyIndex, KittyIndex),
 	}
 );
 
@@ -61,17 +141,25 @@ decl_module! {
 	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
 		fn deposit_event() = default;
 
-		/// Create a new kitty
+		/// Create a new kitty, paying the current gen-0 price
 		pub fn create(origin) {
 			let sender = ensure_signed(origin)?;
 			let kitty_id = Self::next_kitty_id()?;
 
+			let price = Self::gen0_price();
+			if !price.is_zero() {
+				let imbalance = T::Currency::withdraw(&sender, price, WithdrawReason::Fee, ExistenceRequirement::KeepAlive)?;
+				T::OnMintPayment::on_unbalanced(imbalance);
+			}
+			Gen0Count::mutate(|count| *count = count.saturating_add(1));
+
 			// Generate a random 128bit value
 			let dna = Self::random_value(&sender);
 
 			// Create and store kitty
 			let kitty = Kitty(dna);
 			Self::insert_kitty(&sender, kitty_id, kitty);
+			<Gen0Kitties<T>>::insert(kitty_id, true);
 
 			Self::deposit_event(RawEvent::Created(sender, kitty_id));
 		}
@@ -88,9 +176,135 @@ decl_module! {
 		/// Transfer a kitty to new owner
  		pub fn transfer(origin, to: T::AccountId, kitty_id: T::KittyIndex) {
  			let sender = ensure_signed(origin)?;
+			ensure!(!Self::is_locked(kitty_id), "Kitty is locked in an auction");
 
 			Self::transfer_kitty(&sender, &to, kitty_id)?;
 		}
+
+		/// Feed a kitty, the spent tokens are burned and turned into experience
+		pub fn feed(origin, kitty_id: T::KittyIndex, amount: BalanceOf<T>) {
+			let sender = ensure_signed(origin)?;
+			ensure!(Self::is_kitty_owner(&sender, kitty_id), "Only owner can feed kitty");
+
+			let price = T::ExperiencePrice::get();
+			ensure!(!price.is_zero(), "Feeding is disabled");
+			let gained = (amount / price).saturated_into::<u64>();
+			ensure!(gained > 0, "Amount is less than the price of one experience point");
+
+			// only charge for whole experience points
+			let spent = price * gained.saturated_into::<BalanceOf<T>>();
+			let imbalance = T::Currency::withdraw(&sender, spent, WithdrawReason::Transfer, ExistenceRequirement::KeepAlive)?;
+			// burn the fed tokens
+			drop(imbalance);
+
+			let experience = Self::kitty_experience(kitty_id).saturating_add(gained);
+			<KittyExperience<T>>::insert(kitty_id, experience);
+
+			Self::deposit_event(RawEvent::Fed(sender, kitty_id, spent, experience, Self::level_of(experience)));
+		}
+
+		/// List a kitty as a public stud at a fixed breeding fee, or unlist it with None
+		pub fn list_stud(origin, kitty_id: T::KittyIndex, fee: Option<BalanceOf<T>>) {
+			let sender = ensure_signed(origin)?;
+			ensure!(Self::is_kitty_owner(&sender, kitty_id), "Only owner can list stud");
+
+			match fee {
+				Some(f) => <StudListings<T>>::insert(kitty_id, f),
+				None => <StudListings<T>>::remove(kitty_id),
+			}
+			Self::deposit_event(RawEvent::StudListed(sender, kitty_id, fee));
+		}
+
+		/// Breed own kitty with a listed stud, paying the posted fee to the stud's owner.
+		/// `max_fee` protects the breeder from the fee being raised in the meantime.
+		pub fn breed_with_stud(origin, kitty_id: T::KittyIndex, stud_id: T::KittyIndex, max_fee: BalanceOf<T>) {
+			let sender = ensure_signed(origin)?;
+
+			let fee = Self::stud_fee(stud_id).ok_or("Kitty is not listed as stud")?;
+			ensure!(fee <= max_fee, "Stud fee is higher than max fee");
+			let stud_owner = Self::kitty_owner(stud_id).ok_or("Invalid stud_id")?;
+			ensure!(stud_owner != sender, "Use breed for own kitties");
+
+			Self::ensure_parents(kitty_id, stud_id)?;
+			ensure!(Self::is_kitty_owner(&sender, kitty_id), "Not owner of kitty");
+
+			T::Currency::transfer(&sender, &stud_owner, fee)?;
+			let new_kitty_id = Self::breed_unchecked(&sender, kitty_id, stud_id)?;
+
+			Self::deposit_event(RawEvent::StudHired(sender.clone(), stud_owner, stud_id, fee, new_kitty_id));
+			Self::deposit_event(RawEvent::Created(sender, new_kitty_id));
+		}
+
+		/// Burn two kitties and mint an upgraded one, which inherits their experience
+		pub fn fuse(origin, kitty_id_1: T::KittyIndex, kitty_id_2: T::KittyIndex) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(kitty_id_1 != kitty_id_2, "Needs different kitties");
+			ensure!(Self::is_kitty_owner(&sender, kitty_id_1), "Not owner of kitty1");
+			ensure!(Self::is_kitty_owner(&sender, kitty_id_2), "Not owner of kitty2");
+			ensure!(!Self::is_locked(kitty_id_1), "Kitty1 is locked in an auction");
+			ensure!(!Self::is_locked(kitty_id_2), "Kitty2 is locked in an auction");
+
+			let kitty1_dna = Self::kitty(kitty_id_1).ok_or("Invalid kitty_id_1")?.0;
+			let kitty2_dna = Self::kitty(kitty_id_2).ok_or("Invalid kitty_id_2")?.0;
+			let new_kitty_id = Self::next_kitty_id()?;
+
+			// Take the stronger gene of the parents with a random rarity boost
+			let selector = Self::random_value(&sender);
+			let mut new_dna = [0u8; 16];
+			for i in 0..kitty1_dna.len() {
+				new_dna[i] = kitty1_dna[i].max(kitty2_dna[i]).saturating_add(selector[i] >> 4);
+			}
+			let experience = Self::kitty_experience(kitty_id_1).saturating_add(Self::kitty_experience(kitty_id_2));
+
+			Self::burn_kitty(&sender, kitty_id_1);
+			Self::burn_kitty(&sender, kitty_id_2);
+			Self::insert_kitty(&sender, new_kitty_id, Kitty(new_dna));
+			<KittyExperience<T>>::insert(new_kitty_id, experience);
+
+			Self::deposit_event(RawEvent::Fused(sender.clone(), kitty_id_1, kitty_id_2, new_kitty_id));
+			Self::deposit_event(RawEvent::Created(sender, new_kitty_id));
+		}
+
+		/// Claim a unique name for a kitty, its previous name is released
+		pub fn claim_name(origin, kitty_id: T::KittyIndex, name: Vec<u8>) {
+			let sender = ensure_signed(origin)?;
+			ensure!(Self::is_kitty_owner(&sender, kitty_id), "Only owner can name kitty");
+
+			let name = Self::normalize_name(name)?;
+			ensure!(!<NamedKitties<T>>::exists(&name), "Name is already taken");
+
+			let fee = Self::name_fee(name.len());
+			let imbalance = T::Currency::withdraw(&sender, fee, WithdrawReason::Fee, ExistenceRequirement::KeepAlive)?;
+			// burn the fee
+			drop(imbalance);
+
+			Self::release_name_of(kitty_id);
+			<KittyNames<T>>::insert(kitty_id, name.clone());
+			<NamedKitties<T>>::insert(&name, kitty_id);
+
+			Self::deposit_event(RawEvent::NameClaimed(sender, kitty_id, name, fee));
+		}
+
+		/// Release the name of a kitty
+		pub fn release_name(origin, kitty_id: T::KittyIndex) {
+			let sender = ensure_signed(origin)?;
+			ensure!(Self::is_kitty_owner(&sender, kitty_id), "Only owner can release kitty name");
+			ensure!(<KittyNames<T>>::exists(kitty_id), "Kitty has no name");
+
+			Self::release_name_of(kitty_id);
+		}
+
+		/// Gift a kitty to new owner with a memo
+		pub fn gift_kitty(origin, to: T::AccountId, kitty_id: T::KittyIndex, memo: Vec<u8>) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(memo.len() <= MAX_MEMO_LENGTH, "Memo is too long");
+			ensure!(!Self::is_locked(kitty_id), "Kitty is locked in an auction");
+
+			Self::move_kitty(&sender, &to, kitty_id, true)?;
+			Self::deposit_event(RawEvent::Gifted(sender, to, kitty_id, memo));
+		}
 	}
 }
 
@@ -123,6 +337,17 @@ impl<T: Trait> Module<T> {
 		<KittyOwners<T>>::insert(kitty_id, owner.clone());
 
 		Self::insert_owned_kitty(owner, kitty_id);
+		T::OnKittyTransferred::on_item_minted(owner, kitty_id);
+	}
+
+	/// Error of moving a kitty not owned by the account, which may not exist at all
+	fn ownership_error(kitty_id: T::KittyIndex) -> ItemTransferError {
+		if <Kitties<T>>::exists(kitty_id) { ItemTransferError::NotOwner } else { ItemTransferError::Unknown }
+	}
+
+	/// Whether the kitty is held by another module, or locked by one
+	fn is_locked(kitty_id: T::KittyIndex) -> bool {
+		Self::is_reserved(kitty_id) || T::ItemLocks::is_item_locked(kitty_id)
 	}
 
 	fn is_kitty_owner(who: &T::AccountId, kitty_id: T::KittyIndex) -> bool {
@@ -130,28 +355,53 @@ impl<T: Trait> Module<T> {
 	}
 
 	fn transfer_kitty(owner: &T::AccountId, to: &T::AccountId, kitty_id: T::KittyIndex) -> result::Result<(), &'static str> {
+		Self::move_kitty(owner, to, kitty_id, false)
+	}
+
+	fn ensure_kitty_transferable(owner: &T::AccountId, kitty_id: T::KittyIndex) -> result::Result<(), &'static str> {
 		ensure!(Self::is_kitty_owner(owner, kitty_id), "Only owner can transfer kitty");
+		Ok(())
+	}
+
+	fn move_kitty(owner: &T::AccountId, to: &T::AccountId, kitty_id: T::KittyIndex, gift: bool) -> result::Result<(), &'static str> {
+		Self::ensure_kitty_transferable(owner, kitty_id)?;
+		// 被占用的猫咪只能由占用方转移
+		ensure!(!Self::is_reserved(kitty_id), "Kitty is reserved");
 
 		Self::do_transfer(owner, to, kitty_id);
 		Self::deposit_event(RawEvent::Transferred(owner.clone(), to.clone(), kitty_id));
+		T::OnKittyTransferred::on_item_transferred(owner, to, kitty_id, gift);
 
 		Ok(())
 	}
 
 	fn do_breed(sender: &T::AccountId, kitty_id_1: T::KittyIndex, kitty_id_2: T::KittyIndex) -> result::Result<T::KittyIndex, &'static str> {
-		let kitty1 = Self::kitty(kitty_id_1);
-		let kitty2 = Self::kitty(kitty_id_2);
-
-		ensure!(kitty1.is_some(), "Invalid kitty_id_1");
-		ensure!(kitty2.is_some(), "Invalid kitty_id_2");
-		ensure!(kitty_id_1 != kitty_id_2, "Needs different parent");
+		Self::ensure_parents(kitty_id_1, kitty_id_2)?;
 		ensure!(Self::kitty_owner(&kitty_id_1).map(|owner| owner == *sender).unwrap_or(false), "Not onwer of kitty1");
  		ensure!(Self::kitty_owner(&kitty_id_2).map(|owner| owner == *sender).unwrap_or(false), "Not owner of kitty2");
 
+		Self::breed_unchecked(sender, kitty_id_1, kitty_id_2)
+	}
+
+	fn ensure_parents(kitty_id_1: T::KittyIndex, kitty_id_2: T::KittyIndex) -> result::Result<(), &'static str> {
+		ensure!(<Kitties<T>>::exists(kitty_id_1), "Invalid kitty_id_1");
+		ensure!(<Kitties<T>>::exists(kitty_id_2), "Invalid kitty_id_2");
+		ensure!(kitty_id_1 != kitty_id_2, "Needs different parent");
+
+		let now = <system::Module<T>>::block_number();
+		ensure!(Self::breeding_cooldown(kitty_id_1) <= now, "Kitty1 is cooling down");
+		ensure!(Self::breeding_cooldown(kitty_id_2) <= now, "Kitty2 is cooling down");
+
+		Ok(())
+	}
+
+	/// Create a child of two kitties, both parents go into cooldown.
+	/// Parents should be checked by `ensure_parents` before.
+	fn breed_unchecked(sender: &T::AccountId, kitty_id_1: T::KittyIndex, kitty_id_2: T::KittyIndex) -> result::Result<T::KittyIndex, &'static str> {
 		let kitty_id = Self::next_kitty_id()?;
 
-		let kitty1_dna = kitty1.unwrap().0;
-		let kitty2_dna = kitty2.unwrap().0;
+		let kitty1_dna = Self::kitty(kitty_id_1).ok_or("Invalid kitty_id_1")?.0;
+		let kitty2_dna = Self::kitty(kitty_id_2).ok_or("Invalid kitty_id_2")?.0;
 
 		// Generate a random 128bit value
 		let selector = Self::random_value(&sender);
@@ -164,13 +414,90 @@ impl<T: Trait> Module<T> {
 
 		Self::insert_kitty(sender, kitty_id, Kitty(new_dna));
 
+		let cooldown_until = <system::Module<T>>::block_number() + T::BreedingCooldown::get();
+		<BreedingCooldowns<T>>::insert(kitty_id_1, cooldown_until);
+		<BreedingCooldowns<T>>::insert(kitty_id_2, cooldown_until);
+
+		T::OnKittyBred::on_kitty_bred(sender, kitty_id);
+
 		Ok(kitty_id)
 	}
 
+	/// Current price of a generation-0 kitty, increasing stepwise with the gen-0 supply
+	pub fn gen0_price() -> BalanceOf<T> {
+		let step = T::Gen0PriceStep::get().max(1);
+		let level = Self::gen0_count() / step;
+		T::Gen0BasePrice::get().saturating_mul(level.saturating_add(1).into())
+	}
+
+	/// Remove a kitty and everything attached to it
+	fn burn_kitty(owner: &T::AccountId, kitty_id: T::KittyIndex) {
+		<OwnedKittiesList<T>>::remove(owner, kitty_id);
+		<Kitties<T>>::remove(kitty_id);
+		<KittyOwners<T>>::remove(kitty_id);
+		<KittyExperience<T>>::remove(kitty_id);
+		<BreedingCooldowns<T>>::remove(kitty_id);
+		<StudListings<T>>::remove(kitty_id);
+		<Gen0Kitties<T>>::remove(kitty_id);
+		<ReservedKitties<T>>::remove(kitty_id);
+		Self::release_name_of(kitty_id);
+
+		T::OnKittyBurned::on_item_burned(owner, kitty_id);
+	}
+
+	/// Names are ascii alphanumeric, case insensitive
+	fn normalize_name(name: Vec<u8>) -> result::Result<Vec<u8>, &'static str> {
+		ensure!(name.len() >= MIN_NAME_LENGTH, "Name is too short");
+		ensure!(name.len() <= MAX_NAME_LENGTH, "Name is too long");
+		ensure!(name.iter().all(|c| c.is_ascii_alphanumeric()), "Name should be ascii alphanumeric");
+		Ok(name.to_ascii_lowercase())
+	}
+
+	/// Fee for claiming a name, longer names are cheaper
+	pub fn name_fee(length: usize) -> BalanceOf<T> {
+		let units = (MAX_NAME_LENGTH + 1).saturating_sub(length) as u32;
+		T::NameFee::get() * units.into()
+	}
+
+	fn release_name_of(kitty_id: T::KittyIndex) {
+		if let Some(name) = <KittyNames<T>>::take(kitty_id) {
+			<NamedKitties<T>>::remove(&name);
+			Self::deposit_event(RawEvent::NameReleased(kitty_id, name));
+		}
+	}
+
+	/// Level of a kitty with the given experience
+	pub fn level_of(experience: u64) -> u32 {
+		(experience / EXPERIENCE_PER_LEVEL).integer_sqrt().saturated_into()
+	}
+
+	/// Battle strength of a kitty, base strength from its DNA plus a bonus per level
+	pub fn strength_of(dna: &[u8; 16], level: u32) -> u32 {
+		let base = dna.iter().take(4).map(|g| *g as u32).sum::<u32>() / 4;
+		base.saturating_add(level.saturating_mul(8))
+	}
+
+	/// Public attributes of a kitty
+	pub fn attributes(kitty_id: T::KittyIndex) -> Option<KittyAttributes> {
+		let dna = Self::kitty(kitty_id)?.0;
+		let experience = Self::kitty_experience(kitty_id);
+		let level = Self::level_of(experience);
+		Some(KittyAttributes {
+			dna: dna,
+			experience: experience,
+			level: level,
+			strength: Self::strength_of(&dna, level),
+		})
+	}
+
 	fn do_transfer(from: &T::AccountId, to: &T::AccountId, kitty_id: T::KittyIndex)  {
  		<OwnedKittiesList<T>>::remove(&from, kitty_id);
  		<OwnedKittiesList<T>>::append(&to, kitty_id);
  		<KittyOwners<T>>::insert(kitty_id, to);
+		// the hold is released once the kitty changes hands
+		<ReservedKitties<T>>::remove(kitty_id);
+		// the stud fee was posted by the previous owner
+		<StudListings<T>>::remove(kitty_id);
  	}
 }
 
@@ -186,8 +513,91 @@ impl<T: Trait> ItemTransfer<<T as system::Trait>::AccountId, T::KittyIndex> for
 		source: &<T as system::Trait>::AccountId,
 		dest: &<T as system::Trait>::AccountId,
 		item_id: T::KittyIndex
-	) -> result::Result<(), &'static str> {
-		Module::<T>::transfer_kitty(source, dest, item_id)
+	) -> result::Result<(), ItemTransferError> {
+		ensure!(!Module::<T>::is_reserved(item_id), ItemTransferError::Frozen);
+		Module::<T>::transfer_kitty(source, dest, item_id).map_err(|_| Module::<T>::ownership_error(item_id))
+	}
+
+	fn transfer_reserved_item(
+		source: &<T as system::Trait>::AccountId,
+		dest: &<T as system::Trait>::AccountId,
+		item_id: T::KittyIndex
+	) -> result::Result<(), ItemTransferError> {
+		Self::can_transfer_reserved(source, item_id)?;
+		<ReservedKitties<T>>::remove(item_id);
+		Module::<T>::transfer_kitty(source, dest, item_id).map_err(|_| Module::<T>::ownership_error(item_id))
+	}
+
+	fn can_transfer(
+		from: &<T as system::Trait>::AccountId,
+		item_id: T::KittyIndex
+	) -> result::Result<(), ItemTransferError> {
+		Module::<T>::ensure_kitty_transferable(from, item_id).map_err(|_| Module::<T>::ownership_error(item_id))?;
+		// held, wrapped into shares or bridged out
+		ensure!(!Module::<T>::is_locked(item_id), ItemTransferError::Frozen);
+		Ok(())
+	}
+
+	fn can_transfer_reserved(
+		from: &<T as system::Trait>::AccountId,
+		item_id: T::KittyIndex
+	) -> result::Result<(), ItemTransferError> {
+		ensure!(Module::<T>::is_kitty_owner(from, item_id), Module::<T>::ownership_error(item_id));
+		ensure!(Module::<T>::is_reserved(item_id), ItemTransferError::Unknown);
+		Ok(())
+	}
+
+	fn reserve_item(
+		who: &<T as system::Trait>::AccountId,
+		item_id: T::KittyIndex
+	) -> result::Result<(), ItemTransferError> {
+		ensure!(Module::<T>::is_kitty_owner(who, item_id), Module::<T>::ownership_error(item_id));
+		ensure!(!Module::<T>::is_reserved(item_id), ItemTransferError::Frozen);
+		<ReservedKitties<T>>::insert(item_id, true);
+		Ok(())
+	}
+
+	fn unreserve_item(item_id: T::KittyIndex) {
+		<ReservedKitties<T>>::remove(item_id);
+	}
+}
+
+impl<T: Trait> OwnedItems<<T as system::Trait>::AccountId, T::KittyIndex> for Module<T> {
+	fn owned_items(who: &<T as system::Trait>::AccountId) -> Vec<T::KittyIndex> {
+		<OwnedKittiesList<T>>::collect(who)
+	}
+}
+
+impl<T: Trait> ItemMint<<T as system::Trait>::AccountId, T::KittyIndex> for Module<T> {
+	/// Mint a gen-0 kitty for free, it does not count into the gen-0 price curve
+	fn mint_item(owner: &<T as system::Trait>::AccountId) -> result::Result<T::KittyIndex, &'static str> {
+		let kitty_id = Self::next_kitty_id()?;
+		let dna = Self::random_value(owner);
+
+		Self::insert_kitty(owner, kitty_id, Kitty(dna));
+		<Gen0Kitties<T>>::insert(kitty_id, true);
+
+		Self::deposit_event(RawEvent::Created(owner.clone(), kitty_id));
+		Ok(kitty_id)
+	}
+}
+
+/// Runtime api of kitties module
+pub mod api {
+	use codec::Codec;
+	use rstd::vec::Vec;
+
+	client::decl_runtime_apis! {
+		/// The api to query kitties.
+		pub trait KittiesApi<KittyIndex, Attributes> where
+			KittyIndex: Codec,
+			Attributes: Codec,
+		{
+			/// Get attributes of a kitty, including its level and strength.
+			fn attributes(kitty_id: KittyIndex) -> Option<Attributes>;
+			/// Lookup a kitty by its name.
+			fn kitty_by_name(name: Vec<u8>) -> Option<KittyIndex>;
+		}
 	}
 }
 
@@ -197,7 +607,7 @@ mod tests {
 	use super::*;
 
 	use primitives::{H256};
-	use support::{impl_outer_origin, parameter_types};
+	use support::{impl_outer_origin, parameter_types, assert_ok, assert_noop};
 	use sr_primitives::{traits::{BlakeTwo256, IdentityLookup}, testing::Header};
 	use sr_primitives::weights::Weight;
 	use sr_primitives::Perbill;
@@ -234,16 +644,55 @@ mod tests {
 		type AvailableBlockRatio = AvailableBlockRatio;
 		type Version = ();
 	}
+	parameter_types! {
+		pub const ExistentialDeposit: u64 = 0;
+		pub const TransferFee: u64 = 0;
+		pub const CreationFee: u64 = 0;
+		pub const ExperiencePrice: u64 = 10;
+		pub const NameFee: u64 = 1;
+		pub const BreedingCooldown: u64 = 10;
+		pub const Gen0BasePrice: u64 = 10;
+		pub const Gen0PriceStep: u32 = 2;
+	}
+	impl balances::Trait for Test {
+		type Balance = u64;
+		type OnFreeBalanceZero = ();
+		type OnNewAccount = ();
+		type Event = ();
+		type TransferPayment = ();
+		type DustRemoval = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type TransferFee = TransferFee;
+		type CreationFee = CreationFee;
+	}
 	impl Trait for Test {
 		type KittyIndex = u32;
 		type Event = ();
+		type ItemLocks = ();
+		type Currency = balances::Module<Test>;
+		type ExperiencePrice = ExperiencePrice;
+		type NameFee = NameFee;
+		type BreedingCooldown = BreedingCooldown;
+		type OnKittyBurned = ();
+		type OnKittyBred = ();
+		type OnKittyTransferred = ();
+		type Gen0BasePrice = Gen0BasePrice;
+		type Gen0PriceStep = Gen0PriceStep;
+		type OnMintPayment = ();
 	}
 	type OwnedKittiesTest = OwnedKitties<Test>;
+	type KittyModule = Module<Test>;
+	type Balances = balances::Module<Test>;
 
 	// This function basically just builds a genesis storage key/value store according to
 	// our desired mockup.
 	fn new_test_ext() -> runtime_io::TestExternalities {
-		system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
+		let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+		balances::GenesisConfig::<Test> {
+			balances: vec![(1, 1000), (2, 1000)],
+			vesting: vec![],
+		}.assimilate_storage(&mut t).unwrap();
+		t.into()
 	}
 
 	#[test]
@@ -358,4 +807,169 @@ mod tests {
 			assert_eq!(OwnedKittiesTest::get(&(0, Some(2))), None);
 		});
 	}
+
+	#[test]
+	fn gift_kitty_works() {
+		new_test_ext().execute_with(|| {
+			KittyModule::insert_kitty(&1, 0, Kitty([0u8; 16]));
+
+			assert_noop!(KittyModule::gift_kitty(Origin::signed(1), 2, 0, vec![0u8; MAX_MEMO_LENGTH + 1]), "Memo is too long");
+			assert_noop!(KittyModule::gift_kitty(Origin::signed(2), 3, 0, b"meow".to_vec()), "Only owner can transfer kitty");
+
+			assert_ok!(KittyModule::gift_kitty(Origin::signed(1), 2, 0, b"meow".to_vec()));
+			assert_eq!(KittyModule::kitty_owner(0), Some(2));
+		});
+	}
+
+	#[test]
+	fn reserved_kitty_can_not_be_moved_by_owner() {
+		new_test_ext().execute_with(|| {
+			KittyModule::insert_kitty(&1, 0, Kitty([0u8; 16]));
+
+			assert_noop!(KittyModule::reserve_item(&2, 0), ItemTransferError::NotOwner);
+			assert_noop!(KittyModule::reserve_item(&1, 1), ItemTransferError::Unknown);
+			assert_ok!(KittyModule::reserve_item(&1, 0));
+			assert_noop!(KittyModule::reserve_item(&1, 0), ItemTransferError::Frozen);
+			assert_noop!(KittyModule::transfer(Origin::signed(1), 2, 0), "Kitty is locked in an auction");
+			assert_noop!(KittyModule::gift_kitty(Origin::signed(1), 2, 0, vec![]), "Kitty is locked in an auction");
+
+			// other modules can not move a held kitty either
+			assert_eq!(KittyModule::can_transfer(&1, 0), Err(ItemTransferError::Frozen));
+			assert_noop!(KittyModule::transfer_item(&1, &2, 0), ItemTransferError::Frozen);
+			assert_ok!(KittyModule::can_transfer_reserved(&1, 0));
+			assert_noop!(KittyModule::transfer_reserved_item(&2, &3, 0), ItemTransferError::NotOwner);
+
+			// the holder still moves the kitty, which releases the hold
+			assert_ok!(KittyModule::transfer_reserved_item(&1, &2, 0));
+			assert!(!KittyModule::is_reserved(0));
+			assert_eq!(KittyModule::kitty_owner(0), Some(2));
+			assert_noop!(KittyModule::transfer_reserved_item(&2, &1, 0), ItemTransferError::Unknown);
+			assert_ok!(KittyModule::transfer(Origin::signed(2), 1, 0));
+		});
+	}
+
+	#[test]
+	fn kitty_names_are_unique() {
+		new_test_ext().execute_with(|| {
+			KittyModule::insert_kitty(&1, 0, Kitty([0u8; 16]));
+			KittyModule::insert_kitty(&1, 1, Kitty([0u8; 16]));
+
+			assert_noop!(KittyModule::claim_name(Origin::signed(1), 0, b"ab".to_vec()), "Name is too short");
+			assert_noop!(KittyModule::claim_name(Origin::signed(1), 0, b"tom cat".to_vec()), "Name should be ascii alphanumeric");
+
+			assert_ok!(KittyModule::claim_name(Origin::signed(1), 0, b"Tom".to_vec()));
+			assert_eq!(Balances::free_balance(&1), 1000 - 30);
+			assert_eq!(KittyModule::kitty_by_name(b"tom".to_vec()), Some(0));
+			assert_noop!(KittyModule::claim_name(Origin::signed(1), 1, b"TOM".to_vec()), "Name is already taken");
+
+			// renaming releases the old name
+			assert_ok!(KittyModule::claim_name(Origin::signed(1), 0, b"Thomas".to_vec()));
+			assert_eq!(KittyModule::kitty_by_name(b"tom".to_vec()), None);
+			assert_ok!(KittyModule::claim_name(Origin::signed(1), 1, b"tom".to_vec()));
+		});
+	}
+
+	#[test]
+	fn breed_with_listed_stud_pays_fee() {
+		new_test_ext().execute_with(|| {
+			KittyModule::insert_kitty(&1, 0, Kitty([0u8; 16]));
+			KittyModule::insert_kitty(&2, 1, Kitty([1u8; 16]));
+
+			assert_noop!(KittyModule::breed_with_stud(Origin::signed(2), 1, 0, 100), "Kitty is not listed as stud");
+			assert_ok!(KittyModule::list_stud(Origin::signed(1), 0, Some(50)));
+			assert_noop!(KittyModule::breed_with_stud(Origin::signed(2), 1, 0, 49), "Stud fee is higher than max fee");
+
+			assert_ok!(KittyModule::breed_with_stud(Origin::signed(2), 1, 0, 50));
+			assert_eq!(KittyModule::kitty_owner(2), Some(2));
+			assert_eq!(Balances::free_balance(&1), 1050);
+			assert_eq!(Balances::free_balance(&2), 950);
+
+			// both parents are cooling down
+			assert_noop!(KittyModule::breed_with_stud(Origin::signed(2), 1, 0, 50), "Kitty1 is cooling down");
+			assert_eq!(KittyModule::breeding_cooldown(0), 10);
+		});
+	}
+
+	#[test]
+	fn stud_listing_is_removed_on_transfer() {
+		new_test_ext().execute_with(|| {
+			KittyModule::insert_kitty(&1, 0, Kitty([0u8; 16]));
+			KittyModule::insert_kitty(&2, 1, Kitty([1u8; 16]));
+			assert_ok!(KittyModule::list_stud(Origin::signed(1), 0, Some(50)));
+
+			assert_ok!(KittyModule::transfer(Origin::signed(1), 3, 0));
+			assert_eq!(KittyModule::stud_fee(0), None);
+			assert_noop!(KittyModule::breed_with_stud(Origin::signed(2), 1, 0, 50), "Kitty is not listed as stud");
+		});
+	}
+
+	#[test]
+	fn fuse_burns_parents() {
+		new_test_ext().execute_with(|| {
+			KittyModule::insert_kitty(&1, 0, Kitty([3u8; 16]));
+			KittyModule::insert_kitty(&1, 1, Kitty([5u8; 16]));
+			<KittyExperience<Test>>::insert(0, 30);
+			<KittyExperience<Test>>::insert(1, 70);
+			assert_ok!(KittyModule::claim_name(Origin::signed(1), 0, b"tom".to_vec()));
+
+			assert_noop!(KittyModule::fuse(Origin::signed(2), 0, 1), "Not owner of kitty1");
+			assert_ok!(KittyModule::fuse(Origin::signed(1), 0, 1));
+
+			assert!(KittyModule::kitty(0).is_none());
+			assert!(KittyModule::kitty(1).is_none());
+			assert_eq!(KittyModule::owned_items(&1), vec![2]);
+			assert_eq!(KittyModule::kitty_experience(2), 100);
+			assert!(KittyModule::kitty(2).unwrap().0.iter().all(|g| *g >= 5));
+			// name is released
+			assert_eq!(KittyModule::kitty_by_name(b"tom".to_vec()), None);
+		});
+	}
+
+	#[test]
+	fn gen0_price_grows_with_supply() {
+		new_test_ext().execute_with(|| {
+			assert_eq!(KittyModule::gen0_price(), 10);
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+			assert_eq!(Balances::free_balance(&1), 1000 - 20);
+
+			assert_eq!(KittyModule::gen0_price(), 20);
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+			assert_eq!(Balances::free_balance(&1), 1000 - 40);
+			assert_eq!(KittyModule::gen0_count(), 3);
+
+			// breeding does not count as gen-0
+			assert_ok!(KittyModule::breed(Origin::signed(1), 0, 1));
+			assert_eq!(KittyModule::gen0_count(), 3);
+		});
+	}
+
+	#[test]
+	fn gen0_price_does_not_overflow() {
+		new_test_ext().execute_with(|| {
+			Gen0Count::put(u32::max_value());
+			assert_eq!(KittyModule::gen0_price(), 10 * (u32::max_value() as u64 / 2 + 1));
+		});
+	}
+
+	#[test]
+	fn feeding_burns_tokens_and_levels_up() {
+		new_test_ext().execute_with(|| {
+			KittyModule::insert_kitty(&1, 0, Kitty([0u8; 16]));
+
+			assert_noop!(KittyModule::feed(Origin::signed(2), 0, 100), "Only owner can feed kitty");
+			assert_noop!(KittyModule::feed(Origin::signed(1), 0, 9), "Amount is less than the price of one experience point");
+
+			// 405 buys 40 experience points, the remainder is not charged
+			assert_ok!(KittyModule::feed(Origin::signed(1), 0, 405));
+			assert_eq!(Balances::free_balance(&1), 600);
+			assert_eq!(KittyModule::kitty_experience(0), 40);
+			assert_eq!(KittyModule::attributes(0).unwrap().level, 0);
+
+			assert_ok!(KittyModule::feed(Origin::signed(1), 0, 600));
+			assert_eq!(KittyModule::attributes(0).unwrap().level, 1);
+			// all fed tokens are burned, only account 2's balance is left
+			assert_eq!(Balances::total_issuance(), 1000);
+		});
+	}
 }
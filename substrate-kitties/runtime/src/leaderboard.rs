@@ -0,0 +1,239 @@
+//! Bounded leaderboards of top sellers by volume and top buyers by spend.
+//!
+//! Fed by the `OnItemSold` hook. The boards are reset every epoch, the boards of
+//! the previous epoch are kept as the result of the trading competition.
+
+use support::{decl_module, decl_storage, decl_event};
+use support::traits::{Get, Currency};
+use sr_primitives::traits::{Zero, Saturating};
+use codec::{Encode, Decode};
+use rstd::prelude::*;
+use crate::traits::OnItemSold;
+
+pub trait Trait: system::Trait {
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+	/// Currency the items are paid with
+	type Currency: Currency<Self::AccountId>;
+	/// Number of blocks in a competition epoch
+	type EpochLength: Get<Self::BlockNumber>;
+	/// Max number of accounts on a leaderboard
+	type MaxLeaderboardSize: Get<u32>;
+}
+
+pub type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+
+#[derive(Encode, Decode, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct Leaderboard<AccountId, Balance, BlockNumber> {
+	pub epoch: BlockNumber, // 竞赛周期序号
+	pub entries: Vec<(AccountId, Balance)>, // 按金额从高到低排列
+}
+
+type LeaderboardOf<T> = Leaderboard<<T as system::Trait>::AccountId, BalanceOf<T>, <T as system::Trait>::BlockNumber>;
+
+decl_storage! {
+	trait Store for Module<T: Trait> as Leaderboards {
+		/// Top sellers by volume of the current epoch
+		pub TopSellers get(fn top_sellers): LeaderboardOf<T>;
+		/// Top buyers by spend of the current epoch
+		pub TopBuyers get(fn top_buyers): LeaderboardOf<T>;
+		/// Final top sellers of the previous epoch
+		pub LastTopSellers get(fn last_top_sellers): LeaderboardOf<T>;
+		/// Final top buyers of the previous epoch
+		pub LastTopBuyers get(fn last_top_buyers): LeaderboardOf<T>;
+
+		/// Sold volume of an account in the epoch it last sold
+		pub SellerVolumes get(fn seller_volume): map T::AccountId => (T::BlockNumber, BalanceOf<T>);
+		/// Spend of an account in the epoch it last bought
+		pub BuyerSpends get(fn buyer_spend): map T::AccountId => (T::BlockNumber, BalanceOf<T>);
+	}
+}
+
+decl_event!(
+	pub enum Event<T> where
+		<T as system::Trait>::BlockNumber,
+	{
+		/// The leaderboards of an epoch are closed. (epoch)
+		LeaderboardsClosed(BlockNumber),
+	}
+);
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		fn deposit_event() = default;
+	}
+}
+
+impl<T: Trait> Module<T> {
+	fn current_epoch() -> T::BlockNumber {
+		let length = T::EpochLength::get();
+		if length.is_zero() {
+			return Zero::zero();
+		}
+		<system::Module<T>>::block_number() / length
+	}
+
+	/// Move the boards of a finished epoch to the last boards
+	fn roll_epoch(epoch: T::BlockNumber) {
+		let sellers = Self::top_sellers();
+		if sellers.epoch == epoch {
+			return;
+		}
+		let buyers = Self::top_buyers();
+
+		<LastTopSellers<T>>::put(sellers.clone());
+		<LastTopBuyers<T>>::put(buyers);
+		<TopSellers<T>>::put(Leaderboard { epoch: epoch, entries: Vec::new() });
+		<TopBuyers<T>>::put(Leaderboard { epoch: epoch, entries: Vec::new() });
+
+		if !sellers.entries.is_empty() {
+			Self::deposit_event(RawEvent::LeaderboardsClosed(sellers.epoch));
+		}
+	}
+
+	/// Add the amount to the epoch total of the account and return the new total
+	fn add_to_total(
+		totals: (T::BlockNumber, BalanceOf<T>),
+		epoch: T::BlockNumber,
+		amount: BalanceOf<T>,
+	) -> (T::BlockNumber, BalanceOf<T>) {
+		let (last_epoch, total) = totals;
+		if last_epoch == epoch {
+			(epoch, total.saturating_add(amount))
+		} else {
+			(epoch, amount)
+		}
+	}
+
+	/// Place the account on the board with its new total, keeping the board bounded
+	fn update_board(board: &mut LeaderboardOf<T>, who: &T::AccountId, total: BalanceOf<T>) {
+		board.entries.retain(|(account, _)| account != who);
+		let pos = board.entries.iter().position(|(_, amount)| *amount < total).unwrap_or(board.entries.len());
+		board.entries.insert(pos, (who.clone(), total));
+		board.entries.truncate(T::MaxLeaderboardSize::get() as usize);
+	}
+}
+
+impl<T: Trait, ItemId> OnItemSold<T::AccountId, ItemId, BalanceOf<T>> for Module<T> {
+	fn on_item_sold(seller: &T::AccountId, buyer: &T::AccountId, _item_id: ItemId, price: BalanceOf<T>) {
+		let epoch = Self::current_epoch();
+		Self::roll_epoch(epoch);
+
+		let seller_total = Self::add_to_total(Self::seller_volume(seller), epoch, price);
+		<SellerVolumes<T>>::insert(seller, seller_total);
+		<TopSellers<T>>::mutate(|board| Self::update_board(board, seller, seller_total.1));
+
+		let buyer_total = Self::add_to_total(Self::buyer_spend(buyer), epoch, price);
+		<BuyerSpends<T>>::insert(buyer, buyer_total);
+		<TopBuyers<T>>::mutate(|board| Self::update_board(board, buyer, buyer_total.1));
+	}
+}
+
+/// Tests for Leaderboard module
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use primitives::H256;
+	use support::{impl_outer_origin, parameter_types};
+	use sr_primitives::{traits::{BlakeTwo256, IdentityLookup}, testing::Header};
+	use sr_primitives::weights::Weight;
+	use sr_primitives::Perbill;
+
+	impl_outer_origin! {
+		pub enum Origin for Test {}
+	}
+
+	#[derive(Clone, Eq, PartialEq, Debug)]
+	pub struct Test;
+	parameter_types! {
+		pub const BlockHashCount: u64 = 250;
+		pub const MaximumBlockWeight: Weight = 1024;
+		pub const MaximumBlockLength: u32 = 2 * 1024;
+		pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+	}
+	impl system::Trait for Test {
+		type Origin = Origin;
+		type Call = ();
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type Event = ();
+		type BlockHashCount = BlockHashCount;
+		type MaximumBlockWeight = MaximumBlockWeight;
+		type MaximumBlockLength = MaximumBlockLength;
+		type AvailableBlockRatio = AvailableBlockRatio;
+		type Version = ();
+	}
+	parameter_types! {
+		pub const ExistentialDeposit: u64 = 0;
+		pub const TransferFee: u64 = 0;
+		pub const CreationFee: u64 = 0;
+		pub const EpochLength: u64 = 10;
+		pub const MaxLeaderboardSize: u32 = 2;
+	}
+	impl balances::Trait for Test {
+		type Balance = u64;
+		type OnFreeBalanceZero = ();
+		type OnNewAccount = ();
+		type Event = ();
+		type TransferPayment = ();
+		type DustRemoval = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type TransferFee = TransferFee;
+		type CreationFee = CreationFee;
+	}
+	impl Trait for Test {
+		type Event = ();
+		type Currency = balances::Module<Test>;
+		type EpochLength = EpochLength;
+		type MaxLeaderboardSize = MaxLeaderboardSize;
+	}
+	type Leaderboards = Module<Test>;
+	type System = system::Module<Test>;
+
+	fn new_test_ext() -> runtime_io::TestExternalities {
+		system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
+	}
+
+	fn sold(seller: u64, buyer: u64, price: u64) {
+		<Leaderboards as OnItemSold<u64, u32, u64>>::on_item_sold(&seller, &buyer, 0, price);
+	}
+
+	#[test]
+	fn boards_are_sorted_and_bounded() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			sold(1, 4, 100);
+			sold(2, 5, 150);
+			sold(1, 4, 80);
+			sold(3, 6, 10);
+
+			assert_eq!(Leaderboards::top_sellers().entries, vec![(1, 180), (2, 150)]);
+			assert_eq!(Leaderboards::top_buyers().entries, vec![(4, 180), (5, 150)]);
+			assert_eq!(Leaderboards::seller_volume(&3), (0, 10));
+		});
+	}
+
+	#[test]
+	fn boards_are_closed_with_the_epoch() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			sold(1, 4, 100);
+			sold(2, 5, 150);
+
+			// 新周期的首笔成交结算上个周期
+			System::set_block_number(12);
+			sold(1, 4, 20);
+			assert_eq!(Leaderboards::last_top_sellers(), Leaderboard { epoch: 0, entries: vec![(2, 150), (1, 100)] });
+			assert_eq!(Leaderboards::last_top_buyers().entries, vec![(5, 150), (4, 100)]);
+			assert_eq!(Leaderboards::top_sellers(), Leaderboard { epoch: 1, entries: vec![(1, 20)] });
+			assert_eq!(Leaderboards::seller_volume(&1), (1, 20));
+			assert_eq!(Leaderboards::seller_volume(&2), (0, 150));
+		});
+	}
+}
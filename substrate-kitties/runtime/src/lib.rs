@@ -9,16 +9,17 @@
 include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
 
 use rstd::prelude::*;
+use codec::{Encode, Decode};
 use primitives::{OpaqueMetadata, crypto::key_types};
 use sr_primitives::{
-	ApplyResult, transaction_validity::TransactionValidity, generic, create_runtime_str,
-	impl_opaque_keys, AnySignature
+	ApplyResult, generic, create_runtime_str, impl_opaque_keys, AnySignature, RuntimeDebug, ModuleId,
+	transaction_validity::{TransactionValidity, TransactionValidityError, ValidTransaction, InvalidTransaction},
 };
 use sr_primitives::traits::{
 	NumberFor, BlakeTwo256, Block as BlockT, StaticLookup, Verify, ConvertInto,
-	Extrinsic, SaturatedConversion,
+	Extrinsic, SaturatedConversion, SignedExtension,
 };
-use sr_primitives::weights::Weight;
+use sr_primitives::weights::{Weight, DispatchInfo};
 use client::{
 	block_builder::api::{CheckInherentsResult, InherentData, self as block_builder_api},
 	runtime_api as client_api, impl_runtime_apis
@@ -38,6 +39,7 @@ pub use timestamp::Call as TimestampCall;
 pub use balances::Call as BalancesCall;
 pub use sr_primitives::{Permill, Perbill};
 pub use support::{StorageValue, construct_runtime, parameter_types, traits::Randomness};
+use support::traits::LockIdentifier;
 
 /// An index to a block.
 pub type BlockNumber = u32;
@@ -75,6 +77,243 @@ mod kitties;
 /// Used for the module auction in `./auction` folder
 mod auction;
 
+/// Used for the module recovery in `./recovery.rs`
+mod recovery;
+
+/// Used for the module gallery in `./gallery.rs`
+mod gallery;
+
+/// Used for the module accessories in `./accessories.rs`
+mod accessories;
+
+/// Used for the module orderbook in `./orderbook.rs`
+mod orderbook;
+
+/// Used for the module analytics in `./analytics.rs`
+mod analytics;
+
+/// Used for the module leaderboard in `./leaderboard.rs`
+mod leaderboard;
+
+/// Used for the module badges in `./badges.rs`
+mod badges;
+
+/// Used for the module reputation in `./reputation.rs`
+mod reputation;
+
+/// Used for the module airdrop in `./airdrop.rs`
+mod airdrop;
+
+/// Used for the module insurance in `./insurance.rs`
+mod insurance;
+
+/// Used for the module shares in `./shares.rs`
+mod shares;
+
+/// Used for the module bridge in `./bridge.rs`
+mod bridge;
+
+/// Used for the module provenance in `./provenance.rs`
+mod provenance;
+
+/// Items kept in thread locals for the module tests, in `./mock_items.rs`
+#[cfg(test)]
+mod mock_items;
+
+use traits::{ItemTransfer, ItemTransferError, IsItemLocked, ClassifyItem, OnItemTransferred};
+
+/// Items which can be traded in the auction module.
+#[derive(Encode, Decode, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum MarketItem {
+	Kitty(u32),
+	Accessory(u32),
+}
+
+impl Default for MarketItem {
+	fn default() -> Self {
+		MarketItem::Kitty(0)
+	}
+}
+
+/// Auction items were kitty ids before accessories could be listed.
+impl From<u32> for MarketItem {
+	fn from(kitty_id: u32) -> Self {
+		MarketItem::Kitty(kitty_id)
+	}
+}
+
+/// Routes auction item transfers to the module owning the item.
+pub struct MarketItems;
+impl ItemTransfer<AccountId, MarketItem> for MarketItems {
+	fn is_item_owner(who: &AccountId, item_id: MarketItem) -> bool {
+		match item_id {
+			MarketItem::Kitty(id) => Kitties::is_item_owner(who, id),
+			MarketItem::Accessory(id) => Accessories::is_item_owner(who, id),
+		}
+	}
+
+	fn transfer_item(source: &AccountId, dest: &AccountId, item_id: MarketItem) -> rstd::result::Result<(), ItemTransferError> {
+		match item_id {
+			MarketItem::Kitty(id) => Kitties::transfer_item(source, dest, id),
+			MarketItem::Accessory(id) => Accessories::transfer_item(source, dest, id),
+		}
+	}
+
+	fn transfer_reserved_item(source: &AccountId, dest: &AccountId, item_id: MarketItem) -> rstd::result::Result<(), ItemTransferError> {
+		match item_id {
+			MarketItem::Kitty(id) => Kitties::transfer_reserved_item(source, dest, id),
+			MarketItem::Accessory(id) => Accessories::transfer_reserved_item(source, dest, id),
+		}
+	}
+
+	fn can_transfer(from: &AccountId, item_id: MarketItem) -> rstd::result::Result<(), ItemTransferError> {
+		match item_id {
+			MarketItem::Kitty(id) => Kitties::can_transfer(from, id),
+			MarketItem::Accessory(id) => Accessories::can_transfer(from, id),
+		}
+	}
+
+	fn can_transfer_reserved(from: &AccountId, item_id: MarketItem) -> rstd::result::Result<(), ItemTransferError> {
+		match item_id {
+			MarketItem::Kitty(id) => Kitties::can_transfer_reserved(from, id),
+			MarketItem::Accessory(id) => Accessories::can_transfer_reserved(from, id),
+		}
+	}
+
+	fn reserve_item(who: &AccountId, item_id: MarketItem) -> rstd::result::Result<(), ItemTransferError> {
+		match item_id {
+			MarketItem::Kitty(id) => Kitties::reserve_item(who, id),
+			MarketItem::Accessory(id) => Accessories::reserve_item(who, id),
+		}
+	}
+
+	fn unreserve_item(item_id: MarketItem) {
+		match item_id {
+			MarketItem::Kitty(id) => Kitties::unreserve_item(id),
+			MarketItem::Accessory(id) => Accessories::unreserve_item(id),
+		}
+	}
+}
+
+/// Classes of market items, sale stats are tracked per class.
+#[derive(Encode, Decode, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum ItemClass {
+	Gen0Kitty,
+	BredKitty,
+	Accessory(accessories::AccessoryKind),
+	Unknown,
+}
+
+/// Tells the class of a market item.
+pub struct MarketItemClasses;
+impl ClassifyItem<MarketItem, ItemClass> for MarketItemClasses {
+	fn class_of(item_id: MarketItem) -> ItemClass {
+		match item_id {
+			MarketItem::Kitty(id) if Kitties::is_gen0(id) => ItemClass::Gen0Kitty,
+			MarketItem::Kitty(_) => ItemClass::BredKitty,
+			MarketItem::Accessory(id) => Accessories::accessory(id)
+				.map_or(ItemClass::Unknown, |accessory| ItemClass::Accessory(accessory.kind)),
+		}
+	}
+}
+
+/// Whether a kitty is held by an auction, wrapped into shares or bridged out.
+pub struct KittyLocks;
+impl IsItemLocked<u32> for KittyLocks {
+	fn is_item_locked(item_id: u32) -> bool {
+		Kitties::is_reserved(item_id) || ExternalItemLocks::is_item_locked(MarketItem::Kitty(item_id))
+	}
+}
+
+/// Whether a market item is locked outside of the auction module, e.g. a kitty wrapped into shares or bridged out.
+pub struct ExternalItemLocks;
+impl IsItemLocked<MarketItem> for ExternalItemLocks {
+	fn is_item_locked(item_id: MarketItem) -> bool {
+		match item_id {
+			MarketItem::Kitty(id) => Shares::is_item_locked(id) || Bridge::is_item_locked(id),
+			MarketItem::Accessory(_) => false,
+		}
+	}
+}
+
+/// Whether a market item is locked in any module.
+pub struct MarketItemLocks;
+impl IsItemLocked<MarketItem> for MarketItemLocks {
+	fn is_item_locked(item_id: MarketItem) -> bool {
+		Auctions::is_item_locked(item_id) || ExternalItemLocks::is_item_locked(item_id)
+	}
+}
+
+/// Whether an accessory is held by an auction.
+pub struct AccessoryLocks;
+impl IsItemLocked<u32> for AccessoryLocks {
+	fn is_item_locked(item_id: u32) -> bool {
+		Accessories::is_reserved(item_id)
+	}
+}
+
+/// Records kitties changing hands in their provenance trail.
+pub struct KittyProvenance;
+impl OnItemTransferred<AccountId, u32> for KittyProvenance {
+	fn on_item_minted(owner: &AccountId, item_id: u32) {
+		Provenance::on_item_minted(owner, MarketItem::Kitty(item_id))
+	}
+
+	fn on_item_transferred(from: &AccountId, to: &AccountId, item_id: u32, gift: bool) {
+		Provenance::on_item_transferred(from, to, MarketItem::Kitty(item_id), gift)
+	}
+}
+
+/// Records accessories changing hands in their provenance trail.
+pub struct AccessoryProvenance;
+impl OnItemTransferred<AccountId, u32> for AccessoryProvenance {
+	fn on_item_minted(owner: &AccountId, item_id: u32) {
+		Provenance::on_item_minted(owner, MarketItem::Accessory(item_id))
+	}
+
+	fn on_item_transferred(from: &AccountId, to: &AccountId, item_id: u32, gift: bool) {
+		Provenance::on_item_transferred(from, to, MarketItem::Accessory(item_id), gift)
+	}
+}
+
+/// Rejects auction bids which can not make the highest bid at pool validation,
+/// so they never take block space and the bidder is not charged for them.
+/// Bids on an auction saturated in this block exhaust its resources and wait for a later block.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+pub struct CheckBid;
+
+impl SignedExtension for CheckBid {
+	type AccountId = AccountId;
+	type Call = Call;
+	type AdditionalSigned = ();
+	type DispatchInfo = DispatchInfo;
+	type Pre = ();
+
+	fn additional_signed(&self) -> rstd::result::Result<(), TransactionValidityError> {
+		Ok(())
+	}
+
+	fn validate(
+		&self,
+		_who: &Self::AccountId,
+		call: &Self::Call,
+		_info: Self::DispatchInfo,
+		_len: usize,
+	) -> TransactionValidity {
+		if let Call::Auctions(auction::Call::participate_auction(auction_id, price, _, terms_hash, _)) = call {
+			if let Err(code) = Auctions::check_bid(*auction_id, *price, terms_hash) {
+				return InvalidTransaction::Custom(code).into();
+			}
+			if Auctions::is_auction_saturated(auction_id) {
+				return InvalidTransaction::ExhaustsResources.into();
+			}
+		}
+		Ok(ValidTransaction::default())
+	}
+}
+
 /// Opaque types. These are used by the CLI to instantiate machinery that don't need to know
 /// the specifics of the runtime. They can then be made to be agnostic over specific formats
 /// of data like extrinsics, allowing for them to continue syncing the network through upgrades
@@ -247,24 +486,345 @@ impl sudo::Trait for Runtime {
 	type Proposal = Call;
 }
 
+parameter_types! {
+	pub const ExperiencePrice: Balance = 1_000_000;
+	pub const NameFee: Balance = 1_000_000_000;
+	pub const BreedingCooldown: BlockNumber = 10 * MINUTES;
+	pub const Gen0BasePrice: Balance = 1_000_000_000;
+	pub const Gen0PriceStep: u32 = 1_000;
+}
+
 impl kitties::Trait for Runtime {
 	type Event = Event;
 	type KittyIndex = u32;
+	type ItemLocks = KittyLocks;
+	type Currency = Balances;
+	type ExperiencePrice = ExperiencePrice;
+	type NameFee = NameFee;
+	type BreedingCooldown = BreedingCooldown;
+	type OnKittyBurned = Accessories;
+	type OnKittyBred = Badges;
+	type OnKittyTransferred = KittyProvenance;
+	type Gen0BasePrice = Gen0BasePrice;
+	type Gen0PriceStep = Gen0PriceStep;
+	type OnMintPayment = ();
+}
+
+parameter_types! {
+	pub const RecoveryConfigDeposit: Balance = 1_000_000_000;
+	pub const RecoveryDeposit: Balance = 10_000_000_000;
+}
+
+impl recovery::Trait for Runtime {
+	type Event = Event;
+	type Call = Call;
+	type ItemId = u32;
+	type Items = Kitties;
+	type ItemLocks = KittyLocks;
+	type Currency = Balances;
+	type ConfigDeposit = RecoveryConfigDeposit;
+	type RecoveryDeposit = RecoveryDeposit;
+}
+
+parameter_types! {
+	pub const GalleryModuleId: ModuleId = ModuleId(*b"py/gallr");
+}
+
+/// Calls a gallery may dispatch: trading and managing its items and funds
+pub struct GalleryCalls;
+impl traits::CallFilter<Call> for GalleryCalls {
+	fn allows(call: &Call) -> bool {
+		match call {
+			Call::Balances(_)
+			| Call::Kitties(_)
+			| Call::Auctions(_)
+			| Call::Accessories(_)
+			| Call::OrderBook(_) => true,
+			_ => false,
+		}
+	}
+}
+
+impl gallery::Trait for Runtime {
+	type Event = Event;
+	type Call = Call;
+	type CallFilter = GalleryCalls;
+	type ModuleId = GalleryModuleId;
+}
+
+impl accessories::Trait for Runtime {
+	type Event = Event;
+	type AccessoryIndex = u32;
+	type KittyIndex = u32;
+	type Kitties = Kitties;
+	type KittyLocks = KittyLocks;
+	type AccessoryLocks = AccessoryLocks;
+	type OnAccessoryTransferred = AccessoryProvenance;
+}
+
+parameter_types! {
+	pub const MaxBidsPerItem: u32 = 32;
 }
 
+impl orderbook::Trait for Runtime {
+	type Event = Event;
+	type ItemId = MarketItem;
+	type Items = MarketItems;
+	type ItemLocks = MarketItemLocks;
+	type Currency = Balances;
+	type MaxBidsPerItem = MaxBidsPerItem;
+	type OnItemSold = SaleHooks;
+}
+
+/// Modules notified of every sale, by auction or at a fixed price.
+type SaleHooks = (Analytics, (Leaderboards, (Badges, (Reputation, Provenance))));
+
 type SubmitTransaction = TransactionSubmitter<AuraId, Runtime, UncheckedExtrinsic>;
 
+/// Whether the offchain worker drives auction timing. It must be the same for the native and
+/// the wasm runtime, so it does not follow cargo features.
+const OFFCHAIN_TIMING: bool = true;
+
+// Without the `offchain` feature nothing drives auction timing offchain.
+#[cfg(not(feature = "offchain"))]
+const _: [(); 0 - OFFCHAIN_TIMING as usize] = [];
+
+parameter_types! {
+	/// Auction rounds last a day, in milliseconds
+	pub const AuctionRoundLength: u64 = 24 * 60 * 60 * 1000;
+	pub const MaxDueBatchSize: u32 = 50;
+	/// The offchain worker drives auction timing
+	pub const OffchainTiming: bool = OFFCHAIN_TIMING;
+	pub const MaxSettlementsPerBlock: u32 = 20;
+	pub const MaxSettlementQueueLength: u32 = 1000;
+	/// Winners have three days to claim their items, in milliseconds
+	pub const ClaimPeriod: u64 = 3 * 24 * 60 * 60 * 1000;
+	pub const RejectionPenalty: Permill = Permill::from_percent(10);
+	pub const MaxAutoRefunds: u32 = 100;
+	/// Unclaimed refunds are swept after a week, in milliseconds
+	pub const RefundPeriod: u64 = 7 * 24 * 60 * 60 * 1000;
+	pub const AuctionCreationDeposit: Balance = 1_000_000_000;
+	pub const AuctionCommentDeposit: Balance = 10_000_000;
+	pub const SponsoredBidFee: Balance = 1_000_000;
+	/// A sponsored account locks a hundred times the fee it saves
+	pub const MinSponsoredBid: Balance = 100_000_000;
+	pub const MinimumBeginPrice: Balance = 1_000_000;
+	pub const MinimumBidAmount: Balance = 1_000_000;
+	/// Auctions pending without moments for a month can be purged, in milliseconds
+	pub const StaleAuctionTimeout: u64 = 30 * 24 * 60 * 60 * 1000;
+	pub const StalePurgeBounty: Permill = Permill::from_percent(10);
+	/// Vested proceeds unlock over 90 days, in milliseconds
+	pub const ProceedsVestingPeriod: u64 = 90 * 24 * 60 * 60 * 1000;
+	pub const DefaultWaitPeriod: u64 = 10 * 60 * 1000;
+	/// Parties of a sale have two weeks to rate each other, in milliseconds
+	pub const RatingWindow: u64 = 14 * 24 * 60 * 60 * 1000;
+	/// Price snapshots are taken every 10 minutes and kept for a week
+	pub const PriceSnapshotInterval: BlockNumber = 10 * MINUTES;
+	pub const MaxPriceSnapshots: u32 = 7 * 24 * 6;
+	/// Jittered auctions close up to 2 minutes after stop_at, in milliseconds
+	pub const MaxCloseJitter: u64 = 2 * 60 * 1000;
+	pub const MaxBidsPerBlock: u32 = 3;
+	pub const MaxAuctionBidsPerBlock: u32 = 50;
+	/// Auctions expire 90 days after creation, in milliseconds
+	pub const MaxAuctionLifetime: u64 = 90 * 24 * 60 * 60 * 1000;
+	pub const AuctionModuleId: ModuleId = ModuleId(*b"py/auctn");
+	pub const AuctionBidLockId: LockIdentifier = *b"auction ";
+	pub const AuctionVestingLockId: LockIdentifier = *b"aucvest ";
+	pub const AuctionChallengeBond: Balance = 1_000_000_000;
+	/// Challenges and reports lapse 7 days after they are made, in milliseconds
+	pub const AuctionReviewTimeout: u64 = 7 * 24 * 60 * 60 * 1000;
+	pub const MaxCallbackGas: u64 = 10_000_000;
+	/// The earlier of two equal bids in a lot ranks higher
+	pub const AuctionTieBreak: auction::TieBreak = auction::TieBreak::FirstWins;
+}
+
 impl auction::Trait for Runtime {
 	type Event = Event;
 	// offchain related
 	type Call = Call;
 	type SubmitTransaction = SubmitTransaction;
 	// auction related
-	type ItemId = u32;
+	type ItemId = MarketItem;
+	type LegacyItemId = u32;
 	type AuctionId = u32;
+	type AuctionIndex = u32;
+	type AuctionIds = auction::SequentialIds;
 	type Currency = Balances;
 	type OnAuctionPayment = ();
-	type AuctionTransfer = Kitties;
+	type AuctionTransfer = MarketItems;
+	type ItemLocks = ExternalItemLocks;
+	type SuggestedPrice = Analytics;
+	type RoundLength = AuctionRoundLength;
+	type MaxDueBatchSize = MaxDueBatchSize;
+	type OffchainTiming = OffchainTiming;
+	type MaxSettlementsPerBlock = MaxSettlementsPerBlock;
+	type MaxSettlementQueueLength = MaxSettlementQueueLength;
+	type ClaimPeriod = ClaimPeriod;
+	type RejectionPenalty = RejectionPenalty;
+	type MaxAutoRefunds = MaxAutoRefunds;
+	type RefundPeriod = RefundPeriod;
+	type CreationDeposit = AuctionCreationDeposit;
+	type CommentDeposit = AuctionCommentDeposit;
+	type SponsoredBidFee = SponsoredBidFee;
+	type MinSponsoredBid = MinSponsoredBid;
+	type MinimumBeginPrice = MinimumBeginPrice;
+	type MinimumBidAmount = MinimumBidAmount;
+	type StaleAuctionTimeout = StaleAuctionTimeout;
+	type StalePurgeBounty = StalePurgeBounty;
+	type ChallengeBond = AuctionChallengeBond;
+	type ModerationOrigin = system::EnsureRoot<AccountId>;
+	type ReviewTimeout = AuctionReviewTimeout;
+	type ModuleId = AuctionModuleId;
+	type BidLockId = AuctionBidLockId;
+	type VestingLockId = AuctionVestingLockId;
+	type VestingPeriod = ProceedsVestingPeriod;
+	type DefaultWaitPeriod = DefaultWaitPeriod;
+	type TieBreak = AuctionTieBreak;
+	type PriceSnapshotInterval = PriceSnapshotInterval;
+	type MaxPriceSnapshots = MaxPriceSnapshots;
+	type MaxCloseJitter = MaxCloseJitter;
+	type MaxBidsPerBlock = MaxBidsPerBlock;
+	type MaxAuctionBidsPerBlock = MaxAuctionBidsPerBlock;
+	type MaxAuctionLifetime = MaxAuctionLifetime;
+	type OnItemSold = SaleHooks;
+	type OnBidPlaced = (Badges, Airdrop);
+	type OnAuctionPenalty = Reputation;
+	type OnAuctionRated = Reputation;
+	type RatingWindow = RatingWindow;
+	type Reputation = Reputation;
+	// no contracts module in this runtime yet, callbacks fail and are reported by event
+	type Contracts = ();
+	type MaxCallbackGas = MaxCallbackGas;
+	type BidSignature = Signature;
+}
+
+parameter_types! {
+	pub const StatsEpochLength: BlockNumber = DAYS;
+	pub const OracleInterval: BlockNumber = HOURS;
+	pub const CompetitionEpochLength: BlockNumber = 7 * DAYS;
+	pub const MaxLeaderboardSize: u32 = 20;
+}
+
+impl leaderboard::Trait for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type EpochLength = CompetitionEpochLength;
+	type MaxLeaderboardSize = MaxLeaderboardSize;
+}
+
+parameter_types! {
+	pub const BidsForBadge: u32 = 100;
+}
+
+impl badges::Trait for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type BidsForBadge = BidsForBadge;
+}
+
+parameter_types! {
+	pub const SaleScore: i32 = 10;
+	pub const PurchaseScore: i32 = 5;
+	pub const DefaultPenalty: i32 = 50;
+	pub const ReportPenalty: i32 = 100;
+	pub const RatingScore: i32 = 2;
+}
+
+impl reputation::Trait for Runtime {
+	type Event = Event;
+	type SaleScore = SaleScore;
+	type PurchaseScore = PurchaseScore;
+	type DefaultPenalty = DefaultPenalty;
+	type ReportPenalty = ReportPenalty;
+	type RatingScore = RatingScore;
+}
+
+parameter_types! {
+	pub const AirdropEpochLength: BlockNumber = DAYS;
+	pub const MaxAirdropRecipients: u32 = 1_000;
+	pub const MaxAirdropPaymentsPerBlock: u32 = 50;
+	pub const AirdropModuleId: ModuleId = ModuleId(*b"py/airdr");
+}
+
+impl airdrop::Trait for Runtime {
+	type Event = Event;
+	type KittyIndex = u32;
+	type Kitties = Kitties;
+	type Currency = Balances;
+	type ModuleId = AirdropModuleId;
+	type EpochLength = AirdropEpochLength;
+	type MaxRecipients = MaxAirdropRecipients;
+	type MaxPaymentsPerBlock = MaxAirdropPaymentsPerBlock;
+}
+
+parameter_types! {
+	pub const PremiumPeriod: BlockNumber = 30 * DAYS;
+	pub const PremiumRate: Permill = Permill::from_percent(1);
+	pub const MaxCoverage: Balance = 1_000_000_000_000_000;
+	pub const InsuranceModuleId: ModuleId = ModuleId(*b"py/insur");
+}
+
+impl insurance::Trait for Runtime {
+	type Event = Event;
+	type KittyIndex = u32;
+	type Kitties = Kitties;
+	type Currency = Balances;
+	type PremiumPeriod = PremiumPeriod;
+	type PremiumRate = PremiumRate;
+	type MaxCoverage = MaxCoverage;
+	type ModerationOrigin = system::EnsureRoot<AccountId>;
+	type ModuleId = InsuranceModuleId;
+}
+
+parameter_types! {
+	pub const SharesModuleId: ModuleId = ModuleId(*b"py/share");
+}
+
+impl shares::Trait for Runtime {
+	type Event = Event;
+	type KittyIndex = u32;
+	type Kitties = Kitties;
+	type KittyLocks = KittyLocks;
+	type ModuleId = SharesModuleId;
+}
+
+parameter_types! {
+	pub const BridgeRelayerThreshold: u32 = 2;
+}
+
+impl bridge::Trait for Runtime {
+	type Event = Event;
+	type KittyIndex = u32;
+	type Kitties = Kitties;
+	type KittyLocks = KittyLocks;
+	type RelayerThreshold = BridgeRelayerThreshold;
+	type BridgeAdminOrigin = system::EnsureRoot<AccountId>;
+}
+
+parameter_types! {
+	pub const MaxProvenanceRecords: u32 = 32;
+	pub const MaxArchivedProvenanceRecords: u32 = 256;
+}
+
+impl provenance::Trait for Runtime {
+	type ItemId = MarketItem;
+	type Currency = Balances;
+	type MaxRecords = MaxProvenanceRecords;
+	type MaxArchivedRecords = MaxArchivedProvenanceRecords;
+}
+
+impl analytics::Trait for Runtime {
+	type Event = Event;
+	// offchain related
+	type Call = Call;
+	type SubmitTransaction = SubmitTransaction;
+	type OracleInterval = OracleInterval;
+	// stats related
+	type ItemId = MarketItem;
+	type ItemClass = ItemClass;
+	type Classifier = MarketItemClasses;
+	type Currency = Balances;
+	type EpochLength = StatsEpochLength;
 }
 
 impl system::offchain::CreateTransaction<Runtime, UncheckedExtrinsic> for Runtime {
@@ -286,7 +846,8 @@ impl system::offchain::CreateTransaction<Runtime, UncheckedExtrinsic> for Runtim
 			system::CheckEra::<Runtime>::from(generic::Era::mortal(period, current_block)),
 			system::CheckNonce::<Runtime>::from(index),
 			system::CheckWeight::<Runtime>::new(),
-			transaction_payment::ChargeTransactionPayment::<Runtime>::from(tip)
+			transaction_payment::ChargeTransactionPayment::<Runtime>::from(tip),
+			CheckBid,
 		);
 		let raw_payload = SignedPayload::new(call, extra).ok()?;
 		let signature = F::sign(public, &raw_payload)?;
@@ -313,7 +874,33 @@ construct_runtime!(
 		// Substrate Kitties module
 		Kitties: kitties::{Module, Storage, Call, Event<T>},
 		// Auction module
-		Auctions: auction::{Module, Call, Storage, Event<T>, ValidateUnsigned},
+		Auctions: auction::{Module, Call, Storage, Config, Event<T>, ValidateUnsigned},
+		// Kitty accessories module
+		Accessories: accessories::{Module, Call, Storage, Event<T>},
+		// Fixed-price order book module
+		OrderBook: orderbook::{Module, Call, Storage, Event<T>},
+		// Sale stats module
+		Analytics: analytics::{Module, Call, Storage, Event<T>, ValidateUnsigned},
+		// Trading competition module
+		Leaderboards: leaderboard::{Module, Call, Storage, Event<T>},
+		// Achievement badges module
+		Badges: badges::{Module, Call, Storage, Event<T>},
+		// Reputation module
+		Reputation: reputation::{Module, Call, Storage, Event<T>},
+		// Airdrop module
+		Airdrop: airdrop::{Module, Call, Storage, Event<T>},
+		// Kitty insurance module
+		Insurance: insurance::{Module, Call, Storage, Event<T>},
+		// Kitty shares module
+		Shares: shares::{Module, Call, Storage, Event<T>},
+		// Ethereum bridge module
+		Bridge: bridge::{Module, Call, Storage, Event<T>},
+		// Social recovery module
+		Recovery: recovery::{Module, Call, Storage, Event<T>},
+		// Item provenance module
+		Provenance: provenance::{Module, Storage},
+		// Multisig gallery accounts module
+		Gallery: gallery::{Module, Call, Storage, Event<T>},
 		RandomnessCollectiveFlip: randomness_collective_flip::{Module, Call, Storage},
 	}
 );
@@ -335,7 +922,8 @@ pub type SignedExtra = (
 	system::CheckEra<Runtime>,
 	system::CheckNonce<Runtime>,
 	system::CheckWeight<Runtime>,
-	transaction_payment::ChargeTransactionPayment<Runtime>
+	transaction_payment::ChargeTransactionPayment<Runtime>,
+	CheckBid,
 );
 /// Unchecked extrinsic type as expected by this runtime.
 pub type UncheckedExtrinsic = generic::UncheckedExtrinsic<Address, Call, Signature, SignedExtra>;
@@ -423,4 +1011,92 @@ impl_runtime_apis! {
 			Grandpa::grandpa_authorities()
 		}
 	}
+
+	impl kitties::api::KittiesApi<Block, u32, kitties::KittyAttributes> for Runtime {
+		fn attributes(kitty_id: u32) -> Option<kitties::KittyAttributes> {
+			Kitties::attributes(kitty_id)
+		}
+
+		fn kitty_by_name(name: Vec<u8>) -> Option<u32> {
+			Kitties::kitty_by_name(name.to_ascii_lowercase())
+		}
+	}
+
+	impl auction::api::AuctionApi<
+		Block,
+		u32,
+		AccountId,
+		auction::SettlementReceipt<Runtime>,
+		BlockNumber,
+		Balance,
+		auction::OwnerDashboardOf<Runtime>,
+	> for Runtime {
+		fn settlement_receipt(auction_id: u32) -> Option<auction::SettlementReceipt<Runtime>> {
+			Auctions::settlement_receipt(auction_id)
+		}
+
+		fn escrow_account(auction_id: u32) -> AccountId {
+			Auctions::escrow_account(auction_id)
+		}
+
+		fn price_chart(auction_id: u32) -> Vec<(BlockNumber, Balance)> {
+			Auctions::price_chart(auction_id)
+		}
+
+		fn owner_dashboard(owner: AccountId) -> auction::OwnerDashboardOf<Runtime> {
+			Auctions::owner_dashboard(&owner)
+		}
+
+		fn auction_by_owner_seq(owner: AccountId, seq: u32) -> Option<u32> {
+			Auctions::auction_by_owner_seq(owner, seq)
+		}
+	}
+
+	impl badges::api::BadgesApi<Block, AccountId, badges::Badge, BlockNumber> for Runtime {
+		fn badges_of(who: AccountId) -> Vec<(badges::Badge, BlockNumber)> {
+			Badges::badges_of(who)
+		}
+	}
+
+	impl reputation::api::ReputationApi<Block, AccountId, reputation::Reputation, reputation::RatingSummary> for Runtime {
+		fn reputation_of(who: AccountId) -> reputation::Reputation {
+			Reputation::reputation_of(who)
+		}
+
+		fn ratings_of(who: AccountId) -> reputation::RatingSummary {
+			Reputation::ratings_of(who)
+		}
+
+		fn average_rating(who: AccountId) -> Option<u32> {
+			Reputation::ratings_of(who).average()
+		}
+	}
+
+	impl provenance::api::ProvenanceApi<Block, MarketItem, provenance::ProvenanceRecordOf<Runtime>> for Runtime {
+		fn provenance_of(item_id: MarketItem) -> Vec<provenance::ProvenanceRecordOf<Runtime>> {
+			Provenance::provenance_of(item_id)
+		}
+
+		fn archived_count(item_id: MarketItem) -> u32 {
+			Provenance::archived_count(item_id)
+		}
+
+		fn archived_record(item_id: MarketItem, index: u32) -> Option<provenance::ProvenanceRecordOf<Runtime>> {
+			Provenance::archived_record(item_id, index)
+		}
+	}
+
+	impl analytics::api::AnalyticsApi<Block, ItemClass, analytics::ClassStatsOf<Runtime>, Balance> for Runtime {
+		fn class_stats(class: ItemClass) -> analytics::ClassStatsOf<Runtime> {
+			Analytics::class_stats(class)
+		}
+
+		fn previous_class_stats(class: ItemClass) -> Option<analytics::ClassStatsOf<Runtime>> {
+			Analytics::previous_stats(class)
+		}
+
+		fn suggested_floor(class: ItemClass) -> Option<Balance> {
+			Analytics::suggested_floor(class)
+		}
+	}
 }
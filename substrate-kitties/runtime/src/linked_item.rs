@@ -58,6 +58,16 @@ impl<Storage, Key, Value> LinkedList<Storage, Key, Value> where
 		Self::write(key, Some(value), item);
 	}
 
+	pub fn collect(key: &Key) -> rstd::vec::Vec<Value> {
+		let mut values = rstd::vec::Vec::new();
+		let mut next = Self::read_head(key).next;
+		while let Some(value) = next {
+			values.push(value);
+			next = Self::read(key, Some(value)).next;
+		}
+		values
+	}
+
 	pub fn remove(key: &Key, value: Value) {
 		if let Some(item) = Storage::take(&(key.clone(), Some(value))) {
 			let prev = Self::read(key, item.prev);
@@ -0,0 +1,76 @@
+//! Items kept in thread locals, shared by the tests of the modules moving items.
+//!
+//! Each test runs in its own thread, so a test starts without owners, frozen or locked items
+//! until it sets them, usually in its `new_test_ext`.
+
+use std::cell::RefCell;
+use support::ensure;
+use crate::traits::{ItemTransfer, ItemTransferError, OwnedItems, ItemMint, IsItemLocked};
+
+thread_local! {
+	static OWNERS: RefCell<Vec<(u64, u32)>> = RefCell::new(vec![]);
+	static FROZEN: RefCell<Vec<u32>> = RefCell::new(vec![]);
+	static LOCKED: RefCell<Vec<u32>> = RefCell::new(vec![]);
+}
+
+/// Items owned by accounts, kept in a thread local
+pub struct TestItems;
+impl TestItems {
+	/// Replace the (owner, item) pairs of all items
+	pub fn set_owners(owners: Vec<(u64, u32)>) {
+		OWNERS.with(|v| *v.borrow_mut() = owners);
+	}
+	/// Items which can not be transferred, `can_transfer` returns `Frozen` for them
+	pub fn set_frozen(items: Vec<u32>) {
+		FROZEN.with(|v| *v.borrow_mut() = items);
+	}
+	/// Items locked e.g. in an auction, as told by `TestLocks`
+	pub fn set_locked(items: Vec<u32>) {
+		LOCKED.with(|v| *v.borrow_mut() = items);
+	}
+}
+impl ItemTransfer<u64, u32> for TestItems {
+	fn is_item_owner(who: &u64, item_id: u32) -> bool {
+		OWNERS.with(|v| v.borrow().contains(&(*who, item_id)))
+	}
+	fn transfer_item(source: &u64, dest: &u64, item_id: u32) -> rstd::result::Result<(), ItemTransferError> {
+		Self::can_transfer(source, item_id)?;
+		OWNERS.with(|v| {
+			for owner in v.borrow_mut().iter_mut().filter(|(_, id)| *id == item_id) {
+				owner.0 = *dest;
+			}
+		});
+		Ok(())
+	}
+	fn can_transfer(from: &u64, item_id: u32) -> rstd::result::Result<(), ItemTransferError> {
+		ensure!(Self::is_item_owner(from, item_id), ItemTransferError::NotOwner);
+		ensure!(!FROZEN.with(|v| v.borrow().contains(&item_id)), ItemTransferError::Frozen);
+		Ok(())
+	}
+	fn reserve_item(_who: &u64, _item_id: u32) -> rstd::result::Result<(), ItemTransferError> {
+		Ok(())
+	}
+	fn unreserve_item(_item_id: u32) {}
+}
+impl OwnedItems<u64, u32> for TestItems {
+	fn owned_items(who: &u64) -> Vec<u32> {
+		OWNERS.with(|v| v.borrow().iter().filter(|(owner, _)| owner == who).map(|(_, id)| *id).collect())
+	}
+}
+impl ItemMint<u64, u32> for TestItems {
+	fn mint_item(owner: &u64) -> rstd::result::Result<u32, &'static str> {
+		OWNERS.with(|v| {
+			let id = v.borrow().len() as u32;
+			v.borrow_mut().push((*owner, id));
+			Ok(id)
+		})
+	}
+}
+
+/// Items locked e.g. in an auction, kept in a thread local
+pub struct TestLocks;
+impl IsItemLocked<u32> for TestLocks {
+	fn is_item_locked(item_id: u32) -> bool {
+		LOCKED.with(|v| v.borrow().contains(&item_id))
+	}
+}
@@ -0,0 +1,389 @@
+//! Fixed-price order book for items, complementing the auction module.
+//!
+//! Sellers post an ask per item, buyers post bids per item with the price
+//! reserved from their balance. Crossing orders are matched right away at
+//! the price of the resting order. Every order has an expiry block, expired
+//! orders are ignored by matching and can be pruned by anyone.
+
+use support::{decl_module, decl_storage, decl_event, ensure, Parameter};
+use support::traits::{Get, Currency, ReservableCurrency};
+use sr_primitives::traits::{Member, Zero};
+use codec::{Encode, Decode};
+use system::ensure_signed;
+use rstd::prelude::*;
+use crate::traits::{ItemTransfer, IsItemLocked, OnItemSold};
+
+pub trait Trait: system::Trait {
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+	/// Item Id
+	type ItemId: Parameter + Member + Copy;
+	/// Interface for transfer item
+	type Items: ItemTransfer<Self::AccountId, Self::ItemId>;
+	/// Tell whether an item is locked, e.g. in an auction
+	type ItemLocks: IsItemLocked<Self::ItemId>;
+	/// Currency for paying orders, bids are reserved
+	type Currency: ReservableCurrency<Self::AccountId>;
+	/// Max number of open bids on one item
+	type MaxBidsPerItem: Get<u32>;
+	/// Handler for the item being sold, e.g. sale stats
+	type OnItemSold: OnItemSold<Self::AccountId, Self::ItemId, BalanceOf<Self>>;
+}
+
+pub type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+
+#[derive(Encode, Decode, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct Order<AccountId, Balance, BlockNumber> {
+	who: AccountId, // 挂单人
+	price: Balance, // 价格
+	expires_at: BlockNumber, // 过期区块
+}
+
+type OrderOf<T> = Order<<T as system::Trait>::AccountId, BalanceOf<T>, <T as system::Trait>::BlockNumber>;
+
+decl_storage! {
+	trait Store for Module<T: Trait> as OrderBook {
+		/// Ask of an item
+		pub Asks get(fn ask): map T::ItemId => Option<OrderOf<T>>;
+		/// Bids of an item, sorted by price from high to low, one per buyer
+		pub Bids get(fn bids): map T::ItemId => Vec<OrderOf<T>>;
+	}
+}
+
+decl_event!(
+	pub enum Event<T> where
+		<T as system::Trait>::AccountId,
+		<T as Trait>::ItemId,
+		Balance = BalanceOf<T>,
+	{
+		/// An ask is placed. (seller, item, price)
+		AskPlaced(AccountId, ItemId, Balance),
+		/// A bid is placed. (buyer, item, price)
+		BidPlaced(AccountId, ItemId, Balance),
+		/// An ask is cancelled or expired. (seller, item)
+		AskRemoved(AccountId, ItemId),
+		/// A bid is cancelled or expired, the reserved balance is returned. (buyer, item)
+		BidRemoved(AccountId, ItemId),
+		/// An item is traded. (seller, buyer, item, price)
+		Traded(AccountId, AccountId, ItemId, Balance),
+	}
+);
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		fn deposit_event() = default;
+
+		/// Post an ask, replacing the previous one. Matched right away with the best bid if it crosses.
+		pub fn place_ask(origin, item: T::ItemId, price: BalanceOf<T>, expires_at: T::BlockNumber) {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_can_sell(&sender, item)?;
+			ensure!(expires_at > <system::Module<T>>::block_number(), "Order already expired");
+
+			let best_bid = Self::active_bids(item).into_iter()
+				.find(|bid| bid.price >= price && bid.who != sender);
+			if let Some(bid) = best_bid {
+				// 成交前买单保持预留，成交后才移除
+				Self::do_trade(&sender, &bid.who, item, bid.price, bid.price)?;
+				Self::take_bid(item, &bid.who);
+				<Asks<T>>::remove(item);
+				return Ok(());
+			}
+
+			<Asks<T>>::insert(item, Order { who: sender.clone(), price: price, expires_at: expires_at });
+			Self::deposit_event(RawEvent::AskPlaced(sender, item, price));
+		}
+
+		/// Cancel own ask
+		pub fn cancel_ask(origin, item: T::ItemId) {
+			let sender = ensure_signed(origin)?;
+			let ask = Self::ask(item).ok_or("No ask for item")?;
+			ensure!(ask.who == sender, "Not the seller");
+
+			<Asks<T>>::remove(item);
+			Self::deposit_event(RawEvent::AskRemoved(sender, item));
+		}
+
+		/// Post a bid with the price reserved, replacing the previous one of the buyer.
+		/// Matched right away at the ask price if it crosses the ask.
+		pub fn place_bid(origin, item: T::ItemId, price: BalanceOf<T>, expires_at: T::BlockNumber) {
+			let sender = ensure_signed(origin)?;
+			ensure!(!price.is_zero(), "Bid price should not be zero");
+			ensure!(expires_at > <system::Module<T>>::block_number(), "Order already expired");
+
+			if let Some(ask) = Self::active_ask(item) {
+				if ask.price <= price && ask.who != sender && Self::ensure_can_trade(&ask.who, item).is_ok() {
+					// the previous bid of the buyer is returned before paying the ask
+					let previous = Self::bids(item).into_iter()
+						.find(|bid| bid.who == sender)
+						.map(|bid| bid.price)
+						.unwrap_or_else(Zero::zero);
+					ensure!(
+						ask.price <= previous || T::Currency::can_reserve(&sender, ask.price - previous),
+						"Not enough balance to pay the ask"
+					);
+
+					Self::remove_bid(item, &sender);
+					T::Currency::reserve(&sender, ask.price)?;
+					Self::do_trade(&ask.who, &sender, item, ask.price, ask.price)?;
+					<Asks<T>>::remove(item);
+					return Ok(());
+				}
+			}
+
+			let mut bids = Self::bids(item);
+			let had_bid = bids.iter().any(|bid| bid.who == sender);
+			ensure!(had_bid || (bids.len() as u32) < T::MaxBidsPerItem::get(), "Too many bids on item");
+
+			Self::remove_bid(item, &sender);
+			T::Currency::reserve(&sender, price)?;
+
+			bids = Self::bids(item);
+			let pos = bids.iter().position(|bid| bid.price < price).unwrap_or(bids.len());
+			bids.insert(pos, Order { who: sender.clone(), price: price, expires_at: expires_at });
+			<Bids<T>>::insert(item, bids);
+
+			Self::deposit_event(RawEvent::BidPlaced(sender, item, price));
+		}
+
+		/// Cancel own bid and get the reserved balance back
+		pub fn cancel_bid(origin, item: T::ItemId) {
+			let sender = ensure_signed(origin)?;
+			ensure!(Self::bids(item).iter().any(|bid| bid.who == sender), "No bid for item");
+
+			Self::remove_bid(item, &sender);
+		}
+
+		/// Remove expired orders of an item, anyone can call this
+		pub fn prune_expired(origin, item: T::ItemId) {
+			ensure_signed(origin)?;
+			let now = <system::Module<T>>::block_number();
+
+			if let Some(ask) = Self::ask(item) {
+				if ask.expires_at <= now {
+					<Asks<T>>::remove(item);
+					Self::deposit_event(RawEvent::AskRemoved(ask.who, item));
+				}
+			}
+			let expired: Vec<T::AccountId> = Self::bids(item).into_iter()
+				.filter(|bid| bid.expires_at <= now)
+				.map(|bid| bid.who)
+				.collect();
+			for who in expired.iter() {
+				Self::remove_bid(item, who);
+			}
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	fn ensure_can_sell(seller: &T::AccountId, item: T::ItemId) -> support::dispatch::Result {
+		ensure!(T::Items::is_item_owner(seller, item), "Only owner can sell item");
+		ensure!(!T::ItemLocks::is_item_locked(item), "Item is locked in an auction");
+		Ok(())
+	}
+
+	/// The seller can sell the item and the item can be handed over
+	fn ensure_can_trade(seller: &T::AccountId, item: T::ItemId) -> support::dispatch::Result {
+		Self::ensure_can_sell(seller, item)?;
+		T::Items::can_transfer(seller, item)?;
+		Ok(())
+	}
+
+	fn active_ask(item: T::ItemId) -> Option<OrderOf<T>> {
+		let now = <system::Module<T>>::block_number();
+		Self::ask(item).filter(|ask| ask.expires_at > now)
+	}
+
+	fn active_bids(item: T::ItemId) -> Vec<OrderOf<T>> {
+		let now = <system::Module<T>>::block_number();
+		Self::bids(item).into_iter().filter(|bid| bid.expires_at > now).collect()
+	}
+
+	/// Remove the bid of the buyer and return the reserved balance
+	fn remove_bid(item: T::ItemId, who: &T::AccountId) {
+		if let Some(bid) = Self::take_bid(item, who) {
+			T::Currency::unreserve(who, bid.price);
+			Self::deposit_event(RawEvent::BidRemoved(who.clone(), item));
+		}
+	}
+
+	/// Remove the bid of the buyer from the book, leaving the reserved balance as is
+	fn take_bid(item: T::ItemId, who: &T::AccountId) -> Option<OrderOf<T>> {
+		let mut bids = Self::bids(item);
+		let pos = bids.iter().position(|bid| bid.who == *who)?;
+		let bid = bids.remove(pos);
+		if bids.is_empty() {
+			<Bids<T>>::remove(item);
+		} else {
+			<Bids<T>>::insert(item, bids);
+		}
+		Some(bid)
+	}
+
+	/// Pay the seller from the buyer's reserved balance and hand over the item.
+	/// `reserved` of the buyer is consumed, the part above `price` is returned.
+	fn do_trade(
+		seller: &T::AccountId,
+		buyer: &T::AccountId,
+		item: T::ItemId,
+		price: BalanceOf<T>,
+		reserved: BalanceOf<T>,
+	) -> support::dispatch::Result {
+		Self::ensure_can_trade(seller, item)?;
+		ensure!(T::Currency::reserved_balance(buyer) >= reserved, "Bid is not fully reserved");
+
+		T::Currency::repatriate_reserved(buyer, seller, price)?;
+		T::Currency::unreserve(buyer, reserved - price);
+		T::Items::transfer_item(seller, buyer, item)?;
+
+		Self::deposit_event(RawEvent::Traded(seller.clone(), buyer.clone(), item, price));
+		T::OnItemSold::on_item_sold(seller, buyer, item, price);
+		Ok(())
+	}
+}
+
+/// Tests for OrderBook module
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use primitives::H256;
+	use support::{impl_outer_origin, parameter_types, assert_ok, assert_noop};
+	use sr_primitives::{traits::{BlakeTwo256, IdentityLookup}, testing::Header};
+	use sr_primitives::weights::Weight;
+	use sr_primitives::Perbill;
+	use crate::mock_items::TestItems;
+
+	impl_outer_origin! {
+		pub enum Origin for Test {}
+	}
+
+	#[derive(Clone, Eq, PartialEq, Debug)]
+	pub struct Test;
+	parameter_types! {
+		pub const BlockHashCount: u64 = 250;
+		pub const MaximumBlockWeight: Weight = 1024;
+		pub const MaximumBlockLength: u32 = 2 * 1024;
+		pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+	}
+	impl system::Trait for Test {
+		type Origin = Origin;
+		type Call = ();
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type Event = ();
+		type BlockHashCount = BlockHashCount;
+		type MaximumBlockWeight = MaximumBlockWeight;
+		type MaximumBlockLength = MaximumBlockLength;
+		type AvailableBlockRatio = AvailableBlockRatio;
+		type Version = ();
+	}
+	parameter_types! {
+		pub const ExistentialDeposit: u64 = 0;
+		pub const TransferFee: u64 = 0;
+		pub const CreationFee: u64 = 0;
+		pub const MaxBidsPerItem: u32 = 3;
+	}
+	impl balances::Trait for Test {
+		type Balance = u64;
+		type OnFreeBalanceZero = ();
+		type OnNewAccount = ();
+		type Event = ();
+		type TransferPayment = ();
+		type DustRemoval = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type TransferFee = TransferFee;
+		type CreationFee = CreationFee;
+	}
+	impl Trait for Test {
+		type Event = ();
+		type ItemId = u32;
+		type Items = TestItems;
+		type ItemLocks = ();
+		type Currency = balances::Module<Test>;
+		type MaxBidsPerItem = MaxBidsPerItem;
+		type OnItemSold = ();
+	}
+	type OrderBook = Module<Test>;
+	type Balances = balances::Module<Test>;
+
+	fn new_test_ext() -> runtime_io::TestExternalities {
+		let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+		balances::GenesisConfig::<Test> {
+			balances: vec![(1, 1000), (2, 1000), (3, 1000)],
+			vesting: vec![],
+		}.assimilate_storage(&mut t).unwrap();
+		TestItems::set_owners(vec![(1, 0)]);
+		t.into()
+	}
+
+	#[test]
+	fn crossing_ask_is_paid_from_the_reserved_bid() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(OrderBook::place_bid(Origin::signed(2), 0, 150, 10));
+			assert_ok!(OrderBook::place_bid(Origin::signed(3), 0, 120, 10));
+			assert_eq!(Balances::reserved_balance(&2), 150);
+
+			// 最高买单按买单价成交
+			assert_ok!(OrderBook::place_ask(Origin::signed(1), 0, 100, 10));
+			assert!(TestItems::is_item_owner(&2, 0));
+			assert_eq!(Balances::free_balance(&1), 1150);
+			assert_eq!(Balances::free_balance(&2), 850);
+			assert_eq!(Balances::reserved_balance(&2), 0);
+			assert_eq!(Balances::reserved_balance(&3), 120);
+			assert_eq!(OrderBook::bids(0).iter().map(|bid| bid.who).collect::<Vec<_>>(), vec![3]);
+			assert_eq!(OrderBook::ask(0), None);
+		});
+	}
+
+	#[test]
+	fn failed_crossing_ask_keeps_the_bid_reserved() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(OrderBook::place_bid(Origin::signed(2), 0, 150, 10));
+
+			assert_noop!(OrderBook::place_ask(Origin::signed(3), 0, 100, 10), "Only owner can sell item");
+			TestItems::set_frozen(vec![0]);
+			assert_noop!(OrderBook::place_ask(Origin::signed(1), 0, 100, 10), "Item is frozen");
+			assert_eq!(Balances::reserved_balance(&2), 150);
+			assert_eq!(OrderBook::bids(0).len(), 1);
+			assert_eq!(Balances::free_balance(&1), 1000);
+		});
+	}
+
+	#[test]
+	fn crossing_bid_pays_the_ask_price() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(OrderBook::place_ask(Origin::signed(1), 0, 100, 10));
+			assert_ok!(OrderBook::place_bid(Origin::signed(2), 0, 80, 10));
+			assert_eq!(Balances::reserved_balance(&2), 80);
+
+			// 之前的买单退回后按卖单价成交
+			assert_ok!(OrderBook::place_bid(Origin::signed(2), 0, 120, 10));
+			assert!(TestItems::is_item_owner(&2, 0));
+			assert_eq!(Balances::free_balance(&1), 1100);
+			assert_eq!(Balances::free_balance(&2), 900);
+			assert_eq!(Balances::reserved_balance(&2), 0);
+			assert!(OrderBook::bids(0).is_empty());
+			assert_eq!(OrderBook::ask(0), None);
+		});
+	}
+
+	#[test]
+	fn crossing_bid_is_checked_before_the_previous_bid_is_returned() {
+		new_test_ext().execute_with(|| {
+			let _ = Balances::deposit_creating(&4, 90);
+			assert_ok!(OrderBook::place_bid(Origin::signed(4), 0, 60, 10));
+			assert_ok!(OrderBook::place_ask(Origin::signed(1), 0, 100, 10));
+
+			assert_noop!(OrderBook::place_bid(Origin::signed(4), 0, 120, 10), "Not enough balance to pay the ask");
+			assert_eq!(Balances::reserved_balance(&4), 60);
+			assert_eq!(OrderBook::bids(0).len(), 1);
+			assert!(TestItems::is_item_owner(&1, 0));
+		});
+	}
+}
@@ -0,0 +1,257 @@
+//! Provenance trail of market items, fed by hooks of the item and market modules.
+//!
+//! Every mint, transfer, gift and sale of an item is appended to its trail, so
+//! buyers can verify the history of an item before bidding. Only the latest
+//! `MaxRecords` records are kept in the trail, older ones are moved to an archive
+//! indexed by their position. The archive keeps the latest `MaxArchivedRecords`
+//! records of an item, older ones are pruned.
+
+use support::{decl_module, decl_storage, Parameter};
+use support::traits::{Get, Currency};
+use sr_primitives::traits::Member;
+use codec::{Encode, Decode};
+use rstd::prelude::*;
+use crate::traits::{OnItemSold, OnItemTransferred};
+
+pub trait Trait: system::Trait {
+	/// Item Id
+	type ItemId: Parameter + Member + Copy;
+	/// Currency the items are paid with
+	type Currency: Currency<Self::AccountId>;
+	/// Number of records kept in the trail of an item, older records are archived
+	type MaxRecords: Get<u32>;
+	/// Number of archived records kept per item, older ones are pruned
+	type MaxArchivedRecords: Get<u32>;
+}
+
+pub type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+
+/// How an item changed hands
+#[derive(Encode, Decode, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum TransferKind<AccountId, Balance> {
+	/// Minted to the owner. (owner)
+	Minted(AccountId),
+	/// Transferred. (from, to)
+	Transferred(AccountId, AccountId),
+	/// Gifted. (from, to)
+	Gifted(AccountId, AccountId),
+	/// Sold by auction or at a fixed price. (seller, buyer, price)
+	Sold(AccountId, AccountId, Balance),
+}
+
+#[derive(Encode, Decode, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct ProvenanceRecord<AccountId, Balance, BlockNumber> {
+	pub transfer: TransferKind<AccountId, Balance>, // 流转方式
+	pub block: BlockNumber, // 流转所在区块
+}
+
+pub type ProvenanceRecordOf<T> = ProvenanceRecord<
+	<T as system::Trait>::AccountId,
+	BalanceOf<T>,
+	<T as system::Trait>::BlockNumber,
+>;
+
+decl_storage! {
+	trait Store for Module<T: Trait> as Provenance {
+		/// Latest records of an item, oldest first
+		pub Trails get(fn provenance_of): map T::ItemId => Vec<ProvenanceRecordOf<T>>;
+		/// Archived records of an item, by position from the first record
+		pub ArchivedRecords get(fn archived_record): double_map T::ItemId, twox_128(u32) => Option<ProvenanceRecordOf<T>>;
+		/// Number of records ever archived for an item, including pruned ones
+		pub ArchivedCount get(fn archived_count): map T::ItemId => u32;
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {}
+}
+
+impl<T: Trait> Module<T> {
+	/// Position of the oldest archived record of the item which is not pruned
+	pub fn first_archived(item_id: T::ItemId) -> u32 {
+		Self::archived_count(item_id).saturating_sub(T::MaxArchivedRecords::get())
+	}
+
+	/// Append a record to the trail of the item, archiving the oldest ones beyond `MaxRecords`
+	fn append(item_id: T::ItemId, transfer: TransferKind<T::AccountId, BalanceOf<T>>) {
+		let mut records = Self::provenance_of(item_id);
+		records.push(ProvenanceRecord {
+			transfer: transfer,
+			block: <system::Module<T>>::block_number(),
+		});
+
+		let max = T::MaxRecords::get().max(1) as usize;
+		if records.len() > max {
+			let mut archived = Self::archived_count(item_id);
+			let keep = T::MaxArchivedRecords::get();
+			for record in records.drain(..records.len() - max) {
+				<ArchivedRecords<T>>::insert(item_id, archived, record);
+				archived = archived.saturating_add(1);
+				// 超出归档上限时删除最旧的归档记录
+				if archived > keep {
+					<ArchivedRecords<T>>::remove(item_id, archived - keep - 1);
+				}
+			}
+			<ArchivedCount<T>>::insert(item_id, archived);
+		}
+		<Trails<T>>::insert(item_id, records);
+	}
+}
+
+impl<T: Trait> OnItemTransferred<T::AccountId, T::ItemId> for Module<T> {
+	fn on_item_minted(owner: &T::AccountId, item_id: T::ItemId) {
+		Self::append(item_id, TransferKind::Minted(owner.clone()));
+	}
+
+	fn on_item_transferred(from: &T::AccountId, to: &T::AccountId, item_id: T::ItemId, gift: bool) {
+		let transfer = if gift {
+			TransferKind::Gifted(from.clone(), to.clone())
+		} else {
+			TransferKind::Transferred(from.clone(), to.clone())
+		};
+		Self::append(item_id, transfer);
+	}
+}
+
+/// A sale turns the transfer it made in the same block into a sale record
+impl<T: Trait> OnItemSold<T::AccountId, T::ItemId, BalanceOf<T>> for Module<T> {
+	fn on_item_sold(seller: &T::AccountId, buyer: &T::AccountId, item_id: T::ItemId, price: BalanceOf<T>) {
+		let now = <system::Module<T>>::block_number();
+		let transfer = TransferKind::Transferred(seller.clone(), buyer.clone());
+		let mut records = Self::provenance_of(item_id);
+		match records.last_mut() {
+			Some(last) if last.block == now && last.transfer == transfer => {
+				last.transfer = TransferKind::Sold(seller.clone(), buyer.clone(), price);
+				<Trails<T>>::insert(item_id, records);
+			},
+			_ => Self::append(item_id, TransferKind::Sold(seller.clone(), buyer.clone(), price)),
+		}
+	}
+}
+
+/// Runtime api of this module
+pub mod api {
+	use codec::Codec;
+	use rstd::vec::Vec;
+
+	client::decl_runtime_apis! {
+		/// The api to query the provenance of items.
+		pub trait ProvenanceApi<ItemId, Record> where
+			ItemId: Codec,
+			Record: Codec,
+		{
+			/// Latest records of the item, oldest first.
+			fn provenance_of(item_id: ItemId) -> Vec<Record>;
+			/// Number of records ever archived for the item, the oldest may be pruned.
+			fn archived_count(item_id: ItemId) -> u32;
+			/// Archived record of the item at the position.
+			fn archived_record(item_id: ItemId, index: u32) -> Option<Record>;
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use primitives::H256;
+	use support::{impl_outer_origin, parameter_types};
+	use sr_primitives::{traits::{BlakeTwo256, IdentityLookup}, testing::Header};
+	use sr_primitives::weights::Weight;
+	use sr_primitives::Perbill;
+
+	impl_outer_origin! {
+		pub enum Origin for Test {}
+	}
+
+	#[derive(Clone, Eq, PartialEq, Debug)]
+	pub struct Test;
+	parameter_types! {
+		pub const BlockHashCount: u64 = 250;
+		pub const MaximumBlockWeight: Weight = 1024;
+		pub const MaximumBlockLength: u32 = 2 * 1024;
+		pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+	}
+	impl system::Trait for Test {
+		type Origin = Origin;
+		type Call = ();
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type Event = ();
+		type BlockHashCount = BlockHashCount;
+		type MaximumBlockWeight = MaximumBlockWeight;
+		type MaximumBlockLength = MaximumBlockLength;
+		type AvailableBlockRatio = AvailableBlockRatio;
+		type Version = ();
+	}
+	parameter_types! {
+		pub const ExistentialDeposit: u64 = 0;
+		pub const TransferFee: u64 = 0;
+		pub const CreationFee: u64 = 0;
+		pub const MaxRecords: u32 = 2;
+		pub const MaxArchivedRecords: u32 = 3;
+	}
+	impl balances::Trait for Test {
+		type Balance = u64;
+		type OnFreeBalanceZero = ();
+		type OnNewAccount = ();
+		type Event = ();
+		type TransferPayment = ();
+		type DustRemoval = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type TransferFee = TransferFee;
+		type CreationFee = CreationFee;
+	}
+	impl Trait for Test {
+		type ItemId = u32;
+		type Currency = balances::Module<Test>;
+		type MaxRecords = MaxRecords;
+		type MaxArchivedRecords = MaxArchivedRecords;
+	}
+	type Provenance = Module<Test>;
+
+	fn new_test_ext() -> runtime_io::TestExternalities {
+		system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
+	}
+
+	#[test]
+	fn sales_replace_the_transfer_they_made() {
+		new_test_ext().execute_with(|| {
+			Provenance::on_item_minted(&1, 7);
+			Provenance::on_item_transferred(&1, &2, 7, false);
+			Provenance::on_item_sold(&1, &2, 7, 100);
+			assert_eq!(
+				Provenance::provenance_of(7).into_iter().map(|r| r.transfer).collect::<Vec<_>>(),
+				vec![TransferKind::Minted(1), TransferKind::Sold(1, 2, 100)]
+			);
+		});
+	}
+
+	#[test]
+	fn old_records_are_archived_then_pruned() {
+		new_test_ext().execute_with(|| {
+			Provenance::on_item_minted(&1, 7);
+			for i in 1..7 {
+				Provenance::on_item_transferred(&i, &(i + 1), 7, false);
+			}
+
+			// 7条记录：最近2条在流转记录中，其余5条归档但只保留最近3条
+			let trail = Provenance::provenance_of(7);
+			assert_eq!(trail.len(), 2);
+			assert_eq!(trail[1].transfer, TransferKind::Transferred(6, 7));
+			assert_eq!(Provenance::archived_count(7), 5);
+			assert_eq!(Provenance::first_archived(7), 2);
+			assert_eq!(Provenance::archived_record(7, 0), None);
+			assert_eq!(Provenance::archived_record(7, 1), None);
+			assert_eq!(Provenance::archived_record(7, 2).map(|r| r.transfer), Some(TransferKind::Transferred(2, 3)));
+			assert_eq!(Provenance::archived_record(7, 4).map(|r| r.transfer), Some(TransferKind::Transferred(4, 5)));
+		});
+	}
+}
@@ -0,0 +1,438 @@
+//! Social recovery of kitties.
+//!
+//! An account registers a set of friends. When it loses its key, a rescuer
+//! initiates a recovery, the friends vouch for it, and after the delay period
+//! the rescuer can claim the lost account's kitties and act on its behalf
+//! (e.g. to stop its auctions and move the proceeds).
+//!
+//! Kitties still locked in an auction are skipped by `claim_kitties`, they can
+//! be claimed again once the auction is over. At most `MAX_CLAIMED_KITTIES` are
+//! moved by one call, the rest are moved by calling it again.
+//!
+//! A recovery config reserves `ConfigDeposit` from the account until it is removed.
+//! Initiating a recovery reserves `RecoveryDeposit` from the rescuer, returned when
+//! the account is recovered and paid to the account if it closes the recovery.
+//! Once one rescuer recovered the account, the others can cancel their recoveries to get
+//! their deposits back. A config can only be removed when no recovery is in progress.
+
+use support::{decl_module, decl_storage, decl_event, ensure, Parameter};
+use support::traits::{Get, Currency, ReservableCurrency};
+use sr_primitives::traits::{Member, Dispatchable, CheckedAdd};
+use codec::{Encode, Decode};
+use system::ensure_signed;
+use rstd::prelude::*;
+use crate::traits::{ItemTransfer, IsItemLocked, OwnedItems};
+
+/// Max number of friends in a recovery config
+const MAX_FRIENDS: usize = 9;
+/// Max number of kitties moved by one `claim_kitties`
+const MAX_CLAIMED_KITTIES: usize = 50;
+
+pub trait Trait: system::Trait {
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+	/// The call type which a rescuer can dispatch on behalf of the lost account
+	type Call: Parameter + Dispatchable<Origin = <Self as system::Trait>::Origin>;
+	/// Item Id
+	type ItemId: Parameter + Member + Copy;
+	/// Interface for listing and transferring items
+	type Items: ItemTransfer<Self::AccountId, Self::ItemId> + OwnedItems<Self::AccountId, Self::ItemId>;
+	/// Tell whether an item is locked, e.g. in an auction
+	type ItemLocks: IsItemLocked<Self::ItemId>;
+	/// Currency of the deposits
+	type Currency: ReservableCurrency<Self::AccountId>;
+	/// Deposit reserved from an account for its recovery config
+	type ConfigDeposit: Get<BalanceOf<Self>>;
+	/// Deposit reserved from a rescuer for initiating a recovery
+	type RecoveryDeposit: Get<BalanceOf<Self>>;
+}
+
+pub type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+
+#[derive(Encode, Decode, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct RecoveryConfig<AccountId, Balance, BlockNumber> {
+	friends: Vec<AccountId>, // 好友列表，已排序
+	threshold: u16, // 需要的担保人数
+	delay_period: BlockNumber, // 发起恢复后需要等待的区块数
+	deposit: Balance, // 预留的押金
+}
+
+#[derive(Encode, Decode, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct ActiveRecovery<AccountId, Balance, BlockNumber> {
+	created: BlockNumber, // 发起恢复的区块
+	deposit: Balance, // 发起人预留的押金
+	friends: Vec<AccountId>, // 已担保的好友
+}
+
+type RecoveryConfigOf<T> = RecoveryConfig<<T as system::Trait>::AccountId, BalanceOf<T>, <T as system::Trait>::BlockNumber>;
+type ActiveRecoveryOf<T> = ActiveRecovery<<T as system::Trait>::AccountId, BalanceOf<T>, <T as system::Trait>::BlockNumber>;
+
+decl_storage! {
+	trait Store for Module<T: Trait> as Recovery {
+		/// Recovery config of an account
+		pub RecoveryConfigs get(fn recovery_config): map T::AccountId => Option<RecoveryConfigOf<T>>;
+		/// Active recoveries, (lost account, rescuer) => vouches
+		pub ActiveRecoveries get(fn active_recovery): double_map T::AccountId, twox_128(T::AccountId) => Option<ActiveRecoveryOf<T>>;
+		/// Number of recoveries in progress for a lost account
+		pub ActiveRecoveryCount get(fn active_recovery_count): map T::AccountId => u32;
+		/// Lost account => rescuer which recovered it
+		pub RecoveredBy get(fn recovered_by): map T::AccountId => Option<T::AccountId>;
+	}
+}
+
+decl_event!(
+	pub enum Event<T> where
+		<T as system::Trait>::AccountId,
+	{
+		/// A recovery config is created. (account)
+		RecoveryCreated(AccountId),
+		/// A recovery config is removed. (account)
+		RecoveryRemoved(AccountId),
+		/// A recovery is initiated. (lost, rescuer)
+		RecoveryInitiated(AccountId, AccountId),
+		/// A friend vouched for a recovery. (lost, rescuer, friend)
+		RecoveryVouched(AccountId, AccountId, AccountId),
+		/// A recovery is closed by the lost account. (lost, rescuer)
+		RecoveryClosed(AccountId, AccountId),
+		/// A rescuer cancelled its recovery of an account recovered by another one. (lost, rescuer)
+		RecoveryCancelled(AccountId, AccountId),
+		/// A lost account is recovered. (lost, rescuer)
+		AccountRecovered(AccountId, AccountId),
+		/// Kitties are moved from the lost account to the rescuer. (lost, rescuer, moved, skipped_locked)
+		KittiesRecovered(AccountId, AccountId, u32, u32),
+		/// A call is dispatched on behalf of a recovered account. (lost, rescuer, result)
+		RecoveredCallDispatched(AccountId, AccountId, bool),
+	}
+);
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		fn deposit_event() = default;
+
+		/// Register friends who can vouch for recovering the sender's account
+		pub fn create_recovery(origin, friends: Vec<T::AccountId>, threshold: u16, delay_period: T::BlockNumber) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(!<RecoveryConfigs<T>>::exists(&sender), "Recovery config already exists");
+			ensure!(!friends.is_empty(), "At least one friend is required");
+			ensure!(friends.len() <= MAX_FRIENDS, "Too many friends");
+			ensure!(threshold >= 1 && threshold as usize <= friends.len(), "Invalid threshold");
+
+			let mut sorted_friends = friends;
+			sorted_friends.sort();
+			ensure!(sorted_friends.windows(2).all(|w| w[0] != w[1]), "Duplicate friends");
+			ensure!(!sorted_friends.contains(&sender), "Can not be a friend of yourself");
+
+			let deposit = T::ConfigDeposit::get();
+			T::Currency::reserve(&sender, deposit)?;
+			<RecoveryConfigs<T>>::insert(&sender, RecoveryConfig {
+				friends: sorted_friends,
+				threshold: threshold,
+				delay_period: delay_period,
+				deposit: deposit,
+			});
+			Self::deposit_event(RawEvent::RecoveryCreated(sender));
+		}
+
+		/// Remove the sender's recovery config
+		pub fn remove_recovery(origin) {
+			let sender = ensure_signed(origin)?;
+
+			let config = Self::recovery_config(&sender).ok_or("Recovery config does not exist")?;
+			ensure!(Self::active_recovery_count(&sender) == 0, "Recovery is in progress");
+			<RecoveryConfigs<T>>::remove(&sender);
+			T::Currency::unreserve(&sender, config.deposit);
+			Self::deposit_event(RawEvent::RecoveryRemoved(sender));
+		}
+
+		/// Start recovering a lost account to the sender
+		pub fn initiate_recovery(origin, account: T::AccountId) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(<RecoveryConfigs<T>>::exists(&account), "Recovery config does not exist");
+			ensure!(!<ActiveRecoveries<T>>::exists(&account, &sender), "Recovery already started");
+			ensure!(!<RecoveredBy<T>>::exists(&account), "Account already recovered");
+
+			let deposit = T::RecoveryDeposit::get();
+			T::Currency::reserve(&sender, deposit)?;
+			<ActiveRecoveries<T>>::insert(&account, &sender, ActiveRecovery {
+				created: <system::Module<T>>::block_number(),
+				deposit: deposit,
+				friends: Vec::new(),
+			});
+			<ActiveRecoveryCount<T>>::mutate(&account, |count| *count = count.saturating_add(1));
+			Self::deposit_event(RawEvent::RecoveryInitiated(account, sender));
+		}
+
+		/// A friend of the lost account vouches for the rescuer
+		pub fn vouch_recovery(origin, lost: T::AccountId, rescuer: T::AccountId) {
+			let sender = ensure_signed(origin)?;
+
+			let config = Self::recovery_config(&lost).ok_or("Recovery config does not exist")?;
+			ensure!(config.friends.binary_search(&sender).is_ok(), "Not a friend of the lost account");
+			let mut active = Self::active_recovery(&lost, &rescuer).ok_or("Recovery not started")?;
+			ensure!(!active.friends.contains(&sender), "Already vouched");
+
+			active.friends.push(sender.clone());
+			<ActiveRecoveries<T>>::insert(&lost, &rescuer, active);
+			Self::deposit_event(RawEvent::RecoveryVouched(lost, rescuer, sender));
+		}
+
+		/// The rescuer claims the lost account once enough friends vouched and the delay passed
+		pub fn claim_recovery(origin, account: T::AccountId) {
+			let sender = ensure_signed(origin)?;
+
+			let config = Self::recovery_config(&account).ok_or("Recovery config does not exist")?;
+			let active = Self::active_recovery(&account, &sender).ok_or("Recovery not started")?;
+			ensure!(!<RecoveredBy<T>>::exists(&account), "Account already recovered");
+			let unlock_at = active.created.checked_add(&config.delay_period).ok_or("Recovery delay period overflow")?;
+			ensure!(<system::Module<T>>::block_number() >= unlock_at, "Recovery delay period not passed");
+			ensure!(active.friends.len() >= config.threshold as usize, "Not enough friends vouched");
+
+			Self::remove_active_recovery(&account, &sender);
+			T::Currency::unreserve(&sender, active.deposit);
+			<RecoveredBy<T>>::insert(&account, &sender);
+			Self::deposit_event(RawEvent::AccountRecovered(account, sender));
+		}
+
+		/// The account owner closes a recovery it did not ask for
+		pub fn close_recovery(origin, rescuer: T::AccountId) {
+			let sender = ensure_signed(origin)?;
+
+			let active = Self::active_recovery(&sender, &rescuer).ok_or("Recovery not started")?;
+			Self::remove_active_recovery(&sender, &rescuer);
+			// the rescuer's deposit goes to the account it tried to take over
+			let _ = T::Currency::repatriate_reserved(&rescuer, &sender, active.deposit);
+			Self::deposit_event(RawEvent::RecoveryClosed(sender, rescuer));
+		}
+
+		/// A rescuer cancels its recovery of an account already recovered by another rescuer
+		pub fn cancel_recovery(origin, account: T::AccountId) {
+			let sender = ensure_signed(origin)?;
+
+			let active = Self::active_recovery(&account, &sender).ok_or("Recovery not started")?;
+			ensure!(<RecoveredBy<T>>::exists(&account), "Account is not recovered yet");
+			Self::remove_active_recovery(&account, &sender);
+			T::Currency::unreserve(&sender, active.deposit);
+			Self::deposit_event(RawEvent::RecoveryCancelled(account, sender));
+		}
+
+		/// Move the kitties which are not locked in an auction from the lost account to the rescuer,
+		/// at most `MAX_CLAIMED_KITTIES` in one call
+		pub fn claim_kitties(origin, account: T::AccountId) {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_rescuer(&account, &sender)?;
+
+			let (locked, free): (Vec<_>, Vec<_>) = T::Items::owned_items(&account).into_iter()
+				.partition(|item| T::ItemLocks::is_item_locked(*item));
+			let claimed: Vec<T::ItemId> = free.into_iter().take(MAX_CLAIMED_KITTIES).collect();
+			for item in claimed.iter() {
+				T::Items::can_transfer(&account, *item)?;
+			}
+			for item in claimed.iter() {
+				T::Items::transfer_item(&account, &sender, *item)?;
+			}
+			let moved = claimed.len() as u32;
+			let skipped = locked.len() as u32;
+			Self::deposit_event(RawEvent::KittiesRecovered(account, sender, moved, skipped));
+		}
+
+		/// Dispatch a call on behalf of the recovered account, e.g. to manage its auctions
+		pub fn as_recovered(origin, account: T::AccountId, call: Box<<T as Trait>::Call>) {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_rescuer(&account, &sender)?;
+
+			let res = call.dispatch(system::RawOrigin::Signed(account.clone()).into()).is_ok();
+			Self::deposit_event(RawEvent::RecoveredCallDispatched(account, sender, res));
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	fn remove_active_recovery(account: &T::AccountId, rescuer: &T::AccountId) {
+		<ActiveRecoveries<T>>::remove(account, rescuer);
+		<ActiveRecoveryCount<T>>::mutate(account, |count| *count = count.saturating_sub(1));
+	}
+
+	fn ensure_rescuer(account: &T::AccountId, rescuer: &T::AccountId) -> support::dispatch::Result {
+		ensure!(Self::recovered_by(account).as_ref() == Some(rescuer), "Not the rescuer of the account");
+		Ok(())
+	}
+}
+
+/// Tests for Recovery module
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use primitives::H256;
+	use support::{impl_outer_origin, parameter_types, assert_ok, assert_noop};
+	use sr_primitives::{traits::{BlakeTwo256, IdentityLookup}, testing::Header};
+	use sr_primitives::weights::Weight;
+	use sr_primitives::Perbill;
+	use crate::mock_items::{TestItems, TestLocks};
+
+	impl_outer_origin! {
+		pub enum Origin for Test {}
+	}
+
+	#[derive(Clone, Eq, PartialEq, Debug)]
+	pub struct Test;
+	parameter_types! {
+		pub const BlockHashCount: u64 = 250;
+		pub const MaximumBlockWeight: Weight = 1024;
+		pub const MaximumBlockLength: u32 = 2 * 1024;
+		pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+	}
+	impl system::Trait for Test {
+		type Origin = Origin;
+		type Call = ();
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type Event = ();
+		type BlockHashCount = BlockHashCount;
+		type MaximumBlockWeight = MaximumBlockWeight;
+		type MaximumBlockLength = MaximumBlockLength;
+		type AvailableBlockRatio = AvailableBlockRatio;
+		type Version = ();
+	}
+	parameter_types! {
+		pub const ExistentialDeposit: u64 = 0;
+		pub const TransferFee: u64 = 0;
+		pub const CreationFee: u64 = 0;
+		pub const ConfigDeposit: u64 = 10;
+		pub const RecoveryDeposit: u64 = 20;
+	}
+	impl balances::Trait for Test {
+		type Balance = u64;
+		type OnFreeBalanceZero = ();
+		type OnNewAccount = ();
+		type Event = ();
+		type TransferPayment = ();
+		type DustRemoval = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type TransferFee = TransferFee;
+		type CreationFee = CreationFee;
+	}
+	impl Trait for Test {
+		type Event = ();
+		type Call = balances::Call<Test>;
+		type ItemId = u32;
+		type Items = TestItems;
+		type ItemLocks = TestLocks;
+		type Currency = balances::Module<Test>;
+		type ConfigDeposit = ConfigDeposit;
+		type RecoveryDeposit = RecoveryDeposit;
+	}
+	type Recovery = Module<Test>;
+	type Balances = balances::Module<Test>;
+	type System = system::Module<Test>;
+
+	fn new_test_ext() -> runtime_io::TestExternalities {
+		let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+		balances::GenesisConfig::<Test> {
+			balances: vec![(1, 1000), (2, 1000), (5, 1000)],
+			vesting: vec![],
+		}.assimilate_storage(&mut t).unwrap();
+		t.into()
+	}
+
+	#[test]
+	fn recovery_needs_deposits_vouches_and_delay() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(Recovery::create_recovery(Origin::signed(3), vec![4], 1, 10), "not enough free funds");
+			assert_ok!(Recovery::create_recovery(Origin::signed(1), vec![4, 3], 2, 10));
+			assert_eq!(Balances::reserved_balance(&1), 10);
+
+			System::set_block_number(1);
+			assert_ok!(Recovery::initiate_recovery(Origin::signed(2), 1));
+			assert_eq!(Balances::reserved_balance(&2), 20);
+			assert_noop!(Recovery::vouch_recovery(Origin::signed(5), 1, 2), "Not a friend of the lost account");
+			assert_ok!(Recovery::vouch_recovery(Origin::signed(3), 1, 2));
+			assert_noop!(Recovery::claim_recovery(Origin::signed(2), 1), "Recovery delay period not passed");
+
+			System::set_block_number(11);
+			assert_noop!(Recovery::claim_recovery(Origin::signed(2), 1), "Not enough friends vouched");
+			assert_ok!(Recovery::vouch_recovery(Origin::signed(4), 1, 2));
+			assert_ok!(Recovery::claim_recovery(Origin::signed(2), 1));
+			assert_eq!(Recovery::recovered_by(1), Some(2));
+			assert_eq!(Balances::reserved_balance(&2), 0);
+			assert_eq!(Balances::free_balance(&2), 1000);
+
+			assert_ok!(Recovery::remove_recovery(Origin::signed(1)));
+			assert_eq!(Balances::reserved_balance(&1), 0);
+		});
+	}
+
+	#[test]
+	fn closed_recovery_pays_the_deposit_to_the_account() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Recovery::create_recovery(Origin::signed(1), vec![3], 1, 10));
+			assert_ok!(Recovery::initiate_recovery(Origin::signed(2), 1));
+			assert_ok!(Recovery::close_recovery(Origin::signed(1), 2));
+			assert_eq!(Recovery::active_recovery(1, 2), None);
+			assert_eq!(Balances::reserved_balance(&2), 0);
+			assert_eq!(Balances::free_balance(&2), 980);
+			assert_eq!(Balances::free_balance(&1), 1010);
+		});
+	}
+
+	#[test]
+	fn other_rescuers_cancel_once_the_account_is_recovered() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Recovery::create_recovery(Origin::signed(1), vec![3], 1, 0));
+			assert_ok!(Recovery::initiate_recovery(Origin::signed(2), 1));
+			assert_ok!(Recovery::initiate_recovery(Origin::signed(5), 1));
+			assert_eq!(Recovery::active_recovery_count(1), 2);
+			assert_noop!(Recovery::remove_recovery(Origin::signed(1)), "Recovery is in progress");
+			assert_noop!(Recovery::cancel_recovery(Origin::signed(5), 1), "Account is not recovered yet");
+
+			assert_ok!(Recovery::vouch_recovery(Origin::signed(3), 1, 2));
+			assert_ok!(Recovery::claim_recovery(Origin::signed(2), 1));
+			assert_noop!(Recovery::claim_recovery(Origin::signed(5), 1), "Account already recovered");
+			assert_noop!(Recovery::cancel_recovery(Origin::signed(2), 1), "Recovery not started");
+			assert_ok!(Recovery::cancel_recovery(Origin::signed(5), 1));
+			assert_eq!(Balances::reserved_balance(&5), 0);
+			assert_eq!(Balances::free_balance(&5), 1000);
+
+			assert_eq!(Recovery::active_recovery_count(1), 0);
+			assert_ok!(Recovery::remove_recovery(Origin::signed(1)));
+		});
+	}
+
+	#[test]
+	fn delay_period_overflow_is_rejected() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Recovery::create_recovery(Origin::signed(1), vec![3], 1, u64::max_value()));
+			System::set_block_number(1);
+			assert_ok!(Recovery::initiate_recovery(Origin::signed(2), 1));
+			assert_ok!(Recovery::vouch_recovery(Origin::signed(3), 1, 2));
+			assert_noop!(Recovery::claim_recovery(Origin::signed(2), 1), "Recovery delay period overflow");
+		});
+	}
+
+	#[test]
+	fn claimed_kitties_are_bounded_and_skip_locked_ones() {
+		new_test_ext().execute_with(|| {
+			TestItems::set_owners((0..60).map(|id| (1, id)).collect());
+			TestItems::set_locked(vec![0, 1]);
+			assert_ok!(Recovery::create_recovery(Origin::signed(1), vec![3], 1, 0));
+			assert_ok!(Recovery::initiate_recovery(Origin::signed(2), 1));
+			assert_noop!(Recovery::claim_kitties(Origin::signed(2), 1), "Not the rescuer of the account");
+			assert_ok!(Recovery::vouch_recovery(Origin::signed(3), 1, 2));
+			assert_ok!(Recovery::claim_recovery(Origin::signed(2), 1));
+
+			assert_ok!(Recovery::claim_kitties(Origin::signed(2), 1));
+			assert_eq!(TestItems::owned_items(&2).len(), MAX_CLAIMED_KITTIES);
+			assert_ok!(Recovery::claim_kitties(Origin::signed(2), 1));
+			assert_eq!(TestItems::owned_items(&2).len(), 58);
+			assert_eq!(TestItems::owned_items(&1), vec![0, 1]);
+		});
+	}
+}
@@ -0,0 +1,270 @@
+//! Reputation scores of market accounts, updated by hooks of the market modules.
+//!
+//! Sales and paid purchases raise the score, payment defaults and upheld
+//! reports against an auction owner lower it. The score may go negative.
+//! Only the first sale between two accounts scores, in either direction, so
+//! trading an item back and forth does not farm the score.
+//! Ratings left by the other party of a sale are averaged apart, and move the
+//! score up or down from a neutral 3 stars.
+
+use support::{decl_module, decl_storage, decl_event, StorageDoubleMap};
+use support::traits::Get;
+use codec::{Encode, Decode};
+use crate::traits::{OnItemSold, OnAuctionPenalty, OnAuctionRated, ReputationScore};
+
+pub trait Trait: system::Trait {
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+	/// Score added to the seller of a sale
+	type SaleScore: Get<i32>;
+	/// Score added to the buyer of a sale
+	type PurchaseScore: Get<i32>;
+	/// Score taken from a winner defaulting on a payment
+	type DefaultPenalty: Get<i32>;
+	/// Score taken from an auction owner found fraudulent
+	type ReportPenalty: Get<i32>;
+	/// Score added per star above 3 of a rating, taken per star below 3
+	type RatingScore: Get<i32>;
+}
+
+#[derive(Encode, Decode, Default, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct Reputation {
+	/// Items sold
+	pub sales: u32,
+	/// Items bought and paid
+	pub purchases: u32,
+	/// Payments defaulted on
+	pub defaults: u32,
+	/// Upheld reports against the account's auctions
+	pub upheld_reports: u32,
+	/// Overall score
+	pub score: i32,
+}
+
+/// Ratings an account received from the other party of its sales
+#[derive(Encode, Decode, Default, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct RatingSummary {
+	/// Number of ratings
+	pub count: u32,
+	/// Sum of the stars
+	pub total_stars: u32,
+}
+
+impl RatingSummary {
+	/// Average stars in hundredths, e.g. 450 for 4.5 stars. None without ratings.
+	pub fn average(&self) -> Option<u32> {
+		if self.count == 0 {
+			return None;
+		}
+		Some(self.total_stars.saturating_mul(100) / self.count)
+	}
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as Reputation {
+		/// Reputation of an account
+		pub Reputations get(fn reputation_of): map T::AccountId => Reputation;
+		/// Ratings received by an account
+		pub Ratings get(fn ratings_of): map T::AccountId => RatingSummary;
+		/// Pairs of accounts which already scored a sale, the lower account first
+		pub TradedPairs get(fn has_traded): double_map T::AccountId, blake2_256(T::AccountId) => bool;
+	}
+}
+
+decl_event!(
+	pub enum Event<T> where
+		<T as system::Trait>::AccountId,
+	{
+		/// The score of an account changes. (account, new_score)
+		ScoreChanged(AccountId, i32),
+	}
+);
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		fn deposit_event() = default;
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// Update the record of the account and add `delta` to its score
+	fn update(who: &T::AccountId, delta: i32, record: impl FnOnce(&mut Reputation)) {
+		let score = <Reputations<T>>::mutate(who, |reputation| {
+			record(reputation);
+			reputation.score = reputation.score.saturating_add(delta);
+			reputation.score
+		});
+		Self::deposit_event(RawEvent::ScoreChanged(who.clone(), score));
+	}
+}
+
+impl<T: Trait, ItemId, Balance> OnItemSold<T::AccountId, ItemId, Balance> for Module<T> {
+	fn on_item_sold(seller: &T::AccountId, buyer: &T::AccountId, _item_id: ItemId, _price: Balance) {
+		// 同一对账户之间只有首次成交计分，防止来回倒卖刷分
+		let (first, second) = if seller < buyer { (seller, buyer) } else { (buyer, seller) };
+		let scored = seller != buyer && !Self::has_traded(first, second);
+		if scored {
+			<TradedPairs<T>>::insert(first, second, true);
+		}
+		let (sale_score, purchase_score) = if scored {
+			(T::SaleScore::get(), T::PurchaseScore::get())
+		} else {
+			(0, 0)
+		};
+		Self::update(seller, sale_score, |r| r.sales = r.sales.saturating_add(1));
+		Self::update(buyer, purchase_score, |r| r.purchases = r.purchases.saturating_add(1));
+	}
+}
+
+impl<T: Trait> OnAuctionPenalty<T::AccountId> for Module<T> {
+	fn on_payment_default(who: &T::AccountId) {
+		Self::update(who, 0i32.saturating_sub(T::DefaultPenalty::get()), |r| r.defaults = r.defaults.saturating_add(1));
+	}
+
+	fn on_report_upheld(owner: &T::AccountId) {
+		Self::update(owner, 0i32.saturating_sub(T::ReportPenalty::get()), |r| r.upheld_reports = r.upheld_reports.saturating_add(1));
+	}
+}
+
+impl<T: Trait> OnAuctionRated<T::AccountId> for Module<T> {
+	fn on_rated(who: &T::AccountId, stars: u8) {
+		<Ratings<T>>::mutate(who, |ratings| {
+			ratings.count = ratings.count.saturating_add(1);
+			ratings.total_stars = ratings.total_stars.saturating_add(stars as u32);
+		});
+		let delta = (stars as i32 - 3).saturating_mul(T::RatingScore::get());
+		if delta != 0 {
+			Self::update(who, delta, |_| {});
+		}
+	}
+}
+
+impl<T: Trait> ReputationScore<T::AccountId> for Module<T> {
+	fn reputation_score(who: &T::AccountId) -> i32 {
+		Self::reputation_of(who).score
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use primitives::H256;
+	use support::{impl_outer_origin, parameter_types};
+	use sr_primitives::{traits::{BlakeTwo256, IdentityLookup}, testing::Header};
+	use sr_primitives::weights::Weight;
+	use sr_primitives::Perbill;
+
+	impl_outer_origin! {
+		pub enum Origin for Test {}
+	}
+
+	#[derive(Clone, Eq, PartialEq, Debug)]
+	pub struct Test;
+	parameter_types! {
+		pub const BlockHashCount: u64 = 250;
+		pub const MaximumBlockWeight: Weight = 1024;
+		pub const MaximumBlockLength: u32 = 2 * 1024;
+		pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+	}
+	impl system::Trait for Test {
+		type Origin = Origin;
+		type Call = ();
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type Event = ();
+		type BlockHashCount = BlockHashCount;
+		type MaximumBlockWeight = MaximumBlockWeight;
+		type MaximumBlockLength = MaximumBlockLength;
+		type AvailableBlockRatio = AvailableBlockRatio;
+		type Version = ();
+	}
+	parameter_types! {
+		pub const SaleScore: i32 = 10;
+		pub const PurchaseScore: i32 = 5;
+		pub const DefaultPenalty: i32 = i32::min_value();
+		pub const ReportPenalty: i32 = 50;
+		pub const RatingScore: i32 = 2;
+	}
+	impl Trait for Test {
+		type Event = ();
+		type SaleScore = SaleScore;
+		type PurchaseScore = PurchaseScore;
+		type DefaultPenalty = DefaultPenalty;
+		type ReportPenalty = ReportPenalty;
+		type RatingScore = RatingScore;
+	}
+	type Reputations = Module<Test>;
+
+	fn new_test_ext() -> runtime_io::TestExternalities {
+		system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
+	}
+
+	fn sold(seller: u64, buyer: u64) {
+		<Reputations as OnItemSold<u64, u32, u64>>::on_item_sold(&seller, &buyer, 0, 100);
+	}
+
+	#[test]
+	fn only_the_first_sale_between_two_accounts_scores() {
+		new_test_ext().execute_with(|| {
+			sold(1, 2);
+			sold(2, 1);
+			sold(1, 2);
+			assert_eq!(Reputations::reputation_of(1), Reputation { sales: 2, purchases: 1, score: 10, ..Default::default() });
+			assert_eq!(Reputations::reputation_of(2), Reputation { sales: 1, purchases: 2, score: 5, ..Default::default() });
+			assert!(Reputations::has_traded(1, 2));
+
+			// a new counterparty scores again
+			sold(1, 3);
+			assert_eq!(Reputations::reputation_of(1).score, 20);
+			assert_eq!(Reputations::reputation_of(3).score, 5);
+		});
+	}
+
+	#[test]
+	fn penalties_and_ratings_move_the_score() {
+		new_test_ext().execute_with(|| {
+			sold(1, 2);
+			<Reputations as OnAuctionPenalty<u64>>::on_report_upheld(&1);
+			assert_eq!(Reputations::reputation_of(1).score, -40);
+			assert_eq!(Reputations::reputation_of(1).upheld_reports, 1);
+
+			// the penalty is negated without overflow
+			<Reputations as OnAuctionPenalty<u64>>::on_payment_default(&2);
+			assert_eq!(Reputations::reputation_of(2).score, i32::max_value());
+			assert_eq!(Reputations::reputation_of(2).defaults, 1);
+
+			<Reputations as OnAuctionRated<u64>>::on_rated(&1, 5);
+			<Reputations as OnAuctionRated<u64>>::on_rated(&1, 2);
+			assert_eq!(Reputations::reputation_of(1).score, -38);
+			assert_eq!(Reputations::ratings_of(1).average(), Some(350));
+		});
+	}
+}
+
+/// Runtime api of this module
+pub mod api {
+	use codec::Codec;
+
+	client::decl_runtime_apis! {
+		/// The api to query reputation of accounts.
+		pub trait ReputationApi<AccountId, Reputation, RatingSummary> where
+			AccountId: Codec,
+			Reputation: Codec,
+			RatingSummary: Codec,
+		{
+			/// Reputation of the account.
+			fn reputation_of(who: AccountId) -> Reputation;
+			/// Ratings received by the account.
+			fn ratings_of(who: AccountId) -> RatingSummary;
+			/// Average stars received by the account in hundredths, None without ratings.
+			fn average_rating(who: AccountId) -> Option<u32>;
+		}
+	}
+}
@@ -0,0 +1,223 @@
+//! Wrap a kitty into fungible shares.
+//!
+//! The wrapped kitty is moved to the escrow account of the module and locked,
+//! so it can not be auctioned, fused or gifted. Shares can be transferred
+//! freely, the account holding all shares of a kitty can unwrap it and receives
+//! the kitty from escrow.
+
+use support::{decl_module, decl_storage, decl_event, ensure, Parameter};
+use support::traits::Get;
+use sr_primitives::ModuleId;
+use sr_primitives::traits::{Member, AccountIdConversion};
+use system::ensure_signed;
+use crate::traits::{ItemTransfer, IsItemLocked};
+
+pub trait Trait: system::Trait {
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+	/// Kitty index
+	type KittyIndex: Parameter + Member + Copy;
+	/// Interface for transferring kitties
+	type Kitties: ItemTransfer<Self::AccountId, Self::KittyIndex>;
+	/// Tell whether a kitty is locked by other modules, e.g. in an auction
+	type KittyLocks: IsItemLocked<Self::KittyIndex>;
+	/// Id of the module, wrapped kitties are kept in its escrow account
+	type ModuleId: Get<ModuleId>;
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as Shares {
+		/// Account which wrapped a kitty held in escrow
+		pub WrappedKitties get(fn wrapped_by): map T::KittyIndex => Option<T::AccountId>;
+		/// Total shares of a wrapped kitty
+		pub TotalShares get(fn total_shares): map T::KittyIndex => u64;
+		/// Shares of a wrapped kitty held by an account
+		pub ShareBalances get(fn share_balance): double_map T::KittyIndex, twox_128(T::AccountId) => u64;
+	}
+}
+
+decl_event!(
+	pub enum Event<T> where
+		<T as system::Trait>::AccountId,
+		<T as Trait>::KittyIndex,
+	{
+		/// A kitty is wrapped into shares. (owner, kitty_id, shares)
+		Wrapped(AccountId, KittyIndex, u64),
+		/// Shares are transferred. (kitty_id, from, to, amount)
+		SharesTransferred(KittyIndex, AccountId, AccountId, u64),
+		/// A kitty is unwrapped by the holder of all shares. (holder, kitty_id)
+		Unwrapped(AccountId, KittyIndex),
+	}
+);
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		fn deposit_event() = default;
+
+		/// Id of the module, wrapped kitties are kept in its escrow account
+		const ModuleId: ModuleId = T::ModuleId::get();
+
+		/// Move an owned kitty into escrow and mint all its shares to the owner
+		pub fn wrap_kitty(origin, kitty_id: T::KittyIndex, shares: u64) {
+			let sender = ensure_signed(origin)?;
+			ensure!(shares > 1, "Should mint more than one share");
+			ensure!(T::Kitties::is_item_owner(&sender, kitty_id), "Not owner of kitty");
+			ensure!(!T::KittyLocks::is_item_locked(kitty_id), "Kitty is locked in an auction");
+			ensure!(!<WrappedKitties<T>>::exists(kitty_id), "Kitty is already wrapped");
+
+			T::Kitties::transfer_item(&sender, &Self::escrow_account(), kitty_id)?;
+			<WrappedKitties<T>>::insert(kitty_id, sender.clone());
+			<TotalShares<T>>::insert(kitty_id, shares);
+			<ShareBalances<T>>::insert(kitty_id, &sender, shares);
+
+			Self::deposit_event(RawEvent::Wrapped(sender, kitty_id, shares));
+		}
+
+		/// Transfer shares of a wrapped kitty
+		pub fn transfer_shares(origin, kitty_id: T::KittyIndex, to: T::AccountId, amount: u64) {
+			let sender = ensure_signed(origin)?;
+			ensure!(amount > 0, "Should transfer some shares");
+			let balance = Self::share_balance(kitty_id, &sender);
+			ensure!(balance >= amount, "Not enough shares");
+
+			if sender != to {
+				let to_balance = Self::share_balance(kitty_id, &to).checked_add(amount).ok_or("Shares overflow")?;
+				Self::set_share_balance(kitty_id, &sender, balance - amount);
+				Self::set_share_balance(kitty_id, &to, to_balance);
+			}
+
+			Self::deposit_event(RawEvent::SharesTransferred(kitty_id, sender, to, amount));
+		}
+
+		/// Burn all shares of a kitty and take the kitty out of escrow
+		pub fn unwrap_kitty(origin, kitty_id: T::KittyIndex) {
+			let sender = ensure_signed(origin)?;
+			ensure!(<WrappedKitties<T>>::exists(kitty_id), "Kitty is not wrapped");
+			ensure!(Self::share_balance(kitty_id, &sender) == Self::total_shares(kitty_id), "Should hold all shares");
+
+			T::Kitties::transfer_item(&Self::escrow_account(), &sender, kitty_id)?;
+			<WrappedKitties<T>>::remove(kitty_id);
+			<TotalShares<T>>::remove(kitty_id);
+			<ShareBalances<T>>::remove(kitty_id, &sender);
+
+			Self::deposit_event(RawEvent::Unwrapped(sender, kitty_id));
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// The account holding the wrapped kitties
+	pub fn escrow_account() -> T::AccountId {
+		T::ModuleId::get().into_account()
+	}
+
+	fn set_share_balance(kitty_id: T::KittyIndex, who: &T::AccountId, amount: u64) {
+		if amount == 0 {
+			<ShareBalances<T>>::remove(kitty_id, who);
+		} else {
+			<ShareBalances<T>>::insert(kitty_id, who, amount);
+		}
+	}
+}
+
+impl<T: Trait> IsItemLocked<T::KittyIndex> for Module<T> {
+	fn is_item_locked(kitty_id: T::KittyIndex) -> bool {
+		<WrappedKitties<T>>::exists(kitty_id)
+	}
+}
+
+/// Tests for Shares module
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use primitives::H256;
+	use support::{impl_outer_origin, parameter_types, assert_ok, assert_noop};
+	use sr_primitives::{traits::{BlakeTwo256, IdentityLookup}, testing::Header};
+	use sr_primitives::weights::Weight;
+	use sr_primitives::Perbill;
+	use crate::mock_items::{TestItems, TestLocks};
+
+	impl_outer_origin! {
+		pub enum Origin for Test {}
+	}
+
+	#[derive(Clone, Eq, PartialEq, Debug)]
+	pub struct Test;
+	parameter_types! {
+		pub const BlockHashCount: u64 = 250;
+		pub const MaximumBlockWeight: Weight = 1024;
+		pub const MaximumBlockLength: u32 = 2 * 1024;
+		pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+		pub const SharesModuleId: ModuleId = ModuleId(*b"py/share");
+	}
+	impl system::Trait for Test {
+		type Origin = Origin;
+		type Call = ();
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type Event = ();
+		type BlockHashCount = BlockHashCount;
+		type MaximumBlockWeight = MaximumBlockWeight;
+		type MaximumBlockLength = MaximumBlockLength;
+		type AvailableBlockRatio = AvailableBlockRatio;
+		type Version = ();
+	}
+	impl Trait for Test {
+		type Event = ();
+		type KittyIndex = u32;
+		type Kitties = TestItems;
+		type KittyLocks = TestLocks;
+		type ModuleId = SharesModuleId;
+	}
+	type Shares = Module<Test>;
+
+	fn new_test_ext() -> runtime_io::TestExternalities {
+		TestItems::set_owners(vec![(1, 0), (1, 1)]);
+		system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
+	}
+
+	#[test]
+	fn wrapped_kitty_is_held_in_escrow() {
+		new_test_ext().execute_with(|| {
+			let escrow = Shares::escrow_account();
+			assert_noop!(Shares::wrap_kitty(Origin::signed(2), 0, 100), "Not owner of kitty");
+			assert_noop!(Shares::wrap_kitty(Origin::signed(1), 0, 1), "Should mint more than one share");
+			TestItems::set_locked(vec![1]);
+			assert_noop!(Shares::wrap_kitty(Origin::signed(1), 1, 100), "Kitty is locked in an auction");
+
+			assert_ok!(Shares::wrap_kitty(Origin::signed(1), 0, 100));
+			assert!(TestItems::is_item_owner(&escrow, 0));
+			assert!(!TestItems::is_item_owner(&1, 0));
+			assert_eq!(Shares::wrapped_by(0), Some(1));
+			assert_eq!(Shares::share_balance(0, &1), 100);
+			assert!(Shares::is_item_locked(0));
+			assert_noop!(Shares::wrap_kitty(Origin::signed(1), 0, 100), "Not owner of kitty");
+		});
+	}
+
+	#[test]
+	fn holder_of_all_shares_unwraps_the_kitty() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Shares::wrap_kitty(Origin::signed(1), 0, 100));
+			assert_noop!(Shares::transfer_shares(Origin::signed(1), 0, 2, 101), "Not enough shares");
+			assert_ok!(Shares::transfer_shares(Origin::signed(1), 0, 2, 60));
+			assert_eq!(Shares::share_balance(0, &1), 40);
+			assert_eq!(Shares::share_balance(0, &2), 60);
+			assert_noop!(Shares::unwrap_kitty(Origin::signed(2), 0), "Should hold all shares");
+
+			assert_ok!(Shares::transfer_shares(Origin::signed(1), 0, 2, 40));
+			assert_ok!(Shares::unwrap_kitty(Origin::signed(2), 0));
+			assert!(TestItems::is_item_owner(&2, 0));
+			assert_eq!(Shares::wrapped_by(0), None);
+			assert_eq!(Shares::total_shares(0), 0);
+			assert_eq!(Shares::share_balance(0, &2), 0);
+			assert!(!Shares::is_item_locked(0));
+			assert_noop!(Shares::unwrap_kitty(Origin::signed(2), 0), "Kitty is not wrapped");
+		});
+	}
+}
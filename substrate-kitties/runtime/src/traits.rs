@@ -1,9 +1,224 @@
+use codec::{Encode, Decode};
+use sr_primitives::RuntimeDebug;
 use support::dispatch::Result;
+use rstd::vec::Vec;
+
+/// Why an item could not be transferred or held
+#[derive(Encode, Decode, Clone, Copy, Eq, PartialEq, RuntimeDebug)]
+pub enum ItemTransferError {
+	/// The account does not own the item
+	NotOwner,
+	/// The item is frozen, held or locked, e.g. lent out or bridged out
+	Frozen,
+	/// The item does not exist, or could not be moved for another reason
+	Unknown,
+}
+
+impl From<ItemTransferError> for &'static str {
+	fn from(err: ItemTransferError) -> &'static str {
+		match err {
+			ItemTransferError::NotOwner => "Item is not owned by the account",
+			ItemTransferError::Frozen => "Item is frozen",
+			ItemTransferError::Unknown => "Item can not be transferred",
+		}
+	}
+}
 
 /// Means for interacting with transfering items between accounts
 pub trait ItemTransfer<AccountId, ItemId> {
 	/// Ensure item's owner
 	fn is_item_owner(who: &AccountId, item_id: ItemId) -> bool;
-	/// Transfer item from one to one
-	fn transfer_item(source: &AccountId, dest: &AccountId, item_id: ItemId) -> Result;
+	/// Transfer item from one to one, a held item can only be moved by `transfer_reserved_item`
+	fn transfer_item(source: &AccountId, dest: &AccountId, item_id: ItemId) -> rstd::result::Result<(), ItemTransferError>;
+	/// Transfer an item held by the caller, releasing the hold, e.g. to hand a sold item to the winner.
+	/// Modules which never hold items move it like `transfer_item`.
+	fn transfer_reserved_item(source: &AccountId, dest: &AccountId, item_id: ItemId) -> rstd::result::Result<(), ItemTransferError> {
+		Self::transfer_item(source, dest, item_id)
+	}
+	/// Ensure the item could be transferred from its owner now, e.g. it is not held, frozen or lent out
+	fn can_transfer(from: &AccountId, item_id: ItemId) -> rstd::result::Result<(), ItemTransferError>;
+	/// Ensure the caller could move its held item now with `transfer_reserved_item`.
+	/// Modules which never hold items check it like `can_transfer`.
+	fn can_transfer_reserved(from: &AccountId, item_id: ItemId) -> rstd::result::Result<(), ItemTransferError> {
+		Self::can_transfer(from, item_id)
+	}
+	/// Place a hold on the item of the owner, e.g. while it is listed in an auction.
+	/// The owner can not move a held item, the hold is released when the item changes hands.
+	fn reserve_item(who: &AccountId, item_id: ItemId) -> rstd::result::Result<(), ItemTransferError>;
+	/// Release the hold on the item, if any
+	fn unreserve_item(item_id: ItemId);
+}
+
+/// Means for listing the items owned by an account
+pub trait OwnedItems<AccountId, ItemId> {
+	/// All items owned by the account
+	fn owned_items(who: &AccountId) -> Vec<ItemId>;
+}
+
+/// Means for minting new items to an account
+pub trait ItemMint<AccountId, ItemId> {
+	/// Mint a new item for the owner
+	fn mint_item(owner: &AccountId) -> rstd::result::Result<ItemId, &'static str>;
+}
+
+/// Handler for items being burned
+pub trait OnItemBurned<AccountId, ItemId> {
+	/// Called after the item of the owner is burned
+	fn on_item_burned(owner: &AccountId, item_id: ItemId);
+}
+
+impl<AccountId, ItemId> OnItemBurned<AccountId, ItemId> for () {
+	fn on_item_burned(_owner: &AccountId, _item_id: ItemId) {}
+}
+
+/// Handler for items changing hands, other than by a sale
+pub trait OnItemTransferred<AccountId, ItemId> {
+	/// Called after a new item is minted to the owner
+	fn on_item_minted(owner: &AccountId, item_id: ItemId);
+	/// Called after the item is transferred, `gift` tells a gift from a plain transfer
+	fn on_item_transferred(from: &AccountId, to: &AccountId, item_id: ItemId, gift: bool);
+}
+
+impl<AccountId, ItemId> OnItemTransferred<AccountId, ItemId> for () {
+	fn on_item_minted(_owner: &AccountId, _item_id: ItemId) {}
+	fn on_item_transferred(_from: &AccountId, _to: &AccountId, _item_id: ItemId, _gift: bool) {}
+}
+
+/// Means for checking whether an item is locked by another module, e.g. listed in an auction
+pub trait IsItemLocked<ItemId> {
+	/// Whether the item is locked now
+	fn is_item_locked(item_id: ItemId) -> bool;
+}
+
+impl<ItemId> IsItemLocked<ItemId> for () {
+	fn is_item_locked(_item_id: ItemId) -> bool {
+		false
+	}
+}
+
+/// Handler for items being sold, by auction or at a fixed price
+pub trait OnItemSold<AccountId, ItemId, Balance> {
+	/// Called after the item is paid and transferred to the buyer
+	fn on_item_sold(seller: &AccountId, buyer: &AccountId, item_id: ItemId, price: Balance);
+}
+
+impl<AccountId, ItemId, Balance> OnItemSold<AccountId, ItemId, Balance> for () {
+	fn on_item_sold(_seller: &AccountId, _buyer: &AccountId, _item_id: ItemId, _price: Balance) {}
+}
+
+impl<AccountId, ItemId: Copy, Balance: Copy, A, B> OnItemSold<AccountId, ItemId, Balance> for (A, B) where
+	A: OnItemSold<AccountId, ItemId, Balance>,
+	B: OnItemSold<AccountId, ItemId, Balance>,
+{
+	fn on_item_sold(seller: &AccountId, buyer: &AccountId, item_id: ItemId, price: Balance) {
+		A::on_item_sold(seller, buyer, item_id, price);
+		B::on_item_sold(seller, buyer, item_id, price);
+	}
+}
+
+/// Means for looking up a suggested minimum price of an item
+pub trait SuggestedPrice<ItemId, Balance> {
+	/// Suggested minimum price, None when there is no suggestion
+	fn suggested_price(item_id: ItemId) -> Option<Balance>;
+}
+
+impl<ItemId, Balance> SuggestedPrice<ItemId, Balance> for () {
+	fn suggested_price(_item_id: ItemId) -> Option<Balance> {
+		None
+	}
+}
+
+/// Means for grouping items into classes, e.g. for sale stats
+pub trait ClassifyItem<ItemId, Class> {
+	/// Class of the item
+	fn class_of(item_id: ItemId) -> Class;
+}
+
+/// Handler for bids placed in auctions
+pub trait OnBidPlaced<AccountId, Balance> {
+	/// Called after the bid is accepted
+	fn on_bid_placed(bidder: &AccountId, price: Balance);
+}
+
+impl<AccountId, Balance> OnBidPlaced<AccountId, Balance> for () {
+	fn on_bid_placed(_bidder: &AccountId, _price: Balance) {}
+}
+
+impl<AccountId, Balance: Copy, A, B> OnBidPlaced<AccountId, Balance> for (A, B) where
+	A: OnBidPlaced<AccountId, Balance>,
+	B: OnBidPlaced<AccountId, Balance>,
+{
+	fn on_bid_placed(bidder: &AccountId, price: Balance) {
+		A::on_bid_placed(bidder, price);
+		B::on_bid_placed(bidder, price);
+	}
+}
+
+/// Handler for misconduct in auctions, e.g. reputation
+pub trait OnAuctionPenalty<AccountId> {
+	/// Called after the winner of an auction defaults on its payment
+	fn on_payment_default(who: &AccountId);
+	/// Called after a report or challenge against the owner's auction is upheld
+	fn on_report_upheld(owner: &AccountId);
+}
+
+impl<AccountId> OnAuctionPenalty<AccountId> for () {
+	fn on_payment_default(_who: &AccountId) {}
+	fn on_report_upheld(_owner: &AccountId) {}
+}
+
+/// Handler for ratings left between the parties of a settled auction, e.g. reputation
+pub trait OnAuctionRated<AccountId> {
+	/// Called after the counterparty of a sale rates the account, from 1 to 5 stars
+	fn on_rated(who: &AccountId, stars: u8);
+}
+
+impl<AccountId> OnAuctionRated<AccountId> for () {
+	fn on_rated(_who: &AccountId, _stars: u8) {}
+}
+
+/// Means for calling a smart contract, e.g. through the contracts module
+pub trait ContractCall<AccountId> {
+	/// Whether contracts can be called at all
+	fn is_supported() -> bool;
+	/// Call the contract on behalf of the caller, which pays for at most `gas_limit`
+	fn call_contract(caller: &AccountId, contract: &AccountId, gas_limit: u64, input: Vec<u8>) -> Result;
+}
+
+impl<AccountId> ContractCall<AccountId> for () {
+	fn is_supported() -> bool {
+		false
+	}
+
+	fn call_contract(_caller: &AccountId, _contract: &AccountId, _gas_limit: u64, _input: Vec<u8>) -> Result {
+		Err("Contracts are not supported")
+	}
+}
+
+/// Means for restricting the calls dispatched on behalf of another account, e.g. a gallery
+pub trait CallFilter<Call> {
+	/// Whether the call may be dispatched
+	fn allows(call: &Call) -> bool;
+}
+
+/// Means for looking up the reputation score of an account
+pub trait ReputationScore<AccountId> {
+	/// Current score of the account
+	fn reputation_score(who: &AccountId) -> i32;
+}
+
+impl<AccountId> ReputationScore<AccountId> for () {
+	fn reputation_score(_who: &AccountId) -> i32 {
+		0
+	}
+}
+
+/// Handler for kitties being bred
+pub trait OnKittyBred<AccountId, KittyIndex> {
+	/// Called after the new kitty is bred for the owner
+	fn on_kitty_bred(owner: &AccountId, kitty_id: KittyIndex);
+}
+
+impl<AccountId, KittyIndex> OnKittyBred<AccountId, KittyIndex> for () {
+	fn on_kitty_bred(_owner: &AccountId, _kitty_id: KittyIndex) {}
 }